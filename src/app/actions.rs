@@ -1,11 +1,16 @@
-use crate::app::state::{AppState, ExportMode, ExportMenuStage};
+use crate::app::state::{AppState, ExportMode, ExportMenuStage, InputMode, EditorField, GrpcEditorField, Panel, ProtocolType, ResponseTab};
 use crate::import::import_postman_collection;
 use crate::models::collection::Collection;
+use crate::models::layout::RESIZE_STEP;
 use crate::models::request::HttpRequest;
-use crate::models::GrpcRequest;
+use crate::models::response::HttpResponse;
+use crate::models::{protocol::RequestType, GrpcRequest, GrpcResponse};
+use crate::utils::binary::sniff;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Commands that keys can be bound to, see `keymap`
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Action {
     Quit,
     ToggleHelp,
@@ -21,7 +26,13 @@ pub enum Action {
     NewCollection,
     DeleteCollection,
     EditCollection,
+    NewFolder,
+    EditDefaults,
+    MoveFolder,
+    ExpandFolder,
+    CollapseFolder,
     CopyResponse,
+    SaveResponseBody,
     OpenCurlExportMenu,
     OpenGrpcurlExportMenu,
     ExportRequestCurl,
@@ -34,13 +45,64 @@ pub enum Action {
     PageDownRequests,
     EnterRequestSearch,
     ExitRequestSearch,
+    SendRequest,
+    CancelRequest,
+    DuplicateRequest,
+    DeleteRequest,
+    UndoDelete,
+    OpenTrash,
+    CopyRequest,
+    CutRequest,
+    PasteRequest,
+    MoveRequestUp,
+    MoveRequestDown,
+    MoveCollectionUp,
+    MoveCollectionDown,
+    DuplicateCollection,
+    NextRequestTab,
+    PrevRequestTab,
+    PinRequestTab,
+    CloseRequestTab,
+    OpenExportMenu,
+    OpenProxySettings,
+    OpenProtoLoader,
+    EditRequest,
+    ScrollResponseDown,
+    ScrollResponseUp,
+    NextResponseTab,
+    ToggleTreeView,
+    FilterResponse,
+    SearchResponse,
+    NextSearchMatch,
+    PrevSearchMatch,
+    ViewResponseAs,
+    PinResponse,
+    CompareResponses,
+    FinishEditing,
+    NextField,
+    PrevField,
+    UndoEdit,
+    RedoEdit,
+    FormatBody,
+    OpenExternalEditor,
+    GrowPanel,
+    ShrinkPanel,
+    ToggleZoom,
+    ToggleCollectionsPanel,
+    ToggleRequestsPanel,
+    CycleLayout,
+    ResetLayout,
+    OpenCommandPalette,
 }
 
 impl Action {
     pub fn execute(&self, state: &mut AppState) {
         match self {
             Action::Quit => state.should_quit = true,
-            Action::ToggleHelp => state.show_help = !state.show_help,
+            Action::ToggleHelp => {
+                state.show_help = !state.show_help;
+                state.help_scroll = 0;
+            }
             Action::NextPanel => state.next_panel(),
             Action::PrevPanel => state.prev_panel(),
             Action::NextRequest => state.next_request(),
@@ -55,7 +117,10 @@ impl Action {
                         request.collection_id = Some(collection.id);
                     }
                 }
-                state.requests.push(request);
+                if let Some(folder_id) = state.selected_folder {
+                    state.request_folders.insert(request.id, folder_id);
+                }
+                state.requests.push(RequestType::Http(request));
                 state.selected_request = Some(state.requests.len() - 1);
             }
             Action::NewGrpcRequest => {
@@ -65,37 +130,90 @@ impl Action {
                         request.collection_id = Some(collection.id);
                     }
                 }
-                state.grpc_requests.push(request);
-                state.selected_request = Some(state.grpc_requests.len() - 1);
+                if let Some(folder_id) = state.selected_folder {
+                    state.request_folders.insert(request.id, folder_id);
+                }
+                state.requests.push(RequestType::Grpc(request));
+                state.selected_request = Some(state.requests.len() - 1);
             }
             Action::NewCollection => {
                 let collection_num = state.collections.len() + 1;
                 let collection = Collection::new(format!("Collection {}", collection_num));
                 state.collections.push(collection);
                 state.selected_collection = Some(state.collections.len() - 1);
+                state.selected_folder = None;
                 state.update_selected_request_for_collection();
             }
-            Action::DeleteCollection => {
-                if let Some(idx) = state.selected_collection {
-                    state.collections.remove(idx);
-                    if state.collections.is_empty() {
-                        state.selected_collection = None;
-                    } else if idx >= state.collections.len() {
-                        state.selected_collection = Some(state.collections.len() - 1);
+            Action::EditCollection => {
+                state.start_editing_collection();
+            }
+            Action::NewFolder => {
+                state.new_folder();
+            }
+            Action::EditDefaults => state.open_defaults_editor(),
+            Action::MoveFolder => {
+                if state.moving_folder.is_some() {
+                    if let Err(e) = state.drop_folder() {
+                        state.status_message = Some(e);
                     }
+                } else {
+                    state.start_moving_folder();
                 }
             }
-            Action::EditCollection => {
-                state.start_editing_collection();
+            Action::MoveCollectionUp | Action::MoveCollectionDown => {
+                let down = *self == Action::MoveCollectionDown;
+                if state.selected_folder.is_some() {
+                    state.shift_folder(down);
+                } else {
+                    state.shift_collection(down);
+                }
             }
+            Action::DuplicateCollection => {
+                state.duplicate_collection();
+            }
+            Action::ExpandFolder => state.expand_tree_node(),
+            Action::CollapseFolder => state.collapse_tree_node(),
             Action::CopyResponse => {
-                if let Some(response) = &state.current_response {
-                    let text_to_copy = response.formatted_body();
+                let text_to_copy = if state.is_response_body_shown() {
+                    state.response_display_text().map(str::to_string)
+                } else {
+                    state.response_tab_text()
+                };
+                if let Some(text_to_copy) = text_to_copy {
                     if let Ok(mut clipboard) = arboard::Clipboard::new() {
                         let _ = clipboard.set_text(text_to_copy);
                     }
                 }
             }
+            Action::SaveResponseBody => {
+                let Some(response) = state.current_response.as_ref() else {
+                    return;
+                };
+                if response.body.is_empty() {
+                    state.status_message = Some("The response has no body to save".to_string());
+                    return;
+                }
+
+                // Saved next to the curl exports, named after the request
+                let exports_dir = PathBuf::from("exports");
+                let _ = fs::create_dir_all(&exports_dir);
+
+                let request_name = state.get_current_request().map_or("response", |r| r.name.as_str());
+                let safe_name = request_name
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+                    .collect::<String>();
+                let extension = sniff(&response.body)
+                    .map_or_else(|| response.body_format().extension(), |kind| kind.extension);
+                let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+                let filepath = exports_dir.join(format!("{}_{}.{}", safe_name, timestamp, extension));
+
+                // The bytes as received, not the formatted view
+                state.status_message = Some(match fs::write(&filepath, &response.body) {
+                    Ok(_) => format!("Saved {} bytes to {}", response.body.len(), filepath.display()),
+                    Err(e) => format!("Failed to save the body: {}", e),
+                });
+            }
             Action::OpenCurlExportMenu => {
                 state.show_export_menu = true;
                 state.export_mode = Some(ExportMode::RequestCurl);
@@ -114,8 +232,8 @@ impl Action {
             }
             Action::ExportRequestCurl => {
                 if let Some(request_idx) = state.export_selected_request {
-                    if let Some(request) = state.requests.get(request_idx) {
-                        let curl = request.to_curl();
+                    if let Some(request) = state.requests.get(request_idx).and_then(RequestType::as_http).map(|r| state.resolve_http_request(r)) {
+                        let curl = request.to_curl_with_proxy(state.effective_proxy(request.collection_id));
 
                         // Create exports directory if it doesn't exist
                         let exports_dir = PathBuf::from("exports");
@@ -160,7 +278,7 @@ impl Action {
             }
             Action::ExportGrpcRequestGrpcurl => {
                 if let Some(request_idx) = state.export_selected_request {
-                    if let Some(request) = state.grpc_requests.get(request_idx) {
+                    if let Some(request) = state.requests.get(request_idx).and_then(RequestType::as_grpc).map(|r| state.resolve_grpc_request(r)) {
                         let grpcurl = request.to_grpcurl();
 
                         // Create exports directory if it doesn't exist
@@ -237,27 +355,33 @@ impl Action {
                 }
 
                 match import_postman_collection(path) {
-                    Ok((collection, requests)) => {
-                        let num_requests = requests.len();
+                    Ok(imported) => {
+                        let num_requests = imported.requests.len();
+                        let num_folders = imported.folders.len();
+                        let collection_name = imported.collection.name.clone();
 
-                        // Add the collection
-                        state.collections.push(collection.clone());
+                        // Add the collection and its folders
+                        state.collections.push(imported.collection);
                         state.selected_collection = Some(state.collections.len() - 1);
+                        state.selected_folder = None;
+                        state.folders.extend(imported.folders);
+                        state.node_defaults.extend(imported.defaults);
 
                         // Add all the requests
-                        for request in requests {
-                            state.requests.push(request);
+                        for (request, folder_id) in imported.requests {
+                            if let Some(folder_id) = folder_id {
+                                state.request_folders.insert(request.id, folder_id);
+                            }
+                            state.requests.push(RequestType::Http(request));
                         }
 
-                        // Select the first imported request
-                        if num_requests > 0 {
-                            state.selected_request = Some(state.requests.len() - num_requests);
-                        }
+                        state.update_selected_request_for_collection();
 
                         state.import_result_message = Some(format!(
-                            "Successfully imported collection '{}' with {} request(s)",
-                            collection.name,
-                            num_requests
+                            "Successfully imported collection '{}' with {} request(s) in {} folder(s)",
+                            collection_name,
+                            num_requests,
+                            num_folders
                         ));
                     }
                     Err(e) => {
@@ -266,77 +390,18 @@ impl Action {
                 }
             }
             Action::FirstRequest => {
-                // Jump to first request in current collection
-                let collection_id = state.selected_collection
-                    .and_then(|idx| state.collections.get(idx))
-                    .map(|c| c.id);
-
-                match state.protocol_type {
-                    crate::app::state::ProtocolType::Http => {
-                        if let Some(first_idx) = state.requests.iter().position(|r| {
-                            match (collection_id, r.collection_id) {
-                                (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                                (None, None) => true,
-                                _ => false,
-                            }
-                        }) {
-                            state.selected_request = Some(first_idx);
-                            state.clear_input_buffers();
-                            state.reset_request_list_scroll();
-                        }
-                    }
-                    crate::app::state::ProtocolType::Grpc => {
-                        if let Some(first_idx) = state.grpc_requests.iter().position(|r| {
-                            match (collection_id, r.collection_id) {
-                                (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                                (None, None) => true,
-                                _ => false,
-                            }
-                        }) {
-                            state.selected_request = Some(first_idx);
-                            state.clear_input_buffers();
-                            state.reset_request_list_scroll();
-                        }
-                    }
+                // Jump to the first request in the current collection or folder
+                if let Some(&first_idx) = state.visible_request_indices().first() {
+                    state.selected_request = Some(first_idx);
+                    state.clear_input_buffers();
+                    state.reset_request_list_scroll();
                 }
             }
             Action::LastRequest => {
-                // Jump to last request in current collection
-                let collection_id = state.selected_collection
-                    .and_then(|idx| state.collections.get(idx))
-                    .map(|c| c.id);
-
-                match state.protocol_type {
-                    crate::app::state::ProtocolType::Http => {
-                        let last_idx = state.requests.iter().enumerate()
-                            .filter(|(_, r)| match (collection_id, r.collection_id) {
-                                (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                                (None, None) => true,
-                                _ => false,
-                            })
-                            .last()
-                            .map(|(idx, _)| idx);
-
-                        if let Some(idx) = last_idx {
-                            state.selected_request = Some(idx);
-                            state.clear_input_buffers();
-                        }
-                    }
-                    crate::app::state::ProtocolType::Grpc => {
-                        let last_idx = state.grpc_requests.iter().enumerate()
-                            .filter(|(_, r)| match (collection_id, r.collection_id) {
-                                (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                                (None, None) => true,
-                                _ => false,
-                            })
-                            .last()
-                            .map(|(idx, _)| idx);
-
-                        if let Some(idx) = last_idx {
-                            state.selected_request = Some(idx);
-                            state.clear_input_buffers();
-                        }
-                    }
+                // Jump to the last request in the current collection or folder
+                if let Some(&last_idx) = state.visible_request_indices().last() {
+                    state.selected_request = Some(last_idx);
+                    state.clear_input_buffers();
                 }
             }
             Action::PageUpRequests => {
@@ -353,12 +418,116 @@ impl Action {
                     state.next_request();
                 }
             }
+            Action::CopyRequest => state.copy_request(),
+            Action::CutRequest => state.cut_request(),
+            Action::PasteRequest => {
+                if let Err(e) = state.paste_request() {
+                    state.status_message = Some(e);
+                }
+            }
+            Action::MoveRequestUp | Action::MoveRequestDown => {
+                if let Err(e) = state.shift_request(*self == Action::MoveRequestDown) {
+                    state.status_message = Some(e);
+                }
+            }
             Action::EnterRequestSearch => {
                 state.enter_request_search_mode();
             }
             Action::ExitRequestSearch => {
                 state.exit_request_search_mode();
             }
+            Action::CancelRequest => {
+                // Cancel the in-flight request of the active tab
+                if let Some(cancelled) = state.cancel_in_flight() {
+                    let duration_ms = cancelled.started_at.elapsed().as_millis() as u64;
+                    match cancelled.protocol {
+                        ProtocolType::Http => {
+                            state.current_response = Some(HttpResponse::cancelled(cancelled.request_id, duration_ms));
+                        }
+                        ProtocolType::Grpc => {
+                            state.grpc_response = Some(GrpcResponse::cancelled(cancelled.request_id, duration_ms));
+                        }
+                    }
+                    state.reset_response_scroll();
+                }
+            }
+            Action::NextRequestTab => state.next_tab(),
+            Action::PrevRequestTab => state.prev_tab(),
+            Action::PinRequestTab => state.pin_active_tab(),
+            Action::CloseRequestTab => state.close_active_tab(),
+            Action::OpenExportMenu => match state.protocol_type() {
+                ProtocolType::Http => Action::OpenCurlExportMenu.execute(state),
+                ProtocolType::Grpc => Action::OpenGrpcurlExportMenu.execute(state),
+            },
+            Action::OpenProxySettings => state.open_proxy_settings(),
+            Action::OpenProtoLoader => state.open_proto_loader(),
+            Action::EditRequest => {
+                state.pin_active_tab();
+                match state.protocol_type() {
+                    ProtocolType::Http => {
+                        state.load_current_request_to_input();
+                        state.editor_focused_field = EditorField::Name;
+                    }
+                    ProtocolType::Grpc => {
+                        state.load_current_grpc_request_to_input();
+                        state.grpc_editor_focused_field = GrpcEditorField::ServerUrl;
+                    }
+                }
+                state.input_mode = InputMode::Editing;
+            }
+            Action::ScrollResponseDown => state.scroll_response_down(),
+            Action::ScrollResponseUp => state.scroll_response_up(),
+            Action::NextResponseTab => state.next_response_tab(),
+            Action::ToggleTreeView => {
+                state.set_response_tab(ResponseTab::Body);
+                state.toggle_response_tree_mode();
+            }
+            Action::FilterResponse => state.enter_response_filter_mode(),
+            Action::SearchResponse => state.enter_response_search_mode(),
+            Action::NextSearchMatch => state.next_search_match(),
+            Action::PrevSearchMatch => state.prev_search_match(),
+            Action::ViewResponseAs => state.cycle_response_view_as(),
+            Action::PinResponse => state.toggle_pinned_response(),
+            Action::CompareResponses => state.open_diff_popup(),
+            Action::NextField => state.next_editor_field(),
+            Action::PrevField => state.prev_editor_field(),
+            Action::UndoEdit => {
+                if let Some(editor) = state.focused_text_editor() {
+                    editor.undo();
+                }
+            }
+            Action::RedoEdit => {
+                if let Some(editor) = state.focused_text_editor() {
+                    editor.redo();
+                }
+            }
+            Action::FormatBody => {
+                let formatted = state.focused_text_editor().map(|editor| editor.format_json());
+                if let Some(Err(e)) = formatted {
+                    state.status_message = Some(format!("Not valid JSON, {}", e));
+                }
+            }
+            Action::GrowPanel => {
+                state.resize_focused_panel(RESIZE_STEP);
+            }
+            Action::ShrinkPanel => {
+                state.resize_focused_panel(-RESIZE_STEP);
+            }
+            Action::ToggleZoom => state.zoomed = !state.zoomed,
+            Action::ToggleCollectionsPanel => state.toggle_sidebar(Panel::Collections),
+            Action::ToggleRequestsPanel => state.toggle_sidebar(Panel::Requests),
+            Action::CycleLayout => state.cycle_arrangement(),
+            Action::ResetLayout => state.reset_layout(),
+            Action::OpenCommandPalette => state.open_command_palette(),
+            Action::OpenTrash => state.open_trash(),
+            // These need the storage, the request channels or the terminal, main.rs runs them
+            Action::SendRequest
+            | Action::DuplicateRequest
+            | Action::DeleteRequest
+            | Action::DeleteCollection
+            | Action::UndoDelete
+            | Action::FinishEditing
+            | Action::OpenExternalEditor => {}
         }
     }
 }
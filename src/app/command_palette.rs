@@ -0,0 +1,258 @@
+use crate::app::actions::Action;
+use crate::app::keymap::{palette_actions, KeyScope};
+use crate::app::state::AppState;
+use crate::models::protocol::RequestType;
+use crate::models::folder;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cmp::Reverse;
+use uuid::Uuid;
+
+/// What picking a palette entry does
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaletteItem {
+    /// Run the action, from the panel it belongs to
+    Action(Action, KeyScope),
+    Collection(Uuid),
+    Folder(Uuid),
+    Request(Uuid),
+}
+
+/// One row of the palette
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaletteEntry {
+    pub item: PaletteItem,
+    pub kind: &'static str,
+    pub label: String,
+    pub detail: String,
+    /// Current keys of an action, e.g. `j / ↓`
+    pub keys: Option<String>,
+    /// Character positions in `label` that matched the query, for highlighting
+    pub matched: Vec<usize>,
+    score: i64,
+}
+
+/// The query typed into the palette and the highlighted row
+#[derive(Clone, Debug, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    /// Byte offset into `query`
+    pub cursor: usize,
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    /// Every action, collection and request matching the query, best first.
+    /// With an empty query everything is listed in its usual order.
+    pub fn entries(&self, state: &AppState) -> Vec<PaletteEntry> {
+        let terms: Vec<&str> = self.query.split_whitespace().collect();
+        let mut entries: Vec<PaletteEntry> = candidates(state)
+            .into_iter()
+            .filter_map(|(mut entry, fields)| {
+                for term in &terms {
+                    // Each term counts where it matches best: the label or one of the other fields
+                    let label = fuzzy_match(term, &entry.label);
+                    let other = fields.iter().filter_map(|field| fuzzy_match(term, field)).map(|(score, _)| score).max();
+                    match (label, other) {
+                        (Some((score, positions)), other) if other.is_none_or(|other| score >= other) => {
+                            entry.score += score;
+                            entry.matched.extend(positions);
+                        }
+                        (_, Some(score)) => entry.score += score,
+                        _ => return None,
+                    }
+                }
+                entry.matched.sort_unstable();
+                entry.matched.dedup();
+                Some(entry)
+            })
+            .collect();
+        // Stable, so equal scores keep the usual order
+        entries.sort_by_key(|entry| Reverse(entry.score));
+        entries
+    }
+
+    /// Typing and moving in the query; returns whether the key was used
+    pub fn input(&mut self, key: KeyEvent) -> bool {
+        let cursor = self.cursor.min(self.query.len());
+        let prev = self.query[..cursor].chars().next_back().map_or(cursor, |c| cursor - c.len_utf8());
+        let next = self.query[cursor..].chars().next().map_or(cursor, |c| cursor + c.len_utf8());
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                self.query.clear();
+                self.cursor = 0;
+            }
+            (KeyCode::Char(c), modifiers) if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                self.query.insert(cursor, c);
+                self.cursor = cursor + c.len_utf8();
+            }
+            (KeyCode::Backspace, _) => {
+                self.query.replace_range(prev..cursor, "");
+                self.cursor = prev;
+            }
+            (KeyCode::Delete, _) => self.query.replace_range(cursor..next, ""),
+            (KeyCode::Left, _) => self.cursor = prev,
+            (KeyCode::Right, _) => self.cursor = next,
+            (KeyCode::Home, _) => self.cursor = 0,
+            (KeyCode::End, _) => self.cursor = self.query.len(),
+            _ => return false,
+        }
+        // A new query starts again from its best match
+        self.selected = 0;
+        true
+    }
+
+    /// Move the highlight by `delta` rows, stopping at either end
+    pub fn move_selection(&mut self, delta: isize, len: usize) {
+        self.selected = self.selected.saturating_add_signed(delta).min(len.saturating_sub(1));
+    }
+}
+
+/// Every entry before filtering, with the text besides the label that the query may match
+fn candidates(state: &AppState) -> Vec<(PaletteEntry, Vec<String>)> {
+    let entry = |item, kind, label: &str, detail: String, keys| PaletteEntry {
+        item,
+        kind,
+        label: label.to_string(),
+        detail,
+        keys,
+        matched: Vec::new(),
+        score: 0,
+    };
+    let collection_name = |id: Option<Uuid>| {
+        id.and_then(|id| state.collections.iter().find(|c| c.id == id))
+            .map_or_else(String::new, |c| format!("  in {}", c.name))
+    };
+    // Folder path after the collection name, e.g. `  in Shop/Users/Admin`
+    let location = |collection_id: Option<Uuid>, request_id: Uuid| match state.request_folders.get(&request_id) {
+        Some(&folder_id) => format!("{}/{}", collection_name(collection_id), folder::path(&state.folders, folder_id)),
+        None => collection_name(collection_id),
+    };
+    let tags = |tags: &[String]| tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>();
+
+    let mut candidates = Vec::new();
+    for (action, description) in palette_actions() {
+        let scope = state.keymap.home_scope(action);
+        let detail = if scope == KeyScope::Global { String::new() } else { scope.title().to_string() };
+        let keys = state.keymap.keys_label(scope, action);
+        candidates.push((entry(PaletteItem::Action(action, scope), "Action", description, detail, keys), Vec::new()));
+    }
+    for collection in &state.collections {
+        let count = state.requests.iter().filter(|r| r.collection_id() == Some(collection.id)).count();
+        let detail = format!("{} request{}", count, if count == 1 { "" } else { "s" });
+        let fields = collection.description.iter().cloned().collect();
+        candidates.push((entry(PaletteItem::Collection(collection.id), "Collection", &collection.name, detail, None), fields));
+    }
+    for folder in &state.folders {
+        let path = folder::path(&state.folders, folder.id);
+        let detail = format!("{}{}", path, collection_name(Some(folder.collection_id)));
+        let mut fields = vec![path];
+        fields.extend(folder.description.iter().cloned());
+        candidates.push((entry(PaletteItem::Folder(folder.id), "Folder", &folder.name, detail, None), fields));
+    }
+    for request in &state.requests {
+        let item = PaletteItem::Request(request.id());
+        match request {
+            RequestType::Http(request) => {
+                let detail = format!("{} {}{}", request.method.as_str(), request.url, location(request.collection_id, request.id));
+                let mut fields = vec![request.method.as_str().to_string(), request.url.clone()];
+                fields.extend(tags(&request.tags));
+                candidates.push((entry(item, "HTTP", &request.name, detail, None), fields));
+            }
+            RequestType::Grpc(request) => {
+                let method = format!("{}/{}", request.service_name, request.method_name);
+                let detail = format!("{} {}{}", request.server_url, method, location(request.collection_id, request.id));
+                let mut fields = vec!["gRPC".to_string(), request.server_url.clone(), method];
+                fields.extend(tags(&request.tags));
+                candidates.push((entry(item, "gRPC", &request.name, detail, None), fields));
+            }
+        }
+    }
+    candidates
+}
+
+/// Score `text` for containing the characters of `term` in order, ignoring case, with the
+/// character positions matched. Runs of consecutive characters and word starts score higher,
+/// gaps lower; `None` when `term` is not in `text`.
+pub fn fuzzy_match(term: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let term: Vec<char> = term.chars().flat_map(char::to_lowercase).collect();
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let first = *term.first()?;
+
+    let word_start = |i: usize| {
+        i == 0 || !chars[i - 1].is_alphanumeric() || (chars[i - 1].is_lowercase() && chars[i].is_uppercase())
+    };
+    // Match greedily from each place the first character occurs and keep the best run
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for start in (0..lower.len()).filter(|&i| lower[i] == first) {
+        let mut positions = vec![start];
+        let mut next = start + 1;
+        for c in &term[1..] {
+            let Some(found) = (next..lower.len()).find(|&i| lower[i] == *c) else { break };
+            positions.push(found);
+            next = found + 1;
+        }
+        if positions.len() < term.len() {
+            // Later starts only have less text left to match in
+            break;
+        }
+
+        let mut score = 0;
+        for (n, &i) in positions.iter().enumerate() {
+            score += 1;
+            if word_start(i) {
+                score += 8;
+            }
+            if n > 0 {
+                let gap = (i - positions[n - 1] - 1) as i64;
+                score += if gap == 0 { 5 } else { -gap.min(10) };
+            }
+        }
+        // Text starting with the match ranks first, a match early in the text before a late one
+        score -= (start as i64).min(10) / 2;
+        if best.as_ref().is_none_or(|(best, _)| score > *best) {
+            best = Some((score, positions));
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::request::{HttpMethod, HttpRequest};
+
+    #[test]
+    fn queries_match_actions_and_requests_by_any_field() {
+        assert_eq!(fuzzy_match("snd", "Send request").map(|(_, p)| p), Some(vec![0, 2, 3]));
+        assert!(fuzzy_match("xyz", "Send request").is_none());
+        // Word starts and runs beat scattered letters
+        let score = |term, text| fuzzy_match(term, text).unwrap().0;
+        assert!(score("req", "Next request") > score("req", "Reset panel sizes and query"));
+        assert!(score("sr", "Send request") > score("sr", "Scroll response up"));
+
+        let mut state = AppState::new();
+        let mut request = HttpRequest::new("List users".to_string(), HttpMethod::POST, "https://api.test/users".to_string());
+        request.tags = vec!["admin".to_string()];
+        state.requests = vec![RequestType::Http(request)];
+        let search = |query: &str| {
+            let palette = CommandPalette { query: query.to_string(), ..Default::default() };
+            palette.entries(&state)
+        };
+
+        let entries = search("post users #adm");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, "HTTP");
+        assert_eq!(entries[0].matched, vec![5, 6, 7, 8, 9]);
+
+        let entries = search("send req");
+        assert_eq!(entries[0].item, PaletteItem::Action(Action::SendRequest, KeyScope::Global));
+        assert_eq!(entries[0].keys.as_deref(), Some("Enter"));
+        let copy = search("copy shown").into_iter().next().unwrap();
+        assert_eq!(copy.item, PaletteItem::Action(Action::CopyResponse, KeyScope::Response));
+        assert_eq!(copy.detail, "Response panel");
+
+        assert!(search("").len() > 40);
+        assert!(search("zzzz").is_empty());
+    }
+}
@@ -0,0 +1,692 @@
+use crate::app::actions::Action;
+use crate::app::state::Panel;
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+use std::path::PathBuf;
+
+/// A key together with its modifiers, e.g. `ctrl+f` or `G`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Shift is dropped where the key already carries it: `G` is a shifted `g`, BackTab a shifted Tab
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Parse `ctrl+f`, `alt+]`, `shift+tab`, `G`, `pagedown`, `space`, `f5` and the like
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        // `+` on its own, or as the last key after a modifier (`ctrl++`)
+        let (prefix, key) = match text.strip_suffix("++") {
+            Some(prefix) => (Some(prefix), "+"),
+            None if text == "+" => (None, "+"),
+            None => match text.rsplit_once('+') {
+                Some((prefix, key)) => (Some(prefix), key),
+                None => (None, text),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for name in prefix.into_iter().flat_map(|prefix| prefix.split('+')) {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "option" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("unknown modifier `{}` in `{}`", name, text),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => bail!("unknown key `{}` in `{}`", key, text),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Where a binding applies. Panel bindings are tried before the global ones; edit mode has its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyScope {
+    Global,
+    Collections,
+    Requests,
+    Editor,
+    Response,
+    Editing,
+}
+
+impl KeyScope {
+    pub const ALL: [KeyScope; 6] = [
+        KeyScope::Global,
+        KeyScope::Collections,
+        KeyScope::Requests,
+        KeyScope::Editor,
+        KeyScope::Response,
+        KeyScope::Editing,
+    ];
+
+    /// Table name in `keymap.toml`
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyScope::Global => "global",
+            KeyScope::Collections => "collections",
+            KeyScope::Requests => "requests",
+            KeyScope::Editor => "editor",
+            KeyScope::Response => "response",
+            KeyScope::Editing => "editing",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyScope::Global => "Everywhere",
+            KeyScope::Collections => "Collections panel",
+            KeyScope::Requests => "Requests panel",
+            KeyScope::Editor => "Request editor",
+            KeyScope::Response => "Response panel",
+            KeyScope::Editing => "Edit mode",
+        }
+    }
+
+    /// The panel whose bindings these are, `None` for the global and edit mode ones
+    pub fn panel(&self) -> Option<Panel> {
+        match self {
+            KeyScope::Collections => Some(Panel::Collections),
+            KeyScope::Requests => Some(Panel::Requests),
+            KeyScope::Editor => Some(Panel::RequestEditor),
+            KeyScope::Response => Some(Panel::Response),
+            KeyScope::Global | KeyScope::Editing => None,
+        }
+    }
+
+    pub fn for_panel(panel: Panel) -> Self {
+        match panel {
+            Panel::Collections => KeyScope::Collections,
+            Panel::Requests => KeyScope::Requests,
+            Panel::RequestEditor => KeyScope::Editor,
+            Panel::Response => KeyScope::Response,
+        }
+    }
+}
+
+/// Bindable commands with their name in `keymap.toml` and their help text
+const COMMANDS: &[(Action, &str, &str)] = &[
+    (Action::Quit, "quit", "Quit"),
+    (Action::ToggleHelp, "toggle_help", "Toggle this help"),
+    (Action::OpenCommandPalette, "command_palette", "Command palette: run an action or jump to a request"),
+    (Action::NextPanel, "next_panel", "Next panel"),
+    (Action::PrevPanel, "prev_panel", "Previous panel"),
+    (Action::SendRequest, "send_request", "Send request"),
+    (Action::CancelRequest, "cancel_request", "Cancel in-flight request"),
+    (Action::NewRequest, "new_request", "New HTTP request"),
+    (Action::NewGrpcRequest, "new_grpc_request", "New gRPC request"),
+    (Action::DuplicateRequest, "duplicate_request", "Duplicate request"),
+    (Action::DeleteRequest, "delete_request", "Delete request"),
+    (Action::UndoDelete, "undo_delete", "Undo the last delete"),
+    (Action::OpenTrash, "open_trash", "Trash: restore or purge deleted items"),
+    (Action::NextRequestTab, "next_request_tab", "Next request tab"),
+    (Action::PrevRequestTab, "prev_request_tab", "Previous request tab"),
+    (Action::PinRequestTab, "pin_request_tab", "Keep tab open (pin preview tab)"),
+    (Action::CloseRequestTab, "close_request_tab", "Close request tab"),
+    (Action::OpenExportMenu, "open_export_menu", "Export request (curl/grpcurl)"),
+    (Action::OpenImportMenu, "open_import_menu", "Import a Postman collection"),
+    (Action::OpenProxySettings, "open_proxy_settings", "Proxy settings"),
+    (Action::OpenProtoLoader, "open_proto_loader", "Load proto files (gRPC)"),
+    (Action::GrowPanel, "grow_panel", "Grow the focused panel"),
+    (Action::ShrinkPanel, "shrink_panel", "Shrink the focused panel"),
+    (Action::ToggleZoom, "toggle_zoom", "Zoom the focused panel / back"),
+    (Action::ToggleCollectionsPanel, "toggle_collections", "Hide / show the collections panel"),
+    (Action::ToggleRequestsPanel, "toggle_requests", "Hide / show the requests panel"),
+    (Action::CycleLayout, "cycle_layout", "Layout: auto / side by side / stacked"),
+    (Action::ResetLayout, "reset_layout", "Reset panel sizes and show all panels"),
+    (Action::NextCollection, "next_collection", "Next collection / folder"),
+    (Action::PrevCollection, "prev_collection", "Previous collection / folder"),
+    (Action::ExpandFolder, "expand_folder", "Open collection / folder"),
+    (Action::CollapseFolder, "collapse_folder", "Close collection / folder, or go up"),
+    (Action::NewCollection, "new_collection", "New collection"),
+    (Action::NewFolder, "new_folder", "New folder in the selected collection / folder"),
+    (Action::EditCollection, "edit_collection", "Rename collection / folder"),
+    (Action::EditDefaults, "edit_defaults", "Auth, headers, base URL and variables of collection / folder"),
+    (Action::MoveFolder, "move_folder", "Move folder: pick up, then drop into the selection"),
+    (Action::DeleteCollection, "delete_collection", "Delete collection / folder and its requests"),
+    (Action::DuplicateCollection, "duplicate_collection", "Duplicate collection with its folders and requests"),
+    (Action::MoveCollectionUp, "move_collection_up", "Move collection / folder up"),
+    (Action::MoveCollectionDown, "move_collection_down", "Move collection / folder down"),
+    (Action::NextRequest, "next_request", "Next request"),
+    (Action::PrevRequest, "prev_request", "Previous request"),
+    (Action::FirstRequest, "first_request", "First request"),
+    (Action::LastRequest, "last_request", "Last request"),
+    (Action::PageDownRequests, "page_down_requests", "Page down"),
+    (Action::PageUpRequests, "page_up_requests", "Page up"),
+    (Action::EnterRequestSearch, "search_requests", "Search requests"),
+    (Action::MoveRequestUp, "move_request_up", "Move request up"),
+    (Action::MoveRequestDown, "move_request_down", "Move request down"),
+    (Action::CopyRequest, "copy_request", "Copy request"),
+    (Action::CutRequest, "cut_request", "Cut request, paste moves it"),
+    (Action::PasteRequest, "paste_request", "Paste request into the selected collection / folder"),
+    (Action::EditRequest, "edit_request", "Edit request"),
+    (Action::NextEditorTab, "next_editor_tab", "Next tab (Params/Headers/Body/Auth)"),
+    (Action::ScrollResponseDown, "scroll_response_down", "Scroll down"),
+    (Action::ScrollResponseUp, "scroll_response_up", "Scroll up"),
+    (Action::NextResponseTab, "next_response_tab", "Next tab (Body/Headers/Cookies/Raw/Request)"),
+    (Action::CopyResponse, "copy_response", "Copy the shown text"),
+    (Action::ToggleTreeView, "toggle_tree_view", "Toggle JSON tree view"),
+    (Action::FilterResponse, "filter_response", "Filter with jq/JSONPath"),
+    (Action::SearchResponse, "search_response", "Search the response"),
+    (Action::NextSearchMatch, "next_search_match", "Next search match"),
+    (Action::PrevSearchMatch, "prev_search_match", "Previous search match"),
+    (Action::ViewResponseAs, "view_response_as", "View body as JSON/XML/HTML/YAML/CSV/Form/Text/Hex"),
+    (Action::SaveResponseBody, "save_response_body", "Save response body to exports/"),
+    (Action::PinResponse, "pin_response", "Pin response for comparison"),
+    (Action::CompareResponses, "compare_responses", "Compare two responses"),
+    (Action::FinishEditing, "finish_editing", "Save & exit edit mode"),
+    (Action::NextField, "next_field", "Next field"),
+    (Action::PrevField, "prev_field", "Previous field"),
+    (Action::UndoEdit, "undo", "Undo (body/message)"),
+    (Action::RedoEdit, "redo", "Redo (body/message)"),
+    (Action::FormatBody, "format_body", "Format JSON (body/message)"),
+    (Action::OpenExternalEditor, "open_external_editor", "Open body/message in $VISUAL/$EDITOR"),
+];
+
+/// Actions that only mean something in edit mode; `[editing]` also takes the request tab switches
+const EDITING_ACTIONS: &[Action] = &[
+    Action::FinishEditing,
+    Action::NextField,
+    Action::PrevField,
+    Action::UndoEdit,
+    Action::RedoEdit,
+    Action::FormatBody,
+    Action::OpenExternalEditor,
+];
+
+/// The built-in bindings, in help order
+const DEFAULT_BINDINGS: &[(KeyScope, Action, &[&str])] = &[
+    (KeyScope::Global, Action::Quit, &["q", "ctrl+c"]),
+    (KeyScope::Global, Action::ToggleHelp, &["?"]),
+    (KeyScope::Global, Action::OpenCommandPalette, &[":", "ctrl+p"]),
+    (KeyScope::Global, Action::NextPanel, &["tab"]),
+    (KeyScope::Global, Action::PrevPanel, &["shift+tab"]),
+    (KeyScope::Global, Action::SendRequest, &["enter"]),
+    (KeyScope::Global, Action::CancelRequest, &["esc"]),
+    (KeyScope::Global, Action::NewRequest, &["n"]),
+    (KeyScope::Global, Action::NewGrpcRequest, &["N"]),
+    (KeyScope::Global, Action::DuplicateRequest, &["y"]),
+    (KeyScope::Global, Action::DeleteRequest, &["d"]),
+    (KeyScope::Global, Action::UndoDelete, &["u"]),
+    (KeyScope::Global, Action::OpenTrash, &["X"]),
+    (KeyScope::Global, Action::NextRequestTab, &["]"]),
+    (KeyScope::Global, Action::PrevRequestTab, &["["]),
+    (KeyScope::Global, Action::PinRequestTab, &["T"]),
+    (KeyScope::Global, Action::CloseRequestTab, &["w"]),
+    (KeyScope::Global, Action::OpenExportMenu, &["o"]),
+    (KeyScope::Global, Action::OpenImportMenu, &["i"]),
+    (KeyScope::Global, Action::OpenProxySettings, &["P"]),
+    (KeyScope::Global, Action::OpenProtoLoader, &["l"]),
+    (KeyScope::Global, Action::GrowPanel, &[">"]),
+    (KeyScope::Global, Action::ShrinkPanel, &["<"]),
+    (KeyScope::Global, Action::ToggleZoom, &["z"]),
+    (KeyScope::Global, Action::ToggleCollectionsPanel, &["b"]),
+    (KeyScope::Global, Action::ToggleRequestsPanel, &["B"]),
+    (KeyScope::Global, Action::CycleLayout, &["|"]),
+    (KeyScope::Global, Action::ResetLayout, &["="]),
+    (KeyScope::Collections, Action::NextCollection, &["j", "down"]),
+    (KeyScope::Collections, Action::PrevCollection, &["k", "up"]),
+    (KeyScope::Collections, Action::ExpandFolder, &["right"]),
+    (KeyScope::Collections, Action::CollapseFolder, &["left"]),
+    (KeyScope::Collections, Action::NewCollection, &["c"]),
+    (KeyScope::Collections, Action::NewFolder, &["F"]),
+    (KeyScope::Collections, Action::EditCollection, &["e"]),
+    (KeyScope::Collections, Action::EditDefaults, &["s"]),
+    (KeyScope::Collections, Action::MoveFolder, &["m"]),
+    (KeyScope::Collections, Action::DeleteCollection, &["x"]),
+    (KeyScope::Collections, Action::DuplicateCollection, &["y"]),
+    (KeyScope::Collections, Action::MoveCollectionUp, &["K", "shift+up"]),
+    (KeyScope::Collections, Action::MoveCollectionDown, &["J", "shift+down"]),
+    (KeyScope::Collections, Action::PasteRequest, &["v"]),
+    (KeyScope::Requests, Action::NextRequest, &["j", "down"]),
+    (KeyScope::Requests, Action::PrevRequest, &["k", "up"]),
+    (KeyScope::Requests, Action::FirstRequest, &["g"]),
+    (KeyScope::Requests, Action::LastRequest, &["G"]),
+    (KeyScope::Requests, Action::PageDownRequests, &["ctrl+f"]),
+    (KeyScope::Requests, Action::PageUpRequests, &["ctrl+b"]),
+    (KeyScope::Requests, Action::EnterRequestSearch, &["/"]),
+    (KeyScope::Requests, Action::MoveRequestUp, &["K", "shift+up"]),
+    (KeyScope::Requests, Action::MoveRequestDown, &["J", "shift+down"]),
+    (KeyScope::Requests, Action::CopyRequest, &["c"]),
+    (KeyScope::Requests, Action::CutRequest, &["x"]),
+    (KeyScope::Requests, Action::PasteRequest, &["v"]),
+    (KeyScope::Editor, Action::EditRequest, &["e"]),
+    (KeyScope::Editor, Action::NextEditorTab, &["t"]),
+    (KeyScope::Response, Action::ScrollResponseDown, &["j", "down"]),
+    (KeyScope::Response, Action::ScrollResponseUp, &["k", "up"]),
+    (KeyScope::Response, Action::NextResponseTab, &["t"]),
+    (KeyScope::Response, Action::CopyResponse, &["c"]),
+    (KeyScope::Response, Action::ToggleTreeView, &["v"]),
+    (KeyScope::Response, Action::FilterResponse, &["f"]),
+    (KeyScope::Response, Action::SearchResponse, &["/"]),
+    (KeyScope::Response, Action::NextSearchMatch, &["n"]),
+    (KeyScope::Response, Action::PrevSearchMatch, &["N"]),
+    (KeyScope::Response, Action::ViewResponseAs, &["V"]),
+    (KeyScope::Response, Action::SaveResponseBody, &["S"]),
+    (KeyScope::Response, Action::PinResponse, &["m"]),
+    (KeyScope::Response, Action::CompareResponses, &["D"]),
+    (KeyScope::Editing, Action::FinishEditing, &["esc"]),
+    (KeyScope::Editing, Action::NextField, &["tab"]),
+    (KeyScope::Editing, Action::PrevField, &["shift+tab"]),
+    (KeyScope::Editing, Action::NextRequestTab, &["alt+]"]),
+    (KeyScope::Editing, Action::PrevRequestTab, &["alt+["]),
+    (KeyScope::Editing, Action::UndoEdit, &["ctrl+z"]),
+    (KeyScope::Editing, Action::RedoEdit, &["ctrl+y"]),
+    (KeyScope::Editing, Action::FormatBody, &["ctrl+f"]),
+    (KeyScope::Editing, Action::OpenExternalEditor, &["ctrl+o"]),
+];
+
+/// Changes to the defaults for vim users: h/l between panels and half-page scrolling
+const VIM_BINDINGS: &[(KeyScope, Action, &[&str])] = &[
+    (KeyScope::Global, Action::NextPanel, &["tab", "l"]),
+    (KeyScope::Global, Action::PrevPanel, &["shift+tab", "h"]),
+    (KeyScope::Global, Action::OpenProtoLoader, &["L"]),
+    (KeyScope::Requests, Action::PageDownRequests, &["ctrl+f", "ctrl+d"]),
+    (KeyScope::Requests, Action::PageUpRequests, &["ctrl+b", "ctrl+u"]),
+];
+
+/// Changes to the defaults for emacs users: Ctrl+N/P to move, Ctrl+V/Alt+V to page, Ctrl+G to cancel
+const EMACS_BINDINGS: &[(KeyScope, Action, &[&str])] = &[
+    (KeyScope::Global, Action::CancelRequest, &["ctrl+g", "esc"]),
+    (KeyScope::Global, Action::OpenCommandPalette, &["alt+x", ":"]),
+    (KeyScope::Collections, Action::NextCollection, &["ctrl+n", "down"]),
+    (KeyScope::Collections, Action::PrevCollection, &["ctrl+p", "up"]),
+    (KeyScope::Collections, Action::ExpandFolder, &["ctrl+f", "right"]),
+    (KeyScope::Collections, Action::CollapseFolder, &["ctrl+b", "left"]),
+    (KeyScope::Requests, Action::NextRequest, &["ctrl+n", "down"]),
+    (KeyScope::Requests, Action::PrevRequest, &["ctrl+p", "up"]),
+    (KeyScope::Requests, Action::FirstRequest, &["alt+<", "home"]),
+    (KeyScope::Requests, Action::LastRequest, &["alt+>", "end"]),
+    (KeyScope::Requests, Action::PageDownRequests, &["ctrl+v", "pagedown"]),
+    (KeyScope::Requests, Action::PageUpRequests, &["alt+v", "pageup"]),
+    (KeyScope::Requests, Action::EnterRequestSearch, &["ctrl+s", "/"]),
+    (KeyScope::Response, Action::ScrollResponseDown, &["ctrl+n", "down"]),
+    (KeyScope::Response, Action::ScrollResponseUp, &["ctrl+p", "up"]),
+    (KeyScope::Response, Action::SearchResponse, &["ctrl+s", "/"]),
+    (KeyScope::Editing, Action::FinishEditing, &["esc", "ctrl+g"]),
+    (KeyScope::Editing, Action::UndoEdit, &["ctrl+z", "ctrl+_"]),
+];
+
+/// A starting point for `keymap.toml`, the file's own bindings are applied on top
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeymapPreset {
+    Default,
+    Vim,
+    Emacs,
+}
+
+impl KeymapPreset {
+    fn parse(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "default" => Ok(KeymapPreset::Default),
+            "vim" => Ok(KeymapPreset::Vim),
+            "emacs" => Ok(KeymapPreset::Emacs),
+            _ => bail!("unknown preset `{}`, expected default, vim or emacs", name),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Binding {
+    scope: KeyScope,
+    action: Action,
+    chords: Vec<KeyChord>,
+}
+
+/// Maps key chords to actions, per scope
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(KeymapPreset::Default)
+    }
+}
+
+impl Keymap {
+    pub fn preset(preset: KeymapPreset) -> Self {
+        let mut keymap = Keymap { bindings: Vec::new() };
+        let overrides = match preset {
+            KeymapPreset::Default => &[][..],
+            KeymapPreset::Vim => VIM_BINDINGS,
+            KeymapPreset::Emacs => EMACS_BINDINGS,
+        };
+        for (scope, action, chords) in DEFAULT_BINDINGS.iter().chain(overrides) {
+            let chords = chords
+                .iter()
+                .map(|chord| KeyChord::parse(chord).expect("built-in key chords parse"))
+                .collect();
+            keymap.bind(*scope, *action, chords);
+        }
+        keymap
+    }
+
+    /// `keymap.toml` in the nexus config directory
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("nexus").join("keymap.toml"))
+    }
+
+    /// The keymap from `keymap.toml`, or the default one when there is no such file
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::config_path().filter(|path| path.exists()) else {
+            return Ok(Self::default());
+        };
+        let text = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_toml(&text).with_context(|| format!("Invalid keymap in {}", path.display()))
+    }
+
+    /// A `preset` key picks the starting point, then each `[scope]` table rebinds actions:
+    /// `action = "key"` or `action = ["key", "key"]`, with `[]` to unbind.
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let table: toml::Table = toml::from_str(text)?;
+        let preset = match table.get("preset") {
+            Some(toml::Value::String(name)) => KeymapPreset::parse(name)?,
+            Some(_) => bail!("`preset` must be a string"),
+            None => KeymapPreset::Default,
+        };
+        let mut keymap = Self::preset(preset);
+
+        for (name, value) in &table {
+            if name == "preset" {
+                continue;
+            }
+            let scope = KeyScope::ALL
+                .into_iter()
+                .find(|scope| scope.name() == name)
+                .ok_or_else(|| anyhow!("unknown section [{}]", name))?;
+            let toml::Value::Table(actions) = value else {
+                bail!("[{}] must be a table of action = keys", name);
+            };
+            for (action_name, keys) in actions {
+                let action = COMMANDS
+                    .iter()
+                    .find(|(_, command, _)| command == action_name)
+                    .map(|(action, _, _)| *action)
+                    .ok_or_else(|| anyhow!("[{}] unknown action `{}`", name, action_name))?;
+                let editing_action = EDITING_ACTIONS.contains(&action);
+                if scope == KeyScope::Editing
+                    && !editing_action
+                    && !matches!(action, Action::NextRequestTab | Action::PrevRequestTab)
+                {
+                    bail!("[editing] `{}` is not available in edit mode", action_name);
+                }
+                if scope != KeyScope::Editing && editing_action {
+                    bail!("[{}] `{}` only works in [editing]", name, action_name);
+                }
+                let keys: Vec<&str> = match keys {
+                    toml::Value::String(key) => vec![key.as_str()],
+                    toml::Value::Array(keys) => keys
+                        .iter()
+                        .map(|key| key.as_str().ok_or_else(|| anyhow!("[{}] {}: keys must be strings", name, action_name)))
+                        .collect::<Result<_>>()?,
+                    _ => bail!("[{}] {}: expected a key or a list of keys", name, action_name),
+                };
+                let chords = keys
+                    .into_iter()
+                    .map(KeyChord::parse)
+                    .collect::<Result<_>>()
+                    .with_context(|| format!("[{}] {}", name, action_name))?;
+                keymap.bind(scope, action, chords);
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// Replace the keys of `action` in `scope`. A key bound to another action of the scope moves to this one.
+    pub fn bind(&mut self, scope: KeyScope, action: Action, chords: Vec<KeyChord>) {
+        for binding in self.bindings.iter_mut().filter(|b| b.scope == scope && b.action != action) {
+            binding.chords.retain(|chord| !chords.contains(chord));
+        }
+        match self.bindings.iter_mut().find(|b| b.scope == scope && b.action == action) {
+            Some(binding) => binding.chords = chords,
+            None => self.bindings.push(Binding { scope, action, chords }),
+        }
+    }
+
+    /// Actions bound to the key, those of `scope` first and then the global ones.
+    /// Edit mode does not fall back to the global bindings, as its keys mostly type text.
+    pub fn actions(&self, scope: KeyScope, key: &KeyEvent) -> Vec<Action> {
+        let chord = KeyChord::from_event(key);
+        let lookup = |scope: KeyScope| {
+            self.bindings
+                .iter()
+                .filter(move |b| b.scope == scope && b.chords.contains(&chord))
+                .map(|b| b.action)
+        };
+        let mut actions: Vec<Action> = lookup(scope).collect();
+        if !matches!(scope, KeyScope::Global | KeyScope::Editing) {
+            actions.extend(lookup(KeyScope::Global));
+        }
+        actions
+    }
+
+    pub fn chords(&self, scope: KeyScope, action: Action) -> &[KeyChord] {
+        self.bindings
+            .iter()
+            .find(|b| b.scope == scope && b.action == action)
+            .map_or(&[], |b| b.chords.as_slice())
+    }
+
+    /// The keys of an action joined for display, e.g. `j / ↓`; `None` when it is unbound
+    pub fn keys_label(&self, scope: KeyScope, action: Action) -> Option<String> {
+        let chords = self.chords(scope, action);
+        if chords.is_empty() {
+            return None;
+        }
+        Some(chords.iter().map(|chord| chord.to_string()).collect::<Vec<_>>().join(" / "))
+    }
+
+    /// The first key of the action for short hints, `None` when it is unbound
+    pub fn first_key(&self, scope: KeyScope, action: Action) -> Option<String> {
+        self.chords(scope, action).first().map(|chord| chord.to_string())
+    }
+
+    /// `key: label` for each action, leaving out unbound ones
+    pub fn hints(&self, scope: KeyScope, hints: &[(Action, &str)]) -> Vec<String> {
+        hints
+            .iter()
+            .filter_map(|(action, label)| Some(format!("{}: {}", self.first_key(scope, *action)?, label)))
+            .collect()
+    }
+
+    /// Hints for a panel title, ` [key: label, ...]` or nothing when no action is bound
+    pub fn title_hints(&self, scope: KeyScope, hints: &[(Action, &str)]) -> String {
+        let hints = self.hints(scope, hints);
+        if hints.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", hints.join(", "))
+        }
+    }
+
+    /// The scope an action is used from: where it has keys, or where it was bound before being unbound
+    pub fn home_scope(&self, action: Action) -> KeyScope {
+        let bindings = || self.bindings.iter().filter(|b| b.action == action);
+        bindings()
+            .find(|b| !b.chords.is_empty())
+            .or_else(|| bindings().next())
+            .map_or(KeyScope::Global, |b| b.scope)
+    }
+
+    /// (keys, description) of every bound action of the scope, in help order
+    pub fn help_entries(&self, scope: KeyScope) -> Vec<(String, &'static str)> {
+        self.bindings
+            .iter()
+            .filter(|b| b.scope == scope)
+            .filter_map(|b| {
+                let keys = self.keys_label(scope, b.action)?;
+                Some((keys, describe(b.action)))
+            })
+            .collect()
+    }
+}
+
+/// Actions offered by the command palette with their description, in help order.
+/// Edit mode actions are left out, the palette only opens in normal mode.
+pub fn palette_actions() -> impl Iterator<Item = (Action, &'static str)> {
+    COMMANDS
+        .iter()
+        .filter(|(action, _, _)| !EDITING_ACTIONS.contains(action) && *action != Action::OpenCommandPalette)
+        .map(|(action, _, description)| (*action, *description))
+}
+
+fn describe(action: Action) -> &'static str {
+    COMMANDS
+        .iter()
+        .find(|(command, _, _)| *command == action)
+        .map_or("", |(_, _, description)| description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn chords_parse_and_display() {
+        assert_eq!(KeyChord::parse("ctrl+f").unwrap(), KeyChord::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+        assert_eq!(KeyChord::parse("shift+g").unwrap(), KeyChord::parse("G").unwrap());
+        assert_eq!(KeyChord::parse("Shift+Tab").unwrap().code, KeyCode::BackTab);
+        assert_eq!(KeyChord::parse("ctrl++").unwrap().code, KeyCode::Char('+'));
+        assert_eq!(KeyChord::parse("alt+]").unwrap().to_string(), "Alt+]");
+        assert_eq!(KeyChord::parse("pagedown").unwrap().to_string(), "PgDn");
+        assert!(KeyChord::parse("hyper+x").is_err());
+        assert!(KeyChord::parse("f13").is_err());
+
+        // crossterm reports `G` with Shift and BackTab with Shift, both match the plain chord
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.actions(KeyScope::Requests, &press(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            vec![Action::LastRequest]
+        );
+        assert_eq!(
+            keymap.actions(KeyScope::Response, &press(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            vec![Action::PrevPanel]
+        );
+    }
+
+    #[test]
+    fn panel_bindings_come_before_global_ones() {
+        let keymap = Keymap::default();
+        let n = press(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(keymap.actions(KeyScope::Response, &n), vec![Action::NextSearchMatch, Action::NewRequest]);
+        assert_eq!(keymap.actions(KeyScope::Requests, &n), vec![Action::NewRequest]);
+        assert!(keymap.actions(KeyScope::Editing, &n).is_empty());
+    }
+
+    #[test]
+    fn config_rebinds_on_top_of_a_preset() {
+        let keymap = Keymap::from_toml(
+            r#"
+            preset = "vim"
+
+            [global]
+            quit = "ctrl+q"
+            new_grpc_request = []
+
+            [response]
+            copy_response = ["y", "c"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(keymap.keys_label(KeyScope::Global, Action::Quit).as_deref(), Some("Ctrl+q"));
+        assert_eq!(keymap.keys_label(KeyScope::Global, Action::NewGrpcRequest), None);
+        assert_eq!(keymap.keys_label(KeyScope::Global, Action::NextPanel).as_deref(), Some("Tab / l"));
+        // `l` moved from the proto loader to the panel switch
+        assert_eq!(keymap.keys_label(KeyScope::Global, Action::OpenProtoLoader).as_deref(), Some("L"));
+        // The panel binding wins, the global `y` still duplicates elsewhere
+        assert_eq!(
+            keymap.actions(KeyScope::Response, &press(KeyCode::Char('y'), KeyModifiers::NONE)),
+            vec![Action::CopyResponse, Action::DuplicateRequest]
+        );
+
+        let error = Keymap::from_toml("[response]\ncopy = \"y\"").unwrap_err();
+        assert_eq!(error.to_string(), "[response] unknown action `copy`");
+        assert!(Keymap::from_toml("preset = \"nano\"").is_err());
+        assert!(Keymap::from_toml("[global]\nquit = \"ctrl+\"").is_err());
+        assert!(Keymap::from_toml("[global]\nundo = \"u\"").is_err());
+        assert!(Keymap::from_toml("[editing]\nquit = \"ctrl+q\"").is_err());
+    }
+}
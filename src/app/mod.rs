@@ -1,3 +1,5 @@
 pub mod state;
 pub mod actions;
-
+pub mod text_editor;
+pub mod keymap;
+pub mod command_palette;
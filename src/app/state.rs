@@ -1,11 +1,47 @@
 use crate::models::{
+    auth::{AuthFieldKind, AuthKind},
     collection::Collection,
+    cookie::parse_set_cookie,
+    defaults::{Inherited, RequestDefaults},
+    folder::{self, Folder},
+    json_tree::JsonTree,
+    layout::PanelLayout,
+    oauth2::OAuth2Token,
+    protocol::RequestType,
+    proxy::{ProxyScheme, ProxySettings},
     request::HttpRequest,
     response::HttpResponse,
+    trash::{self, TrashEntry, TrashKind},
     GrpcRequest,
     GrpcResponse,
     ProtoSchema,
 };
+use crate::app::actions::Action;
+use crate::app::command_palette::CommandPalette;
+use crate::app::keymap::{KeyScope, Keymap};
+use crate::app::text_editor::TextEditor;
+use crate::config::Config;
+use crate::utils::{
+    body_format::BodyFormat,
+    diff::{self, ReportLine},
+    text_search::{self, TextMatch},
+};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::task::AbortHandle;
+use uuid::Uuid;
+
+pub use crate::models::protocol::ProtocolType;
+
+/// How long typing must pause before the filter is evaluated
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// A filter evaluation that is due to run in the background
+pub struct FilterJob {
+    pub generation: u64,
+    pub expression: String,
+    pub inputs: Vec<serde_json::Value>,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ExportMode {
@@ -28,6 +64,15 @@ pub enum Panel {
     Response,
 }
 
+impl Panel {
+    /// In screen order, left to right
+    pub const ALL: [Panel; 4] = [Panel::Collections, Panel::Requests, Panel::RequestEditor, Panel::Response];
+
+    pub fn index(self) -> usize {
+        Panel::ALL.iter().position(|panel| *panel == self).unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EditorTab {
     Params,
@@ -36,6 +81,66 @@ pub enum EditorTab {
     Auth,
 }
 
+impl EditorTab {
+    pub const ALL: [EditorTab; 4] = [EditorTab::Params, EditorTab::Headers, EditorTab::Body, EditorTab::Auth];
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|tab| tab == self).unwrap_or(0)
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            EditorTab::Params => "Params",
+            EditorTab::Headers => "Headers",
+            EditorTab::Body => "Body",
+            EditorTab::Auth => "Auth",
+        }
+    }
+
+    /// The field edited on this tab
+    pub fn field(&self) -> EditorField {
+        match self {
+            EditorTab::Params => EditorField::Params,
+            EditorTab::Headers => EditorField::Headers,
+            EditorTab::Body => EditorField::Body,
+            EditorTab::Auth => EditorField::Auth,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseTab {
+    Body,
+    Headers,
+    Cookies,
+    Raw,
+    Request,
+}
+
+impl ResponseTab {
+    pub const ALL: [ResponseTab; 5] = [
+        ResponseTab::Body,
+        ResponseTab::Headers,
+        ResponseTab::Cookies,
+        ResponseTab::Raw,
+        ResponseTab::Request,
+    ];
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|tab| tab == self).unwrap_or(0)
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ResponseTab::Body => "Body",
+            ResponseTab::Headers => "Headers",
+            ResponseTab::Cookies => "Cookies",
+            ResponseTab::Raw => "Raw",
+            ResponseTab::Request => "Request",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InputMode {
     Normal,
@@ -70,31 +175,316 @@ pub enum KeyValueEditMode {
     Value,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-#[allow(dead_code)]
-pub enum ProtocolType {
-    Http,
-    Grpc,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ProtoLoaderFocus {
     Input,
     SchemaList,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProxyField {
+    Scope,
+    Inherit,
+    Enabled,
+    Scheme,
+    Host,
+    Port,
+    Username,
+    Password,
+    NoProxy,
+    UseSystemEnv,
+}
+
+impl ProxyField {
+    pub const ALL: [ProxyField; 10] = [
+        ProxyField::Scope,
+        ProxyField::Inherit,
+        ProxyField::Enabled,
+        ProxyField::Scheme,
+        ProxyField::Host,
+        ProxyField::Port,
+        ProxyField::Username,
+        ProxyField::Password,
+        ProxyField::NoProxy,
+        ProxyField::UseSystemEnv,
+    ];
+}
+
+/// Which proxy settings the proxy popup is editing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProxyScope {
+    Global,
+    Collection(Uuid),
+}
+
+/// Input buffers of the proxy settings popup
+#[derive(Debug, Clone)]
+pub struct ProxyForm {
+    pub scope: ProxyScope,
+    /// Collection scope only: use the global settings instead of an override
+    pub inherit: bool,
+    pub enabled: bool,
+    pub scheme: ProxyScheme,
+    pub host: String,
+    pub port: String,
+    pub username: String,
+    pub password: String,
+    pub no_proxy: String,
+    pub use_system_env: bool,
+    pub focused_field: ProxyField,
+    pub cursor: usize,
+    pub error: Option<String>,
+}
+
+impl ProxyForm {
+    fn new(scope: ProxyScope, inherit: bool, settings: &ProxySettings) -> Self {
+        Self {
+            scope,
+            inherit,
+            enabled: settings.enabled,
+            scheme: settings.scheme,
+            host: settings.host.clone(),
+            port: settings.port.to_string(),
+            username: settings.username.clone(),
+            password: settings.password.clone(),
+            no_proxy: settings.no_proxy.join(", "),
+            use_system_env: settings.use_system_env,
+            focused_field: ProxyField::Scope,
+            cursor: 0,
+            error: None,
+        }
+    }
+
+    /// Whether a field is shown for the current scope
+    pub fn is_field_visible(&self, field: ProxyField) -> bool {
+        match field {
+            ProxyField::Scope => true,
+            ProxyField::Inherit => matches!(self.scope, ProxyScope::Collection(_)),
+            _ => !self.inherit,
+        }
+    }
+
+    /// The text buffer of the focused field, if it is a text field
+    pub fn focused_text_mut(&mut self) -> Option<&mut String> {
+        match self.focused_field {
+            ProxyField::Host => Some(&mut self.host),
+            ProxyField::Port => Some(&mut self.port),
+            ProxyField::Username => Some(&mut self.username),
+            ProxyField::Password => Some(&mut self.password),
+            ProxyField::NoProxy => Some(&mut self.no_proxy),
+            _ => None,
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.move_focus(1);
+    }
+
+    pub fn prev_field(&mut self) {
+        self.move_focus(ProxyField::ALL.len() - 1);
+    }
+
+    fn move_focus(&mut self, step: usize) {
+        let mut idx = ProxyField::ALL.iter().position(|f| *f == self.focused_field).unwrap_or(0);
+        loop {
+            idx = (idx + step) % ProxyField::ALL.len();
+            if self.is_field_visible(ProxyField::ALL[idx]) {
+                break;
+            }
+        }
+        self.focused_field = ProxyField::ALL[idx];
+        self.cursor = self.focused_text_mut().map(|text| text.len()).unwrap_or(0);
+    }
+
+    /// Validate the form. `None` for a collection that inherits the global settings.
+    pub fn to_settings(&self) -> Result<Option<ProxySettings>, String> {
+        if self.inherit {
+            return Ok(None);
+        }
+        let port = self.port.trim().parse::<u16>()
+            .map_err(|_| format!("Invalid port: '{}'", self.port.trim()))?;
+        if self.enabled && self.host.trim().is_empty() {
+            return Err("Proxy host cannot be empty when the proxy is enabled".to_string());
+        }
+        if self.host.contains("://") {
+            return Err("Enter the proxy host without a scheme, e.g. proxy.corp.example.com".to_string());
+        }
+        Ok(Some(ProxySettings {
+            enabled: self.enabled,
+            scheme: self.scheme,
+            host: self.host.trim().to_string(),
+            port,
+            username: self.username.clone(),
+            password: self.password.clone(),
+            no_proxy: ProxySettings::parse_no_proxy(&self.no_proxy),
+            use_system_env: self.use_system_env,
+        }))
+    }
+}
+
+/// A response kept for comparison, with the name of the request it answered
+#[derive(Debug, Clone)]
+pub struct ResponseSnapshot {
+    pub request_name: String,
+    pub response: HttpResponse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffStage {
+    SelectingResponses,
+    ShowingDiff,
+}
+
+/// A request that has been dispatched to a background task and has not completed yet
+#[derive(Debug)]
+pub struct InFlightRequest {
+    pub dispatch_id: Uuid,
+    pub tab_id: Uuid,
+    pub request_id: Uuid,
+    pub protocol: ProtocolType,
+    pub started_at: Instant,
+    pub abort_handle: AbortHandle,
+}
+
+/// A request copied or cut in the requests panel, to be pasted into a collection or folder
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestClipboard {
+    Copy(Uuid),
+    /// Pasting moves the request
+    Cut(Uuid),
+}
+
+/// A destructive action that runs once the user confirms it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfirmAction {
+    /// The selected collection or folder with everything in it
+    DeleteNode,
+    PurgeTrashEntry(Uuid),
+    EmptyTrash,
+}
+
+/// The question shown in the confirmation popup
+#[derive(Debug, Clone, PartialEq)]
+pub struct Confirmation {
+    pub action: ConfirmAction,
+    pub message: String,
+}
+
+/// A row of the collections tree: a collection, or one of its folders when `folder` is set
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TreeRow {
+    /// Index into the collections
+    pub collection: usize,
+    pub folder: Option<Uuid>,
+    pub depth: usize,
+    pub has_children: bool,
+    pub expanded: bool,
+}
+
+/// The request a tab points at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TabTarget {
+    pub protocol: ProtocolType,
+    pub request_id: Option<Uuid>,
+}
+
+/// Snapshot of the editor input buffers, kept for tabs with unsaved edits
+#[derive(Debug, Clone)]
+pub struct EditorDraft {
+    pub editor_focused_field: EditorField,
+    pub grpc_editor_focused_field: GrpcEditorField,
+    pub kv_edit_mode: KeyValueEditMode,
+    pub name_input: String,
+    pub name_cursor: usize,
+    pub method_input: usize,
+    pub url_input: String,
+    pub url_cursor: usize,
+    pub params_input: Vec<(String, String)>,
+    pub params_selected: usize,
+    pub headers_input: Vec<(String, String)>,
+    pub headers_selected: usize,
+    pub body_editor: TextEditor,
+    pub auth_kind_input: AuthKind,
+    pub auth_values_input: Vec<String>,
+    pub auth_selected: usize,
+    pub auth_cursor: usize,
+    pub grpc_name_input: String,
+    pub grpc_name_cursor: usize,
+    pub grpc_server_url_input: String,
+    pub grpc_server_url_cursor: usize,
+    pub grpc_service_name_input: String,
+    pub grpc_service_name_cursor: usize,
+    pub grpc_method_name_input: String,
+    pub grpc_method_name_cursor: usize,
+    pub grpc_message_editor: TextEditor,
+    pub grpc_metadata_input: Vec<(String, String)>,
+    pub grpc_metadata_selected: usize,
+}
+
+/// An open request tab.
+///
+/// The active tab's response, scroll position and edit buffers live directly on
+/// `AppState`; they are stashed here while the tab is in the background.
+#[derive(Debug, Clone)]
+pub struct RequestTab {
+    pub id: Uuid,
+    pub target: TabTarget,
+    /// Preview tabs are replaced when another request is selected
+    pub preview: bool,
+    pub current_response: Option<HttpResponse>,
+    pub grpc_response: Option<GrpcResponse>,
+    pub response_scroll: u16,
+    pub loading_message: String,
+    pub draft: Option<EditorDraft>,
+}
+
+impl RequestTab {
+    fn preview(target: TabTarget) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            target,
+            preview: true,
+            current_response: None,
+            grpc_response: None,
+            response_scroll: 0,
+            loading_message: String::new(),
+            draft: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct AppState {
     pub collections: Vec<Collection>,
-    pub requests: Vec<HttpRequest>,
+    /// HTTP and gRPC requests of every collection, in list order
+    pub requests: Vec<RequestType>,
     pub current_response: Option<HttpResponse>,
     
     pub selected_collection: Option<usize>,
     pub selected_request: Option<usize>,
+
+    // Folders
+    pub folders: Vec<Folder>,
+    /// Folder of each request that is not at the top of its collection, by request id
+    pub request_folders: HashMap<Uuid, Uuid>,
+    /// Folder selected in the collections tree, `None` for the top of the selected collection
+    pub selected_folder: Option<Uuid>,
+    /// Collections and folders opened in the collections tree
+    pub expanded_nodes: HashSet<Uuid>,
+    /// Folder picked up to be moved, it goes into the collection or folder selected next
+    pub moving_folder: Option<Uuid>,
+    pub request_clipboard: Option<RequestClipboard>,
     
     pub focused_panel: Panel,
+    /// Panel sizes, hidden sidebars and arrangement, saved between sessions
+    pub panel_layout: PanelLayout,
+    /// The focused panel fills the screen
+    pub zoomed: bool,
     pub editor_tab: EditorTab,
     pub show_help: bool,
+    pub help_scroll: u16,
+    /// Key bindings, from `keymap.toml` when there is one
+    pub keymap: Keymap,
     pub show_welcome: bool,
     pub show_export_menu: bool,
     pub export_mode: Option<ExportMode>,
@@ -110,6 +500,24 @@ pub struct AppState {
     
     pub is_loading: bool,
     pub loading_message: String,
+    pub in_flight: Vec<InFlightRequest>,
+
+    // Response comparison
+    /// HTTP responses received this session, oldest first
+    pub response_history: Vec<ResponseSnapshot>,
+    pub pinned_response: Option<ResponseSnapshot>,
+    pub show_diff_popup: bool,
+    pub diff_stage: DiffStage,
+    pub diff_cursor: usize,
+    /// Response ids of the compared sides: A is the baseline, B the one compared to it
+    pub diff_a: Option<Uuid>,
+    pub diff_b: Option<Uuid>,
+    pub diff_report: Vec<ReportLine>,
+    pub diff_scroll: u16,
+
+    // Open request tabs
+    pub tabs: Vec<RequestTab>,
+    pub active_tab: usize,
     
     pub editor_focused_field: EditorField,
     pub kv_edit_mode: KeyValueEditMode,
@@ -129,14 +537,62 @@ pub struct AppState {
     pub params_selected: usize,
     pub headers_input: Vec<(String, String)>,
     pub headers_selected: usize,
-    pub body_input: String,
-    pub body_cursor: usize,
-    pub auth_input: String,
+    pub body_editor: TextEditor,
+    pub auth_kind_input: AuthKind,
+    pub auth_values_input: Vec<String>,
+    pub auth_selected: usize,
     pub auth_cursor: usize,
     
     // Response viewer scroll state
     pub response_scroll: u16,
 
+    // Response tabs; `response_scroll` belongs to the active one, the others are kept here
+    pub response_tab: ResponseTab,
+    pub response_tab_scrolls: [u16; ResponseTab::ALL.len()],
+
+    // Foldable JSON tree view of the response
+    pub response_tree_mode: bool,
+    pub json_tree: Option<JsonTree>,
+    /// Id of the response the tree was built from
+    pub json_tree_source: Option<Uuid>,
+
+    // Response filter (jq or JSONPath)
+    pub response_filter_mode: bool,
+    pub response_filter_input: String,
+    pub response_filter_cursor: usize,
+    /// Outcome of the filter for the displayed response, `None` without a filter
+    pub response_filter_result: Option<Result<Vec<serde_json::Value>, String>>,
+    /// Request the filter was entered for; it is dropped when another request is shown
+    pub response_filter_request: Option<Uuid>,
+    /// Most recent first, per request id
+    pub recent_filters: HashMap<Uuid, Vec<String>>,
+    pub recent_filter_index: Option<usize>,
+    /// Outcome of the last one-off action, e.g. where a body was saved. Cleared by the next key.
+    pub status_message: Option<String>,
+    /// "View as" overrides of the detected body format, per request id
+    pub response_view_as: HashMap<Uuid, BodyFormat>,
+    /// Parsed JSON documents of the displayed response, the filter input
+    pub response_json: Option<Vec<serde_json::Value>>,
+    /// Text view of the displayed response or filter output, rebuilt when either changes
+    pub response_text: Option<String>,
+    /// Bumped on every filter change so results of older evaluations are dropped
+    pub response_filter_generation: u64,
+    /// When the debounced filter should be evaluated
+    pub response_filter_due: Option<Instant>,
+    pub response_filter_task: Option<AbortHandle>,
+
+    // Incremental search in the response body
+    pub response_search_mode: bool,
+    pub response_search_input: String,
+    pub response_search_cursor: usize,
+    pub response_search_case_sensitive: bool,
+    pub response_search_regex: bool,
+    pub response_search_matches: Vec<TextMatch>,
+    pub response_search_current: usize,
+    pub response_search_error: Option<String>,
+    /// Set when the viewer should scroll to the current match on the next draw
+    pub response_search_jump: bool,
+
     // Request list scroll state
     pub request_list_scroll: u16,
 
@@ -148,9 +604,6 @@ pub struct AppState {
 
     // gRPC support
     #[allow(dead_code)]
-    pub protocol_type: ProtocolType,
-    pub grpc_requests: Vec<GrpcRequest>,
-    #[allow(dead_code)]
     pub grpc_response: Option<GrpcResponse>,
     pub proto_schemas: Vec<ProtoSchema>,
 
@@ -164,8 +617,7 @@ pub struct AppState {
     pub grpc_service_name_cursor: usize,
     pub grpc_method_name_input: String,
     pub grpc_method_name_cursor: usize,
-    pub grpc_message_input: String,
-    pub grpc_message_cursor: usize,
+    pub grpc_message_editor: TextEditor,
     pub grpc_metadata_input: Vec<(String, String)>,
     pub grpc_metadata_selected: usize,
 
@@ -177,6 +629,36 @@ pub struct AppState {
     pub selected_proto_schema: Option<usize>, // Index into proto_schemas
     pub proto_loader_focus: ProtoLoaderFocus,
 
+    // OAuth2 access tokens by request id
+    pub oauth2_tokens: HashMap<Uuid, OAuth2Token>,
+
+    // Proxy settings
+    pub proxy_settings: ProxySettings,
+    pub collection_proxies: HashMap<Uuid, ProxySettings>,
+    pub show_proxy_settings: bool,
+    pub proxy_form: ProxyForm,
+
+    // Auth, headers, base URL and variables of collections and folders, by their id
+    pub node_defaults: HashMap<Uuid, RequestDefaults>,
+    pub show_defaults_editor: bool,
+    /// Collection or folder whose defaults are being edited
+    pub defaults_target: Option<Uuid>,
+    pub defaults_editor: TextEditor,
+    pub defaults_error: Option<String>,
+
+    // Command palette
+    pub show_command_palette: bool,
+    pub command_palette: CommandPalette,
+
+    // Deleted collections, folders, requests and proto schemas, most recent first
+    pub trash: Vec<TrashEntry>,
+    /// Trash entries deleted this session, undo restores the last one
+    pub undo_stack: Vec<Uuid>,
+    pub show_trash: bool,
+    pub selected_trash: usize,
+    /// Destructive action waiting for a yes
+    pub confirmation: Option<Confirmation>,
+
     pub should_quit: bool,
 }
 
@@ -189,10 +671,21 @@ impl AppState {
             
             selected_collection: None,
             selected_request: None,
+
+            folders: Vec::new(),
+            request_folders: HashMap::new(),
+            selected_folder: None,
+            expanded_nodes: HashSet::new(),
+            moving_folder: None,
+            request_clipboard: None,
             
             focused_panel: Panel::Collections,
+            panel_layout: PanelLayout::default(),
+            zoomed: false,
             editor_tab: EditorTab::Params,
             show_help: false,
+            help_scroll: 0,
+            keymap: Keymap::default(),
             show_welcome: true,
             show_export_menu: false,
             export_mode: None,
@@ -208,6 +701,19 @@ impl AppState {
             
             is_loading: false,
             loading_message: String::new(),
+            in_flight: Vec::new(),
+            response_history: Vec::new(),
+            pinned_response: None,
+            show_diff_popup: false,
+            diff_stage: DiffStage::SelectingResponses,
+            diff_cursor: 0,
+            diff_a: None,
+            diff_b: None,
+            diff_report: Vec::new(),
+            diff_scroll: 0,
+
+            tabs: Vec::new(),
+            active_tab: 0,
             
             editor_focused_field: EditorField::Url,
             kv_edit_mode: KeyValueEditMode::None,
@@ -225,12 +731,43 @@ impl AppState {
             params_selected: 0,
             headers_input: Vec::new(),
             headers_selected: 0,
-            body_input: String::new(),
-            body_cursor: 0,
-            auth_input: String::new(),
+            body_editor: TextEditor::default(),
+            auth_kind_input: AuthKind::None,
+            auth_values_input: Vec::new(),
+            auth_selected: 0,
             auth_cursor: 0,
 
             response_scroll: 0,
+            response_tab: ResponseTab::Body,
+            response_tab_scrolls: [0; ResponseTab::ALL.len()],
+            response_tree_mode: false,
+            json_tree: None,
+            json_tree_source: None,
+
+            response_filter_mode: false,
+            response_filter_input: String::new(),
+            response_filter_cursor: 0,
+            response_filter_result: None,
+            response_filter_request: None,
+            status_message: None,
+            response_view_as: HashMap::new(),
+            response_json: None,
+            response_text: None,
+            response_filter_generation: 0,
+            response_filter_due: None,
+            response_filter_task: None,
+            recent_filters: HashMap::new(),
+            recent_filter_index: None,
+
+            response_search_mode: false,
+            response_search_input: String::new(),
+            response_search_cursor: 0,
+            response_search_case_sensitive: false,
+            response_search_regex: false,
+            response_search_matches: Vec::new(),
+            response_search_current: 0,
+            response_search_error: None,
+            response_search_jump: false,
 
             // Request list scroll state
             request_list_scroll: 0,
@@ -242,8 +779,6 @@ impl AppState {
             filtered_request_indices: Vec::new(),
 
             // gRPC support
-            protocol_type: ProtocolType::Http,
-            grpc_requests: Vec::new(),
             grpc_response: None,
             proto_schemas: Vec::new(),
 
@@ -257,8 +792,7 @@ impl AppState {
             grpc_service_name_cursor: 0,
             grpc_method_name_input: String::new(),
             grpc_method_name_cursor: 0,
-            grpc_message_input: String::new(),
-            grpc_message_cursor: 0,
+            grpc_message_editor: TextEditor::default(),
             grpc_metadata_input: Vec::new(),
             grpc_metadata_selected: 0,
 
@@ -270,16 +804,44 @@ impl AppState {
             selected_proto_schema: None,
             proto_loader_focus: ProtoLoaderFocus::Input,
 
+            oauth2_tokens: HashMap::new(),
+
+            // Proxy settings
+            proxy_settings: ProxySettings::default(),
+            collection_proxies: HashMap::new(),
+            show_proxy_settings: false,
+            proxy_form: ProxyForm::new(ProxyScope::Global, false, &ProxySettings::default()),
+            node_defaults: HashMap::new(),
+            show_defaults_editor: false,
+            defaults_target: None,
+            defaults_editor: TextEditor::default(),
+            defaults_error: None,
+            show_command_palette: false,
+            command_palette: CommandPalette::default(),
+
+            trash: Vec::new(),
+            undo_stack: Vec::new(),
+            show_trash: false,
+            selected_trash: 0,
+            confirmation: None,
+
             should_quit: false,
         }
     }
     
+    /// The protocol of the selected request, which decides the editor and response view
+    pub fn protocol_type(&self) -> ProtocolType {
+        self.selected_request
+            .and_then(|idx| self.requests.get(idx))
+            .map_or(ProtocolType::Http, RequestType::protocol)
+    }
+
     pub fn get_current_request(&self) -> Option<&HttpRequest> {
-        self.selected_request.and_then(|idx| self.requests.get(idx))
+        self.selected_request.and_then(|idx| self.requests.get(idx)).and_then(RequestType::as_http)
     }
     
     pub fn get_current_request_mut(&mut self) -> Option<&mut HttpRequest> {
-        self.selected_request.and_then(|idx| self.requests.get_mut(idx))
+        self.selected_request.and_then(|idx| self.requests.get_mut(idx)).and_then(RequestType::as_http_mut)
     }
     
     pub fn load_current_request_to_input(&mut self) {
@@ -294,10 +856,8 @@ impl AppState {
                 .map(|(k, v)| (k.clone(), v.clone()))
                 .collect();
             let body = request.body.clone().unwrap_or_default();
-            let auth = match &request.auth {
-                crate::models::request::AuthType::Bearer { token } => token.clone(),
-                _ => String::new(),
-            };
+            let auth_kind = request.auth.kind();
+            let auth_values = request.auth.to_field_values();
             
             self.name_input = name;
             self.name_cursor = self.name_input.len();
@@ -316,61 +876,217 @@ impl AppState {
             self.headers_input = headers;
             self.headers_selected = 0;
             
-            self.body_input = body;
-            self.body_cursor = self.body_input.len();
+            self.body_editor = TextEditor::new(&body);
             
-            self.auth_input = auth;
-            self.auth_cursor = self.auth_input.len();
+            self.auth_kind_input = auth_kind;
+            self.auth_values_input = auth_values;
+            self.auth_selected = 0;
+            self.auth_cursor = 0;
         }
     }
     
     pub fn save_input_to_request(&mut self) {
-        // Clone all the input values first to avoid borrow checker issues
-        let name = self.name_input.trim().to_string();
-        let method_idx = self.method_input;
-        let url = self.url_input.clone();
-        let params = self.params_input.clone();
-        let headers = self.headers_input.clone();
-        let body = self.body_input.clone();
-        let auth = self.auth_input.clone();
-        
+        let draft = self.capture_draft();
         if let Some(request) = self.get_current_request_mut() {
-            if !name.is_empty() {
-                request.name = name;
+            apply_http_draft(request, &draft);
+        }
+    }
+
+    // Auth tab form helpers
+
+    /// Field indices shown in the Auth tab. Row 0 is the auth type, row `n` is `fields[n - 1]`.
+    pub fn auth_visible_fields(&self) -> Vec<usize> {
+        self.auth_kind_input.visible_fields(&self.auth_values_input)
+    }
+
+    /// The body or gRPC message editor, when it has focus in edit mode
+    pub fn focused_text_editor(&mut self) -> Option<&mut TextEditor> {
+        if self.show_defaults_editor {
+            return Some(&mut self.defaults_editor);
+        }
+        if self.input_mode != InputMode::Editing {
+            return None;
+        }
+        match self.protocol_type() {
+            ProtocolType::Http if self.editor_focused_field == EditorField::Body => Some(&mut self.body_editor),
+            ProtocolType::Grpc if self.grpc_editor_focused_field == GrpcEditorField::Message => {
+                Some(&mut self.grpc_message_editor)
             }
-            
-            if let Some(method) = crate::models::request::HttpMethod::all().get(method_idx) {
-                request.method = method.clone();
+            _ => None,
+        }
+    }
+
+    /// File extension of the text in `focused_text_editor`, so external editors pick the syntax
+    pub fn focused_text_extension(&self) -> &'static str {
+        match self.protocol_type() {
+            ProtocolType::Grpc => "json",
+            ProtocolType::Http => match self.headers_input.iter().find(|(key, _)| key.eq_ignore_ascii_case("content-type")) {
+                Some((_, value)) => BodyFormat::from_content_type(value).extension(),
+                None if self.is_body_input_json() => "json",
+                None => "txt",
+            },
+        }
+    }
+
+    /// Whether a param, header or metadata key or value is being typed, which owns Esc and Tab
+    pub fn is_editing_key_value(&self) -> bool {
+        if self.input_mode != InputMode::Editing || self.kv_edit_mode == KeyValueEditMode::None {
+            return false;
+        }
+        match self.protocol_type() {
+            ProtocolType::Http => matches!(self.editor_focused_field, EditorField::Params | EditorField::Headers),
+            ProtocolType::Grpc => self.grpc_editor_focused_field == GrpcEditorField::Metadata,
+        }
+    }
+
+    /// Move edit mode to the next field, the HTTP editor tab follows the field
+    pub fn next_editor_field(&mut self) {
+        self.kv_edit_mode = KeyValueEditMode::None;
+        match self.protocol_type() {
+            ProtocolType::Http => {
+                self.editor_focused_field = match self.editor_focused_field {
+                    EditorField::Name => EditorField::Method,
+                    EditorField::Method => EditorField::Url,
+                    EditorField::Url => EditorField::Params,
+                    EditorField::Params => EditorField::Headers,
+                    EditorField::Headers => EditorField::Body,
+                    EditorField::Body => EditorField::Auth,
+                    EditorField::Auth => EditorField::Name,
+                };
+                self.sync_editor_tab_with_field();
             }
-            
-            request.url = url;
-            
-            request.query_params.clear();
-            for (key, value) in &params {
-                if !key.is_empty() {
-                    request.query_params.insert(key.clone(), value.clone());
-                }
+            ProtocolType::Grpc => {
+                self.grpc_editor_focused_field = match self.grpc_editor_focused_field {
+                    GrpcEditorField::Name => GrpcEditorField::ServerUrl,
+                    GrpcEditorField::ServerUrl => GrpcEditorField::ServiceName,
+                    GrpcEditorField::ServiceName => GrpcEditorField::MethodName,
+                    GrpcEditorField::MethodName => GrpcEditorField::Message,
+                    GrpcEditorField::Message => GrpcEditorField::Metadata,
+                    GrpcEditorField::Metadata => GrpcEditorField::Name,
+                };
             }
-            
-            request.headers.clear();
-            for (key, value) in &headers {
-                if !key.is_empty() {
-                    request.headers.insert(key.clone(), value.clone());
-                }
+        }
+    }
+
+    pub fn prev_editor_field(&mut self) {
+        self.kv_edit_mode = KeyValueEditMode::None;
+        match self.protocol_type() {
+            ProtocolType::Http => {
+                self.editor_focused_field = match self.editor_focused_field {
+                    EditorField::Name => EditorField::Auth,
+                    EditorField::Method => EditorField::Name,
+                    EditorField::Url => EditorField::Method,
+                    EditorField::Params => EditorField::Url,
+                    EditorField::Headers => EditorField::Params,
+                    EditorField::Body => EditorField::Headers,
+                    EditorField::Auth => EditorField::Body,
+                };
+                self.sync_editor_tab_with_field();
             }
-            
-            request.body = if !body.is_empty() {
-                Some(body)
-            } else {
-                None
-            };
-            
-            if !auth.is_empty() {
-                request.auth = crate::models::request::AuthType::Bearer {
-                    token: auth,
+            ProtocolType::Grpc => {
+                self.grpc_editor_focused_field = match self.grpc_editor_focused_field {
+                    GrpcEditorField::Name => GrpcEditorField::Metadata,
+                    GrpcEditorField::ServerUrl => GrpcEditorField::Name,
+                    GrpcEditorField::ServiceName => GrpcEditorField::ServerUrl,
+                    GrpcEditorField::MethodName => GrpcEditorField::ServiceName,
+                    GrpcEditorField::Message => GrpcEditorField::MethodName,
+                    GrpcEditorField::Metadata => GrpcEditorField::Message,
                 };
-            } else {
-                request.auth = crate::models::request::AuthType::None;
+            }
+        }
+    }
+
+    /// Whether a popup is shown over the panels and takes the keyboard
+    pub fn is_popup_open(&self) -> bool {
+        self.show_welcome
+            || self.show_export_menu
+            || self.show_import_menu
+            || self.show_proto_loader
+            || self.show_proxy_settings
+            || self.show_defaults_editor
+            || self.show_diff_popup
+            || self.show_command_palette
+            || self.show_trash
+            || self.confirmation.is_some()
+            || self.show_help
+    }
+
+    /// Move edit mode to `field`, e.g. when it is clicked
+    pub fn focus_editor_field(&mut self, field: EditorField) {
+        self.kv_edit_mode = KeyValueEditMode::None;
+        self.editor_focused_field = field;
+        self.sync_editor_tab_with_field();
+    }
+
+    /// Show the tab of the focused field; Name, Method and URL keep the current one
+    fn sync_editor_tab_with_field(&mut self) {
+        self.editor_tab = match self.editor_focused_field {
+            EditorField::Params => EditorTab::Params,
+            EditorField::Headers => EditorTab::Headers,
+            EditorField::Body => EditorTab::Body,
+            EditorField::Auth => EditorTab::Auth,
+            _ => self.editor_tab,
+        };
+    }
+
+    /// Insert a paste into the body or message editor, `false` when neither is being edited
+    pub fn paste_into_editor(&mut self, text: &str) -> bool {
+        let Some(editor) = self.focused_text_editor() else {
+            return false;
+        };
+        editor.paste(text);
+        true
+    }
+
+    /// Whether the body being edited is checked as JSON: by its Content-Type header,
+    /// or without one when it starts with a bracket
+    pub fn is_body_input_json(&self) -> bool {
+        match self.headers_input.iter().find(|(key, _)| key.eq_ignore_ascii_case("content-type")) {
+            Some((_, value)) => BodyFormat::from_content_type(value) == BodyFormat::Json,
+            None => matches!(
+                self.body_editor.lines().iter().flat_map(|line| line.chars()).find(|c| !c.is_whitespace()),
+                Some('{' | '[')
+            ),
+        }
+    }
+
+    /// Index into `auth_values_input` of the selected row, `None` for the auth type row
+    pub fn selected_auth_field(&self) -> Option<usize> {
+        let row = self.auth_selected.checked_sub(1)?;
+        self.auth_visible_fields().get(row).copied()
+    }
+
+    pub fn select_auth_row(&mut self, row: usize) {
+        let rows = self.auth_visible_fields().len() + 1;
+        self.auth_selected = row.min(rows - 1);
+        self.auth_cursor = self.selected_auth_field()
+            .and_then(|idx| self.auth_values_input.get(idx))
+            .map(|value| value.len())
+            .unwrap_or(0);
+    }
+
+    /// Cycle the auth type, or the option of a choice field, depending on the selected row
+    pub fn cycle_auth_option(&mut self, forward: bool) {
+        let step = |len: usize, idx: usize| if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+
+        match self.selected_auth_field() {
+            None => {
+                let kinds = AuthKind::all();
+                let idx = kinds.iter().position(|k| *k == self.auth_kind_input).unwrap_or(0);
+                self.auth_kind_input = kinds[step(kinds.len(), idx)];
+                self.auth_values_input = self.auth_kind_input.default_values();
+            }
+            Some(field_idx) => {
+                let AuthFieldKind::Choice(options) = self.auth_kind_input.fields()[field_idx].kind else {
+                    return;
+                };
+                let current = self.auth_values_input.get(field_idx).cloned().unwrap_or_default();
+                let idx = options.iter().position(|o| *o == current).unwrap_or(0);
+                if let Some(value) = self.auth_values_input.get_mut(field_idx) {
+                    *value = options[step(options.len(), idx)].to_string();
+                }
+                // Switching the grant type changes which fields are shown
+                self.select_auth_row(self.auth_selected);
             }
         }
     }
@@ -398,8 +1114,7 @@ impl AppState {
             self.grpc_method_name_input = method_name;
             self.grpc_method_name_cursor = self.grpc_method_name_input.len();
 
-            self.grpc_message_input = message;
-            self.grpc_message_cursor = self.grpc_message_input.len();
+            self.grpc_message_editor = TextEditor::new(&message);
 
             self.grpc_metadata_input = metadata;
             self.grpc_metadata_selected = 0;
@@ -407,52 +1122,73 @@ impl AppState {
     }
 
     pub fn save_grpc_input_to_request(&mut self) {
-        // Clone all the input values first to avoid borrow checker issues
-        let name = self.grpc_name_input.trim().to_string();
-        let server_url = self.grpc_server_url_input.clone();
-        let service_name = self.grpc_service_name_input.clone();
-        let method_name = self.grpc_method_name_input.clone();
-        let message = self.grpc_message_input.clone();
-        let metadata = self.grpc_metadata_input.clone();
-
+        let draft = self.capture_draft();
         if let Some(request) = self.get_current_grpc_request_mut() {
-            if !name.is_empty() {
-                request.name = name;
-            }
+            apply_grpc_draft(request, &draft);
+        }
+    }
 
-            request.server_url = server_url;
-            request.service_name = service_name;
-            request.method_name = method_name;
-            request.message_json = message;
+    /// Focus the next shown panel, hidden sidebars are skipped
+    pub fn next_panel(&mut self) {
+        self.step_panel(1);
+    }
 
-            request.metadata.clear();
-            for (key, value) in &metadata {
-                if !key.is_empty() {
-                    request.metadata.insert(key.clone(), value.clone());
-                }
+    pub fn prev_panel(&mut self) {
+        self.step_panel(Panel::ALL.len() - 1);
+    }
+
+    fn step_panel(&mut self, step: usize) {
+        let mut index = self.focused_panel.index();
+        loop {
+            index = (index + step) % Panel::ALL.len();
+            if self.panel_layout.is_visible(index) {
+                break;
             }
         }
+        self.focused_panel = Panel::ALL[index];
     }
 
+    // Layout helpers
 
-    pub fn next_panel(&mut self) {
-        use Panel::*;
-        self.focused_panel = match self.focused_panel {
-            Collections => Requests,
-            Requests => RequestEditor,
-            RequestEditor => Response,
-            Response => Collections,
-        };
+    /// Focus `panel` unless its sidebar is hidden, then the focus stays where it is
+    pub fn focus_panel(&mut self, panel: Panel) {
+        if self.panel_layout.is_visible(panel.index()) {
+            self.focused_panel = panel;
+        }
     }
-    
-    pub fn prev_panel(&mut self) {
-        use Panel::*;
-        self.focused_panel = match self.focused_panel {
-            Collections => Response,
-            Requests => Collections,
-            RequestEditor => Requests,
-            Response => RequestEditor,
-        };
+
+    /// Grow or shrink the focused panel by `delta` percent; returns whether it changed
+    pub fn resize_focused_panel(&mut self, delta: i16) -> bool {
+        if self.zoomed {
+            return false;
+        }
+        self.panel_layout.resize(self.focused_panel.index(), delta)
+    }
+
+    /// Hide or show the collections or requests sidebar, moving the focus off it when hidden
+    pub fn toggle_sidebar(&mut self, panel: Panel) {
+        match panel {
+            Panel::Collections => self.panel_layout.show_collections = !self.panel_layout.show_collections,
+            Panel::Requests => self.panel_layout.show_requests = !self.panel_layout.show_requests,
+            _ => return,
+        }
+        if !self.panel_layout.is_visible(self.focused_panel.index()) {
+            self.next_panel();
+        }
+        if self.panel_layout.is_visible(panel.index()) {
+            self.zoomed = false;
+        }
+    }
+
+    pub fn cycle_arrangement(&mut self) {
+        self.panel_layout.arrangement = self.panel_layout.arrangement.next();
+        self.status_message = Some(format!("Layout: {}", self.panel_layout.arrangement.as_str()));
+    }
+
+    /// Back to the configured widths with every panel shown
+    pub fn reset_layout(&mut self) {
+        self.panel_layout = PanelLayout::default();
+        self.zoomed = false;
     }
     
     pub fn next_editor_tab(&mut self) {
@@ -466,159 +1202,725 @@ impl AppState {
     }
     
     pub fn next_request(&mut self) {
-        // Get the collection_id to filter by
-        let collection_id = self.selected_collection
-            .and_then(|idx| self.collections.get(idx))
-            .map(|c| c.id);
+        let visible = self.visible_request_indices();
+        let position = self.selected_request.and_then(|selected| visible.iter().position(|&idx| idx == selected));
+        let next = match position {
+            Some(position) => visible.get(position + 1),
+            None => visible.first(),
+        };
+        if let Some(&idx) = next {
+            self.selected_request = Some(idx);
+            self.clear_input_buffers();
+        }
+    }
 
-        match self.protocol_type {
-            ProtocolType::Http => {
-                if let Some(current_idx) = self.selected_request {
-                    // Find the next request that belongs to the same collection
-                    for idx in (current_idx + 1)..self.requests.len() {
-                        let request_collection_id = self.requests.get(idx).and_then(|r| r.collection_id);
-                        if collection_id == request_collection_id {
-                            self.selected_request = Some(idx);
-                            self.clear_input_buffers();
-                            return;
-                        }
-                    }
-                } else if !self.requests.is_empty() {
-                    // Find the first request that belongs to the selected collection
-                    for (idx, request) in self.requests.iter().enumerate() {
-                        if collection_id == request.collection_id {
-                            self.selected_request = Some(idx);
-                            self.clear_input_buffers();
-                            return;
-                        }
-                    }
-                }
+    pub fn prev_request(&mut self) {
+        let visible = self.visible_request_indices();
+        let position = self.selected_request.and_then(|selected| visible.iter().position(|&idx| idx == selected));
+        if let Some(&idx) = position.and_then(|position| position.checked_sub(1)).and_then(|prev| visible.get(prev)) {
+            self.selected_request = Some(idx);
+            self.clear_input_buffers();
+        }
+    }
+
+    /// Rows of the collections tree: every collection, with the folders of the expanded ones below it
+    pub fn collection_tree(&self) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        for (idx, collection) in self.collections.iter().enumerate() {
+            let expanded = self.expanded_nodes.contains(&collection.id);
+            rows.push(TreeRow {
+                collection: idx,
+                folder: None,
+                depth: 0,
+                has_children: !folder::children(&self.folders, collection.id, None).is_empty(),
+                expanded,
+            });
+            if expanded {
+                self.push_folder_rows(&mut rows, idx, None, 1);
             }
-            ProtocolType::Grpc => {
-                if let Some(current_idx) = self.selected_request {
-                    // Find the next gRPC request that belongs to the same collection
-                    for idx in (current_idx + 1)..self.grpc_requests.len() {
-                        let request_collection_id = self.grpc_requests.get(idx).and_then(|r| r.collection_id);
-                        if collection_id == request_collection_id {
-                            self.selected_request = Some(idx);
-                            self.clear_input_buffers();
-                            return;
-                        }
-                    }
-                } else if !self.grpc_requests.is_empty() {
-                    // Find the first gRPC request that belongs to the selected collection
-                    for (idx, request) in self.grpc_requests.iter().enumerate() {
-                        if collection_id == request.collection_id {
-                            self.selected_request = Some(idx);
-                            self.clear_input_buffers();
-                            return;
-                        }
-                    }
-                }
+        }
+        rows
+    }
+
+    fn push_folder_rows(&self, rows: &mut Vec<TreeRow>, collection: usize, parent: Option<Uuid>, depth: usize) {
+        let collection_id = self.collections[collection].id;
+        for child in folder::children(&self.folders, collection_id, parent) {
+            let expanded = self.expanded_nodes.contains(&child.id);
+            rows.push(TreeRow {
+                collection,
+                folder: Some(child.id),
+                depth,
+                has_children: !folder::children(&self.folders, collection_id, Some(child.id)).is_empty(),
+                expanded,
+            });
+            if expanded {
+                self.push_folder_rows(rows, collection, Some(child.id), depth + 1);
             }
         }
     }
 
-    pub fn prev_request(&mut self) {
-        // Get the collection_id to filter by
-        let collection_id = self.selected_collection
-            .and_then(|idx| self.collections.get(idx))
-            .map(|c| c.id);
+    /// Row of the collections tree with the selected collection or folder
+    pub fn selected_tree_row(&self) -> Option<usize> {
+        self.collection_tree()
+            .iter()
+            .position(|row| Some(row.collection) == self.selected_collection && row.folder == self.selected_folder)
+    }
 
-        match self.protocol_type {
-            ProtocolType::Http => {
-                if let Some(current_idx) = self.selected_request {
-                    // Find the previous request that belongs to the same collection
-                    if current_idx > 0 {
-                        for idx in (0..current_idx).rev() {
-                            let request_collection_id = self.requests.get(idx).and_then(|r| r.collection_id);
-                            if collection_id == request_collection_id {
-                                self.selected_request = Some(idx);
-                                self.clear_input_buffers();
-                                return;
-                            }
-                        }
-                    }
-                }
-            }
-            ProtocolType::Grpc => {
-                if let Some(current_idx) = self.selected_request {
-                    // Find the previous gRPC request that belongs to the same collection
-                    if current_idx > 0 {
-                        for idx in (0..current_idx).rev() {
-                            let request_collection_id = self.grpc_requests.get(idx).and_then(|r| r.collection_id);
-                            if collection_id == request_collection_id {
-                                self.selected_request = Some(idx);
-                                self.clear_input_buffers();
-                                return;
-                            }
-                        }
-                    }
-                }
-            }
+    /// Select the collection or folder on a row of the collections tree
+    pub fn select_tree_row(&mut self, row: usize) {
+        if let Some(row) = self.collection_tree().get(row) {
+            self.select_node(row.collection, row.folder);
         }
     }
-    
-    pub fn next_collection(&mut self) {
-        if let Some(idx) = self.selected_collection {
-            if idx < self.collections.len().saturating_sub(1) {
-                self.selected_collection = Some(idx + 1);
-                self.update_selected_request_for_collection();
-            }
-        } else if !self.collections.is_empty() {
-            self.selected_collection = Some(0);
+
+    /// Select a collection, or a folder in it, and the first request directly inside
+    fn select_node(&mut self, collection: usize, folder: Option<Uuid>) {
+        if self.selected_collection != Some(collection) || self.selected_folder != folder {
+            self.selected_collection = Some(collection);
+            self.selected_folder = folder;
             self.update_selected_request_for_collection();
         }
     }
+
+    pub fn next_collection(&mut self) {
+        let next = self.selected_tree_row().map_or(0, |row| row + 1);
+        self.select_tree_row(next);
+    }
     
     pub fn prev_collection(&mut self) {
-        if let Some(idx) = self.selected_collection {
-            if idx > 0 {
-                self.selected_collection = Some(idx - 1);
-                self.update_selected_request_for_collection();
+        if let Some(row) = self.selected_tree_row().and_then(|row| row.checked_sub(1)) {
+            self.select_tree_row(row);
+        }
+    }
+
+    /// Open the selected collection or folder in the tree, or step into it when it is open already
+    pub fn expand_tree_node(&mut self) {
+        let Some(position) = self.selected_tree_row() else {
+            return;
+        };
+        let row = self.collection_tree()[position];
+        if !row.has_children {
+            return;
+        }
+        if row.expanded {
+            self.select_tree_row(position + 1);
+        } else {
+            self.expanded_nodes.insert(self.node_id(row));
+        }
+    }
+
+    /// Close the selected collection or folder, or go up to the parent when it is closed already
+    pub fn collapse_tree_node(&mut self) {
+        let Some(position) = self.selected_tree_row() else {
+            return;
+        };
+        let row = self.collection_tree()[position];
+        if row.expanded {
+            self.expanded_nodes.remove(&self.node_id(row));
+        } else if let Some(folder_id) = row.folder {
+            let parent = self.folders.iter().find(|f| f.id == folder_id).and_then(|f| f.parent_id);
+            self.select_node(row.collection, parent);
+        }
+    }
+
+    fn node_id(&self, row: TreeRow) -> Uuid {
+        row.folder.unwrap_or(self.collections[row.collection].id)
+    }
+
+    /// Open the collection and folders above a folder so its row is shown
+    fn reveal_folder(&mut self, folder_id: Uuid) {
+        if let Some(collection_id) = self.folders.iter().find(|f| f.id == folder_id).map(|f| f.collection_id) {
+            self.expanded_nodes.insert(collection_id);
+        }
+        self.expanded_nodes.extend(folder::ancestors(&self.folders, folder_id));
+    }
+
+    /// Select the folder a request sits in, so the request list shows it
+    pub fn follow_request_folder(&mut self, request_id: Uuid) {
+        self.selected_folder = self.request_folders.get(&request_id).copied();
+        if let Some(folder_id) = self.selected_folder {
+            self.reveal_folder(folder_id);
+        }
+    }
+
+    /// Whether a request sits directly in the selected collection or folder
+    fn in_selected_node(&self, collection_id: Option<Uuid>, request_id: Uuid) -> bool {
+        let selected_collection_id = self.selected_collection
+            .and_then(|idx| self.collections.get(idx))
+            .map(|c| c.id);
+        collection_id == selected_collection_id && self.request_folders.get(&request_id).copied() == self.selected_folder
+    }
+
+    /// `Collection/Folder/Sub` for the selected folder, `None` at the top of a collection
+    pub fn selected_folder_path(&self) -> Option<String> {
+        self.selected_folder.map(|id| folder::path(&self.folders, id))
+    }
+
+    /// Add a folder inside the selected collection or folder, select it and start naming it
+    pub fn new_folder(&mut self) -> Option<&Folder> {
+        let collection = self.selected_collection?;
+        let collection_id = self.collections.get(collection)?.id;
+        let mut new_folder = Folder::new("New Folder".to_string(), collection_id, self.selected_folder);
+        new_folder.sort_index = folder::next_sort_index(&self.folders, collection_id, self.selected_folder);
+        let folder_id = new_folder.id;
+        self.folders.push(new_folder);
+        self.expanded_nodes.insert(self.selected_folder.unwrap_or(collection_id));
+        self.select_node(collection, Some(folder_id));
+        self.start_editing_collection();
+        self.folders.last()
+    }
+
+    /// Pick up the selected folder to drop it into another collection or folder
+    pub fn start_moving_folder(&mut self) -> bool {
+        self.moving_folder = self.selected_folder;
+        if let Some(folder_id) = self.moving_folder {
+            self.status_message = Some(format!(
+                "Moving {}: select where to put it and press the move key again, Esc to cancel",
+                folder::path(&self.folders, folder_id)
+            ));
+        }
+        self.moving_folder.is_some()
+    }
+
+    pub fn cancel_moving_folder(&mut self) {
+        self.moving_folder = None;
+        self.status_message = Some("Move cancelled".to_string());
+    }
+
+    /// Put the folder being moved, with everything in it, into the selected collection or folder.
+    /// Returns the ids of the folders that changed.
+    pub fn drop_folder(&mut self) -> Result<Vec<Uuid>, String> {
+        let folder_id = self.moving_folder.ok_or("No folder is being moved")?;
+        let collection = self.selected_collection.ok_or("Select a collection or folder to move it into")?;
+        let collection_id = self.collections.get(collection).ok_or("Collection not found")?.id;
+        let subtree = folder::subtree(&self.folders, folder_id);
+        if self.selected_folder.is_some_and(|target| subtree.contains(&target)) {
+            return Err("A folder cannot be moved into itself".to_string());
+        }
+
+        let sort_index = folder::next_sort_index(&self.folders, collection_id, self.selected_folder);
+        let now = chrono::Utc::now();
+        for folder in self.folders.iter_mut().filter(|f| subtree.contains(&f.id)) {
+            if folder.id == folder_id {
+                folder.parent_id = self.selected_folder;
+                folder.sort_index = sort_index;
             }
+            folder.collection_id = collection_id;
+            folder.updated_at = now;
+        }
+        // Requests in the moved folders go along to the new collection
+        let moved_requests: HashSet<Uuid> = self.request_folders.iter()
+            .filter(|(_, folder_id)| subtree.contains(folder_id))
+            .map(|(request_id, _)| *request_id)
+            .collect();
+        for request in self.requests.iter_mut().filter(|r| moved_requests.contains(&r.id())) {
+            request.set_collection_id(Some(collection_id));
         }
+
+        self.moving_folder = None;
+        self.reveal_folder(folder_id);
+        self.select_node(collection, Some(folder_id));
+        self.status_message = Some(format!("Moved to {}", folder::path(&self.folders, folder_id)));
+        Ok(subtree)
     }
-    
-    pub fn update_selected_request_for_collection(&mut self) {
-        match self.protocol_type {
-            ProtocolType::Http => {
-                if let Some(collection_idx) = self.selected_collection {
-                    if let Some(collection) = self.collections.get(collection_idx) {
-                        let collection_id = collection.id;
-                        if let Some(first_request_idx) = self.requests.iter().position(|r| r.collection_id == Some(collection_id)) {
-                            self.selected_request = Some(first_request_idx);
-                        } else {
-                            self.selected_request = None;
-                        }
-                    }
-                } else {
-                    if let Some(first_request_idx) = self.requests.iter().position(|r| r.collection_id.is_none()) {
-                        self.selected_request = Some(first_request_idx);
-                    } else {
-                        self.selected_request = None;
-                    }
+
+    /// Swap the selected collection with the one above or below it, `false` at either end
+    pub fn shift_collection(&mut self, down: bool) -> bool {
+        let Some(idx) = self.selected_collection else {
+            return false;
+        };
+        let Some(other) = neighbour(idx, down).filter(|&other| other < self.collections.len()) else {
+            return false;
+        };
+        self.collections.swap(idx, other);
+        self.selected_collection = Some(other);
+        true
+    }
+
+    /// Swap the selected folder with the one above or below it in the same parent.
+    /// Returns the renumbered folders, none at either end.
+    pub fn shift_folder(&mut self, down: bool) -> Vec<Uuid> {
+        let Some(folder) = self.selected_folder.and_then(|id| self.folders.iter().find(|f| f.id == id)) else {
+            return Vec::new();
+        };
+        let mut siblings: Vec<Uuid> = folder::children(&self.folders, folder.collection_id, folder.parent_id)
+            .iter()
+            .map(|f| f.id)
+            .collect();
+        let Some(position) = siblings.iter().position(|&id| id == folder.id) else {
+            return Vec::new();
+        };
+        let Some(other) = neighbour(position, down).filter(|&other| other < siblings.len()) else {
+            return Vec::new();
+        };
+        siblings.swap(position, other);
+        let now = chrono::Utc::now();
+        for (sort_index, id) in siblings.iter().enumerate() {
+            if let Some(folder) = self.folders.iter_mut().find(|f| f.id == *id) {
+                folder.sort_index = sort_index as u32;
+                folder.updated_at = now;
+            }
+        }
+        siblings
+    }
+
+    /// Swap the selected request with the one above or below it in its collection or folder.
+    /// Returns the new sort indices of the list, empty at either end.
+    pub fn shift_request(&mut self, down: bool) -> Result<Vec<(Uuid, u32)>, String> {
+        if self.request_search_mode || !self.request_search_input.is_empty() {
+            return Err("Clear the search to reorder requests".to_string());
+        }
+        let list = self.node_request_indices();
+        let Some(position) = self.selected_request.and_then(|selected| list.iter().position(|&idx| idx == selected)) else {
+            return Ok(Vec::new());
+        };
+        let Some(&other) = neighbour(position, down).and_then(|other| list.get(other)) else {
+            return Ok(Vec::new());
+        };
+        self.requests.swap(list[position], other);
+        self.selected_request = Some(other);
+        Ok(self.node_sort_indices())
+    }
+
+    /// The requests directly in the selected collection or folder with their position, to be saved
+    pub fn node_sort_indices(&self) -> Vec<(Uuid, u32)> {
+        self.node_request_indices()
+            .into_iter()
+            .enumerate()
+            .map(|(sort_index, idx)| (self.requests[idx].id(), sort_index as u32))
+            .collect()
+    }
+
+    pub fn copy_request(&mut self) {
+        self.put_request_on_clipboard(RequestClipboard::Copy);
+    }
+
+    pub fn cut_request(&mut self) {
+        self.put_request_on_clipboard(RequestClipboard::Cut);
+    }
+
+    fn put_request_on_clipboard(&mut self, clip: fn(Uuid) -> RequestClipboard) {
+        let Some(request) = self.selected_request.and_then(|idx| self.requests.get(idx)) else {
+            return;
+        };
+        let clipboard = clip(request.id());
+        let verb = match clipboard {
+            RequestClipboard::Copy(_) => "Copied",
+            RequestClipboard::Cut(_) => "Cut",
+        };
+        self.status_message = Some(format!(
+            "{} {}: select a collection or folder and paste it there",
+            verb,
+            request.name()
+        ));
+        self.request_clipboard = Some(clipboard);
+    }
+
+    /// Paste the copied or cut request into the selected collection or folder. A copy gets a
+    /// new id, a cut request moves. Returns the id of the pasted request.
+    pub fn paste_request(&mut self) -> Result<Uuid, String> {
+        let clipboard = self.request_clipboard.ok_or("Copy or cut a request first")?;
+        let collection = self.selected_collection
+            .and_then(|idx| self.collections.get(idx))
+            .ok_or("Select a collection or folder to paste into")?;
+        let collection_id = collection.id;
+        let target = match self.selected_folder {
+            Some(folder_id) => folder::path(&self.folders, folder_id),
+            None => collection.name.clone(),
+        };
+        let (RequestClipboard::Copy(request_id) | RequestClipboard::Cut(request_id)) = clipboard;
+        let Some(idx) = self.requests.iter().position(|r| r.id() == request_id) else {
+            self.request_clipboard = None;
+            return Err("The request no longer exists".to_string());
+        };
+        let in_target = self.in_selected_node(self.requests[idx].collection_id(), request_id);
+
+        let mut request = match clipboard {
+            RequestClipboard::Copy(_) => {
+                let source = &self.requests[idx];
+                let name = if in_target { format!("{} (copy)", source.name()) } else { source.name().to_string() };
+                source.duplicate(name)
+            }
+            RequestClipboard::Cut(_) if in_target => {
+                self.request_clipboard = None;
+                return Err(format!("{} is in {} already", self.requests[idx].name(), target));
+            }
+            RequestClipboard::Cut(_) => {
+                self.request_clipboard = None;
+                self.requests.remove(idx)
+            }
+        };
+        request.set_collection_id(Some(collection_id));
+        match self.selected_folder {
+            Some(folder_id) => self.request_folders.insert(request.id(), folder_id),
+            None => self.request_folders.remove(&request.id()),
+        };
+        let pasted_id = request.id();
+        self.status_message = Some(format!("Pasted {} into {}", request.name(), target));
+        self.requests.push(request);
+        self.selected_request = Some(self.requests.len() - 1);
+        self.clear_input_buffers();
+        Ok(pasted_id)
+    }
+
+    /// Copy the selected collection with its folders, requests, defaults and proxy under new ids,
+    /// add it after the others and select it. Returns the id of the copy.
+    pub fn duplicate_collection(&mut self) -> Option<Uuid> {
+        let source = self.collections.get(self.selected_collection?)?.clone();
+        let mut copy = Collection::new(format!("{} (copy)", source.name));
+        copy.description = source.description.clone();
+        let copy_id = copy.id;
+
+        let mut new_ids = HashMap::from([(source.id, copy_id)]);
+        let folders: Vec<Folder> = self.folders.iter().filter(|f| f.collection_id == source.id).cloned().collect();
+        new_ids.extend(folders.iter().map(|f| (f.id, Uuid::new_v4())));
+        let now = chrono::Utc::now();
+        for mut folder in folders {
+            folder.id = new_ids[&folder.id];
+            folder.collection_id = copy_id;
+            folder.parent_id = folder.parent_id.and_then(|id| new_ids.get(&id).copied());
+            folder.created_at = now;
+            folder.updated_at = now;
+            self.folders.push(folder);
+        }
+
+        let requests: Vec<RequestType> = self.requests.iter()
+            .filter(|r| r.collection_id() == Some(source.id))
+            .cloned()
+            .collect();
+        for request in requests {
+            let mut request_copy = request.duplicate(request.name().to_string());
+            request_copy.set_collection_id(Some(copy_id));
+            if let Some(folder_id) = self.request_folders.get(&request.id()).and_then(|id| new_ids.get(id)) {
+                self.request_folders.insert(request_copy.id(), *folder_id);
+            }
+            self.requests.push(request_copy);
+        }
+
+        for (old_id, new_id) in &new_ids {
+            if let Some(defaults) = self.node_defaults.get(old_id).cloned() {
+                self.node_defaults.insert(*new_id, defaults);
+            }
+        }
+        if let Some(proxy) = self.collection_proxies.get(&source.id).cloned() {
+            self.collection_proxies.insert(copy_id, proxy);
+        }
+
+        self.collections.push(copy);
+        self.select_node(self.collections.len() - 1, None);
+        self.status_message = Some(format!("Duplicated {}", source.name));
+        Some(copy_id)
+    }
+
+    /// Ask before deleting the selected collection or folder when there is anything in it.
+    /// `false` when it is empty and can go to the trash right away.
+    pub fn confirm_node_deletion(&mut self) -> bool {
+        let Some(collection) = self.selected_collection.and_then(|idx| self.collections.get(idx)) else {
+            return false;
+        };
+        let (title, folders, requests) = match self.selected_folder {
+            Some(folder_id) => {
+                let subtree = folder::subtree(&self.folders, folder_id);
+                let requests = self.request_folders.values().filter(|id| subtree.contains(id)).count();
+                (format!("folder \"{}\"", folder::path(&self.folders, folder_id)), subtree.len() - 1, requests)
+            }
+            None => (
+                format!("collection \"{}\"", collection.name),
+                self.folders.iter().filter(|f| f.collection_id == collection.id).count(),
+                self.requests.iter().filter(|r| r.collection_id() == Some(collection.id)).count(),
+            ),
+        };
+        if folders == 0 && requests == 0 {
+            return false;
+        }
+        self.confirmation = Some(Confirmation {
+            action: ConfirmAction::DeleteNode,
+            message: format!("Delete {} with {}?", title, trash::describe_contents(folders, requests)),
+        });
+        true
+    }
+
+    /// Move the selected collection with its folders, requests, defaults and proxy to the trash
+    pub fn trash_collection(&mut self) -> Option<&TrashEntry> {
+        let idx = self.selected_collection.filter(|&idx| idx < self.collections.len())?;
+        let collection = self.collections.remove(idx);
+        let collection_id = collection.id;
+        let mut entry = TrashEntry::new(TrashKind::Collection, collection.name.clone());
+
+        let request_ids: HashSet<Uuid> = self.requests.iter()
+            .filter(|r| r.collection_id() == Some(collection_id))
+            .map(RequestType::id)
+            .collect();
+        self.take_requests(&mut entry, &request_ids);
+        let (folders, kept): (Vec<Folder>, Vec<Folder>) = std::mem::take(&mut self.folders)
+            .into_iter()
+            .partition(|f| f.collection_id == collection_id);
+        self.folders = kept;
+        let nodes: Vec<Uuid> = std::iter::once(collection_id).chain(folders.iter().map(|f| f.id)).collect();
+        self.take_node_defaults(&mut entry, &nodes);
+        if let Some(proxy) = self.collection_proxies.remove(&collection_id) {
+            entry.collection_proxies.push((collection_id, proxy));
+        }
+        if self.moving_folder.is_some_and(|id| nodes.contains(&id)) {
+            self.moving_folder = None;
+        }
+        entry.folders = folders;
+        entry.collections.push(collection);
+
+        self.selected_folder = None;
+        self.selected_collection = match self.collections.len() {
+            0 => None,
+            len => Some(idx.min(len - 1)),
+        };
+        // Tabs of the deleted requests are closed on the next sync
+        self.update_selected_request_for_collection();
+        Some(self.put_in_trash(entry))
+    }
+
+    /// Move the selected folder with the folders and requests inside it to the trash
+    pub fn trash_folder(&mut self) -> Option<&TrashEntry> {
+        let folder_id = self.selected_folder?;
+        let folder = self.folders.iter().find(|f| f.id == folder_id)?;
+        let parent = folder.parent_id;
+        let mut entry = TrashEntry::new(TrashKind::Folder, folder::path(&self.folders, folder_id));
+        let subtree = folder::subtree(&self.folders, folder_id);
+
+        let request_ids: HashSet<Uuid> = self.request_folders.iter()
+            .filter(|(_, folder_id)| subtree.contains(folder_id))
+            .map(|(request_id, _)| *request_id)
+            .collect();
+        self.take_requests(&mut entry, &request_ids);
+        let (folders, kept): (Vec<Folder>, Vec<Folder>) = std::mem::take(&mut self.folders)
+            .into_iter()
+            .partition(|f| subtree.contains(&f.id));
+        self.folders = kept;
+        entry.folders = folders;
+        self.take_node_defaults(&mut entry, &subtree);
+        if self.moving_folder.is_some_and(|id| subtree.contains(&id)) {
+            self.moving_folder = None;
+        }
+
+        // Back to the folder or collection it was in
+        self.selected_folder = parent;
+        self.update_selected_request_for_collection();
+        Some(self.put_in_trash(entry))
+    }
+
+    /// Move the selected request to the trash
+    pub fn trash_request(&mut self) -> Option<&TrashEntry> {
+        let idx = self.selected_request.filter(|&idx| idx < self.requests.len())?;
+        let request = self.requests.remove(idx);
+        let mut entry = TrashEntry::new(TrashKind::Request, request.name().to_string());
+        if let Some(folder_id) = self.request_folders.remove(&request.id()) {
+            entry.request_folders.push((request.id(), folder_id));
+        }
+        entry.requests.push(request);
+
+        // The request's tab is closed on the next sync
+        self.update_selected_request_for_collection();
+        Some(self.put_in_trash(entry))
+    }
+
+    /// Move a loaded proto schema to the trash
+    pub fn trash_proto_schema(&mut self, idx: usize) -> Option<&TrashEntry> {
+        if idx >= self.proto_schemas.len() {
+            return None;
+        }
+        let schema = self.proto_schemas.remove(idx);
+        let mut entry = TrashEntry::new(TrashKind::ProtoSchema, schema.name.clone());
+        entry.proto_schemas.push(schema);
+        Some(self.put_in_trash(entry))
+    }
+
+    /// Take the requests out of the list with their folders and their order within them
+    fn take_requests(&mut self, entry: &mut TrashEntry, request_ids: &HashSet<Uuid>) {
+        let mut positions: HashMap<(Option<Uuid>, Option<Uuid>), u32> = HashMap::new();
+        let (requests, kept): (Vec<RequestType>, Vec<RequestType>) = std::mem::take(&mut self.requests)
+            .into_iter()
+            .partition(|r| request_ids.contains(&r.id()));
+        self.requests = kept;
+        for request in &requests {
+            let folder_id = self.request_folders.remove(&request.id());
+            if let Some(folder_id) = folder_id {
+                entry.request_folders.push((request.id(), folder_id));
+            }
+            let position = positions.entry((request.collection_id(), folder_id)).or_default();
+            entry.sort_indices.push((request.id(), *position));
+            *position += 1;
+        }
+        entry.requests = requests;
+    }
+
+    fn take_node_defaults(&mut self, entry: &mut TrashEntry, nodes: &[Uuid]) {
+        for node_id in nodes {
+            self.expanded_nodes.remove(node_id);
+            if let Some(defaults) = self.node_defaults.remove(node_id) {
+                entry.node_defaults.push((*node_id, defaults));
+            }
+        }
+    }
+
+    fn put_in_trash(&mut self, entry: TrashEntry) -> &TrashEntry {
+        let undo_hint = self
+            .keymap
+            .first_key(KeyScope::Global, Action::UndoDelete)
+            .map_or_else(String::new, |key| format!(" ({} to undo)", key));
+        self.status_message = Some(format!("Moved {} to the trash{}", entry.title(), undo_hint));
+        self.undo_stack.push(entry.id);
+        self.trash.insert(0, entry);
+        &self.trash[0]
+    }
+
+    /// Restore what the last delete of this session moved to the trash
+    pub fn undo_delete(&mut self) -> Result<TrashEntry, String> {
+        let id = *self.undo_stack.last().ok_or("Nothing to undo")?;
+        let restored = self.restore_from_trash(id);
+        if restored.is_err() {
+            // Leave it in the trash so the next undo goes further back
+            self.undo_stack.pop();
+        }
+        restored
+    }
+
+    /// Put a trash entry back and select it. Returns the entry so it can be saved again.
+    pub fn restore_from_trash(&mut self, id: Uuid) -> Result<TrashEntry, String> {
+        let position = self.trash.iter().position(|e| e.id == id).ok_or("It is no longer in the trash")?;
+        let entry = &self.trash[position];
+
+        // Folders and requests go back into their collection, which must exist
+        let collection_exists = |collection_id: Uuid| {
+            self.collections.iter().chain(&entry.collections).any(|c| c.id == collection_id)
+        };
+        let missing = entry.folders.iter()
+            .map(|f| f.collection_id)
+            .chain(entry.requests.iter().filter_map(RequestType::collection_id))
+            .find(|&collection_id| !collection_exists(collection_id));
+        if let Some(collection_id) = missing {
+            let holder = self.trash.iter().find(|e| e.collections.iter().any(|c| c.id == collection_id));
+            return Err(match holder {
+                Some(holder) => format!("Restore {} first, {} was in it", holder.title(), entry.title()),
+                None => format!("The collection of {} was purged, it cannot be restored", entry.title()),
+            });
+        }
+
+        let mut entry = self.trash.remove(position);
+        self.undo_stack.retain(|&undo_id| undo_id != id);
+        self.selected_trash = self.selected_trash.min(self.trash.len().saturating_sub(1));
+
+        // Folders deleted since then are not there to go back into, the top of the collection is
+        let folder_ids: HashSet<Uuid> = self.folders.iter().chain(&entry.folders).map(|f| f.id).collect();
+        for folder in &mut entry.folders {
+            folder.parent_id = folder.parent_id.filter(|id| folder_ids.contains(id));
+        }
+        entry.request_folders.retain(|(_, folder_id)| folder_ids.contains(folder_id));
+
+        self.collections.extend(entry.collections.iter().cloned());
+        self.folders.extend(entry.folders.iter().cloned());
+        self.requests.extend(entry.requests.iter().cloned());
+        self.request_folders.extend(entry.request_folders.iter().copied());
+        self.node_defaults.extend(entry.node_defaults.iter().cloned());
+        self.collection_proxies.extend(entry.collection_proxies.iter().cloned());
+        self.proto_schemas.extend(entry.proto_schemas.iter().cloned());
+        if self.selected_proto_schema.is_none() && !self.proto_schemas.is_empty() {
+            self.selected_proto_schema = Some(0);
+        }
+
+        match entry.kind {
+            TrashKind::Collection => {
+                if let Some(collection) = entry.collections.first() {
+                    self.jump_to_collection(collection.id);
                 }
             }
-            ProtocolType::Grpc => {
-                if let Some(collection_idx) = self.selected_collection {
-                    if let Some(collection) = self.collections.get(collection_idx) {
-                        let collection_id = collection.id;
-                        if let Some(first_request_idx) = self.grpc_requests.iter().position(|r| r.collection_id == Some(collection_id)) {
-                            self.selected_request = Some(first_request_idx);
-                        } else {
-                            self.selected_request = None;
-                        }
-                    }
-                } else {
-                    if let Some(first_request_idx) = self.grpc_requests.iter().position(|r| r.collection_id.is_none()) {
-                        self.selected_request = Some(first_request_idx);
-                    } else {
-                        self.selected_request = None;
-                    }
+            TrashKind::Folder => {
+                // The deleted folder is the one whose parent was not deleted with it
+                let top = entry.folders.iter().find(|f| !entry.folders.iter().any(|p| Some(p.id) == f.parent_id));
+                if let Some(folder) = top {
+                    self.jump_to_folder(folder.id);
+                }
+            }
+            TrashKind::Request => {
+                if let Some(request) = entry.requests.first() {
+                    self.jump_to_request(request.id());
                 }
             }
+            TrashKind::ProtoSchema => {}
+        }
+        self.status_message = Some(format!("Restored {}", entry.title()));
+        Ok(entry)
+    }
+
+    /// Remove a trash entry for good, with the OAuth2 tokens and recent filters of its requests.
+    /// Returns the entry so its stored data can be deleted too.
+    pub fn purge_from_trash(&mut self, id: Uuid) -> Option<TrashEntry> {
+        let position = self.trash.iter().position(|e| e.id == id)?;
+        let entry = self.trash.remove(position);
+        self.undo_stack.retain(|&undo_id| undo_id != id);
+        self.selected_trash = self.selected_trash.min(self.trash.len().saturating_sub(1));
+        for request in &entry.requests {
+            self.oauth2_tokens.remove(&request.id());
+            self.recent_filters.remove(&request.id());
+        }
+        self.status_message = Some(format!("Purged {}", entry.title()));
+        Some(entry)
+    }
+
+    pub fn open_trash(&mut self) {
+        self.show_trash = true;
+        self.selected_trash = 0;
+    }
+
+    pub fn close_trash(&mut self) {
+        self.show_trash = false;
+    }
+
+    /// Ask before purging the selected trash entry
+    pub fn confirm_purge(&mut self) {
+        if let Some(entry) = self.trash.get(self.selected_trash) {
+            self.confirmation = Some(Confirmation {
+                action: ConfirmAction::PurgeTrashEntry(entry.id),
+                message: format!("Delete {} for good? This cannot be undone.", entry.title()),
+            });
+        }
+    }
+
+    /// Purge everything in the trash
+    pub fn empty_trash(&mut self) -> Vec<TrashEntry> {
+        let ids: Vec<Uuid> = self.trash.iter().map(|e| e.id).collect();
+        let purged = ids.into_iter().filter_map(|id| self.purge_from_trash(id)).collect();
+        self.status_message = Some("Emptied the trash".to_string());
+        purged
+    }
+
+    /// Ask before emptying the trash
+    pub fn confirm_empty_trash(&mut self) {
+        let items = match self.trash.len() {
+            0 => return,
+            1 => "the 1 item".to_string(),
+            n => format!("all {} items", n),
+        };
+        self.confirmation = Some(Confirmation {
+            action: ConfirmAction::EmptyTrash,
+            message: format!("Delete {} in the trash for good? This cannot be undone.", items),
+        });
+    }
+
+    pub fn select_collection(&mut self, idx: usize) {
+        if idx < self.collections.len() {
+            self.select_node(idx, None);
+        }
+    }
+
+    /// Select a request by index into `requests`
+    pub fn select_request(&mut self, idx: usize) {
+        if self.selected_request != Some(idx) {
+            self.selected_request = Some(idx);
+            self.clear_input_buffers();
         }
+    }
+
+    /// Select the first request directly in the selected collection or folder
+    pub fn update_selected_request_for_collection(&mut self) {
+        self.selected_request = self.node_request_indices().first().copied();
         self.clear_input_buffers();
     }
     
@@ -633,9 +1935,10 @@ impl AppState {
         self.params_selected = 0;
         self.headers_input.clear();
         self.headers_selected = 0;
-        self.body_input.clear();
-        self.body_cursor = 0;
-        self.auth_input.clear();
+        self.body_editor = TextEditor::default();
+        self.auth_kind_input = AuthKind::None;
+        self.auth_values_input.clear();
+        self.auth_selected = 0;
         self.auth_cursor = 0;
         self.input_mode = InputMode::Normal;
         self.kv_edit_mode = KeyValueEditMode::None;
@@ -649,29 +1952,34 @@ impl AppState {
         self.grpc_service_name_cursor = 0;
         self.grpc_method_name_input.clear();
         self.grpc_method_name_cursor = 0;
-        self.grpc_message_input.clear();
-        self.grpc_message_cursor = 0;
+        self.grpc_message_editor = TextEditor::default();
         self.grpc_metadata_input.clear();
         self.grpc_metadata_selected = 0;
     }
     
+    /// Rename the selected folder, or the collection when no folder is selected
     pub fn start_editing_collection(&mut self) {
-        if let Some(idx) = self.selected_collection {
-            if let Some(collection) = self.collections.get(idx) {
-                self.editing_collection = true;
-                self.collection_name_input = collection.name.clone();
-                self.collection_name_cursor = self.collection_name_input.len();
-            }
+        let name = match self.selected_folder {
+            Some(folder_id) => self.folders.iter().find(|f| f.id == folder_id).map(|f| &f.name),
+            None => self.selected_collection.and_then(|idx| self.collections.get(idx)).map(|c| &c.name),
+        };
+        if let Some(name) = name {
+            self.collection_name_input = name.clone();
+            self.collection_name_cursor = self.collection_name_input.len();
+            self.editing_collection = true;
         }
     }
     
     pub fn save_collection_name(&mut self) {
-        if let Some(idx) = self.selected_collection {
-            if let Some(collection) = self.collections.get_mut(idx) {
-                let trimmed_name = self.collection_name_input.trim();
-                if !trimmed_name.is_empty() {
-                    collection.name = trimmed_name.to_string();
+        let trimmed_name = self.collection_name_input.trim();
+        if !trimmed_name.is_empty() {
+            if let Some(folder_id) = self.selected_folder {
+                if let Some(folder) = self.folders.iter_mut().find(|f| f.id == folder_id) {
+                    folder.name = trimmed_name.to_string();
+                    folder.updated_at = chrono::Utc::now();
                 }
+            } else if let Some(collection) = self.selected_collection.and_then(|idx| self.collections.get_mut(idx)) {
+                collection.name = trimmed_name.to_string();
             }
         }
         self.editing_collection = false;
@@ -737,153 +2045,1030 @@ impl AppState {
     
     pub fn reset_response_scroll(&mut self) {
         self.response_scroll = 0;
+        self.response_tab_scrolls = [0; ResponseTab::ALL.len()];
     }
 
-    // Request list scroll helpers
-    pub fn scroll_request_list_to(&mut self, position: u16) {
-        self.request_list_scroll = position;
+    /// Switch response tabs, keeping each tab's scroll position
+    pub fn set_response_tab(&mut self, tab: ResponseTab) {
+        if tab == self.response_tab {
+            return;
+        }
+        self.response_tab_scrolls[self.response_tab.index()] = self.response_scroll;
+        self.response_scroll = self.response_tab_scrolls[tab.index()];
+        self.response_tab = tab;
     }
 
-    pub fn reset_request_list_scroll(&mut self) {
-        self.request_list_scroll = 0;
+    pub fn next_response_tab(&mut self) {
+        let next = ResponseTab::ALL[(self.response_tab.index() + 1) % ResponseTab::ALL.len()];
+        self.set_response_tab(next);
     }
 
-    // Search/filter helpers
-    pub fn enter_request_search_mode(&mut self) {
-        self.request_search_mode = true;
-        self.request_search_input.clear();
-        self.request_search_cursor = 0;
-        self.filtered_request_indices.clear();
+    /// Whether the response viewer shows the body, which gRPC responses always do
+    pub fn is_response_body_shown(&self) -> bool {
+        self.protocol_type() == ProtocolType::Grpc || self.response_tab == ResponseTab::Body
     }
 
-    pub fn exit_request_search_mode(&mut self) {
-        self.request_search_mode = false;
-        self.request_search_input.clear();
-        self.request_search_cursor = 0;
-        self.filtered_request_indices.clear();
+    /// Plain text of a non-body response tab, as shown and copied
+    pub fn response_tab_text(&self) -> Option<String> {
+        let response = self.current_response.as_ref()?;
+        match self.response_tab {
+            ResponseTab::Body => self.response_display_text().map(str::to_string),
+            ResponseTab::Headers => Some(
+                response.raw_headers
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            ResponseTab::Cookies => Some(
+                response.set_cookie_headers()
+                    .map(|header| match parse_set_cookie(header) {
+                        Some(cookie) => cookie.display_lines().join("\n"),
+                        None => format!("{} (unparsed)", header),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            ),
+            ResponseTab::Raw => Some(response.raw_text()),
+            ResponseTab::Request => response.sent_request.as_ref().map(|request| request.to_raw()),
+        }
     }
 
-    pub fn update_request_filter(&mut self) {
-        self.filtered_request_indices.clear();
+    pub fn toggle_response_tree_mode(&mut self) {
+        self.response_tree_mode = !self.response_tree_mode;
+    }
 
-        if self.request_search_input.is_empty() {
-            return; // No filter, show all
-        }
+    /// Whether the response viewer currently shows the JSON tree
+    pub fn is_json_tree_shown(&self) -> bool {
+        self.response_tree_mode && self.json_tree.is_some() && self.is_response_body_shown()
+    }
 
-        let search_lower = self.request_search_input.to_lowercase();
-        let selected_collection_id = self.selected_collection
-            .and_then(|idx| self.collections.get(idx))
-            .map(|c| c.id);
+    /// Rebuild the JSON tree and filter output when the displayed response changes
+    pub fn sync_json_tree(&mut self) {
+        let source = match self.protocol_type() {
+            ProtocolType::Http => self.current_response.as_ref().map(|r| r.id),
+            ProtocolType::Grpc => self.grpc_response.as_ref().map(|r| r.id),
+        };
+        if source == self.json_tree_source {
+            return;
+        }
+        self.json_tree_source = source;
+        self.response_json = self.response_json_values();
+        // Show the whole response until the filter has been evaluated for it
+        self.response_filter_result = None;
+        self.json_tree = self.response_json.clone().map(JsonTree::new);
+        self.response_scroll = 0;
+        self.rebuild_response_text();
+        self.refresh_response_search();
+
+        // A filter typed for one request makes no sense for another
+        let request_id = self.current_request_id();
+        if !self.response_filter_input.is_empty() && self.response_filter_request != request_id {
+            self.clear_response_filter();
+            return;
+        }
+        self.refresh_response_filter();
+    }
 
-        match self.protocol_type {
-            ProtocolType::Http => {
-                for (idx, request) in self.requests.iter().enumerate() {
-                    // Check if request belongs to current collection
-                    let in_collection = match (selected_collection_id, request.collection_id) {
-                        (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                        (None, None) => true,
-                        _ => false,
-                    };
-
-                    if in_collection && (
-                        request.name.to_lowercase().contains(&search_lower) ||
-                        request.url.to_lowercase().contains(&search_lower) ||
-                        request.method.as_str().to_lowercase().contains(&search_lower)
-                    ) {
-                        self.filtered_request_indices.push(idx);
-                    }
-                }
-            }
-            ProtocolType::Grpc => {
-                for (idx, request) in self.grpc_requests.iter().enumerate() {
-                    let in_collection = match (selected_collection_id, request.collection_id) {
-                        (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                        (None, None) => true,
-                        _ => false,
-                    };
-
-                    if in_collection && (
-                        request.name.to_lowercase().contains(&search_lower) ||
-                        request.server_url.to_lowercase().contains(&search_lower) ||
-                        request.service_name.to_lowercase().contains(&search_lower) ||
-                        request.method_name.to_lowercase().contains(&search_lower)
-                    ) {
-                        self.filtered_request_indices.push(idx);
-                    }
-                }
-            }
+    /// Format the displayed body is shown as: the "view as" choice or the content type
+    pub fn response_body_format(&self) -> BodyFormat {
+        match self.protocol_type() {
+            ProtocolType::Http => self.current_request_id()
+                .and_then(|id| self.response_view_as.get(&id).copied())
+                .or_else(|| self.current_response.as_ref().map(|r| r.body_format()))
+                .unwrap_or(BodyFormat::Text),
+            ProtocolType::Grpc => BodyFormat::Json,
         }
     }
 
-    pub fn get_request_list_position(&self) -> Option<(usize, usize)> {
-        // Returns (current_position_1_indexed, total_count)
-        if let Some(selected_idx) = self.selected_request {
-            let selected_collection_id = self.selected_collection
-                .and_then(|idx| self.collections.get(idx))
-                .map(|c| c.id);
-
-            let (visible_indices, total) = if self.request_search_mode && !self.request_search_input.is_empty() {
-                // Use filtered indices
-                (self.filtered_request_indices.clone(), self.filtered_request_indices.len())
-            } else {
-                // Build list of all visible indices (matching collection filter)
-                let indices: Vec<usize> = match self.protocol_type {
-                    ProtocolType::Http => {
-                        self.requests.iter().enumerate()
-                            .filter(|(_, r)| match (selected_collection_id, r.collection_id) {
-                                (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                                (None, None) => true,
-                                _ => false,
-                            })
-                            .map(|(idx, _)| idx)
-                            .collect()
-                    }
-                    ProtocolType::Grpc => {
-                        self.grpc_requests.iter().enumerate()
-                            .filter(|(_, r)| match (selected_collection_id, r.collection_id) {
-                                (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                                (None, None) => true,
-                                _ => false,
-                            })
-                            .map(|(idx, _)| idx)
-                            .collect()
-                    }
-                };
-                let count = indices.len();
-                (indices, count)
-            };
+    /// Whether the body format was picked by hand instead of from the content type
+    pub fn is_response_view_as_set(&self) -> bool {
+        self.protocol_type() == ProtocolType::Http
+            && self.current_request_id().is_some_and(|id| self.response_view_as.contains_key(&id))
+    }
 
-            if let Some(pos) = visible_indices.iter().position(|&idx| idx == selected_idx) {
-                return Some((pos + 1, total)); // 1-indexed for display
-            }
+    /// Show the HTTP body as the next format, back to the detected one after a full cycle
+    pub fn cycle_response_view_as(&mut self) {
+        if self.protocol_type() != ProtocolType::Http {
+            return;
+        }
+        let (Some(request_id), Some(response)) = (self.current_request_id(), self.current_response.as_ref()) else {
+            return;
+        };
+        let detected = response.body_format();
+        let next = self.response_body_format().next();
+        if next == detected {
+            self.response_view_as.remove(&request_id);
+        } else {
+            self.response_view_as.insert(request_id, next);
         }
+        self.set_response_tab(ResponseTab::Body);
 
-        None
+        // Rebuild the text, tree and filter output for the new format
+        self.json_tree_source = None;
+        self.sync_json_tree();
     }
 
-    // gRPC request helpers
+    /// The JSON documents of the displayed response, one per gRPC message
+    fn response_json_values(&self) -> Option<Vec<serde_json::Value>> {
+        match self.protocol_type() {
+            ProtocolType::Http => self.current_response
+                .as_ref()
+                .filter(|_| self.response_body_format() == BodyFormat::Json)
+                .and_then(|response| response.body_text.as_deref())
+                .and_then(|text| serde_json::from_str(text).ok())
+                .map(|value| vec![value]),
+            ProtocolType::Grpc => self.grpc_response
+                .as_ref()
+                .filter(|response| !response.messages.is_empty())
+                .and_then(|response| {
+                    response.messages
+                        .iter()
+                        .map(|msg| serde_json::from_str(&msg.message_json).ok())
+                        .collect::<Option<Vec<_>>>()
+                }),
+        }
+    }
 
-    #[allow(dead_code)]
-    pub fn get_current_grpc_request(&self) -> Option<&GrpcRequest> {
-        self.selected_request.and_then(|idx| self.grpc_requests.get(idx))
+    /// Re-run the filter right away, e.g. when the response changed
+    pub fn refresh_response_filter(&mut self) {
+        self.schedule_response_filter(Duration::ZERO);
     }
 
-    #[allow(dead_code)]
-    pub fn get_current_grpc_request_mut(&mut self) -> Option<&mut GrpcRequest> {
-        self.selected_request.and_then(|idx| self.grpc_requests.get_mut(idx))
+    /// Re-run the filter once typing has paused
+    pub fn debounce_response_filter(&mut self) {
+        self.schedule_response_filter(FILTER_DEBOUNCE);
     }
 
-    #[allow(dead_code)]
-    pub fn get_all_requests_count(&self) -> usize {
-        match self.protocol_type {
-            ProtocolType::Http => self.requests.len(),
-            ProtocolType::Grpc => self.grpc_requests.len(),
+    fn schedule_response_filter(&mut self, delay: Duration) {
+        self.response_filter_generation += 1;
+        self.response_filter_due = None;
+        if let Some(task) = self.response_filter_task.take() {
+            task.abort();
+        }
+
+        if self.response_filter_input.trim().is_empty() {
+            self.show_response_filter_result(None);
+        } else if self.response_json.is_none() {
+            self.show_response_filter_result(Some(Err("response is not JSON".to_string())));
+        } else {
+            self.response_filter_due = Some(Instant::now() + delay);
         }
     }
 
-    // Proto file management helpers
+    /// Hand out the pending filter evaluation once its debounce delay has passed
+    pub fn take_due_response_filter(&mut self, now: Instant) -> Option<FilterJob> {
+        if self.response_filter_due.is_none_or(|due| due > now) {
+            return None;
+        }
+        self.response_filter_due = None;
+        Some(FilterJob {
+            generation: self.response_filter_generation,
+            expression: self.response_filter_input.trim().to_string(),
+            inputs: self.response_json.clone()?,
+        })
+    }
 
-    pub fn get_selected_proto_schema(&self) -> Option<&ProtoSchema> {
+    /// Whether a filter evaluation is waiting or running
+    pub fn is_response_filter_pending(&self) -> bool {
+        self.response_filter_due.is_some() || self.response_filter_task.is_some()
+    }
+
+    /// Show the outcome of a background evaluation unless the filter changed since
+    pub fn apply_response_filter_result(&mut self, generation: u64, result: Result<Vec<serde_json::Value>, String>) {
+        if generation != self.response_filter_generation {
+            return;
+        }
+        self.response_filter_task = None;
+        self.show_response_filter_result(Some(result));
+    }
+
+    /// Rebuild the tree from the filter output, or from the whole response without one
+    fn show_response_filter_result(&mut self, result: Option<Result<Vec<serde_json::Value>, String>>) {
+        self.response_filter_result = result;
+        self.json_tree = match &self.response_filter_result {
+            Some(Ok(filtered)) => Some(JsonTree::new(filtered.clone())),
+            _ => self.response_json.clone().map(JsonTree::new),
+        };
+        self.response_scroll = 0;
+        self.rebuild_response_text();
+        self.refresh_response_search();
+    }
+
+    /// The filter output as pretty-printed JSON, one value per block
+    pub fn filtered_response_text(&self) -> Option<String> {
+        let Some(Ok(values)) = &self.response_filter_result else {
+            return None;
+        };
+        Some(
+            values
+                .iter()
+                .map(|value| serde_json::to_string_pretty(value).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    /// The text shown by the response viewer outside of tree mode
+    pub fn response_display_text(&self) -> Option<&str> {
+        self.response_text.as_deref()
+    }
+
+    /// Pretty-print the displayed response once instead of on every frame and keystroke
+    fn rebuild_response_text(&mut self) {
+        if let Some(filtered) = self.filtered_response_text() {
+            self.response_text = Some(filtered);
+            return;
+        }
+        self.response_text = match self.protocol_type() {
+            ProtocolType::Http => self.current_response.as_ref().map(|r| r.formatted_body(self.response_body_format())),
+            ProtocolType::Grpc => self.grpc_response.as_ref().map(|response| {
+                if response.messages.is_empty() {
+                    if response.status.code == 0 {
+                        "Success (no message body)".to_string()
+                    } else {
+                        format!("Error: {}", response.status.message)
+                    }
+                } else {
+                    response.messages
+                        .iter()
+                        .map(|msg| msg.message_json.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                }
+            }),
+        };
+    }
+
+    // Response search helpers
+
+    pub fn enter_response_search_mode(&mut self) {
+        self.set_response_tab(ResponseTab::Body);
+        self.response_search_mode = true;
+        self.response_search_cursor = self.response_search_input.len();
+        // Matches are highlighted in the text view
+        self.response_tree_mode = false;
+    }
+
+    /// Recompute the matches after the query, options or displayed text changed
+    pub fn refresh_response_search(&mut self) {
+        let text = if self.response_search_input.is_empty() {
+            None
+        } else {
+            self.response_display_text()
+        };
+
+        let result = text_search::find_matches(
+            text.unwrap_or_default(),
+            &self.response_search_input,
+            self.response_search_case_sensitive,
+            self.response_search_regex,
+        );
+        match result {
+            Ok(matches) => {
+                self.response_search_matches = matches;
+                self.response_search_error = None;
+            }
+            Err(e) => {
+                self.response_search_matches.clear();
+                self.response_search_error = Some(format!("{:#}", e));
+            }
+        }
+
+        self.response_search_current = 0;
+        self.response_search_jump = !self.response_search_matches.is_empty();
+    }
+
+    pub fn next_search_match(&mut self) {
+        if self.response_search_matches.is_empty() {
+            return;
+        }
+        self.response_search_current = (self.response_search_current + 1) % self.response_search_matches.len();
+        self.response_search_jump = true;
+    }
+
+    pub fn prev_search_match(&mut self) {
+        if self.response_search_matches.is_empty() {
+            return;
+        }
+        let count = self.response_search_matches.len();
+        self.response_search_current = (self.response_search_current + count - 1) % count;
+        self.response_search_jump = true;
+    }
+
+    pub fn toggle_search_case_sensitive(&mut self) {
+        self.response_search_case_sensitive = !self.response_search_case_sensitive;
+        self.refresh_response_search();
+    }
+
+    pub fn toggle_search_regex(&mut self) {
+        self.response_search_regex = !self.response_search_regex;
+        self.refresh_response_search();
+    }
+
+    pub fn clear_response_search(&mut self) {
+        self.response_search_mode = false;
+        self.response_search_input.clear();
+        self.response_search_cursor = 0;
+        self.refresh_response_search();
+    }
+
+    pub fn current_request_id(&self) -> Option<Uuid> {
+        self.selected_request.and_then(|idx| self.requests.get(idx)).map(RequestType::id)
+    }
+
+    pub fn enter_response_filter_mode(&mut self) {
+        self.set_response_tab(ResponseTab::Body);
+        self.response_filter_mode = true;
+        self.response_filter_cursor = self.response_filter_input.len();
+        self.response_filter_request = self.current_request_id();
+        self.recent_filter_index = None;
+    }
+
+    /// Keep the filter applied and remember it. Returns the request whose recent filters changed.
+    pub fn confirm_response_filter(&mut self) -> Option<Uuid> {
+        self.response_filter_mode = false;
+        let expression = self.response_filter_input.trim().to_string();
+        let request_id = self.response_filter_request?;
+        if expression.is_empty() {
+            return None;
+        }
+
+        let recent = self.recent_filters.entry(request_id).or_default();
+        recent.retain(|f| f != &expression);
+        recent.insert(0, expression);
+        recent.truncate(Config::global().history.recent_filters);
+        Some(request_id)
+    }
+
+    pub fn clear_response_filter(&mut self) {
+        self.response_filter_mode = false;
+        self.response_filter_input.clear();
+        self.response_filter_cursor = 0;
+        self.recent_filter_index = None;
+        self.refresh_response_filter();
+    }
+
+    /// Step through the recent filters of the request, older with `older == true`
+    pub fn cycle_recent_filter(&mut self, older: bool) {
+        let Some(recent) = self.response_filter_request.and_then(|id| self.recent_filters.get(&id)) else {
+            return;
+        };
+        if recent.is_empty() {
+            return;
+        }
+
+        let index = match (self.recent_filter_index, older) {
+            (None, true) => Some(0),
+            (None, false) => return,
+            (Some(idx), true) => Some((idx + 1).min(recent.len() - 1)),
+            (Some(0), false) => None,
+            (Some(idx), false) => Some(idx - 1),
+        };
+
+        self.response_filter_input = index.map(|idx| recent[idx].clone()).unwrap_or_default();
+        self.response_filter_cursor = self.response_filter_input.len();
+        self.recent_filter_index = index;
+        self.debounce_response_filter();
+    }
+
+    // Response comparison helpers
+
+    /// Keep a received response in the session history
+    pub fn record_response(&mut self, request_id: Uuid, response: &HttpResponse) {
+        let request_name = self.requests
+            .iter()
+            .find(|r| r.id() == request_id)
+            .map_or_else(|| "Deleted request".to_string(), |r| r.name().to_string());
+        self.response_history.push(ResponseSnapshot { request_name, response: response.clone() });
+        let keep = Config::global().history.responses;
+        if self.response_history.len() > keep {
+            self.response_history.drain(..self.response_history.len() - keep);
+        }
+    }
+
+    /// Pin the displayed response as the baseline for comparisons, or unpin it
+    pub fn toggle_pinned_response(&mut self) {
+        let Some(response) = self.current_response.as_ref() else {
+            return;
+        };
+        if self.pinned_response.as_ref().is_some_and(|pinned| pinned.response.id == response.id) {
+            self.pinned_response = None;
+            self.status_message = Some("Unpinned the response".to_string());
+            return;
+        }
+        let request_name = self.get_current_request().map_or_else(String::new, |r| r.name.clone());
+        self.pinned_response = Some(ResponseSnapshot { request_name, response: response.clone() });
+        self.status_message = Some(match self.keymap.first_key(KeyScope::Response, Action::CompareResponses) {
+            Some(key) => format!("Pinned the response, press {} to compare it with another one", key),
+            None => "Pinned the response".to_string(),
+        });
+    }
+
+    /// The pinned response, then the history from newest to oldest
+    pub fn diff_candidates(&self) -> Vec<&ResponseSnapshot> {
+        let pinned_id = self.pinned_response.as_ref().map(|pinned| pinned.response.id);
+        self.pinned_response
+            .iter()
+            .chain(self.response_history.iter().rev().filter(|entry| Some(entry.response.id) != pinned_id))
+            .collect()
+    }
+
+    fn diff_candidate(&self, id: Option<Uuid>) -> Option<&ResponseSnapshot> {
+        let id = id?;
+        self.diff_candidates().into_iter().find(|entry| entry.response.id == id)
+    }
+
+    /// Compare the displayed response (B) with the pinned one, or else with the previous response (A)
+    pub fn open_diff_popup(&mut self) {
+        let candidates = self.diff_candidates();
+        if candidates.len() < 2 {
+            self.status_message = Some("Send or pin at least two responses to compare them".to_string());
+            return;
+        }
+
+        let current_id = self.current_response.as_ref().map(|r| r.id);
+        let b = current_id
+            .filter(|id| candidates.iter().any(|entry| entry.response.id == *id))
+            .unwrap_or(candidates[0].response.id);
+        let a = candidates
+            .iter()
+            .map(|entry| entry.response.id)
+            .find(|id| *id != b);
+
+        self.diff_b = Some(b);
+        self.diff_a = a;
+        self.diff_cursor = 0;
+        self.diff_stage = DiffStage::SelectingResponses;
+        self.show_diff_popup = true;
+    }
+
+    pub fn close_diff_popup(&mut self) {
+        self.show_diff_popup = false;
+        self.diff_report.clear();
+    }
+
+    pub fn move_diff_cursor(&mut self, delta: isize) {
+        let count = self.diff_candidates().len();
+        if count > 0 {
+            self.diff_cursor = self.diff_cursor.saturating_add_signed(delta).min(count - 1);
+        }
+    }
+
+    /// Use the response under the cursor as side A (`true`) or B
+    pub fn set_diff_side(&mut self, side_a: bool) {
+        let Some(id) = self.diff_candidates().get(self.diff_cursor).map(|entry| entry.response.id) else {
+            return;
+        };
+        if side_a {
+            self.diff_a = Some(id);
+        } else {
+            self.diff_b = Some(id);
+        }
+    }
+
+    pub fn swap_diff_sides(&mut self) {
+        std::mem::swap(&mut self.diff_a, &mut self.diff_b);
+        if self.diff_stage == DiffStage::ShowingDiff {
+            self.show_diff();
+        }
+    }
+
+    /// Build the comparison of A and B
+    pub fn show_diff(&mut self) {
+        let (Some(a), Some(b)) = (self.diff_candidate(self.diff_a), self.diff_candidate(self.diff_b)) else {
+            return;
+        };
+        self.diff_report = diff::compare_responses(&a.response, &b.response);
+        self.diff_scroll = 0;
+        self.diff_stage = DiffStage::ShowingDiff;
+    }
+
+    /// Short description of a compared side for titles and lists
+    pub fn diff_side_label(&self, id: Option<Uuid>) -> String {
+        match self.diff_candidate(id) {
+            Some(entry) => format!(
+                "{} {} ({})",
+                entry.response.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                entry.request_name,
+                entry.response.status_text
+            ),
+            None => "-".to_string(),
+        }
+    }
+
+    // In-flight request helpers
+
+    /// Record a newly spawned request for the active tab. A request that is
+    /// still running in the same tab is aborted, since its response would
+    /// replace the new one.
+    pub fn track_in_flight(&mut self, dispatch_id: Uuid, request_id: Uuid, protocol: ProtocolType, abort_handle: AbortHandle) {
+        let Some(tab_id) = self.active_tab_id() else {
+            abort_handle.abort();
+            return;
+        };
+        self.in_flight.retain(|older| {
+            if older.tab_id == tab_id {
+                older.abort_handle.abort();
+                false
+            } else {
+                true
+            }
+        });
+        self.in_flight.push(InFlightRequest {
+            dispatch_id,
+            tab_id,
+            request_id,
+            protocol,
+            started_at: Instant::now(),
+            abort_handle,
+        });
+        self.refresh_loading_state();
+    }
+
+    /// Remove a completed dispatch. Returns `None` if it was already cancelled.
+    pub fn finish_in_flight(&mut self, dispatch_id: Uuid) -> Option<InFlightRequest> {
+        let pos = self.in_flight.iter().position(|r| r.dispatch_id == dispatch_id)?;
+        let finished = self.in_flight.remove(pos);
+        self.refresh_loading_state();
+        Some(finished)
+    }
+
+    /// Abort the in-flight request of the active tab
+    pub fn cancel_in_flight(&mut self) -> Option<InFlightRequest> {
+        let tab_id = self.active_tab_id()?;
+        let pos = self.in_flight.iter().position(|r| r.tab_id == tab_id)?;
+        let cancelled = self.in_flight.remove(pos);
+        cancelled.abort_handle.abort();
+        self.refresh_loading_state();
+        Some(cancelled)
+    }
+
+    fn refresh_loading_state(&mut self) {
+        let active_tab_id = self.active_tab_id();
+        self.is_loading = self.in_flight.iter().any(|r| Some(r.tab_id) == active_tab_id);
+        if !self.is_loading {
+            self.loading_message.clear();
+        }
+    }
+
+    /// Update the loading message of a request that is still running
+    pub fn set_in_flight_message(&mut self, dispatch_id: Uuid, message: String) {
+        let Some(tab_id) = self.in_flight.iter().find(|r| r.dispatch_id == dispatch_id).map(|r| r.tab_id) else {
+            return;
+        };
+        if self.active_tab_id() == Some(tab_id) {
+            self.loading_message = message;
+        } else if let Some(tab) = self.tabs.iter_mut().find(|t| t.id == tab_id) {
+            tab.loading_message = message;
+        }
+    }
+
+    pub fn is_tab_loading(&self, tab_id: Uuid) -> bool {
+        self.in_flight.iter().any(|r| r.tab_id == tab_id)
+    }
+
+    /// Store a finished HTTP response in the tab that sent it
+    pub fn apply_http_response(&mut self, tab_id: Uuid, response: HttpResponse) {
+        if self.active_tab_id() == Some(tab_id) {
+            self.current_response = Some(response);
+        } else if let Some(tab) = self.tabs.iter_mut().find(|t| t.id == tab_id) {
+            tab.current_response = Some(response);
+            tab.loading_message.clear();
+        }
+    }
+
+    /// Store a finished gRPC response in the tab that sent it
+    pub fn apply_grpc_response(&mut self, tab_id: Uuid, response: GrpcResponse) {
+        if self.active_tab_id() == Some(tab_id) {
+            self.grpc_response = Some(response);
+        } else if let Some(tab) = self.tabs.iter_mut().find(|t| t.id == tab_id) {
+            tab.grpc_response = Some(response);
+            tab.loading_message.clear();
+        }
+    }
+
+    // Tab helpers
+
+    pub fn active_tab_id(&self) -> Option<Uuid> {
+        self.tabs.get(self.active_tab).map(|t| t.id)
+    }
+
+    pub fn current_tab_target(&self) -> TabTarget {
+        TabTarget {
+            protocol: self.protocol_type(),
+            request_id: self.current_request_id(),
+        }
+    }
+
+    /// Keep the active tab pointed at the selected request.
+    ///
+    /// Selecting a request that is open in another tab switches to that tab.
+    /// Otherwise a preview tab is retargeted, or opened next to a pinned tab.
+    pub fn sync_tabs_with_selection(&mut self) {
+        let target = self.current_tab_target();
+
+        // Close tabs whose request has been deleted
+        let stale: Vec<Uuid> = self.tabs
+            .iter()
+            .filter(|tab| !self.tab_target_exists(tab.target))
+            .map(|tab| tab.id)
+            .collect();
+        let active_id = self.active_tab_id();
+        let active_closed = active_id.is_none_or(|id| stale.contains(&id));
+        if !stale.is_empty() {
+            self.in_flight.retain(|r| {
+                if stale.contains(&r.tab_id) {
+                    r.abort_handle.abort();
+                    false
+                } else {
+                    true
+                }
+            });
+            self.tabs.retain(|tab| !stale.contains(&tab.id));
+        }
+
+        if active_closed {
+            // The view on screen belonged to the closed tab, so it is discarded
+            if let Some(idx) = self.find_tab(target) {
+                self.active_tab = idx;
+                self.load_tab_view(idx);
+            } else {
+                self.tabs.push(RequestTab::preview(target));
+                self.active_tab = self.tabs.len() - 1;
+                self.reset_view();
+            }
+            self.refresh_loading_state();
+            return;
+        }
+
+        if let Some(id) = active_id {
+            self.active_tab = self.tabs.iter().position(|t| t.id == id).unwrap_or(0);
+        }
+        if self.tabs[self.active_tab].target == target {
+            return;
+        }
+
+        if let Some(idx) = self.find_tab(target) {
+            self.stash_active_tab_view();
+            self.active_tab = idx;
+            self.load_tab_view(idx);
+        } else if self.tabs[self.active_tab].preview {
+            self.tabs[self.active_tab].target = target;
+            self.reset_view();
+        } else {
+            self.stash_active_tab_view();
+            if let Some(idx) = self.tabs.iter().position(|t| t.preview) {
+                self.tabs[idx] = RequestTab::preview(target);
+                self.active_tab = idx;
+            } else {
+                self.tabs.insert(self.active_tab + 1, RequestTab::preview(target));
+                self.active_tab += 1;
+            }
+            self.reset_view();
+        }
+        self.refresh_loading_state();
+    }
+
+    /// Keep the active tab open when another request is selected
+    pub fn pin_active_tab(&mut self) {
+        if let Some(tab) = self.tabs.get_mut(self.active_tab) {
+            tab.preview = false;
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.switch_to_tab((self.active_tab + 1) % self.tabs.len());
+        }
+    }
+
+    pub fn prev_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.switch_to_tab((self.active_tab + self.tabs.len() - 1) % self.tabs.len());
+        }
+    }
+
+    pub fn switch_to_tab(&mut self, idx: usize) {
+        if idx == self.active_tab || idx >= self.tabs.len() {
+            return;
+        }
+        self.stash_active_tab_view();
+        self.active_tab = idx;
+        self.select_tab_target(self.tabs[idx].target);
+        self.load_tab_view(idx);
+        self.refresh_loading_state();
+    }
+
+    /// Close the active tab, aborting its in-flight request. The last tab stays open.
+    pub fn close_active_tab(&mut self) {
+        if self.tabs.len() <= 1 {
+            return;
+        }
+        let closed = self.tabs.remove(self.active_tab);
+        self.in_flight.retain(|r| {
+            if r.tab_id == closed.id {
+                r.abort_handle.abort();
+                false
+            } else {
+                true
+            }
+        });
+        self.active_tab = self.active_tab.min(self.tabs.len() - 1);
+        self.clear_input_buffers();
+        self.select_tab_target(self.tabs[self.active_tab].target);
+        self.load_tab_view(self.active_tab);
+        self.refresh_loading_state();
+    }
+
+    /// Whether a tab holds edits that have not been saved to its request
+    pub fn tab_has_unsaved_edits(&self, idx: usize) -> bool {
+        let Some(tab) = self.tabs.get(idx) else {
+            return false;
+        };
+        if idx == self.active_tab {
+            // Outside edit mode the input buffers are not loaded from the request
+            self.input_mode == InputMode::Editing && self.draft_changes_request(&self.capture_draft(), tab.target)
+        } else {
+            tab.draft.as_ref().is_some_and(|draft| self.draft_changes_request(draft, tab.target))
+        }
+    }
+
+    /// Compare a draft with the stored request it was loaded from
+    fn draft_changes_request(&self, draft: &EditorDraft, target: TabTarget) -> bool {
+        let Some(request_id) = target.request_id else {
+            return false;
+        };
+        match self.requests.iter().find(|r| r.id() == request_id) {
+            Some(RequestType::Http(request)) => {
+                let mut edited = request.clone();
+                apply_http_draft(&mut edited, draft);
+                serde_json::to_value(&edited).ok() != serde_json::to_value(request).ok()
+            }
+            Some(RequestType::Grpc(request)) => {
+                let mut edited = request.clone();
+                apply_grpc_draft(&mut edited, draft);
+                serde_json::to_value(&edited).ok() != serde_json::to_value(request).ok()
+            }
+            None => false,
+        }
+    }
+
+    fn find_tab(&self, target: TabTarget) -> Option<usize> {
+        target.request_id?;
+        self.tabs.iter().position(|t| t.target == target)
+    }
+
+    fn tab_target_exists(&self, target: TabTarget) -> bool {
+        match target.request_id {
+            None => true,
+            Some(id) => self.requests.iter().any(|r| r.id() == id && r.protocol() == target.protocol),
+        }
+    }
+
+    /// Point the collection and request selection at a tab's request
+    fn select_tab_target(&mut self, target: TabTarget) {
+        let Some(request_id) = target.request_id else {
+            self.selected_request = None;
+            return;
+        };
+        let (index, collection_id) = self.requests.iter().enumerate()
+            .find(|(_, r)| r.id() == request_id)
+            .map(|(idx, r)| (idx, r.collection_id()))
+            .unzip();
+        if let Some(collection_id) = collection_id {
+            self.selected_collection = match collection_id {
+                Some(id) => self.collections.iter().position(|c| c.id == id),
+                None => None,
+            };
+            self.follow_request_folder(request_id);
+        }
+        self.selected_request = index;
+    }
+
+    fn stash_active_tab_view(&mut self) {
+        let draft = if self.input_mode == InputMode::Editing {
+            Some(self.capture_draft())
+        } else {
+            None
+        };
+        if draft.is_some() {
+            self.clear_input_buffers();
+        }
+        let current_response = self.current_response.take();
+        let grpc_response = self.grpc_response.take();
+        let loading_message = std::mem::take(&mut self.loading_message);
+        if let Some(tab) = self.tabs.get_mut(self.active_tab) {
+            tab.current_response = current_response;
+            tab.grpc_response = grpc_response;
+            tab.response_scroll = self.response_scroll;
+            tab.loading_message = loading_message;
+            tab.draft = draft;
+        }
+        self.reset_response_scroll();
+    }
+
+    fn load_tab_view(&mut self, idx: usize) {
+        let Some(tab) = self.tabs.get_mut(idx) else {
+            return;
+        };
+        self.current_response = tab.current_response.take();
+        self.grpc_response = tab.grpc_response.take();
+        self.response_scroll = tab.response_scroll;
+        self.loading_message = std::mem::take(&mut tab.loading_message);
+        if let Some(draft) = tab.draft.take() {
+            self.restore_draft(draft);
+        }
+    }
+
+    fn reset_view(&mut self) {
+        self.current_response = None;
+        self.grpc_response = None;
+        self.reset_response_scroll();
+        self.loading_message.clear();
+    }
+
+    fn capture_draft(&self) -> EditorDraft {
+        EditorDraft {
+            editor_focused_field: self.editor_focused_field,
+            grpc_editor_focused_field: self.grpc_editor_focused_field,
+            kv_edit_mode: self.kv_edit_mode,
+            name_input: self.name_input.clone(),
+            name_cursor: self.name_cursor,
+            method_input: self.method_input,
+            url_input: self.url_input.clone(),
+            url_cursor: self.url_cursor,
+            params_input: self.params_input.clone(),
+            params_selected: self.params_selected,
+            headers_input: self.headers_input.clone(),
+            headers_selected: self.headers_selected,
+            body_editor: self.body_editor.clone(),
+            auth_kind_input: self.auth_kind_input,
+            auth_values_input: self.auth_values_input.clone(),
+            auth_selected: self.auth_selected,
+            auth_cursor: self.auth_cursor,
+            grpc_name_input: self.grpc_name_input.clone(),
+            grpc_name_cursor: self.grpc_name_cursor,
+            grpc_server_url_input: self.grpc_server_url_input.clone(),
+            grpc_server_url_cursor: self.grpc_server_url_cursor,
+            grpc_service_name_input: self.grpc_service_name_input.clone(),
+            grpc_service_name_cursor: self.grpc_service_name_cursor,
+            grpc_method_name_input: self.grpc_method_name_input.clone(),
+            grpc_method_name_cursor: self.grpc_method_name_cursor,
+            grpc_message_editor: self.grpc_message_editor.clone(),
+            grpc_metadata_input: self.grpc_metadata_input.clone(),
+            grpc_metadata_selected: self.grpc_metadata_selected,
+        }
+    }
+
+    fn restore_draft(&mut self, draft: EditorDraft) {
+        self.input_mode = InputMode::Editing;
+        self.editor_focused_field = draft.editor_focused_field;
+        self.grpc_editor_focused_field = draft.grpc_editor_focused_field;
+        self.kv_edit_mode = draft.kv_edit_mode;
+        self.name_input = draft.name_input;
+        self.name_cursor = draft.name_cursor;
+        self.method_input = draft.method_input;
+        self.url_input = draft.url_input;
+        self.url_cursor = draft.url_cursor;
+        self.params_input = draft.params_input;
+        self.params_selected = draft.params_selected;
+        self.headers_input = draft.headers_input;
+        self.headers_selected = draft.headers_selected;
+        self.body_editor = draft.body_editor;
+        self.auth_kind_input = draft.auth_kind_input;
+        self.auth_values_input = draft.auth_values_input;
+        self.auth_selected = draft.auth_selected;
+        self.auth_cursor = draft.auth_cursor;
+        self.grpc_name_input = draft.grpc_name_input;
+        self.grpc_name_cursor = draft.grpc_name_cursor;
+        self.grpc_server_url_input = draft.grpc_server_url_input;
+        self.grpc_server_url_cursor = draft.grpc_server_url_cursor;
+        self.grpc_service_name_input = draft.grpc_service_name_input;
+        self.grpc_service_name_cursor = draft.grpc_service_name_cursor;
+        self.grpc_method_name_input = draft.grpc_method_name_input;
+        self.grpc_method_name_cursor = draft.grpc_method_name_cursor;
+        self.grpc_message_editor = draft.grpc_message_editor;
+        self.grpc_metadata_input = draft.grpc_metadata_input;
+        self.grpc_metadata_selected = draft.grpc_metadata_selected;
+    }
+
+    // Request list scroll helpers
+    pub fn scroll_request_list_to(&mut self, position: u16) {
+        self.request_list_scroll = position;
+    }
+
+    pub fn reset_request_list_scroll(&mut self) {
+        self.request_list_scroll = 0;
+    }
+
+    // Search/filter helpers
+    pub fn enter_request_search_mode(&mut self) {
+        self.request_search_mode = true;
+        self.request_search_input.clear();
+        self.request_search_cursor = 0;
+        self.filtered_request_indices.clear();
+    }
+
+    pub fn exit_request_search_mode(&mut self) {
+        self.request_search_mode = false;
+        self.request_search_input.clear();
+        self.request_search_cursor = 0;
+        self.filtered_request_indices.clear();
+    }
+
+    /// Search the requests of the selected collection, in all of its folders
+    pub fn update_request_filter(&mut self) {
+        self.filtered_request_indices.clear();
+
+        if self.request_search_input.is_empty() {
+            return; // No filter, show all
+        }
+
+        let search_lower = self.request_search_input.to_lowercase();
+        let selected_collection_id = self.selected_collection
+            .and_then(|idx| self.collections.get(idx))
+            .map(|c| c.id);
+
+        for (idx, request) in self.requests.iter().enumerate() {
+            // Check if request belongs to current collection
+            let in_collection = match (selected_collection_id, request.collection_id()) {
+                (Some(selected_id), Some(request_id)) => selected_id == request_id,
+                (None, None) => true,
+                _ => false,
+            };
+
+            if in_collection && request.matches_search(&search_lower) {
+                self.filtered_request_indices.push(idx);
+            }
+        }
+    }
+
+    /// Indices of the requests shown in the request list, in order: those directly in the
+    /// selected collection or folder, or the search results while searching
+    pub fn visible_request_indices(&self) -> Vec<usize> {
+        if self.request_search_mode && !self.request_search_input.is_empty() {
+            return self.filtered_request_indices.clone();
+        }
+        self.node_request_indices()
+    }
+
+    /// Indices of the HTTP and gRPC requests directly in the selected collection or folder
+    pub fn node_request_indices(&self) -> Vec<usize> {
+        self.requests.iter().enumerate()
+            .filter(|(_, r)| self.in_selected_node(r.collection_id(), r.id()))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Indices of the requests the export popup offers from the chosen collection
+    pub fn export_request_indices(&self) -> Vec<usize> {
+        let Some(collection) = self.export_selected_collection.and_then(|idx| self.collections.get(idx)) else {
+            return Vec::new();
+        };
+        self.requests.iter().enumerate()
+            .filter(|(_, r)| Some(r.protocol()) == self.export_protocol() && r.collection_id() == Some(collection.id))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// The kind of request the export popup exports
+    pub fn export_protocol(&self) -> Option<ProtocolType> {
+        match self.export_mode {
+            Some(ExportMode::RequestCurl) => Some(ProtocolType::Http),
+            Some(ExportMode::GrpcRequestGrpcurl) => Some(ProtocolType::Grpc),
+            None => None,
+        }
+    }
+
+    pub fn get_request_list_position(&self) -> Option<(usize, usize)> {
+        // Returns (current_position_1_indexed, total_count)
+        let selected_idx = self.selected_request?;
+        let visible_indices = self.visible_request_indices();
+        let pos = visible_indices.iter().position(|&idx| idx == selected_idx)?;
+        Some((pos + 1, visible_indices.len())) // 1-indexed for display
+    }
+
+    // gRPC request helpers
+
+    #[allow(dead_code)]
+    pub fn get_current_grpc_request(&self) -> Option<&GrpcRequest> {
+        self.selected_request.and_then(|idx| self.requests.get(idx)).and_then(RequestType::as_grpc)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_current_grpc_request_mut(&mut self) -> Option<&mut GrpcRequest> {
+        self.selected_request.and_then(|idx| self.requests.get_mut(idx)).and_then(RequestType::as_grpc_mut)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_all_requests_count(&self) -> usize {
+        self.requests.len()
+    }
+
+    // Proto file management helpers
+
+    pub fn get_selected_proto_schema(&self) -> Option<&ProtoSchema> {
         self.selected_proto_schema
             .and_then(|idx| self.proto_schemas.get(idx))
     }
@@ -903,6 +3088,253 @@ impl AppState {
         self.proto_load_result = None;
         self.proto_loader_focus = ProtoLoaderFocus::Input;
     }
+
+    // Proxy settings helpers
+
+    /// The proxy settings that apply to requests in a collection
+    pub fn effective_proxy(&self, collection_id: Option<Uuid>) -> &ProxySettings {
+        collection_id
+            .and_then(|id| self.collection_proxies.get(&id))
+            .unwrap_or(&self.proxy_settings)
+    }
+
+    // Defaults of collections and folders
+
+    /// What a request inherits from its collection and the folders it is in
+    pub fn inherited_for(&self, collection_id: Option<Uuid>, request_id: Uuid) -> Inherited {
+        let Some(collection) = collection_id.and_then(|id| self.collections.iter().find(|c| c.id == id)) else {
+            return Inherited::default();
+        };
+        let folders = self.request_folders.get(&request_id).map_or_else(Vec::new, |&folder_id| {
+            let mut chain = folder::ancestors(&self.folders, folder_id);
+            chain.push(folder_id);
+            chain
+        });
+        let chain = std::iter::once((collection.name.clone(), collection.id)).chain(
+            folders.into_iter().map(|id| (format!("{}/{}", collection.name, folder::path(&self.folders, id)), id)),
+        );
+        Inherited::from_chain(chain.filter_map(|(source, id)| Some((source, self.node_defaults.get(&id)?))))
+    }
+
+    /// The HTTP request with everything inherited applied, as it is sent and exported
+    pub fn resolve_http_request(&self, request: &HttpRequest) -> HttpRequest {
+        self.inherited_for(request.collection_id, request.id).resolve_http(request)
+    }
+
+    /// The gRPC request with everything inherited applied, as it is sent and exported
+    pub fn resolve_grpc_request(&self, request: &GrpcRequest) -> GrpcRequest {
+        self.inherited_for(request.collection_id, request.id).resolve_grpc(request)
+    }
+
+    /// What the selected request inherits, for showing it in the editor
+    pub fn current_inherited(&self) -> Inherited {
+        match self.protocol_type() {
+            ProtocolType::Http => self.get_current_request().map(|r| self.inherited_for(r.collection_id, r.id)),
+            ProtocolType::Grpc => self.get_current_grpc_request().map(|r| self.inherited_for(r.collection_id, r.id)),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Edit the defaults of the selected folder, or of the collection when no folder is selected
+    pub fn open_defaults_editor(&mut self) {
+        let target = self.selected_folder
+            .or_else(|| self.selected_collection.and_then(|idx| self.collections.get(idx)).map(|c| c.id));
+        let Some(target) = target else {
+            return;
+        };
+        let text = self.node_defaults.get(&target).cloned().unwrap_or_default().to_toml();
+        self.defaults_target = Some(target);
+        self.defaults_editor = TextEditor::new(&text);
+        self.defaults_error = None;
+        self.show_defaults_editor = true;
+    }
+
+    pub fn close_defaults_editor(&mut self) {
+        self.show_defaults_editor = false;
+        self.defaults_target = None;
+        self.defaults_error = None;
+    }
+
+    /// Take the edited defaults, keeping the editor open with the error when they do not parse
+    pub fn apply_defaults_editor(&mut self) -> Option<(Uuid, RequestDefaults)> {
+        let target = self.defaults_target?;
+        match RequestDefaults::from_toml(&self.defaults_editor.text()) {
+            Ok(defaults) => {
+                if defaults.is_empty() {
+                    self.node_defaults.remove(&target);
+                } else {
+                    self.node_defaults.insert(target, defaults.clone());
+                }
+                self.close_defaults_editor();
+                Some((target, defaults))
+            }
+            Err(e) => {
+                self.defaults_error = Some(format!("{:#}", e));
+                None
+            }
+        }
+    }
+
+    /// `Collection/Folder` whose defaults are being edited
+    pub fn defaults_target_label(&self) -> String {
+        let Some(target) = self.defaults_target else {
+            return String::new();
+        };
+        if let Some(collection) = self.collections.iter().find(|c| c.id == target) {
+            return collection.name.clone();
+        }
+        let collection = self.folders.iter()
+            .find(|f| f.id == target)
+            .and_then(|f| self.collections.iter().find(|c| c.id == f.collection_id))
+            .map_or("", |c| c.name.as_str());
+        format!("{}/{}", collection, folder::path(&self.folders, target))
+    }
+
+    pub fn open_command_palette(&mut self) {
+        self.show_command_palette = true;
+        self.command_palette = CommandPalette::default();
+    }
+
+    pub fn close_command_palette(&mut self) {
+        self.show_command_palette = false;
+    }
+
+    /// Select and focus a collection picked by id, e.g. from the command palette
+    pub fn jump_to_collection(&mut self, collection_id: Uuid) {
+        if let Some(idx) = self.collections.iter().position(|c| c.id == collection_id) {
+            self.exit_request_search_mode();
+            self.focus_panel(Panel::Collections);
+            self.select_collection(idx);
+        }
+    }
+
+    /// Select and focus a folder picked by id, opening the tree down to it
+    pub fn jump_to_folder(&mut self, folder_id: Uuid) {
+        let Some(collection_id) = self.folders.iter().find(|f| f.id == folder_id).map(|f| f.collection_id) else {
+            return;
+        };
+        if let Some(idx) = self.collections.iter().position(|c| c.id == collection_id) {
+            self.exit_request_search_mode();
+            self.focus_panel(Panel::Collections);
+            self.reveal_folder(folder_id);
+            self.select_node(idx, Some(folder_id));
+        }
+    }
+
+    /// Select and focus a request of either protocol, switching protocol and collection as needed
+    pub fn jump_to_request(&mut self, request_id: Uuid) {
+        let Some(request) = self.requests.iter().find(|r| r.id() == request_id) else {
+            return;
+        };
+        let target = TabTarget { protocol: request.protocol(), request_id: Some(request_id) };
+        self.exit_request_search_mode();
+        self.focus_panel(Panel::Requests);
+        if self.current_request_id() != Some(request_id) {
+            self.select_tab_target(target);
+            self.clear_input_buffers();
+        }
+    }
+
+    pub fn open_proxy_settings(&mut self) {
+        self.show_proxy_settings = true;
+        self.load_proxy_form(ProxyScope::Global);
+    }
+
+    pub fn close_proxy_settings(&mut self) {
+        self.show_proxy_settings = false;
+        self.proxy_form.error = None;
+    }
+
+    /// Switch the proxy popup between the global settings and the selected collection
+    pub fn toggle_proxy_scope(&mut self) {
+        let scope = match self.proxy_form.scope {
+            ProxyScope::Global => match self.selected_collection.and_then(|idx| self.collections.get(idx)) {
+                Some(collection) => ProxyScope::Collection(collection.id),
+                None => return,
+            },
+            ProxyScope::Collection(_) => ProxyScope::Global,
+        };
+        self.load_proxy_form(scope);
+    }
+
+    fn load_proxy_form(&mut self, scope: ProxyScope) {
+        self.proxy_form = match scope {
+            ProxyScope::Global => ProxyForm::new(scope, false, &self.proxy_settings),
+            ProxyScope::Collection(id) => match self.collection_proxies.get(&id) {
+                Some(settings) => ProxyForm::new(scope, false, settings),
+                None => ProxyForm::new(scope, true, &self.proxy_settings),
+            },
+        };
+    }
+
+    pub fn proxy_scope_label(&self) -> String {
+        match self.proxy_form.scope {
+            ProxyScope::Global => "Global".to_string(),
+            ProxyScope::Collection(id) => {
+                let name = self.collections.iter()
+                    .find(|c| c.id == id)
+                    .map(|c| c.name.as_str())
+                    .unwrap_or("Unknown");
+                format!("Collection: {}", name)
+            }
+        }
+    }
+}
+
+/// Write the editor buffers of a draft into an HTTP request
+fn apply_http_draft(request: &mut HttpRequest, draft: &EditorDraft) {
+    let name = draft.name_input.trim();
+    if !name.is_empty() {
+        request.name = name.to_string();
+    }
+
+    if let Some(method) = crate::models::request::HttpMethod::all().get(draft.method_input) {
+        request.method = method.clone();
+    }
+
+    request.url = draft.url_input.clone();
+
+    request.query_params.clear();
+    for (key, value) in &draft.params_input {
+        if !key.is_empty() {
+            request.query_params.insert(key.clone(), value.clone());
+        }
+    }
+
+    request.headers.clear();
+    for (key, value) in &draft.headers_input {
+        if !key.is_empty() {
+            request.headers.insert(key.clone(), value.clone());
+        }
+    }
+
+    request.body = if !draft.body_editor.is_empty() {
+        Some(draft.body_editor.text())
+    } else {
+        None
+    };
+
+    request.auth = crate::models::request::AuthType::from_field_values(draft.auth_kind_input, &draft.auth_values_input);
+}
+
+/// Write the editor buffers of a draft into a gRPC request
+fn apply_grpc_draft(request: &mut GrpcRequest, draft: &EditorDraft) {
+    let name = draft.grpc_name_input.trim();
+    if !name.is_empty() {
+        request.name = name.to_string();
+    }
+
+    request.server_url = draft.grpc_server_url_input.clone();
+    request.service_name = draft.grpc_service_name_input.clone();
+    request.method_name = draft.grpc_method_name_input.clone();
+    request.message_json = draft.grpc_message_editor.text();
+
+    request.metadata.clear();
+    for (key, value) in &draft.grpc_metadata_input {
+        if !key.is_empty() {
+            request.metadata.insert(key.clone(), value.clone());
+        }
+    }
 }
 
 impl Default for AppState {
@@ -911,3 +3343,404 @@ impl Default for AppState {
     }
 }
 
+/// The position above or below `position`, `None` above the first
+fn neighbour(position: usize, down: bool) -> Option<usize> {
+    if down {
+        Some(position + 1)
+    } else {
+        position.checked_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::request::HttpMethod;
+
+    /// State with `count` HTTP requests, the first one selected in a preview tab
+    fn state_with_requests(count: usize) -> AppState {
+        let mut state = AppState::new();
+        for idx in 0..count {
+            state.requests.push(RequestType::Http(HttpRequest::new(format!("r{}", idx), HttpMethod::GET, "http://localhost".to_string())));
+        }
+        state.selected_request = Some(0);
+        state.sync_tabs_with_selection();
+        state
+    }
+
+    fn abort_handle() -> AbortHandle {
+        tokio::spawn(std::future::pending::<()>()).abort_handle()
+    }
+
+    #[tokio::test]
+    async fn in_flight_requests_are_tracked_per_tab() {
+        let mut state = state_with_requests(1);
+        let request_id = state.requests[0].id();
+
+        let first = abort_handle();
+        state.track_in_flight(Uuid::new_v4(), request_id, ProtocolType::Http, first.clone());
+        assert!(state.is_loading);
+
+        // Re-sending from the same tab aborts the older dispatch
+        let second_id = Uuid::new_v4();
+        state.track_in_flight(second_id, request_id, ProtocolType::Http, abort_handle());
+        tokio::task::yield_now().await;
+        assert!(first.is_finished());
+        assert_eq!(state.in_flight.len(), 1);
+
+        let finished = state.finish_in_flight(second_id).unwrap();
+        assert_eq!(finished.request_id, request_id);
+        assert!(!state.is_loading);
+        assert!(state.finish_in_flight(second_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn cancelled_results_are_dropped() {
+        let mut state = state_with_requests(1);
+        let dispatch_id = Uuid::new_v4();
+        let handle = abort_handle();
+        state.track_in_flight(dispatch_id, state.requests[0].id(), ProtocolType::Http, handle.clone());
+
+        let cancelled = state.cancel_in_flight().unwrap();
+        assert_eq!(cancelled.dispatch_id, dispatch_id);
+        tokio::task::yield_now().await;
+        assert!(handle.is_finished());
+        assert!(!state.is_loading);
+
+        // A response arriving after the cancel finds nothing to finish
+        assert!(state.finish_in_flight(dispatch_id).is_none());
+        assert!(state.cancel_in_flight().is_none());
+    }
+
+    #[tokio::test]
+    async fn filter_waits_for_typing_to_pause_and_drops_stale_results() {
+        let mut state = AppState::new();
+        state.response_json = Some(vec![serde_json::json!({"id": 1})]);
+        state.response_filter_input = ".i".to_string();
+        state.debounce_response_filter();
+        let start = Instant::now();
+        assert!(state.take_due_response_filter(start).is_none());
+        assert!(state.is_response_filter_pending());
+
+        let job = state.take_due_response_filter(start + FILTER_DEBOUNCE).unwrap();
+        assert_eq!(job.expression, ".i");
+        let task = abort_handle();
+        state.response_filter_task = Some(task.clone());
+
+        // Typing on aborts the running evaluation and ignores its result
+        state.response_filter_input = ".id".to_string();
+        state.debounce_response_filter();
+        tokio::task::yield_now().await;
+        assert!(task.is_finished());
+        state.apply_response_filter_result(job.generation, Ok(vec![serde_json::Value::Null]));
+        assert!(state.response_filter_result.is_none());
+
+        let job = state.take_due_response_filter(Instant::now() + FILTER_DEBOUNCE).unwrap();
+        state.apply_response_filter_result(job.generation, Ok(vec![serde_json::json!(1)]));
+        assert_eq!(state.response_filter_result, Some(Ok(vec![serde_json::json!(1)])));
+        assert_eq!(state.response_display_text(), Some("1"));
+        assert!(!state.is_response_filter_pending());
+    }
+
+    #[test]
+    fn diff_compares_the_shown_response_with_the_pinned_or_previous_one() {
+        let mut state = state_with_requests(1);
+        let request_id = state.requests[0].id();
+        state.open_diff_popup();
+        assert!(!state.show_diff_popup);
+
+        let first = response_for(request_id);
+        let second = response_for(request_id);
+        let third = response_for(request_id);
+        for response in [&first, &second, &third] {
+            state.record_response(request_id, response);
+        }
+        state.current_response = Some(third.clone());
+        state.open_diff_popup();
+        assert_eq!((state.diff_a, state.diff_b), (Some(second.id), Some(third.id)));
+
+        // A pinned response becomes the baseline
+        state.current_response = Some(first.clone());
+        state.toggle_pinned_response();
+        state.current_response = Some(third.clone());
+        state.open_diff_popup();
+        assert_eq!((state.diff_a, state.diff_b), (Some(first.id), Some(third.id)));
+        assert_eq!(state.diff_candidates().len(), 3);
+
+        state.show_diff();
+        assert_eq!(state.diff_stage, DiffStage::ShowingDiff);
+        assert!(!state.diff_report.is_empty());
+    }
+
+    fn select(state: &mut AppState, idx: usize) {
+        state.selected_request = Some(idx);
+        state.sync_tabs_with_selection();
+    }
+
+    fn response_for(request_id: Uuid) -> HttpResponse {
+        let mut response = HttpResponse::failed(String::new(), None);
+        response.request_id = request_id;
+        response
+    }
+
+    #[test]
+    fn preview_tab_follows_selection_until_pinned() {
+        let mut state = state_with_requests(3);
+        select(&mut state, 1);
+        assert_eq!(state.tabs.len(), 1);
+        assert_eq!(state.tabs[0].target.request_id, Some(state.requests[1].id()));
+
+        state.pin_active_tab();
+        select(&mut state, 2);
+        assert_eq!(state.tabs.len(), 2);
+        assert!(!state.tabs[0].preview);
+        assert!(state.tabs[1].preview);
+        assert_eq!(state.active_tab, 1);
+
+        // Selecting the pinned request switches back to its tab
+        select(&mut state, 1);
+        assert_eq!(state.tabs.len(), 2);
+        assert_eq!(state.active_tab, 0);
+    }
+
+    #[test]
+    fn pinned_tab_keeps_its_response() {
+        let mut state = state_with_requests(2);
+        let first_id = state.requests[0].id();
+        state.pin_active_tab();
+        state.apply_http_response(state.active_tab_id().unwrap(), response_for(first_id));
+
+        // A new request (e.g. from `n`) is selected in its own tab
+        select(&mut state, 1);
+        assert!(state.current_response.is_none());
+
+        select(&mut state, 0);
+        assert_eq!(state.current_response.as_ref().map(|r| r.request_id), Some(first_id));
+    }
+
+    #[test]
+    fn drafts_are_kept_per_tab() {
+        let mut state = state_with_requests(2);
+        state.load_current_request_to_input();
+        state.input_mode = InputMode::Editing;
+        assert!(!state.tab_has_unsaved_edits(0), "entering edit mode alone is not an edit");
+
+        state.url_input = "http://localhost/edited".to_string();
+        assert!(state.tab_has_unsaved_edits(0));
+        state.pin_active_tab();
+
+        select(&mut state, 1);
+        assert_eq!(state.input_mode, InputMode::Normal);
+        assert!(state.url_input.is_empty());
+        assert!(state.tab_has_unsaved_edits(0));
+        assert!(!state.tab_has_unsaved_edits(1));
+
+        select(&mut state, 0);
+        assert_eq!(state.input_mode, InputMode::Editing);
+        assert_eq!(state.url_input, "http://localhost/edited");
+
+        state.save_input_to_request();
+        assert!(!state.tab_has_unsaved_edits(0));
+    }
+
+    #[tokio::test]
+    async fn closing_a_tab_aborts_its_request() {
+        let mut state = state_with_requests(2);
+        state.pin_active_tab();
+        let handle = abort_handle();
+        state.track_in_flight(Uuid::new_v4(), state.requests[0].id(), ProtocolType::Http, handle.clone());
+        select(&mut state, 1);
+        assert!(state.is_tab_loading(state.tabs[0].id));
+        assert!(!state.is_loading, "the loading state follows the active tab");
+
+        state.switch_to_tab(0);
+        assert!(state.is_loading);
+        state.close_active_tab();
+        tokio::task::yield_now().await;
+        assert!(handle.is_finished());
+        assert!(state.in_flight.is_empty());
+        assert_eq!(state.tabs.len(), 1);
+        assert_eq!(state.get_current_request().map(|r| r.id), Some(state.requests[1].id()));
+    }
+
+    #[test]
+    fn tabs_of_deleted_requests_are_closed() {
+        let mut state = state_with_requests(3);
+        state.pin_active_tab();
+        select(&mut state, 1);
+        state.pin_active_tab();
+        select(&mut state, 2);
+        assert_eq!(state.tabs.len(), 3);
+
+        // Delete the first request while the third is shown
+        state.requests.remove(0);
+        state.selected_request = Some(1);
+        state.sync_tabs_with_selection();
+        assert_eq!(state.tabs.len(), 2);
+        assert!(state.tabs.iter().all(|tab| tab.target.request_id.is_some()));
+        assert_eq!(state.tabs[state.active_tab].target.request_id, Some(state.requests[1].id()));
+    }
+
+    #[test]
+    fn folders_form_a_tree_and_move_with_their_requests() {
+        let mut state = state_with_requests(3);
+        let shop = Collection::new("Shop".to_string());
+        let other = Collection::new("Other".to_string());
+        for request in &mut state.requests {
+            request.set_collection_id(Some(shop.id));
+        }
+        state.collections = vec![shop.clone(), other.clone()];
+        state.select_collection(0);
+
+        // Users/Admin, with a request in each and one left at the top
+        let mut new_folder = |name: &str| {
+            let id = state.new_folder().unwrap().id;
+            state.collection_name_input = name.to_string();
+            state.save_collection_name();
+            id
+        };
+        let users = new_folder("Users");
+        let admin = new_folder("Admin");
+        state.request_folders.insert(state.requests[1].id(), users);
+        state.request_folders.insert(state.requests[2].id(), admin);
+
+        let tree: Vec<(usize, Option<Uuid>, usize)> =
+            state.collection_tree().iter().map(|row| (row.collection, row.folder, row.depth)).collect();
+        assert_eq!(tree, vec![(0, None, 0), (0, Some(users), 1), (0, Some(admin), 2), (1, None, 0)]);
+        assert_eq!(state.selected_folder_path().as_deref(), Some("Users/Admin"));
+        assert_eq!(state.node_request_indices(), vec![2]);
+
+        state.collapse_tree_node();
+        assert_eq!(state.selected_folder, Some(users));
+        assert_eq!(state.node_request_indices(), vec![1]);
+        state.collapse_tree_node();
+        assert_eq!(state.collection_tree().len(), 3);
+
+        // Not into itself, but anywhere else along with everything inside
+        assert!(state.start_moving_folder());
+        assert!(state.drop_folder().is_err());
+        state.select_collection(1);
+        assert_eq!(state.drop_folder(), Ok(vec![users, admin]));
+        assert!(state.folders.iter().all(|f| f.collection_id == other.id));
+        assert_eq!(state.requests[1].collection_id(), Some(other.id));
+        assert_eq!(state.requests[2].collection_id(), Some(other.id));
+        assert_eq!(state.requests[0].collection_id(), Some(shop.id));
+        assert_eq!((state.selected_collection, state.selected_folder), (Some(1), Some(users)));
+    }
+
+    #[test]
+    fn http_and_grpc_requests_share_one_list() {
+        let mut state = state_with_requests(2);
+        let mut grpc = GrpcRequest::new("Get user".to_string(), "localhost:50051".to_string());
+        grpc.service_name = "user.UserService".to_string();
+        state.requests.insert(1, RequestType::Grpc(grpc));
+        assert_eq!(state.node_request_indices(), vec![0, 1, 2]);
+
+        // The editor and response view follow the selected request
+        state.next_request();
+        state.sync_tabs_with_selection();
+        assert_eq!(state.protocol_type(), ProtocolType::Grpc);
+        assert_eq!(state.get_current_grpc_request().map(|r| r.name.as_str()), Some("Get user"));
+        assert!(state.get_current_request().is_none());
+        assert_eq!(state.tabs[state.active_tab].target.protocol, ProtocolType::Grpc);
+        state.next_request();
+        assert_eq!(state.protocol_type(), ProtocolType::Http);
+
+        // Search covers both kinds
+        state.request_search_input = "user".to_string();
+        state.update_request_filter();
+        assert_eq!(state.filtered_request_indices, vec![1]);
+        state.request_search_input = "localhost".to_string();
+        state.update_request_filter();
+        assert_eq!(state.filtered_request_indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn requests_reorder_and_paste_between_collections() {
+        let mut state = state_with_requests(3);
+        let shop = Collection::new("Shop".to_string());
+        let other = Collection::new("Other".to_string());
+        for request in &mut state.requests {
+            request.set_collection_id(Some(shop.id));
+        }
+        state.collections = vec![shop.clone(), other.clone()];
+        state.select_collection(0);
+        let ids: Vec<Uuid> = state.requests.iter().map(RequestType::id).collect();
+
+        // r0 moves below r1, and stops at the end
+        assert_eq!(state.shift_request(true).unwrap(), vec![(ids[1], 0), (ids[0], 1), (ids[2], 2)]);
+        assert_eq!(state.current_request_id(), Some(ids[0]));
+        state.shift_request(true).unwrap();
+        assert!(state.shift_request(true).unwrap().is_empty());
+
+        // A copy keeps the original and gets a new id, a cut request moves
+        state.copy_request();
+        state.select_collection(1);
+        let copy_id = state.paste_request().unwrap();
+        assert_ne!(copy_id, ids[0]);
+        assert_eq!(state.requests.len(), 4);
+        state.select_collection(0);
+        state.select_request(state.requests.iter().position(|r| r.id() == ids[2]).unwrap());
+        state.cut_request();
+        assert!(state.paste_request().is_err());
+        state.cut_request();
+        state.select_collection(1);
+        assert_eq!(state.paste_request().unwrap(), ids[2]);
+        assert_eq!(state.requests.len(), 4);
+        let names: Vec<&str> = state.node_request_indices().iter().map(|&idx| state.requests[idx].name()).collect();
+        assert_eq!(names, vec!["r0", "r2"]);
+        assert!(state.request_clipboard.is_none());
+
+        // Duplicating a collection copies its requests under new ids
+        state.select_collection(0);
+        assert!(state.shift_collection(true));
+        assert_eq!(state.collections[1].id, shop.id);
+        let copy = state.duplicate_collection().unwrap();
+        assert_eq!(state.collections[2].name, "Shop (copy)");
+        let copied: Vec<&RequestType> = state.requests.iter().filter(|r| r.collection_id() == Some(copy)).collect();
+        assert_eq!(copied.len(), 2);
+        assert!(copied.iter().all(|r| !ids.contains(&r.id())));
+    }
+
+    #[test]
+    fn deleted_items_go_to_the_trash_until_restored_or_purged() {
+        let mut state = state_with_requests(2);
+        let shop = Collection::new("Shop".to_string());
+        let users = Folder::new("Users".to_string(), shop.id, None);
+        for request in &mut state.requests {
+            request.set_collection_id(Some(shop.id));
+        }
+        let ids: Vec<Uuid> = state.requests.iter().map(RequestType::id).collect();
+        state.request_folders.insert(ids[1], users.id);
+        state.folders.push(users.clone());
+        state.collections.push(shop.clone());
+        state.select_collection(0);
+
+        // A request on its own, then the collection with the rest, after asking
+        state.trash_request().unwrap();
+        assert!(state.confirm_node_deletion());
+        assert_eq!(state.confirmation.as_ref().unwrap().message, "Delete collection \"Shop\" with 1 folder, 1 request?");
+        assert_eq!(state.trash_collection().unwrap().summary(), "1 folder, 1 request");
+        assert!(state.collections.is_empty() && state.folders.is_empty() && state.requests.is_empty());
+        assert_eq!(state.trash.len(), 2);
+
+        // The request needs its collection back first
+        let request_entry = state.trash[1].id;
+        assert!(state.restore_from_trash(request_entry).unwrap_err().contains("Shop"));
+
+        // Undo restores the collection, then the request deleted before it
+        assert_eq!(state.undo_delete().unwrap().kind, TrashKind::Collection);
+        assert_eq!(state.collections[0].id, shop.id);
+        assert_eq!(state.request_folders.get(&ids[1]), Some(&users.id));
+        assert_eq!(state.undo_delete().unwrap().kind, TrashKind::Request);
+        assert_eq!(state.current_request_id(), Some(ids[0]));
+        assert_eq!(state.requests.len(), 2);
+        assert!(state.undo_delete().is_err());
+
+        // Purged entries are gone for good
+        state.trash_request().unwrap();
+        let purged = state.trash[0].id;
+        assert!(state.purge_from_trash(purged).is_some());
+        assert!(state.trash.is_empty() && state.undo_stack.is_empty());
+    }
+}
@@ -0,0 +1,305 @@
+use crate::utils::json_format::reformat_json;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cell::Cell;
+use tui_textarea::{CursorMove, TextArea};
+
+/// Spaces added per nesting level by auto-indent
+const INDENT: &str = "  ";
+
+/// Undo steps kept per editor, every typed character is one step
+const MAX_UNDO_STEPS: usize = 1000;
+
+/// A multi-line buffer for the HTTP body and the gRPC message.
+///
+/// Cursor movement, selection and undo come from `tui_textarea`; this adds
+/// bracket-aware auto-indent, JSON formatting and bracket matching. It is drawn
+/// by `ui::components::text_editor::TextEditorView`.
+#[derive(Clone, Debug)]
+pub struct TextEditor {
+    textarea: TextArea<'static>,
+    /// Top row and left column drawn last frame, the view only scrolls once the cursor leaves it
+    pub(crate) scroll: Cell<(usize, usize)>,
+}
+
+impl Default for TextEditor {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl TextEditor {
+    pub fn new(text: &str) -> Self {
+        let lines = text
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+            .collect();
+        let mut textarea = TextArea::new(lines);
+        textarea.set_max_histories(MAX_UNDO_STEPS);
+        textarea.set_tab_length(INDENT.len() as u8);
+        Self {
+            textarea,
+            scroll: Cell::new((0, 0)),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.textarea.lines().join("\n")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.textarea.is_empty()
+    }
+
+    pub fn lines(&self) -> &[String] {
+        self.textarea.lines()
+    }
+
+    /// 0-based (row, character column)
+    pub fn cursor(&self) -> (usize, usize) {
+        self.textarea.cursor()
+    }
+
+    /// Put the cursor at (row, character column), clamped to the text, dropping the selection
+    pub fn move_cursor_to(&mut self, row: usize, col: usize) {
+        self.textarea.cancel_selection();
+        let clamp = |n: usize| n.min(u16::MAX as usize) as u16;
+        self.textarea.move_cursor(CursorMove::Jump(clamp(row), clamp(col)));
+    }
+
+    /// Selected range as (start, end), end exclusive
+    pub fn selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        self.textarea.selection_range()
+    }
+
+    /// Typing, deleting and moving; returns whether the key was used
+    pub fn input(&mut self, key: KeyEvent) -> bool {
+        match (key.code, key.modifiers) {
+            (KeyCode::Enter, KeyModifiers::NONE) => {
+                self.insert_newline();
+                true
+            }
+            (KeyCode::Char(c @ ('}' | ']')), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                self.dedent_blank_line();
+                self.textarea.insert_char(c);
+                true
+            }
+            (KeyCode::Home, KeyModifiers::CONTROL) => {
+                self.textarea.move_cursor(CursorMove::Top);
+                true
+            }
+            (KeyCode::End, KeyModifiers::CONTROL) => {
+                self.textarea.move_cursor(CursorMove::Bottom);
+                self.textarea.move_cursor(CursorMove::End);
+                true
+            }
+            _ => self.textarea.input(key),
+        }
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.textarea.undo()
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.textarea.redo()
+    }
+
+    pub fn select_all(&mut self) {
+        self.textarea.select_all();
+    }
+
+    /// Copy the selection, `None` when nothing is selected
+    pub fn copy(&mut self) -> Option<String> {
+        if !self.textarea.is_selecting() {
+            return None;
+        }
+        self.textarea.copy();
+        Some(self.textarea.yank_text())
+    }
+
+    /// Cut the selection, `None` when nothing is selected
+    pub fn cut(&mut self) -> Option<String> {
+        if !self.textarea.is_selecting() {
+            return None;
+        }
+        self.textarea.cut();
+        Some(self.textarea.yank_text())
+    }
+
+    /// Insert text over the selection, as one undo step
+    pub fn paste(&mut self, text: &str) {
+        // Some terminals send pasted line breaks as a bare `\r`
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        self.textarea.insert_str(text);
+    }
+
+    /// Paste the last text copied or cut in any editor, for when the system clipboard is unavailable
+    pub fn paste_yanked(&mut self) {
+        self.textarea.paste();
+    }
+
+    /// Delete everything, can be undone
+    pub fn clear(&mut self) {
+        self.textarea.select_all();
+        self.textarea.delete_char();
+    }
+
+    /// Re-indent the buffer as JSON, leaving it untouched when it does not parse
+    pub fn format_json(&mut self) -> Result<()> {
+        let formatted = reformat_json(&self.text())?;
+        self.replace_text(&formatted);
+        Ok(())
+    }
+
+    /// Swap in new content as one undo step, the cursor goes to the top
+    pub fn replace_text(&mut self, text: &str) {
+        if text == self.text() {
+            return;
+        }
+        self.textarea.select_all();
+        self.textarea.insert_str(text);
+        self.textarea.move_cursor(CursorMove::Top);
+    }
+
+    /// Break the line, keeping its indentation and indenting once more after an opening bracket.
+    ///
+    /// Between a bracket pair the closing bracket goes on its own line below the cursor.
+    fn insert_newline(&mut self) {
+        let (row, col) = self.textarea.cursor();
+        let line = &self.textarea.lines()[row];
+        let indent: String = line.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+        let before = line.chars().take(col).filter(|c| !c.is_whitespace()).last();
+        let after = line.chars().skip(col).find(|c| !c.is_whitespace());
+
+        match (before, after) {
+            (Some('{'), Some('}')) | (Some('['), Some(']')) => {
+                self.textarea.insert_str(format!("\n{indent}{INDENT}\n{indent}"));
+                self.textarea.move_cursor(CursorMove::Up);
+                self.textarea.move_cursor(CursorMove::End);
+            }
+            (Some('{' | '['), _) => {
+                self.textarea.insert_str(format!("\n{indent}{INDENT}"));
+            }
+            _ => {
+                self.textarea.insert_str(format!("\n{indent}"));
+            }
+        }
+    }
+
+    /// Drop one indentation level before a closing bracket typed on a blank line
+    fn dedent_blank_line(&mut self) {
+        if self.textarea.is_selecting() {
+            return;
+        }
+        let (row, col) = self.textarea.cursor();
+        let line = &self.textarea.lines()[row];
+        if line.chars().take(col).all(|c| c == ' ') {
+            for _ in 0..col.min(INDENT.len()) {
+                self.textarea.delete_char();
+            }
+        }
+    }
+
+    /// The bracket under or just before the cursor and its partner, as (row, column) positions.
+    ///
+    /// Brackets inside double-quoted strings are skipped. Strings do not span lines,
+    /// so a stray quote in a non-JSON body only affects its own line.
+    pub fn matching_brackets(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (row, col) = self.textarea.cursor();
+        let under = (row, col);
+        let before = col.checked_sub(1).map(|col| (row, col));
+
+        let mut stack: Vec<(char, (usize, usize))> = Vec::new();
+        let mut before_pair = None;
+        for (r, line) in self.textarea.lines().iter().enumerate() {
+            let mut in_string = false;
+            let mut escaped = false;
+            for (c, ch) in line.chars().enumerate() {
+                if in_string {
+                    if escaped {
+                        escaped = false;
+                    } else if ch == '\\' {
+                        escaped = true;
+                    } else if ch == '"' {
+                        in_string = false;
+                    }
+                    continue;
+                }
+                match ch {
+                    '"' => in_string = true,
+                    '{' | '[' | '(' => stack.push((ch, (r, c))),
+                    '}' | ']' | ')' => {
+                        let Some(&(open_char, open)) = stack.last() else {
+                            continue;
+                        };
+                        if matches!((open_char, ch), ('{', '}') | ('[', ']') | ('(', ')')) {
+                            stack.pop();
+                            let pair = (open, (r, c));
+                            if open == under || pair.1 == under {
+                                return Some(pair);
+                            }
+                            if Some(open) == before || Some(pair.1) == before {
+                                before_pair = Some(pair);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        before_pair
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_text(editor: &mut TextEditor, text: &str) {
+        for c in text.chars() {
+            let code = if c == '\n' { KeyCode::Enter } else { KeyCode::Char(c) };
+            editor.input(key(code));
+        }
+    }
+
+    #[test]
+    fn enter_indents_inside_brackets_and_closers_dedent() {
+        let mut editor = TextEditor::default();
+        type_text(&mut editor, "{\n\"a\": [\n1\n]\n}");
+        assert_eq!(editor.text(), "{\n  \"a\": [\n    1\n  ]\n}");
+
+        let mut editor = TextEditor::new("{}");
+        editor.textarea.move_cursor(CursorMove::Forward);
+        editor.input(key(KeyCode::Enter));
+        assert_eq!(editor.text(), "{\n  \n}");
+        assert_eq!(editor.cursor(), (1, 2));
+    }
+
+    #[test]
+    fn format_and_clear_can_be_undone() {
+        let mut editor = TextEditor::new("{\"b\":1,\"a\":[2]}");
+        editor.format_json().unwrap();
+        assert_eq!(editor.text(), "{\n  \"b\": 1,\n  \"a\": [\n    2\n  ]\n}");
+        editor.clear();
+        assert!(editor.is_empty());
+        while editor.undo() {}
+        assert_eq!(editor.text(), "{\"b\":1,\"a\":[2]}");
+
+        assert!(TextEditor::new("{\"a\":}").format_json().is_err());
+    }
+
+    #[test]
+    fn brackets_match_outside_strings() {
+        let mut editor = TextEditor::new("{\"x\": \"}\", \"y\": [1]}");
+        assert_eq!(editor.matching_brackets(), Some(((0, 0), (0, 19))));
+        editor.textarea.move_cursor(CursorMove::Jump(0, 17));
+        assert_eq!(editor.matching_brackets(), Some(((0, 16), (0, 18))));
+        editor.textarea.move_cursor(CursorMove::Jump(0, 3));
+        assert_eq!(editor.matching_brackets(), None);
+    }
+}
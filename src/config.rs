@@ -0,0 +1,290 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Sections of `config.toml`, also the second word of `NEXUS_*` overrides
+const SECTIONS: [&str; 5] = ["http", "grpc", "paths", "history", "ui"];
+
+/// Defaults read once at startup from `config.toml`, then from `NEXUS_<SECTION>_<KEY>`
+/// environment variables. Everything missing keeps the built-in value.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub http: HttpConfig,
+    pub grpc: GrpcConfig,
+    pub paths: PathsConfig,
+    pub history: HistoryConfig,
+    pub ui: UiConfig,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Timeout of new requests, and of OAuth2 token requests
+    pub timeout_seconds: u64,
+    pub follow_redirects: bool,
+    pub max_redirects: usize,
+    pub verify_tls: bool,
+    /// Sent with every request that does not set the header itself
+    pub headers: BTreeMap<String, String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: 30,
+            follow_redirects: true,
+            max_redirects: 10,
+            verify_tls: true,
+            headers: BTreeMap::from([(
+                "User-Agent".to_string(),
+                concat!("nexus/", env!("CARGO_PKG_VERSION")).to_string(),
+            )]),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GrpcConfig {
+    /// Timeout of new gRPC requests
+    pub timeout_seconds: u64,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self { timeout_seconds: 30 }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathsConfig {
+    /// Where the database is kept, the platform data directory by default
+    pub data_dir: Option<PathBuf>,
+    /// Where `nexus.log` is written, `logs` in the config directory by default
+    pub log_dir: Option<PathBuf>,
+}
+
+impl PathsConfig {
+    pub fn data_dir(&self) -> Result<PathBuf> {
+        match &self.data_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(dirs::data_dir().context("Failed to get data directory")?.join("nexus")),
+        }
+    }
+
+    pub fn log_dir(&self) -> Result<PathBuf> {
+        match &self.log_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(dirs::config_dir().context("Could not find config directory")?.join("nexus").join("logs")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// Responses kept for comparison during a session
+    pub responses: usize,
+    /// Filters remembered per request
+    pub recent_filters: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self { responses: 50, recent_filters: 10 }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// Widths of the collections, requests, editor and response panels in percent
+    pub panel_widths: [u16; 4],
+    /// Take mouse clicks and the wheel; off leaves the mouse to the terminal for selecting text
+    pub mouse: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self { panel_widths: [15, 20, 35, 30], mouse: true }
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+impl Config {
+    /// The config in use, the built-in defaults until `install` is called
+    pub fn global() -> &'static Config {
+        CONFIG.get_or_init(Config::default)
+    }
+
+    /// Make this the config returned by `global`, once at startup before anything reads it
+    pub fn install(self) {
+        let _ = CONFIG.set(self);
+    }
+
+    /// `config.toml` in the nexus config directory
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("nexus").join("config.toml"))
+    }
+
+    /// Read `path`, or `NEXUS_CONFIG`, or the default file when it exists, then apply
+    /// `NEXUS_*` environment overrides. A path given explicitly must exist.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = path
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os("NEXUS_CONFIG").filter(|value| !value.is_empty()).map(PathBuf::from))
+            .or_else(|| Self::default_path().filter(|path| path.exists()));
+
+        let text = match &path {
+            Some(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file {}", path.display()))?,
+            None => String::new(),
+        };
+        Self::from_toml(&text, std::env::vars()).with_context(|| match &path {
+            Some(path) => format!("Invalid config in {}", path.display()),
+            None => "Invalid config".to_string(),
+        })
+    }
+
+    /// Parse `text` with the `NEXUS_<SECTION>_<KEY>` variables among `env` on top.
+    ///
+    /// Override values are read as TOML, so `30`, `false` and `["a"]` keep their type,
+    /// and anything that does not parse is taken as a string. Variables naming no
+    /// known section are left alone, other tools use the prefix too.
+    pub fn from_toml(text: &str, env: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        let mut table: toml::Table = toml::from_str(text)?;
+
+        let mut overrides: Vec<(String, String, toml::Value)> = env
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let rest = name.strip_prefix("NEXUS_")?.to_ascii_lowercase();
+                let (section, key) = rest.split_once('_')?;
+                SECTIONS.contains(&section).then(|| {
+                    let value = toml::from_str::<toml::Table>(&format!("value = {}", value))
+                        .ok()
+                        .and_then(|mut table| table.remove("value"))
+                        .unwrap_or(toml::Value::String(value));
+                    (name, format!("{}.{}", section, key), value)
+                })
+            })
+            .collect();
+        overrides.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (name, key, value) in overrides {
+            let (section, field) = key.split_once('.').unwrap_or_default();
+            // Checked alone first, so a mistake names the variable rather than the file
+            let fields = toml::Table::from_iter([(field.to_string(), value.clone())]);
+            let single = toml::Table::from_iter([(section.to_string(), toml::Value::Table(fields))]);
+            toml::Value::Table(single).try_into::<Config>().with_context(|| format!("Invalid {}", name))?;
+
+            match table.entry(section).or_insert_with(|| toml::Value::Table(toml::Table::new())) {
+                toml::Value::Table(section) => {
+                    section.insert(field.to_string(), value);
+                }
+                _ => bail!("`{}` must be a table", section),
+            }
+        }
+
+        let mut config: Config = toml::Value::Table(table).try_into()?;
+        config.paths.data_dir = config.paths.data_dir.take().map(expand_home).transpose()?;
+        config.paths.log_dir = config.paths.log_dir.take().map(expand_home).transpose()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.http.timeout_seconds == 0 {
+            bail!("http.timeout_seconds must be at least 1");
+        }
+        if self.grpc.timeout_seconds == 0 {
+            bail!("grpc.timeout_seconds must be at least 1");
+        }
+        for (name, value) in &self.http.headers {
+            reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow!("http.headers: `{}` is not a valid header name", name))?;
+            reqwest::header::HeaderValue::from_str(value)
+                .map_err(|_| anyhow!("http.headers: the value of `{}` is not a valid header value", name))?;
+        }
+        for (key, dir) in [("data_dir", &self.paths.data_dir), ("log_dir", &self.paths.log_dir)] {
+            if dir.as_ref().is_some_and(|dir| !dir.is_absolute()) {
+                bail!("paths.{} must be an absolute path or start with ~/", key);
+            }
+        }
+
+        let widths = self.ui.panel_widths;
+        if widths.iter().any(|width| *width < 5) {
+            bail!("ui.panel_widths: every panel needs at least 5%, got {:?}", widths);
+        }
+        let total: u16 = widths.iter().sum();
+        if total != 100 {
+            bail!("ui.panel_widths must add up to 100, got {:?} = {}", widths, total);
+        }
+        Ok(())
+    }
+}
+
+/// Replace a leading `~` with the home directory
+fn expand_home(path: PathBuf) -> Result<PathBuf> {
+    match path.strip_prefix("~") {
+        Ok(rest) => Ok(dirs::home_dir().context("Could not find the home directory for ~")?.join(rest)),
+        Err(_) => Ok(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn environment_overrides_the_file() {
+        let config = Config::from_toml(
+            r#"
+            [http]
+            timeout_seconds = 5
+            verify_tls = false
+
+            [http.headers]
+            "User-Agent" = "tests"
+            "#,
+            env(&[
+                ("NEXUS_HTTP_TIMEOUT_SECONDS", "12"),
+                ("NEXUS_UI_PANEL_WIDTHS", "[25, 25, 25, 25]"),
+                ("NEXUS_PATHS_DATA_DIR", "/tmp/nexus data"),
+                ("NEXUS_URL", "https://repository.example.com"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(config.http.timeout_seconds, 12);
+        assert!(!config.http.verify_tls);
+        assert!(config.http.follow_redirects);
+        assert_eq!(config.http.headers["User-Agent"], "tests");
+        assert_eq!(config.ui.panel_widths, [25, 25, 25, 25]);
+        assert_eq!(config.paths.data_dir, Some(PathBuf::from("/tmp/nexus data")));
+        assert_eq!(config.history, HistoryConfig::default());
+    }
+
+    #[test]
+    fn mistakes_are_reported() {
+        let error = |text: &str, vars: &[(&str, &str)]| format!("{:#}", Config::from_toml(text, env(vars)).unwrap_err());
+
+        assert!(error("[http]\ntimeout = 5", &[]).contains("unknown field `timeout`"));
+        assert_eq!(error("[grpc]\ntimeout_seconds = 0", &[]), "grpc.timeout_seconds must be at least 1");
+        assert_eq!(
+            error("[ui]\npanel_widths = [10, 20, 30, 30]", &[]),
+            "ui.panel_widths must add up to 100, got [10, 20, 30, 30] = 90"
+        );
+        assert_eq!(error("[paths]\nlog_dir = \"logs\"", &[]), "paths.log_dir must be an absolute path or start with ~/");
+        assert!(error("", &[("NEXUS_HTTP_VERIFY_TLS", "maybe")]).starts_with("Invalid NEXUS_HTTP_VERIFY_TLS: "));
+        assert!(error("[http.headers]\n\"Bad Name\" = \"x\"", &[]).contains("not a valid header name"));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::models::{GrpcRequest, GrpcResponse, GrpcStatus, GrpcMessage, ProtoSchema};
 use crate::grpc::proto_loader::ProtoLoader;
+use crate::grpc::proxy::ProxyConnector;
+use crate::models::proxy::ProxySettings;
 use crate::grpc::dynamic_invoker::{DynamicInvoker, dynamic_message_to_json};
 use anyhow::{Result, Context, anyhow};
 use std::time::Instant;
@@ -9,12 +11,19 @@ use prost_reflect::DescriptorPool;
 
 pub struct GrpcClient {
     proto_loader: ProtoLoader,
+    proxy: ProxySettings,
 }
 
 impl GrpcClient {
     pub fn new() -> Self {
+        Self::with_proxy(ProxySettings::default())
+    }
+
+    /// Create a client that connects through the given proxy settings
+    pub fn with_proxy(proxy: ProxySettings) -> Self {
         Self {
             proto_loader: ProtoLoader::new(),
+            proxy,
         }
     }
 
@@ -234,10 +243,20 @@ impl GrpcClient {
                 .connect_timeout(std::time::Duration::from_secs(timeout.min(10)));
         }
 
-        let channel = endpoint
-            .connect()
-            .await
-            .context("Failed to connect to server")?;
+        let host = endpoint.uri().host().unwrap_or_default().to_string();
+        let channel = match self.proxy.resolve(&host, use_tls) {
+            Some(proxy) => {
+                let proxy_url = format!("{}://{}:{}", proxy.scheme.as_str(), proxy.host, proxy.port);
+                endpoint
+                    .connect_with_connector(ProxyConnector::new(proxy))
+                    .await
+                    .with_context(|| format!("Failed to connect to server through proxy {}", proxy_url))?
+            }
+            None => endpoint
+                .connect()
+                .await
+                .context("Failed to connect to server")?,
+        };
 
         Ok(channel)
     }
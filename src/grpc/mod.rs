@@ -1,4 +1,5 @@
 pub mod client;
 pub mod proto_loader;
+pub mod proxy;
 pub mod reflection;
 pub mod dynamic_invoker;
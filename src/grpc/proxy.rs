@@ -0,0 +1,215 @@
+use crate::models::proxy::{ProxyEndpoint, ProxyScheme};
+use base64::Engine;
+use hyper_util::rt::TokioIo;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_native_tls::TlsStream;
+use tonic::transport::Uri;
+use tower::Service;
+
+/// Largest CONNECT response header we are willing to read from a proxy
+const MAX_CONNECT_RESPONSE: usize = 8 * 1024;
+
+/// Connector that tunnels gRPC connections through an HTTP(S) CONNECT or SOCKS5 proxy.
+///
+/// For HTTPS proxies the connection to the proxy itself is encrypted first. TLS to
+/// the gRPC server is layered on top of the tunnel by tonic.
+#[derive(Clone)]
+pub struct ProxyConnector {
+    proxy: ProxyEndpoint,
+}
+
+impl ProxyConnector {
+    pub fn new(proxy: ProxyEndpoint) -> Self {
+        Self { proxy }
+    }
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = TokioIo<ProxyStream>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let proxy = self.proxy.clone();
+        Box::pin(async move {
+            let host = uri.host()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Server URL has no host"))?
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string();
+            let port = uri.port_u16().unwrap_or(if uri.scheme_str() == Some("https") { 443 } else { 80 });
+
+            let stream = match proxy.scheme {
+                ProxyScheme::Http => {
+                    let stream = TcpStream::connect((proxy.host.as_str(), proxy.port)).await?;
+                    ProxyStream::Plain(http_connect(stream, &proxy, &host, port).await?)
+                }
+                ProxyScheme::Https => {
+                    let stream = TcpStream::connect((proxy.host.as_str(), proxy.port)).await?;
+                    let connector = tokio_native_tls::native_tls::TlsConnector::new().map_err(io::Error::other)?;
+                    let stream = tokio_native_tls::TlsConnector::from(connector)
+                        .connect(&proxy.host, stream)
+                        .await
+                        .map_err(|e| io::Error::other(format!("TLS handshake with proxy failed: {}", e)))?;
+                    ProxyStream::Tls(Box::new(http_connect(stream, &proxy, &host, port).await?))
+                }
+                ProxyScheme::Socks5 => ProxyStream::Plain(socks5_connect(&proxy, &host, port).await?),
+            };
+
+            Ok(TokioIo::new(stream))
+        })
+    }
+}
+
+/// Connection to the proxy, encrypted for HTTPS proxies
+pub enum ProxyStream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl AsyncRead for ProxyStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ProxyStream::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            ProxyStream::Tls(stream) => Pin::new(stream.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for ProxyStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            ProxyStream::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            ProxyStream::Tls(stream) => Pin::new(stream.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ProxyStream::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            ProxyStream::Tls(stream) => Pin::new(stream.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            ProxyStream::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            ProxyStream::Tls(stream) => Pin::new(stream.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Open a tunnel to `host:port` with an HTTP CONNECT request over an established proxy connection
+async fn http_connect<S>(mut stream: S, proxy: &ProxyEndpoint, host: &str, port: u16) -> io::Result<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let authority = if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", authority);
+    if let Some((username, password)) = &proxy.credentials {
+        let token = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response header byte by byte so no tunnelled data is consumed
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_CONNECT_RESPONSE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Proxy CONNECT response is too large"));
+        }
+        if stream.read(&mut byte).await? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Proxy closed the connection during CONNECT"));
+        }
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if !status.starts_with('2') {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!("Proxy refused CONNECT to {}: {}", authority, status_line),
+        ));
+    }
+
+    Ok(stream)
+}
+
+async fn socks5_connect(proxy: &ProxyEndpoint, host: &str, port: u16) -> io::Result<TcpStream> {
+    let proxy_addr = (proxy.host.as_str(), proxy.port);
+    let result = match &proxy.credentials {
+        Some((username, password)) => {
+            tokio_socks::tcp::Socks5Stream::connect_with_password(proxy_addr, (host, port), username, password).await
+        }
+        None => tokio_socks::tcp::Socks5Stream::connect(proxy_addr, (host, port)).await,
+    };
+
+    result
+        .map(|stream| stream.into_inner())
+        .map_err(|e| io::Error::other(format!("SOCKS5 proxy error: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(scheme: ProxyScheme) -> ProxyEndpoint {
+        ProxyEndpoint {
+            scheme,
+            host: "proxy.corp".to_string(),
+            port: 3128,
+            credentials: Some(("me".to_string(), "secret".to_string())),
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_tunnel_over_any_stream() {
+        let (client, mut proxy) = tokio::io::duplex(1024);
+        let fake_proxy = tokio::spawn(async move {
+            let mut request = vec![0u8; 512];
+            let n = proxy.read(&mut request).await.unwrap();
+            proxy.write_all(b"HTTP/1.1 200 Connection established\r\n\r\ntunnelled").await.unwrap();
+            String::from_utf8_lossy(&request[..n]).into_owned()
+        });
+
+        let mut tunnel = http_connect(client, &endpoint(ProxyScheme::Https), "api.example.com", 443).await.unwrap();
+        let request = fake_proxy.await.unwrap();
+        assert!(request.starts_with("CONNECT api.example.com:443 HTTP/1.1\r\n"), "{}", request);
+        assert!(request.contains("Proxy-Authorization: Basic bWU6c2VjcmV0\r\n"), "{}", request);
+
+        // Bytes after the CONNECT response belong to the tunnel
+        let mut rest = String::new();
+        tunnel.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, "tunnelled");
+    }
+
+    #[tokio::test]
+    async fn refused_connect_is_an_error() {
+        let (client, mut proxy) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            let mut request = vec![0u8; 512];
+            let _ = proxy.read(&mut request).await;
+            let _ = proxy.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n").await;
+        });
+
+        let error = http_connect(client, &endpoint(ProxyScheme::Http), "api.example.com", 443).await.err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionRefused);
+    }
+}
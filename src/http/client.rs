@@ -1,5 +1,7 @@
-use crate::models::{request::HttpRequest, response::HttpResponse};
-use anyhow::Result;
+use crate::config::Config;
+use crate::http::{oauth2, sigv4};
+use crate::models::{oauth2::OAuth2Token, proxy::ProxySettings, request::{AuthType, HttpRequest}, response::{HttpResponse, SentRequest}};
+use anyhow::{bail, Context, Result};
 use std::time::Instant;
 use uuid::Uuid;
 
@@ -10,16 +12,85 @@ pub struct HttpClient {
 
 impl HttpClient {
     pub fn new() -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+        Self::with_proxy(&ProxySettings::default())
+    }
+
+    /// Create a client that connects through the given proxy settings
+    pub fn with_proxy(proxy: &ProxySettings) -> Result<Self> {
+        let defaults = &Config::global().http;
+        Self::build(proxy, defaults.follow_redirects, defaults.verify_tls)
+    }
+
+    /// Create a client for sending `request`, which can turn off redirects and certificate
+    /// checks for itself but not turn them back on when the config turns them off
+    pub fn for_request(proxy: &ProxySettings, request: &HttpRequest) -> Result<Self> {
+        let defaults = &Config::global().http;
+        Self::build(
+            proxy,
+            defaults.follow_redirects && request.follow_redirects,
+            defaults.verify_tls && request.verify_ssl,
+        )
+    }
+
+    fn build(proxy: &ProxySettings, follow_redirects: bool, verify_tls: bool) -> Result<Self> {
+        let defaults = &Config::global().http;
+        let redirect = if follow_redirects {
+            reqwest::redirect::Policy::limited(defaults.max_redirects)
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+        let mut builder = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(defaults.timeout_seconds))
+            .redirect(redirect)
+            .danger_accept_invalid_certs(!verify_tls);
+
+        if let Some(endpoint) = proxy.endpoint() {
+            // reqwest only understands the leading-dot form for subdomain rules
+            let no_proxy = proxy.no_proxy
+                .iter()
+                .map(|rule| rule.strip_prefix('*').filter(|r| r.starts_with('.')).unwrap_or(rule))
+                .collect::<Vec<_>>()
+                .join(",");
+            let reqwest_proxy = reqwest::Proxy::all(endpoint.url())
+                .context("Invalid proxy settings")?
+                .no_proxy(reqwest::NoProxy::from_string(&no_proxy));
+            // Setting an explicit proxy also turns off the system proxy lookup
+            builder = builder.proxy(reqwest_proxy);
+        } else if proxy.enabled || !proxy.use_system_env {
+            builder = builder.no_proxy();
+        }
+
+        let client = builder.build()?;
         
         Ok(Self { client })
     }
     
-    pub async fn execute(&self, request: &HttpRequest) -> Result<HttpResponse> {
-        let start = Instant::now();
-        
+    /// Replace OAuth2 settings on the request with an `Authorization` header carrying an
+    /// access token. Returns the token when a new one was issued, so the caller can cache it.
+    pub async fn prepare_oauth2(
+        &self,
+        request: &mut HttpRequest,
+        cached: Option<&OAuth2Token>,
+        on_authorize: impl FnOnce(&str) + Send,
+    ) -> Result<Option<OAuth2Token>> {
+        let AuthType::OAuth2(config) = &request.auth else {
+            return Ok(None);
+        };
+
+        let (token, issued) = oauth2::obtain_token(&self.client, config, cached, on_authorize).await?;
+        let token_type = if token.token_type.eq_ignore_ascii_case("bearer") {
+            "Bearer"
+        } else {
+            token.token_type.as_str()
+        };
+        request.headers.insert("Authorization".to_string(), format!("{} {}", token_type, token.access_token));
+        request.auth = AuthType::None;
+
+        Ok(issued.then_some(token))
+    }
+
+    /// Build the final request, with auth applied and AWS requests signed
+    pub fn build_request(&self, request: &HttpRequest) -> Result<reqwest::Request> {
         let mut req_builder = match request.method {
             crate::models::request::HttpMethod::GET => {
                 self.client.get(&request.full_url())
@@ -50,6 +121,14 @@ impl HttpClient {
         for (key, value) in &request.headers {
             req_builder = req_builder.header(key, value);
         }
+        for (key, value) in &Config::global().http.headers {
+            if !request.headers.keys().any(|name| name.eq_ignore_ascii_case(key)) {
+                req_builder = req_builder.header(key, value);
+            }
+        }
+        if let Some(timeout) = request.timeout_seconds {
+            req_builder = req_builder.timeout(std::time::Duration::from_secs(timeout));
+        }
         
         req_builder = match &request.auth {
             crate::models::request::AuthType::Bearer { token } => {
@@ -68,48 +147,125 @@ impl HttpClient {
                     }
                 }
             }
-            crate::models::request::AuthType::None => req_builder,
+            crate::models::request::AuthType::OAuth2(_) => {
+                bail!("OAuth2 token has not been fetched for this request");
+            }
+            // Signed below, once the final request is known
+            crate::models::request::AuthType::AwsSigV4 { .. } => req_builder,
+            // Resolved before sending; left unresolved it means no auth
+            crate::models::request::AuthType::None | crate::models::request::AuthType::Inherit => req_builder,
         };
         
         if let Some(body) = &request.body {
             req_builder = req_builder.body(body.clone());
         }
         
-        let response = req_builder.send().await?;
-        let duration = start.elapsed();
-        
-        let status_code = response.status().as_u16();
-        let status_text = response.status().to_string();
-        
-        let headers = response
-            .headers()
-            .iter()
-            .map(|(k, v)| {
-                (
-                    k.to_string(),
-                    v.to_str().unwrap_or("<invalid>").to_string(),
-                )
-            })
-            .collect();
-        
-        let body = response.bytes().await?;
-        let size_bytes = body.len();
-        
-        let body_text = String::from_utf8(body.to_vec()).ok();
-        
-        Ok(HttpResponse {
-            id: Uuid::new_v4(),
-            request_id: request.id,
-            status_code,
-            status_text,
-            headers,
-            body: body.to_vec(),
-            body_text,
-            duration_ms: duration.as_millis() as u64,
-            size_bytes,
-            timestamp: chrono::Utc::now(),
-            error: None,
+        let mut http_request = req_builder.build()?;
+
+        if let crate::models::request::AuthType::AwsSigV4 { access_key, secret_key, session_token, region, service } = &request.auth {
+            let params = sigv4::SigningParams {
+                access_key,
+                secret_key,
+                session_token: Some(session_token.as_str()),
+                region,
+                service,
+                time: chrono::Utc::now(),
+            };
+            sigv4::sign(&mut http_request, &params)?;
+        }
+
+        Ok(http_request)
+    }
+
+    /// Send a request built by `build_request`
+    pub async fn send(&self, request_id: Uuid, http_request: reqwest::Request) -> Result<HttpResponse> {
+        let start = Instant::now();
+        let response = self.client.execute(http_request).await?;
+        read_response(request_id, response, start).await
+    }
+}
+
+/// Collect status, headers and body of a response into an `HttpResponse`
+async fn read_response(request_id: Uuid, response: reqwest::Response, start: Instant) -> Result<HttpResponse> {
+    let duration = start.elapsed();
+
+    let status_code = response.status().as_u16();
+    let status_text = response.status().to_string();
+    let http_version = format!("{:?}", response.version());
+
+    let raw_headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .map(|(k, v)| {
+            (
+                k.to_string(),
+                v.to_str().unwrap_or("<invalid>").to_string(),
+            )
         })
+        .collect();
+    let headers = raw_headers.iter().cloned().collect();
+    
+    let body = response.bytes().await?;
+    let size_bytes = body.len();
+    
+    let body_text = String::from_utf8(body.to_vec()).ok();
+    
+    Ok(HttpResponse {
+        id: Uuid::new_v4(),
+        request_id,
+        status_code,
+        status_text,
+        headers,
+        body: body.to_vec(),
+        body_text,
+        duration_ms: duration.as_millis() as u64,
+        size_bytes,
+        timestamp: chrono::Utc::now(),
+        error: None,
+        cancelled: false,
+        http_version,
+        raw_headers,
+        sent_request: None,
+    })
+}
+
+/// Describe a built request as it goes on the wire, including the `Host`,
+/// `Accept` and `Content-Length` headers reqwest only adds while sending
+pub fn sent_request(http_request: &reqwest::Request) -> SentRequest {
+    let url = http_request.url();
+    let request_headers = http_request.headers();
+    let mut headers = Vec::new();
+
+    if !request_headers.contains_key(reqwest::header::HOST) {
+        if let Some(host) = url.host_str() {
+            let host = match url.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host.to_string(),
+            };
+            headers.push(("host".to_string(), host));
+        }
+    }
+    headers.extend(
+        request_headers
+            .iter()
+            .map(|(k, v)| (k.to_string(), String::from_utf8_lossy(v.as_bytes()).into_owned())),
+    );
+    if !request_headers.contains_key(reqwest::header::ACCEPT) {
+        headers.push(("accept".to_string(), "*/*".to_string()));
+    }
+
+    let body = http_request.body().and_then(|body| body.as_bytes());
+    if let Some(bytes) = body {
+        if !request_headers.contains_key(reqwest::header::CONTENT_LENGTH) {
+            headers.push(("content-length".to_string(), bytes.len().to_string()));
+        }
+    }
+
+    SentRequest {
+        method: http_request.method().to_string(),
+        url: url.to_string(),
+        headers,
+        body: body.map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
     }
 }
 
@@ -119,3 +275,44 @@ impl Default for HttpClient {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::request::HttpMethod;
+
+    fn header<'a>(sent: &'a SentRequest, name: &str) -> Option<&'a str> {
+        sent.headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn sent_request_includes_auth_and_wire_headers() {
+        let mut request = HttpRequest::new("t".to_string(), HttpMethod::POST, "http://localhost:8080/items".to_string());
+        request.auth = AuthType::Bearer { token: "secret".to_string() };
+        request.body = Some("{\"a\":1}".to_string());
+
+        let client = HttpClient::new().unwrap();
+        let sent = sent_request(&client.build_request(&request).unwrap());
+
+        assert_eq!(sent.headers[0], ("host".to_string(), "localhost:8080".to_string()));
+        assert_eq!(header(&sent, "authorization"), Some("Bearer secret"));
+        assert_eq!(header(&sent, "accept"), Some("*/*"));
+        assert_eq!(header(&sent, "content-length"), Some("7"));
+        assert_eq!(sent.body.as_deref(), Some("{\"a\":1}"));
+    }
+
+    #[tokio::test]
+    async fn repeated_set_cookie_headers_are_kept() {
+        let response = ::http::Response::builder()
+            .status(201)
+            .header("set-cookie", "a=1")
+            .header("content-type", "text/plain")
+            .header("set-cookie", "b=2")
+            .body("ok")
+            .unwrap();
+
+        let response = read_response(Uuid::new_v4(), response.into(), Instant::now()).await.unwrap();
+        assert_eq!(response.set_cookie_headers().collect::<Vec<_>>(), vec!["a=1", "b=2"]);
+        assert_eq!(response.raw_text(), "HTTP/1.1 201 Created\nset-cookie: a=1\nset-cookie: b=2\ncontent-type: text/plain\n\nok");
+    }
+}
@@ -1,2 +1,3 @@
 pub mod client;
-
+pub mod oauth2;
+pub mod sigv4;
@@ -0,0 +1,485 @@
+use crate::models::oauth2::{OAuth2Config, OAuth2GrantType, OAuth2Token};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// How long to wait for the browser to come back to the loopback redirect
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+const CALLBACK_PAGE: &str = "<html><body><h3>Login complete</h3><p>You can close this tab and return to nexus.</p></body></html>";
+
+/// Get an access token for the given settings.
+///
+/// A cached token is reused while it is valid, and refreshed with its refresh token
+/// when it is about to expire. Otherwise the configured grant is run from scratch.
+/// For the authorization code grant `on_authorize` receives the URL the user has to
+/// open. Returns the token and whether it was newly issued.
+pub async fn obtain_token(
+    client: &reqwest::Client,
+    config: &OAuth2Config,
+    cached: Option<&OAuth2Token>,
+    on_authorize: impl FnOnce(&str) + Send,
+) -> Result<(OAuth2Token, bool)> {
+    if let Some(token) = cached.filter(|token| token.matches(config)) {
+        if token.is_usable_at(chrono::Utc::now()) {
+            return Ok((token.clone(), false));
+        }
+        if let Some(refresh_token) = &token.refresh_token {
+            match refresh(client, config, refresh_token).await {
+                Ok(token) => return Ok((token, true)),
+                Err(e) => tracing::warn!("OAuth2 token refresh failed, requesting a new token: {:#}", e),
+            }
+        }
+    }
+
+    let token = match config.grant_type {
+        OAuth2GrantType::ClientCredentials => {
+            let mut params = vec![("grant_type", "client_credentials".to_string())];
+            push_scope(&mut params, config);
+            request_token(client, config, params).await?
+        }
+        OAuth2GrantType::Password => {
+            let mut params = vec![
+                ("grant_type", "password".to_string()),
+                ("username", config.username.clone()),
+                ("password", config.password.clone()),
+            ];
+            push_scope(&mut params, config);
+            request_token(client, config, params).await?
+        }
+        OAuth2GrantType::RefreshToken => {
+            if config.refresh_token.is_empty() {
+                bail!("OAuth2 refresh token grant needs a refresh token");
+            }
+            refresh(client, config, &config.refresh_token).await?
+        }
+        OAuth2GrantType::AuthorizationCode => authorize_with_pkce(client, config, on_authorize).await?,
+    };
+
+    Ok((token, true))
+}
+
+fn push_scope(params: &mut Vec<(&'static str, String)>, config: &OAuth2Config) {
+    if !config.scope.is_empty() {
+        params.push(("scope", config.scope.clone()));
+    }
+}
+
+async fn refresh(client: &reqwest::Client, config: &OAuth2Config, refresh_token: &str) -> Result<OAuth2Token> {
+    let mut params = vec![
+        ("grant_type", "refresh_token".to_string()),
+        ("refresh_token", refresh_token.to_string()),
+    ];
+    push_scope(&mut params, config);
+    let mut token = request_token(client, config, params).await?;
+    // Servers may omit the refresh token when it stays the same
+    if token.refresh_token.is_none() {
+        token.refresh_token = Some(refresh_token.to_string());
+    }
+    Ok(token)
+}
+
+/// POST a grant to the token endpoint and parse the token response
+async fn request_token(
+    client: &reqwest::Client,
+    config: &OAuth2Config,
+    mut params: Vec<(&'static str, String)>,
+) -> Result<OAuth2Token> {
+    if config.token_url.is_empty() {
+        bail!("OAuth2 token URL cannot be empty");
+    }
+    if !config.client_id.is_empty() {
+        params.push(("client_id", config.client_id.clone()));
+    }
+
+    let mut req_builder = client
+        .post(&config.token_url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .form(&params);
+    if !config.client_secret.is_empty() {
+        req_builder = req_builder.basic_auth(&config.client_id, Some(&config.client_secret));
+    }
+
+    let response = req_builder
+        .send()
+        .await
+        .context("Failed to reach the OAuth2 token endpoint")?;
+    let status = response.status();
+    let body = response.text().await.context("Failed to read the OAuth2 token response")?;
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap_or(serde_json::Value::Null);
+
+    if !status.is_success() || json.get("error").is_some() {
+        let error = json.get("error").and_then(|v| v.as_str());
+        let description = json.get("error_description").and_then(|v| v.as_str());
+        return Err(match (error, description) {
+            (Some(error), Some(description)) => anyhow!("OAuth2 token request failed ({}): {}: {}", status, error, description),
+            (Some(error), None) => anyhow!("OAuth2 token request failed ({}): {}", status, error),
+            _ => anyhow!("OAuth2 token request failed ({}): {}", status, body.chars().take(200).collect::<String>()),
+        });
+    }
+
+    parse_token_response(&json, config)
+}
+
+fn parse_token_response(json: &serde_json::Value, config: &OAuth2Config) -> Result<OAuth2Token> {
+    let access_token = json.get("access_token")
+        .and_then(|v| v.as_str())
+        .context("OAuth2 token response has no access_token")?;
+
+    // Some servers send expires_in as a string
+    let expires_in = json.get("expires_in").and_then(|v| match v {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    });
+    let now = chrono::Utc::now();
+
+    Ok(OAuth2Token {
+        access_token: access_token.to_string(),
+        token_type: json.get("token_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Bearer")
+            .to_string(),
+        refresh_token: json.get("refresh_token").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        // An expiry too far out to represent is treated as unknown
+        expires_at: expires_in
+            .and_then(chrono::Duration::try_seconds)
+            .and_then(|lifetime| now.checked_add_signed(lifetime)),
+        scope: json.get("scope").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        config_fingerprint: config.fingerprint(),
+        obtained_at: now,
+    })
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// PKCE S256 code challenge for a verifier (RFC 7636)
+fn code_challenge(verifier: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Run the authorization code grant with PKCE, receiving the code on a loopback listener
+async fn authorize_with_pkce(
+    client: &reqwest::Client,
+    config: &OAuth2Config,
+    on_authorize: impl FnOnce(&str) + Send,
+) -> Result<OAuth2Token> {
+    if config.auth_url.is_empty() {
+        bail!("OAuth2 authorization URL cannot be empty");
+    }
+    let redirect = reqwest::Url::parse(&config.redirect_uri).context("Invalid OAuth2 redirect URI")?;
+    let host = redirect.host_str().unwrap_or_default();
+    if redirect.scheme() != "http" || !matches!(host, "127.0.0.1" | "localhost" | "[::1]") {
+        bail!("OAuth2 redirect URI must be a loopback address such as http://127.0.0.1:8765/callback");
+    }
+    let port = redirect.port_or_known_default().unwrap_or(80);
+    let bind_host = if host == "[::1]" { "::1" } else { "127.0.0.1" };
+    let listener = TcpListener::bind((bind_host, port))
+        .await
+        .with_context(|| format!("Failed to listen on {} for the OAuth2 redirect", config.redirect_uri))?;
+
+    let verifier = random_string(64);
+    let state = random_string(32);
+
+    let mut auth_url = reqwest::Url::parse(&config.auth_url).context("Invalid OAuth2 authorization URL")?;
+    {
+        let mut query = auth_url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &config.client_id)
+            .append_pair("redirect_uri", &config.redirect_uri)
+            .append_pair("state", &state)
+            .append_pair("code_challenge", &code_challenge(&verifier))
+            .append_pair("code_challenge_method", "S256");
+        if !config.scope.is_empty() {
+            query.append_pair("scope", &config.scope);
+        }
+    }
+    on_authorize(auth_url.as_str());
+
+    let code = tokio::time::timeout(AUTHORIZATION_TIMEOUT, wait_for_code(&listener, redirect.path(), &state))
+        .await
+        .map_err(|_| anyhow!("Timed out waiting for the OAuth2 login to complete"))??;
+
+    let params = vec![
+        ("grant_type", "authorization_code".to_string()),
+        ("code", code),
+        ("redirect_uri", config.redirect_uri.clone()),
+        ("code_verifier", verifier),
+    ];
+    request_token(client, config, params).await
+}
+
+/// Accept connections until the browser is redirected back with a code
+async fn wait_for_code(listener: &TcpListener, path: &str, expected_state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await.context("Failed to accept the OAuth2 redirect")?;
+
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 16 * 1024 {
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&chunk[..n]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let target = request.split_whitespace().nth(1).unwrap_or_default();
+        let url = reqwest::Url::parse(&format!("http://localhost{}", target)).ok();
+
+        let Some(url) = url.filter(|url| url.path() == path) else {
+            // Browsers also ask for things like /favicon.ico
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            continue;
+        };
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            CALLBACK_PAGE.len(),
+            CALLBACK_PAGE
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+
+        let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.to_string());
+        if let Some(error) = param("error") {
+            match param("error_description") {
+                Some(description) => bail!("OAuth2 authorization failed: {}: {}", error, description),
+                None => bail!("OAuth2 authorization failed: {}", error),
+            }
+        }
+        if param("state").as_deref() != Some(expected_state) {
+            bail!("OAuth2 redirect state does not match, the login was not started by nexus");
+        }
+        return param("code").context("OAuth2 redirect has no authorization code");
+    }
+}
+
+/// Best effort attempt to open a URL in the default browser
+pub fn open_in_browser(url: &str) -> bool {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Minimal stand-in token endpoint: answers every POST with the next canned
+    /// response and records the form bodies it received.
+    async fn token_server(responses: Vec<(u16, String)>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut data = Vec::new();
+                let mut chunk = [0u8; 4096];
+                loop {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    data.extend_from_slice(&chunk[..n]);
+                    let text = String::from_utf8_lossy(&data).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..header_end]
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length").then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        if data.len() >= header_end + 4 + content_length {
+                            recorded.lock().unwrap().push(text);
+                            break;
+                        }
+                    }
+                    if n == 0 {
+                        break;
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn config(grant_type: OAuth2GrantType, token_url: &str) -> OAuth2Config {
+        OAuth2Config {
+            grant_type,
+            token_url: token_url.to_string(),
+            client_id: "nexus".to_string(),
+            client_secret: "s3cret".to_string(),
+            scope: "read write".to_string(),
+            ..OAuth2Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn client_credentials_grant() {
+        let (url, requests) = token_server(vec![(
+            200,
+            r#"{"access_token":"abc","token_type":"bearer","expires_in":3600}"#.to_string(),
+        )])
+        .await;
+        let config = config(OAuth2GrantType::ClientCredentials, &url);
+
+        let (token, issued) = obtain_token(&reqwest::Client::new(), &config, None, |_| {}).await.unwrap();
+        assert!(issued);
+        assert_eq!(token.access_token, "abc");
+        assert!(token.is_usable_at(chrono::Utc::now()));
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.contains("grant_type=client_credentials"));
+        assert!(request.contains("scope=read+write"));
+        assert!(request.contains("Basic bmV4dXM6czNjcmV0"));
+
+        // A valid cached token is reused without contacting the server
+        let (cached, issued) = obtain_token(&reqwest::Client::new(), &config, Some(&token), |_| {}).await.unwrap();
+        assert!(!issued);
+        assert_eq!(cached, token);
+    }
+
+    #[tokio::test]
+    async fn password_grant_reports_server_errors() {
+        let (url, requests) = token_server(vec![(
+            400,
+            r#"{"error":"invalid_grant","error_description":"bad credentials"}"#.to_string(),
+        )])
+        .await;
+        let config = OAuth2Config {
+            username: "alice".to_string(),
+            password: "wrong".to_string(),
+            ..config(OAuth2GrantType::Password, &url)
+        };
+
+        let err = obtain_token(&reqwest::Client::new(), &config, None, |_| {}).await.unwrap_err();
+        assert!(err.to_string().contains("invalid_grant: bad credentials"));
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.contains("grant_type=password&username=alice&password=wrong"));
+    }
+
+    #[tokio::test]
+    async fn expiring_token_is_refreshed() {
+        let (url, requests) = token_server(vec![(
+            200,
+            r#"{"access_token":"fresh","expires_in":"120"}"#.to_string(),
+        )])
+        .await;
+        let config = config(OAuth2GrantType::ClientCredentials, &url);
+        let now = chrono::Utc::now();
+        let expiring = OAuth2Token {
+            access_token: "stale".to_string(),
+            token_type: "Bearer".to_string(),
+            refresh_token: Some("r1".to_string()),
+            expires_at: Some(now + chrono::Duration::seconds(10)),
+            scope: None,
+            config_fingerprint: config.fingerprint(),
+            obtained_at: now,
+        };
+
+        let (token, issued) = obtain_token(&reqwest::Client::new(), &config, Some(&expiring), |_| {}).await.unwrap();
+        assert!(issued);
+        assert_eq!(token.access_token, "fresh");
+        // The server did not rotate the refresh token, so the old one is kept
+        assert_eq!(token.refresh_token.as_deref(), Some("r1"));
+        assert!(requests.lock().unwrap()[0].contains("grant_type=refresh_token&refresh_token=r1"));
+    }
+
+    #[tokio::test]
+    async fn authorization_code_with_pkce() {
+        let (url, requests) = token_server(vec![(
+            200,
+            r#"{"access_token":"from-code","refresh_token":"r2","expires_in":60}"#.to_string(),
+        )])
+        .await;
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let config = OAuth2Config {
+            auth_url: "https://auth.example.com/authorize".to_string(),
+            redirect_uri: format!("http://127.0.0.1:{}/callback", port),
+            client_secret: String::new(),
+            ..config(OAuth2GrantType::AuthorizationCode, &url)
+        };
+
+        // Play the browser: follow the authorization URL straight back to the redirect URI
+        let challenge = Arc::new(Mutex::new(String::new()));
+        let seen_challenge = challenge.clone();
+        let on_authorize = move |auth_url: &str| {
+            let auth_url = reqwest::Url::parse(auth_url).unwrap();
+            let param = |name: &str| auth_url.query_pairs().find(|(k, _)| k == name).unwrap().1.to_string();
+            assert_eq!(param("code_challenge_method"), "S256");
+            *seen_challenge.lock().unwrap() = param("code_challenge");
+            let callback = format!("{}?code=the-code&state={}", param("redirect_uri"), param("state"));
+            tokio::spawn(async move {
+                reqwest::get(callback).await.unwrap();
+            });
+        };
+
+        let (token, _) = obtain_token(&reqwest::Client::new(), &config, None, on_authorize).await.unwrap();
+        assert_eq!(token.access_token, "from-code");
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.contains("grant_type=authorization_code&code=the-code"));
+        let verifier = request
+            .split("code_verifier=")
+            .nth(1)
+            .unwrap()
+            .split('&')
+            .next()
+            .unwrap()
+            .to_string();
+        assert_eq!(code_challenge(&verifier), *challenge.lock().unwrap());
+    }
+
+    #[test]
+    fn pkce_challenge_matches_rfc_example() {
+        // RFC 7636 appendix B
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn huge_expires_in_means_unknown_expiry() {
+        let config = config(OAuth2GrantType::ClientCredentials, "http://localhost/token");
+        let json = serde_json::json!({"access_token": "abc", "expires_in": i64::MAX});
+        let token = parse_token_response(&json, &config).unwrap();
+        assert_eq!(token.expires_at, None);
+    }
+}
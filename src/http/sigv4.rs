@@ -0,0 +1,295 @@
+use anyhow::{anyhow, bail, Context, Result};
+use hmac::{Hmac, Mac};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderName, HeaderValue};
+use sha2::{Digest, Sha256};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Everything except the unreserved characters of RFC 3986
+const URI_ENCODE: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// Same as `URI_ENCODE` but keeps path separators
+const PATH_ENCODE: &AsciiSet = &URI_ENCODE.remove(b'/');
+
+/// Headers that proxies and clients may rewrite, so they are never signed
+const UNSIGNED_HEADERS: &[&str] = &["authorization", "user-agent", "expect", "x-amzn-trace-id"];
+
+/// Credentials and scope used to sign a request
+pub struct SigningParams<'a> {
+    pub access_key: &'a str,
+    pub secret_key: &'a str,
+    pub session_token: Option<&'a str>,
+    pub region: &'a str,
+    pub service: &'a str,
+    pub time: chrono::DateTime<chrono::Utc>,
+}
+
+/// Sign a fully built request with AWS Signature Version 4.
+///
+/// Adds `X-Amz-Date`, `X-Amz-Security-Token` (for temporary credentials),
+/// `X-Amz-Content-Sha256` (S3 only) and the `Authorization` header. All other
+/// headers present on the request are signed, so nothing may be added afterwards.
+pub fn sign(request: &mut reqwest::Request, params: &SigningParams) -> Result<()> {
+    if params.access_key.is_empty() || params.secret_key.is_empty() {
+        bail!("AWS Signature V4 needs an access key ID and secret access key");
+    }
+    if params.region.is_empty() || params.service.is_empty() {
+        bail!("AWS Signature V4 needs a region and service name");
+    }
+
+    let amz_date = params.time.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = params.time.format("%Y%m%d").to_string();
+
+    let body = match request.body() {
+        Some(body) => body.as_bytes().ok_or_else(|| anyhow!("Streaming bodies cannot be signed"))?,
+        None => &[],
+    };
+    let payload_hash = hex(&Sha256::digest(body));
+
+    let headers = request.headers_mut();
+    headers.insert("x-amz-date", HeaderValue::from_str(&amz_date)?);
+    if let Some(token) = params.session_token.filter(|t| !t.is_empty()) {
+        headers.insert("x-amz-security-token", HeaderValue::from_str(token).context("Invalid session token")?);
+    }
+    if params.service == "s3" {
+        headers.insert("x-amz-content-sha256", HeaderValue::from_str(&payload_hash)?);
+    }
+
+    let (canonical_request, signed_headers) = canonical_request(request, params.service, &payload_hash)?;
+    let scope = format!("{}/{}/{}/aws4_request", date, params.region, params.service);
+    let string_to_sign = format!(
+        "{}\n{}\n{}\n{}",
+        ALGORITHM,
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = signing_key(params.secret_key, &date, params.region, params.service);
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    let authorization = format!(
+        "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+        ALGORITHM, params.access_key, scope, signed_headers, signature
+    );
+    request.headers_mut().insert(
+        HeaderName::from_static("authorization"),
+        HeaderValue::from_str(&authorization).context("Invalid access key")?,
+    );
+
+    Ok(())
+}
+
+/// Build the canonical request, returning it with the list of signed headers
+fn canonical_request(request: &reqwest::Request, service: &str, payload_hash: &str) -> Result<(String, String)> {
+    let url = request.url();
+
+    // S3 signs the path as sent, every other service encodes the already encoded path again
+    let canonical_uri = if service == "s3" {
+        let decoded = percent_decode_str(url.path()).decode_utf8_lossy().into_owned();
+        utf8_percent_encode(&decoded, PATH_ENCODE).to_string()
+    } else {
+        utf8_percent_encode(url.path(), PATH_ENCODE).to_string()
+    };
+
+    let mut query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (
+            utf8_percent_encode(&k, URI_ENCODE).to_string(),
+            utf8_percent_encode(&v, URI_ENCODE).to_string(),
+        ))
+        .collect();
+    query.sort();
+    let canonical_query = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    // reqwest only adds the Host header when sending, so derive it from the URL
+    let host = url.host_str().ok_or_else(|| anyhow!("Request URL has no host"))?;
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+
+    let mut headers: Vec<(String, String)> = vec![("host".to_string(), host)];
+    for name in request.headers().keys() {
+        let name = name.as_str();
+        if name == "host" || UNSIGNED_HEADERS.contains(&name) {
+            continue;
+        }
+        let values = request.headers()
+            .get_all(name)
+            .iter()
+            .map(|v| Ok(canonical_header_value(v.to_str()?)))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("Header {} is not valid text", name))?;
+        headers.push((name.to_string(), values.join(",")));
+    }
+    headers.sort();
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method().as_str(),
+        canonical_uri,
+        canonical_query,
+        canonical_headers,
+        signed_headers,
+        payload_hash
+    );
+
+    Ok((canonical, signed_headers))
+}
+
+/// Trim the value and collapse runs of spaces into one
+fn canonical_header_value(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, service.as_bytes());
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // Credentials and timestamp shared by the AWS SigV4 test suite and documentation examples
+    const ACCESS_KEY: &str = "AKIDEXAMPLE";
+    const SECRET_KEY: &str = "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY";
+
+    fn params<'a>(region: &'a str, service: &'a str) -> SigningParams<'a> {
+        SigningParams {
+            access_key: ACCESS_KEY,
+            secret_key: SECRET_KEY,
+            session_token: None,
+            region,
+            service,
+            time: chrono::Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap(),
+        }
+    }
+
+    fn request(method: reqwest::Method, url: &str) -> reqwest::Request {
+        reqwest::Request::new(method, url.parse().unwrap())
+    }
+
+    fn authorization(request: &reqwest::Request) -> &str {
+        request.headers()["authorization"].to_str().unwrap()
+    }
+
+    #[test]
+    fn signing_key_matches_documentation() {
+        let key = signing_key(SECRET_KEY, "20150830", "us-east-1", "iam");
+        assert_eq!(hex(&key), "c4afb1cc5771d871763a393e44b703571b55cc28424d1a5e86da6ed3c154a4b9");
+    }
+
+    #[test]
+    fn get_vanilla() {
+        let mut req = request(reqwest::Method::GET, "https://example.amazonaws.com/");
+        sign(&mut req, &params("us-east-1", "service")).unwrap();
+
+        assert_eq!(
+            authorization(&req),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+        assert_eq!(req.headers()["x-amz-date"], "20150830T123600Z");
+    }
+
+    #[test]
+    fn get_vanilla_query_order() {
+        let mut req = request(reqwest::Method::GET, "https://example.amazonaws.com/?Param2=value2&Param1=value1");
+        sign(&mut req, &params("us-east-1", "service")).unwrap();
+
+        assert!(authorization(&req).ends_with(
+            "Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        ));
+    }
+
+    #[test]
+    fn iam_list_users() {
+        let mut req = request(reqwest::Method::GET, "https://iam.amazonaws.com/?Action=ListUsers&Version=2010-05-08");
+        req.headers_mut().insert(
+            "content-type",
+            HeaderValue::from_static("application/x-www-form-urlencoded; charset=utf-8"),
+        );
+        sign(&mut req, &params("us-east-1", "iam")).unwrap();
+
+        assert_eq!(
+            authorization(&req),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7"
+        );
+    }
+
+    #[test]
+    fn post_vanilla() {
+        let mut req = request(reqwest::Method::POST, "https://example.amazonaws.com/");
+        sign(&mut req, &params("us-east-1", "service")).unwrap();
+
+        assert!(authorization(&req).ends_with(
+            "Signature=5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+        ));
+    }
+
+    #[test]
+    fn post_x_www_form_urlencoded() {
+        let mut req = request(reqwest::Method::POST, "https://example.amazonaws.com/");
+        req.headers_mut().insert("content-type", HeaderValue::from_static("application/x-www-form-urlencoded"));
+        *req.body_mut() = Some("Param1=value1".into());
+        sign(&mut req, &params("us-east-1", "service")).unwrap();
+
+        assert_eq!(
+            authorization(&req),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date, \
+             Signature=ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        );
+    }
+
+    #[test]
+    fn post_with_body_and_session_token() {
+        let mut req = request(reqwest::Method::POST, "https://example.amazonaws.com/");
+        *req.body_mut() = Some("Param1=value1".into());
+        let mut params = params("us-east-1", "service");
+        params.session_token = Some("session");
+        sign(&mut req, &params).unwrap();
+
+        assert_eq!(req.headers()["x-amz-security-token"], "session");
+        assert!(authorization(&req).contains("SignedHeaders=host;x-amz-date;x-amz-security-token,"));
+
+        // The body hash is part of the signature
+        let mut other = request(reqwest::Method::POST, "https://example.amazonaws.com/");
+        *other.body_mut() = Some("Param1=value2".into());
+        sign(&mut other, &params).unwrap();
+        assert_ne!(authorization(&req), authorization(&other));
+    }
+}
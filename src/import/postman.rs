@@ -1,9 +1,13 @@
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use uuid::Uuid;
 
 use crate::models::collection::Collection;
+use crate::models::defaults::RequestDefaults;
+use crate::models::folder::Folder;
 use crate::models::request::{ApiKeyLocation, AuthType, HttpMethod, HttpRequest};
 
 // Postman Collection v2.1 Schema Structures
@@ -52,6 +56,8 @@ pub struct PostmanFolder {
     pub item: Vec<PostmanItem>,
     #[serde(default)]
     pub description: Option<String>,
+    #[serde(default)]
+    pub auth: Option<PostmanAuth>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -155,8 +161,17 @@ pub struct PostmanVariable {
     pub var_type: Option<String>,
 }
 
+/// A collection read from a Postman export, with its folders and the folder of each request
+pub struct ImportedCollection {
+    pub collection: Collection,
+    pub folders: Vec<Folder>,
+    pub requests: Vec<(HttpRequest, Option<Uuid>)>,
+    /// Auth and variables of the collection and folders, by their id
+    pub defaults: HashMap<Uuid, RequestDefaults>,
+}
+
 // Import functions
-pub fn import_postman_collection(path: &Path) -> Result<(Collection, Vec<HttpRequest>)> {
+pub fn import_postman_collection(path: &Path) -> Result<ImportedCollection> {
     let content = fs::read_to_string(path)
         .with_context(|| format!("Failed to read file: {}", path.display()))?;
 
@@ -166,55 +181,62 @@ pub fn import_postman_collection(path: &Path) -> Result<(Collection, Vec<HttpReq
     convert_postman_collection(postman_collection)
 }
 
-fn convert_postman_collection(
-    postman: PostmanCollection,
-) -> Result<(Collection, Vec<HttpRequest>)> {
+fn convert_postman_collection(postman: PostmanCollection) -> Result<ImportedCollection> {
     let mut collection = Collection::new(postman.info.name.clone());
     if let Some(desc) = postman.info.description {
         collection.description = Some(desc);
     }
 
-    let mut requests = Vec::new();
-    let collection_auth = postman.auth;
+    let mut imported = ImportedCollection {
+        collection,
+        folders: Vec::new(),
+        requests: Vec::new(),
+        defaults: HashMap::new(),
+    };
+
+    // Collection auth and variables are inherited by the requests rather than copied into them
+    let defaults = RequestDefaults {
+        auth: postman.auth.as_ref().map(convert_auth).transpose()?,
+        variables: postman.variable.into_iter().map(|v| (v.key, v.value)).collect(),
+        ..Default::default()
+    };
+    if !defaults.is_empty() {
+        imported.defaults.insert(imported.collection.id, defaults);
+    }
 
-    // Process all items (flattening folders)
-    process_items(&postman.item, &mut requests, &collection_auth, None)?;
+    // Process all items, keeping Postman folders as folders
+    process_items(&postman.item, &mut imported, None)?;
 
     // Set collection_id for all requests
-    for request in requests.iter_mut() {
-        request.collection_id = Some(collection.id);
+    for (request, _) in imported.requests.iter_mut() {
+        request.collection_id = Some(imported.collection.id);
     }
 
-    Ok((collection, requests))
+    Ok(imported)
 }
 
-fn process_items(
-    items: &[PostmanItem],
-    requests: &mut Vec<HttpRequest>,
-    collection_auth: &Option<PostmanAuth>,
-    folder_prefix: Option<&str>,
-) -> Result<()> {
+fn process_items(items: &[PostmanItem], imported: &mut ImportedCollection, parent: Option<Uuid>) -> Result<()> {
+    let mut sort_index = 0;
     for item in items {
         match item {
             PostmanItem::Request(req_item) => {
-                let name = if let Some(prefix) = folder_prefix {
-                    format!("{}/{}", prefix, req_item.name)
-                } else {
-                    req_item.name.clone()
-                };
-
-                let request = convert_postman_request(name, &req_item.request, collection_auth)?;
-                requests.push(request);
+                let request = convert_postman_request(req_item.name.clone(), &req_item.request)?;
+                imported.requests.push((request, parent));
             }
-            PostmanItem::Folder(folder) => {
-                let new_prefix = if let Some(prefix) = folder_prefix {
-                    format!("{}/{}", prefix, folder.name)
-                } else {
-                    folder.name.clone()
-                };
+            PostmanItem::Folder(postman_folder) => {
+                let mut folder = Folder::new(postman_folder.name.clone(), imported.collection.id, parent);
+                folder.description = postman_folder.description.clone();
+                folder.sort_index = sort_index;
+                sort_index += 1;
+                let folder_id = folder.id;
+                imported.folders.push(folder);
+                if let Some(auth) = &postman_folder.auth {
+                    let defaults = RequestDefaults { auth: Some(convert_auth(auth)?), ..Default::default() };
+                    imported.defaults.insert(folder_id, defaults);
+                }
 
                 // Recursively process folder items
-                process_items(&folder.item, requests, collection_auth, Some(&new_prefix))?;
+                process_items(&postman_folder.item, imported, Some(folder_id))?;
             }
         }
     }
@@ -222,11 +244,7 @@ fn process_items(
     Ok(())
 }
 
-fn convert_postman_request(
-    name: String,
-    postman_req: &PostmanRequest,
-    collection_auth: &Option<PostmanAuth>,
-) -> Result<HttpRequest> {
+fn convert_postman_request(name: String, postman_req: &PostmanRequest) -> Result<HttpRequest> {
     match postman_req {
         PostmanRequest::Simple(url) => {
             Ok(HttpRequest::new(name, HttpMethod::GET, url.clone()))
@@ -272,13 +290,10 @@ fn convert_postman_request(
                 request.body = extract_body(b);
             }
 
-            // Add auth (request auth takes precedence over collection auth)
-            request.auth = if let Some(a) = auth {
-                convert_auth(a)?
-            } else if let Some(a) = collection_auth {
-                convert_auth(a)?
-            } else {
-                AuthType::None
+            // Without auth of its own a request inherits it from its folder or collection
+            request.auth = match auth {
+                Some(a) => convert_auth(a)?,
+                None => AuthType::Inherit,
             };
 
             Ok(request)
@@ -409,3 +424,55 @@ fn convert_auth(auth: &PostmanAuth) -> Result<AuthType> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::folder;
+
+    #[test]
+    fn postman_folders_become_nested_folders() {
+        let json = r#"{
+            "info": {"name": "Shop"},
+            "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{token}}"}]},
+            "variable": [{"key": "token", "value": "secret"}],
+            "item": [
+                {"name": "Health", "request": "https://shop.test/health"},
+                {"name": "Users", "description": "User admin", "item": [
+                    {"name": "List users", "request": {"method": "GET", "url": "https://shop.test/users"}},
+                    {"name": "Admin", "auth": {"type": "noauth"}, "item": [
+                        {"name": "Ban user", "request": {"method": "POST", "url": "https://shop.test/ban"}}
+                    ]}
+                ]},
+                {"name": "Orders", "item": []}
+            ]
+        }"#;
+        let imported = convert_postman_collection(serde_json::from_str(json).unwrap()).unwrap();
+        let collection_id = imported.collection.id;
+
+        let top: Vec<&str> = folder::children(&imported.folders, collection_id, None)
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(top, vec!["Users", "Orders"]);
+        assert_eq!(imported.folders[0].description.as_deref(), Some("User admin"));
+
+        let names: Vec<(&str, Option<String>)> = imported.requests.iter()
+            .map(|(r, folder_id)| (r.name.as_str(), folder_id.map(|id| folder::path(&imported.folders, id))))
+            .collect();
+        assert_eq!(names, vec![
+            ("Health", None),
+            ("List users", Some("Users".to_string())),
+            ("Ban user", Some("Users/Admin".to_string())),
+        ]);
+        assert!(imported.requests.iter().all(|(r, _)| r.collection_id == Some(collection_id)));
+
+        // Auth stays on the collection and folder it was set on, for the requests to inherit
+        assert!(imported.requests.iter().all(|(r, _)| matches!(r.auth, AuthType::Inherit)));
+        let collection_defaults = &imported.defaults[&collection_id];
+        assert!(matches!(collection_defaults.auth, Some(AuthType::Bearer { ref token }) if token == "{{token}}"));
+        assert_eq!(collection_defaults.variables["token"], "secret");
+        let admin = imported.folders.iter().find(|f| f.name == "Admin").unwrap();
+        assert!(matches!(imported.defaults[&admin.id].auth, Some(AuthType::None)));
+    }
+}
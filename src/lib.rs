@@ -1,4 +1,5 @@
 pub mod app;
+pub mod config;
 pub mod http;
 pub mod import;
 pub mod models;
@@ -1,4 +1,5 @@
 mod app;
+mod config;
 mod grpc;
 mod http;
 mod import;
@@ -7,36 +8,94 @@ mod storage;
 mod ui;
 mod utils;
 
-use app::state::{AppState, InputMode, Panel, EditorField, ProtocolType};
+use app::state::{AppState, InputMode, Panel, EditorField, GrpcEditorField, ProtocolType};
+use models::protocol::RequestType;
+use models::trash::{TrashEntry, TrashKind};
 use app::actions::Action;
+use app::keymap::{KeyScope, Keymap};
+use app::text_editor::TextEditor;
+use ui::hit_map::{text_field_cursor, HitMap, Target};
+use ui::theme::{Palette, Theme};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers, poll},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyCode,
+        KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind, poll,
+    },
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
+use std::collections::VecDeque;
 use std::io;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
 enum HttpResult {
-    Success(models::response::HttpResponse),
-    Error(String),
+    Success(Uuid, models::response::HttpResponse),
+    /// The request failed, with what was sent when it got that far
+    Error(Uuid, String, Option<models::response::SentRequest>),
+    /// Status update while the request is still running, e.g. waiting for an OAuth2 login
+    Progress(Uuid, String),
+    /// A new OAuth2 access token was issued for the request
+    TokenIssued(Uuid, Box<models::oauth2::OAuth2Token>),
 }
 
 #[allow(dead_code)]
 enum GrpcResult {
-    Success(models::GrpcResponse),
-    Error(String),
+    Success(Uuid, models::GrpcResponse),
+    Error(Uuid, String),
 }
 
+const USAGE: &str = "Usage: nexus [--config <path>]
+
+Options:
+  -c, --config <path>  Read settings from this file instead of config.toml in the nexus config directory
+  -h, --help           Print this help";
+
+/// The `--config` path from the command line, `--help` prints the usage and exits
+fn parse_args() -> anyhow::Result<Option<std::path::PathBuf>> {
+    let mut config_path = None;
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("-h" | "--help") => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            Some("-c" | "--config") => {
+                let path = args.next().ok_or_else(|| anyhow::anyhow!("--config needs a path\n\n{}", USAGE))?;
+                config_path = Some(path.into());
+            }
+            Some(arg) if arg.starts_with("--config=") => config_path = Some(arg["--config=".len()..].into()),
+            _ => anyhow::bail!("Unknown argument `{}`\n\n{}", arg.to_string_lossy(), USAGE),
+        }
+    }
+    Ok(config_path)
+}
+
+/// Outcome of a background filter evaluation, tagged with the filter generation it ran for
+type FilterResult = (u64, Result<Vec<serde_json::Value>, String>);
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // Checked before the terminal is taken over, so mistakes print like any command-line error
+    match parse_args().and_then(|path| config::Config::load(path.as_deref())) {
+        Ok(config) => config.install(),
+        Err(e) => {
+            eprintln!("nexus: {:#}", e);
+            std::process::exit(2);
+        }
+    }
+
     utils::logger::init()?;
     
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     stdout.execute(EnterAlternateScreen)?;
+    stdout.execute(EnableBracketedPaste)?;
+    if config::Config::global().ui.mouse {
+        stdout.execute(EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     
@@ -46,9 +105,43 @@ async fn main() -> anyhow::Result<()> {
     
     // Load collections and requests from storage
     state.collections = storage.load_collections()?;
-    state.requests = storage.load_requests()?;
-    state.grpc_requests = storage.load_grpc_requests()?;
+    state.requests = storage.load_all_requests()?;
+    // Collections and requests moved by hand keep their place, the others follow in creation order
+    let sort_indices = storage.load_sort_indices()?;
+    let sort_key = |id: Uuid| sort_indices.get(&id).copied().unwrap_or(u32::MAX);
+    state.collections.sort_by_key(|c| sort_key(c.id));
+    state.requests.sort_by_key(|r| sort_key(r.id()));
+    state.folders = storage.load_folders()?;
+    state.request_folders = storage.load_request_folders()?;
+    state.node_defaults = storage.load_request_defaults()?;
     state.proto_schemas = storage.load_proto_schemas()?;
+    if let Some(proxy_settings) = storage.load_proxy_settings()? {
+        state.proxy_settings = proxy_settings;
+    }
+    state.collection_proxies = storage.load_collection_proxies()?;
+    if let Some(panel_layout) = storage.load_panel_layout()? {
+        state.panel_layout = panel_layout;
+    }
+    state.oauth2_tokens = storage.load_oauth2_tokens()?;
+    state.recent_filters = storage.load_recent_filters()?;
+    state.trash = storage.load_trash()?;
+
+    // A broken keymap.toml or theme.toml leaves the defaults in place, the errors show in the status bar
+    let mut config_errors = Vec::new();
+    match Keymap::load() {
+        Ok(keymap) => state.keymap = keymap,
+        Err(e) => config_errors.push(format!("{:#}, using the default keys", e)),
+    }
+    match Palette::load() {
+        Ok(palette) => Theme::set_palette(palette),
+        Err(e) => {
+            Theme::set_palette(Palette::from_env());
+            config_errors.push(format!("{:#}, using the default colors", e));
+        }
+    }
+    if !config_errors.is_empty() {
+        state.status_message = Some(config_errors.join("; "));
+    }
 
     // Auto-select the most recent proto schema if any exist
     if !state.proto_schemas.is_empty() {
@@ -70,7 +163,7 @@ async fn main() -> anyhow::Result<()> {
         );
         request1.collection_id = Some(collection_id);
         storage.save_request(&request1)?;
-        state.requests.push(request1);
+        state.requests.push(RequestType::Http(request1));
         
         let mut request2 = models::request::HttpRequest::new(
             "List JSONPlaceholder Posts".to_string(),
@@ -79,7 +172,7 @@ async fn main() -> anyhow::Result<()> {
         );
         request2.collection_id = Some(collection_id);
         storage.save_request(&request2)?;
-        state.requests.push(request2);
+        state.requests.push(RequestType::Http(request2));
         
         let mut request3 = models::request::HttpRequest::new(
             "Create Post".to_string(),
@@ -90,7 +183,7 @@ async fn main() -> anyhow::Result<()> {
         .with_body(r#"{"title": "foo", "body": "bar", "userId": 1}"#.to_string());
         request3.collection_id = Some(collection_id);
         storage.save_request(&request3)?;
-        state.requests.push(request3);
+        state.requests.push(RequestType::Http(request3));
         
         let mut request4 = models::request::HttpRequest::new(
             "Search with Params, Headers & Body".to_string(),
@@ -106,7 +199,7 @@ async fn main() -> anyhow::Result<()> {
         .with_body(r#"{"filter": {"userId": 1}, "fields": ["id", "title", "body"]}"#.to_string());
         request4.collection_id = Some(collection_id);
         storage.save_request(&request4)?;
-        state.requests.push(request4);
+        state.requests.push(RequestType::Http(request4));
     } else {
         // Set initial selections if data exists
         if !state.collections.is_empty() {
@@ -114,51 +207,63 @@ async fn main() -> anyhow::Result<()> {
         }
     }
     
-    if !state.requests.is_empty() {
-        state.selected_request = Some(0);
-    }
+    state.update_selected_request_for_collection();
     
-    let http_client = http::client::HttpClient::new()?;
     let (response_tx, mut response_rx) = mpsc::channel::<HttpResult>(32);
     let (grpc_response_tx, mut grpc_response_rx) = mpsc::channel::<GrpcResult>(32);
+    let (filter_tx, mut filter_rx) = mpsc::channel::<FilterResult>(8);
+    // Characters of a paste outside the body editors, handled as typed keys
+    let mut pasted_keys: VecDeque<KeyEvent> = VecDeque::new();
+    // Where the last frame drew what, for mapping clicks back
+    let mut hits = HitMap::default();
 
     loop {
         // Handle HTTP responses
         while let Ok(result) = response_rx.try_recv() {
+            let dispatch_id = match &result {
+                HttpResult::Success(id, _) | HttpResult::Error(id, ..) => *id,
+                HttpResult::Progress(id, message) => {
+                    state.set_in_flight_message(*id, message.clone());
+                    continue;
+                }
+                HttpResult::TokenIssued(request_id, token) => {
+                    let _ = storage.save_oauth2_token(request_id, token);
+                    state.oauth2_tokens.insert(*request_id, token.as_ref().clone());
+                    continue;
+                }
+            };
+            // Results of cancelled or re-sent requests are dropped
+            let Some(finished) = state.finish_in_flight(dispatch_id) else {
+                continue;
+            };
+
             match result {
-                HttpResult::Success(response) => {
-                    state.current_response = Some(response);
-                    state.loading_message.clear();
+                HttpResult::Success(_, response) => {
+                    state.record_response(finished.request_id, &response);
+                    state.apply_http_response(finished.tab_id, response);
                 }
-                HttpResult::Error(error_msg) => {
-                    let error_response = models::response::HttpResponse {
-                        id: Uuid::new_v4(),
-                        request_id: Uuid::new_v4(),
-                        status_code: 0,
-                        status_text: "Request Failed".to_string(),
-                        headers: std::collections::HashMap::new(),
-                        body: Vec::new(),
-                        body_text: Some(error_msg),
-                        duration_ms: 0,
-                        size_bytes: 0,
-                        timestamp: chrono::Utc::now(),
-                        error: None,
-                    };
-                    state.current_response = Some(error_response);
-                    state.loading_message.clear();
+                HttpResult::Error(_, error_msg, sent) => {
+                    let error_response = models::response::HttpResponse::failed(error_msg, sent);
+                    state.apply_http_response(finished.tab_id, error_response);
                 }
+                HttpResult::Progress(..) | HttpResult::TokenIssued(..) => {}
             }
-            state.is_loading = false;
         }
 
         // Handle gRPC responses
         while let Ok(result) = grpc_response_rx.try_recv() {
+            let dispatch_id = match &result {
+                GrpcResult::Success(id, _) | GrpcResult::Error(id, _) => *id,
+            };
+            let Some(finished) = state.finish_in_flight(dispatch_id) else {
+                continue;
+            };
+
             match result {
-                GrpcResult::Success(response) => {
-                    state.grpc_response = Some(response);
-                    state.loading_message.clear();
+                GrpcResult::Success(_, response) => {
+                    state.apply_grpc_response(finished.tab_id, response);
                 }
-                GrpcResult::Error(error_msg) => {
+                GrpcResult::Error(_, error_msg) => {
                     let error_response = models::GrpcResponse {
                         id: Uuid::new_v4(),
                         request_id: Uuid::new_v4(),
@@ -172,24 +277,73 @@ async fn main() -> anyhow::Result<()> {
                         duration_ms: 0,
                         timestamp: chrono::Utc::now(),
                     };
-                    state.grpc_response = Some(error_response);
-                    state.loading_message.clear();
+                    state.apply_grpc_response(finished.tab_id, error_response);
                 }
             }
-            state.is_loading = false;
+        }
+
+        // Results of filters that were edited since are dropped by the state
+        while let Ok((generation, result)) = filter_rx.try_recv() {
+            state.apply_response_filter_result(generation, result);
+        }
+
+        // Point the active tab at the selected request before drawing
+        state.sync_tabs_with_selection();
+        state.sync_json_tree();
+
+        // Evaluate the response filter off the UI thread once typing pauses
+        if let Some(job) = state.take_due_response_filter(std::time::Instant::now()) {
+            let tx = filter_tx.clone();
+            let task = tokio::spawn(async move {
+                let result = utils::json_filter::apply_filter_with_timeout(
+                    job.expression,
+                    job.inputs,
+                    utils::json_filter::FILTER_TIMEOUT,
+                )
+                .await
+                .map_err(|e| e.to_string());
+                let _ = tx.send((job.generation, result)).await;
+            });
+            state.response_filter_task = Some(task.abort_handle());
         }
 
         terminal.draw(|frame| {
-            ui::app::UI::draw(frame, &mut state);
+            hits = ui::app::UI::draw(frame, &mut state);
         })?;
         
-        if poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+        if !pasted_keys.is_empty() || poll(std::time::Duration::from_millis(100))? {
+            let event = match pasted_keys.pop_front() {
+                Some(key) => Event::Key(key),
+                None => event::read()?,
+            };
+            if let Event::Paste(text) = &event {
+                // The body editors take a paste as one edit, elsewhere it is typed key by key
+                if !state.paste_into_editor(text) {
+                    pasted_keys.extend(
+                        text.chars()
+                            .filter(|c| !c.is_control())
+                            .map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)),
+                    );
+                }
+                continue;
+            }
+            if let Event::Mouse(mouse) = event {
+                handle_mouse(&mut state, &hits, mouse, &storage, &response_tx, &grpc_response_tx);
+                continue;
+            }
+            if let Event::Key(key) = event {
             if state.show_welcome {
                 // Any key dismisses the welcome screen
                 state.show_welcome = false;
                 continue;
             }
+            // Status messages stay up until the next key
+            state.status_message = None;
+
+            if state.confirmation.is_some() {
+                handle_confirmation(&mut state, key, &storage);
+                continue;
+            }
             
             if state.show_export_menu {
                 handle_export_menu(&mut state, key);
@@ -206,20 +360,59 @@ async fn main() -> anyhow::Result<()> {
                 continue;
             }
 
+            if state.show_proxy_settings {
+                handle_proxy_settings(&mut state, key, &storage);
+                continue;
+            }
+
+            if state.show_defaults_editor {
+                handle_defaults_editor(&mut state, key, &storage);
+                continue;
+            }
+
+            if state.show_diff_popup {
+                handle_diff_popup(&mut state, key);
+                continue;
+            }
+
+            if state.show_command_palette {
+                handle_command_palette(&mut state, key, &storage, &response_tx, &grpc_response_tx);
+                continue;
+            }
+
+            if state.show_trash {
+                handle_trash(&mut state, key, &storage);
+                continue;
+            }
+
             if state.show_help {
+                let closes = key.code == KeyCode::Esc
+                    || state.keymap.actions(KeyScope::Global, &key).contains(&Action::ToggleHelp);
                 match key.code {
-                    KeyCode::Char('?') | KeyCode::Esc => {
-                        Action::ToggleHelp.execute(&mut state);
-                    }
+                    _ if closes => Action::ToggleHelp.execute(&mut state),
+                    KeyCode::Down | KeyCode::Char('j') => state.help_scroll = state.help_scroll.saturating_add(1),
+                    KeyCode::Up | KeyCode::Char('k') => state.help_scroll = state.help_scroll.saturating_sub(1),
+                    KeyCode::PageDown => state.help_scroll = state.help_scroll.saturating_add(10),
+                    KeyCode::PageUp => state.help_scroll = state.help_scroll.saturating_sub(10),
                     _ => {}
                 }
                 continue;
             }
             
             if state.input_mode == InputMode::Editing {
-                match state.protocol_type {
-                    ProtocolType::Http => handle_edit_mode(&mut state, key, &storage),
-                    ProtocolType::Grpc => handle_grpc_edit_mode(&mut state, key, &storage),
+                let mut handled = false;
+                for action in state.keymap.actions(KeyScope::Editing, &key) {
+                    if perform_editing_action(&mut terminal, &mut state, &storage, action)? {
+                        handled = true;
+                        break;
+                    }
+                }
+                if handled {
+                    continue;
+                }
+                match state.protocol_type() {
+                    ProtocolType::Http => handle_edit_mode(&mut state, key),
+                    ProtocolType::Grpc => handle_grpc_edit_mode(&mut state, key),
                 }
                 continue;
             }
@@ -234,436 +427,766 @@ async fn main() -> anyhow::Result<()> {
                 continue;
             }
 
-            match (key.code, key.modifiers) {
-                (KeyCode::Char('q'), KeyModifiers::NONE) => {
-                    Action::Quit.execute(&mut state);
-                }
-                (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
-                    Action::Quit.execute(&mut state);
-                }
-                (KeyCode::Char('?'), KeyModifiers::NONE) => {
-                    Action::ToggleHelp.execute(&mut state);
-                }
-                (KeyCode::Tab, KeyModifiers::NONE) => {
-                    Action::NextPanel.execute(&mut state);
-                }
-                (KeyCode::BackTab, KeyModifiers::SHIFT) => {
-                    Action::PrevPanel.execute(&mut state);
-                }
-                (KeyCode::Down | KeyCode::Char('j'), KeyModifiers::NONE) => {
-                    match state.focused_panel {
-                        app::state::Panel::Collections => Action::NextCollection.execute(&mut state),
-                        app::state::Panel::Requests => Action::NextRequest.execute(&mut state),
-                        app::state::Panel::Response => state.scroll_response_down(),
-                        _ => {}
-                    }
-                }
-                (KeyCode::Up | KeyCode::Char('k'), KeyModifiers::NONE) => {
-                    match state.focused_panel {
-                        app::state::Panel::Collections => Action::PrevCollection.execute(&mut state),
-                        app::state::Panel::Requests => Action::PrevRequest.execute(&mut state),
-                        app::state::Panel::Response => state.scroll_response_up(),
-                        _ => {}
-                    }
-                }
-                (KeyCode::Char('t'), KeyModifiers::NONE) => {
-                    if state.focused_panel == Panel::RequestEditor {
-                        Action::NextEditorTab.execute(&mut state);
-                    }
-                }
-                (KeyCode::Enter, KeyModifiers::NONE) => {
-                    match state.protocol_type {
-                        ProtocolType::Http => {
-                            if let Some(request) = state.get_current_request().cloned() {
-                                if !state.is_loading {
-                                    state.is_loading = true;
-                                    state.loading_message = format!("Sending {} request...", request.method.as_str());
-                                    state.reset_response_scroll();
-                                    state.current_response = None;
-
-                                    let client = http_client.clone();
-                                    let tx = response_tx.clone();
-
-                                    tokio::spawn(async move {
-                                        let result = match client.execute(&request).await {
-                                            Ok(response) => HttpResult::Success(response),
-                                            Err(e) => HttpResult::Error(e.to_string()),
-                                        };
-                                        let _ = tx.send(result).await;
-                                    });
-                                }
-                            }
-                        }
-                        ProtocolType::Grpc => {
-                            if let Some(request) = state.get_current_grpc_request().cloned() {
-                                if !state.is_loading {
-                                    state.is_loading = true;
-                                    state.loading_message = format!("Calling gRPC method {}...", request.method_name);
-                                    state.reset_response_scroll();
-                                    state.grpc_response = None;
-
-                                    let tx = grpc_response_tx.clone();
-                                    let proto_schema = state.get_selected_proto_schema().cloned();
-
-                                    tokio::spawn(async move {
-                                        let grpc_client = grpc::client::GrpcClient::new();
-                                        let result = match grpc_client.execute_unary(&request, proto_schema.as_ref()).await {
-                                            Ok(response) => GrpcResult::Success(response),
-                                            Err(e) => GrpcResult::Error(e.to_string()),
-                                        };
-                                        let _ = tx.send(result).await;
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-                (KeyCode::Char('n'), KeyModifiers::NONE) => {
-                    match state.protocol_type {
-                        ProtocolType::Http => {
-                            Action::NewRequest.execute(&mut state);
-                            if let Some(request) = state.requests.last() {
-                                let _ = storage.save_request(request);
-                            }
-                            state.current_response = None;
-                            state.reset_response_scroll();
-                        }
-                        ProtocolType::Grpc => {
-                            Action::NewGrpcRequest.execute(&mut state);
-                            if let Some(request) = state.grpc_requests.last() {
-                                let _ = storage.save_grpc_request(request);
-                            }
-                            state.grpc_response = None;
-                            state.reset_response_scroll();
-                        }
-                    }
-                }
-                (KeyCode::Char('g'), KeyModifiers::NONE) => {
-                    // Jump to first request when in Requests panel
-                    if state.focused_panel == Panel::Requests {
-                        Action::FirstRequest.execute(&mut state);
-                    }
-                }
-                (KeyCode::Char('G'), KeyModifiers::SHIFT) => {
-                    // Jump to last request when in Requests panel
-                    if state.focused_panel == Panel::Requests {
-                        Action::LastRequest.execute(&mut state);
-                    }
-                }
-                (KeyCode::Char('f'), KeyModifiers::CONTROL) => {
-                    // Page down when in Requests panel
-                    if state.focused_panel == Panel::Requests {
-                        Action::PageDownRequests.execute(&mut state);
-                    }
-                }
-                (KeyCode::Char('b'), KeyModifiers::CONTROL) => {
-                    // Page up when in Requests panel
-                    if state.focused_panel == Panel::Requests {
-                        Action::PageUpRequests.execute(&mut state);
-                    }
-                }
-                (KeyCode::Char('/'), KeyModifiers::NONE) => {
-                    // Enter search mode when in Requests panel and not already in search mode
-                    if state.focused_panel == Panel::Requests && !state.request_search_mode {
-                        Action::EnterRequestSearch.execute(&mut state);
-                    }
-                }
-                (KeyCode::Char('p'), KeyModifiers::NONE) => {
-                    // Toggle protocol type
-                    state.protocol_type = match state.protocol_type {
-                        ProtocolType::Http => ProtocolType::Grpc,
-                        ProtocolType::Grpc => ProtocolType::Http,
-                    };
+            if state.response_filter_mode {
+                handle_response_filter_mode(&mut state, key, &storage);
+                continue;
+            }
 
-                    // Clear responses and input buffers when switching protocols
-                    state.current_response = None;
-                    state.grpc_response = None;
-                    state.clear_input_buffers();
-                    state.reset_response_scroll();
-
-                    // Reset selection when switching protocols, respecting collection filter
-                    state.update_selected_request_for_collection();
-                }
-                (KeyCode::Char('d'), KeyModifiers::NONE) => {
-                    if let Some(idx) = state.selected_request {
-                        match state.protocol_type {
-                            ProtocolType::Http => {
-                                if let Some(request) = state.requests.get(idx) {
-                                    let request_id = request.id;
-                                    state.requests.remove(idx);
-                                    let _ = storage.delete_request(&request_id);
-
-                                    // Clear response and input buffers
-                                    state.current_response = None;
-                                    state.clear_input_buffers();
-                                    state.reset_response_scroll();
-
-                                    // Update selection to next valid request in collection
-                                    state.update_selected_request_for_collection();
-                                }
-                            }
-                            ProtocolType::Grpc => {
-                                if let Some(request) = state.grpc_requests.get(idx) {
-                                    let request_id = request.id;
-                                    state.grpc_requests.remove(idx);
-                                    let _ = storage.delete_grpc_request(&request_id);
-
-                                    // Clear response and input buffers
-                                    state.grpc_response = None;
-                                    state.clear_input_buffers();
-                                    state.reset_response_scroll();
-
-                                    // Update selection to next valid request in collection
-                                    state.update_selected_request_for_collection();
-                                }
-                            }
-                        }
-                    }
+            if state.response_search_mode {
+                handle_response_search_mode(&mut state, key);
+                continue;
+            }
+
+            if state.focused_panel == Panel::Response && state.is_json_tree_shown() && handle_json_tree(&mut state, key) {
+                continue;
+            }
+
+            // The focused panel's bindings first; an action that does not apply passes the key on
+            for action in state.keymap.actions(KeyScope::for_panel(state.focused_panel), &key) {
+                if perform_action(&mut state, &storage, action, &response_tx, &grpc_response_tx) {
+                    break;
                 }
-                (KeyCode::Char('y'), KeyModifiers::NONE) => {
-                    match state.protocol_type {
-                        ProtocolType::Http => {
-                            if let Some(request) = state.get_current_request() {
-                                let mut new_request = request.clone();
-                                new_request.id = Uuid::new_v4();
-                                new_request.name = format!("{} (copy)", new_request.name);
-                                if let Some(collection_idx) = state.selected_collection {
-                                    if let Some(collection) = state.collections.get(collection_idx) {
-                                        new_request.collection_id = Some(collection.id);
+            }
+            }
+        }
+        
+        if state.should_quit {
+            break;
+        }
+    }
+    
+    disable_raw_mode()?;
+    if config::Config::global().ui.mouse {
+        terminal.backend_mut().execute(DisableMouseCapture)?;
+    }
+    terminal.backend_mut().execute(DisableBracketedPaste)?;
+    terminal.backend_mut().execute(LeaveAlternateScreen)?;
+    
+    Ok(())
+}
+
+/// Run a normal-mode action. `false` when it does not apply right now, so the key's next binding is tried,
+/// e.g. `n` steps through search matches when there are some and creates a request otherwise.
+fn perform_action(
+    state: &mut AppState,
+    storage: &storage::Storage,
+    action: Action,
+    response_tx: &mpsc::Sender<HttpResult>,
+    grpc_response_tx: &mpsc::Sender<GrpcResult>,
+) -> bool {
+    let is_http = state.protocol_type() == ProtocolType::Http;
+    match action {
+        Action::NextSearchMatch | Action::PrevSearchMatch if state.response_search_matches.is_empty() => return false,
+        Action::NextResponseTab | Action::PinResponse | Action::CompareResponses | Action::SaveResponseBody
+            if !is_http =>
+        {
+            return false
+        }
+        Action::OpenProtoLoader if is_http => return false,
+        Action::SendRequest => send_request(state, response_tx, grpc_response_tx),
+        Action::NewRequest | Action::NewGrpcRequest => {
+            action.execute(state);
+            if let Some(request) = state.requests.last() {
+                let _ = storage.save_any_request(request);
+                save_request_folder(state, storage, request.id());
+            }
+        }
+        Action::DuplicateRequest => duplicate_request(state, storage),
+        Action::DeleteRequest => {
+            if let Some(entry) = state.trash_request() {
+                save_to_trash(storage, entry);
+            }
+        }
+        Action::UndoDelete => match state.undo_delete() {
+            Ok(entry) => save_restored(state, storage, &entry),
+            Err(e) => state.status_message = Some(e),
+        },
+        Action::PasteRequest => match state.paste_request() {
+            Ok(request_id) => save_pasted_request(state, storage, request_id),
+            Err(e) => state.status_message = Some(e),
+        },
+        Action::MoveRequestUp | Action::MoveRequestDown => match state.shift_request(action == Action::MoveRequestDown) {
+            Ok(sort_indices) => {
+                for (request_id, sort_index) in sort_indices {
+                    let _ = storage.save_sort_index(&request_id, sort_index);
+                }
+            }
+            Err(e) => state.status_message = Some(e),
+        },
+        Action::MoveCollectionUp | Action::MoveCollectionDown if state.selected_folder.is_some() => {
+            let moved = state.shift_folder(action == Action::MoveCollectionDown);
+            for folder in state.folders.iter().filter(|f| moved.contains(&f.id)) {
+                let _ = storage.save_folder(folder);
+            }
+        }
+        Action::MoveCollectionUp | Action::MoveCollectionDown => {
+            if state.shift_collection(action == Action::MoveCollectionDown) {
+                for (sort_index, collection) in state.collections.iter().enumerate() {
+                    let _ = storage.save_sort_index(&collection.id, sort_index as u32);
+                }
+            }
+        }
+        Action::DuplicateCollection => {
+            if let Some(collection_id) = state.duplicate_collection() {
+                save_collection_contents(state, storage, collection_id);
+            }
+        }
+        Action::NewCollection => {
+            Action::NewCollection.execute(state);
+            if let Some(collection) = state.collections.last() {
+                let _ = storage.save_collection(collection);
+            }
+        }
+        Action::DeleteCollection => {
+            if !state.confirm_node_deletion() {
+                delete_node(state, storage);
+            }
+        }
+        Action::NewFolder => {
+            if let Some(folder) = state.new_folder() {
+                let _ = storage.save_folder(folder);
+            }
+        }
+        Action::MoveFolder if state.moving_folder.is_some() => match state.drop_folder() {
+            Ok(moved) => save_moved_folders(state, storage, &moved),
+            Err(e) => state.status_message = Some(e),
+        },
+        Action::MoveFolder => return state.start_moving_folder(),
+        Action::CancelRequest if state.moving_folder.is_some() => state.cancel_moving_folder(),
+        Action::GrowPanel
+        | Action::ShrinkPanel
+        | Action::ToggleCollectionsPanel
+        | Action::ToggleRequestsPanel
+        | Action::CycleLayout
+        | Action::ResetLayout => {
+            action.execute(state);
+            let _ = storage.save_panel_layout(&state.panel_layout);
+        }
+        _ => action.execute(state),
+    }
+    true
+}
+
+/// Run an edit-mode action, `false` when the key should reach the focused field instead
+fn perform_editing_action(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    state: &mut AppState,
+    storage: &storage::Storage,
+    action: Action,
+) -> anyhow::Result<bool> {
+    let has_text_editor = state.focused_text_editor().is_some();
+    match action {
+        // While a key or value is typed, Esc and Tab finish it or move between the two
+        Action::FinishEditing | Action::NextField | Action::PrevField if state.is_editing_key_value() => return Ok(false),
+        Action::UndoEdit | Action::RedoEdit | Action::FormatBody | Action::OpenExternalEditor if !has_text_editor => {
+            return Ok(false)
+        }
+        Action::FinishEditing => finish_editing(state, storage),
+        Action::OpenExternalEditor => edit_in_external_editor(terminal, state)?,
+        _ => action.execute(state),
+    }
+    Ok(true)
+}
+
+/// Save the edited request and go back to normal mode
+fn finish_editing(state: &mut AppState, storage: &storage::Storage) {
+    match state.protocol_type() {
+        ProtocolType::Http => {
+            state.save_input_to_request();
+            if let Some(request) = state.get_current_request() {
+                let _ = storage.save_request(request);
+            }
+        }
+        ProtocolType::Grpc => {
+            state.save_grpc_input_to_request();
+            if let Some(request) = state.get_current_grpc_request() {
+                let _ = storage.save_grpc_request(request);
+            }
+        }
+    }
+    state.input_mode = InputMode::Normal;
+    state.kv_edit_mode = app::state::KeyValueEditMode::None;
+}
+
+fn send_request(
+    state: &mut AppState,
+    response_tx: &mpsc::Sender<HttpResult>,
+    grpc_response_tx: &mpsc::Sender<GrpcResult>,
+) {
+    // A tab with a request running stays open
+    state.pin_active_tab();
+    match state.protocol_type() {
+        ProtocolType::Http => {
+            if let Some(request) = state.get_current_request().map(|r| state.resolve_http_request(r)) {
+                state.loading_message = format!("Sending {} request...", request.method.as_str());
+                state.reset_response_scroll();
+                state.current_response = None;
+
+                let proxy = state.effective_proxy(request.collection_id).clone();
+                let cached_token = state.oauth2_tokens.get(&request.id).cloned();
+                let tx = response_tx.clone();
+                let dispatch_id = Uuid::new_v4();
+                let request_id = request.id;
+
+                let handle = tokio::spawn(async move {
+                    let mut request = request;
+                    let result = match http::client::HttpClient::for_request(&proxy, &request) {
+                        Ok(client) => {
+                            let progress_tx = tx.clone();
+                            let prepared = client.prepare_oauth2(&mut request, cached_token.as_ref(), |url| {
+                                let message = if http::oauth2::open_in_browser(url) {
+                                    "Waiting for OAuth2 login in the browser...".to_string()
+                                } else {
+                                    format!("Open this URL to log in: {}", url)
+                                };
+                                let _ = progress_tx.try_send(HttpResult::Progress(dispatch_id, message));
+                            }).await;
+                            match prepared {
+                                Ok(issued) => {
+                                    if let Some(token) = issued {
+                                        let _ = tx.send(HttpResult::TokenIssued(request_id, Box::new(token))).await;
                                     }
-                                }
-                                state.requests.push(new_request.clone());
-                                state.selected_request = Some(state.requests.len() - 1);
-                                let _ = storage.save_request(&new_request);
-                                // Clear response and input buffers for the new request
-                                state.current_response = None;
-                                state.clear_input_buffers();
-                                state.reset_response_scroll();
-                            }
-                        }
-                        ProtocolType::Grpc => {
-                            if let Some(request) = state.get_current_grpc_request() {
-                                let mut new_request = request.clone();
-                                new_request.id = Uuid::new_v4();
-                                new_request.name = format!("{} (copy)", new_request.name);
-                                if let Some(collection_idx) = state.selected_collection {
-                                    if let Some(collection) = state.collections.get(collection_idx) {
-                                        new_request.collection_id = Some(collection.id);
+                                    match client.build_request(&request) {
+                                        Ok(http_request) => {
+                                            let sent = http::client::sent_request(&http_request);
+                                            match client.send(request_id, http_request).await {
+                                                Ok(mut response) => {
+                                                    response.sent_request = Some(sent);
+                                                    HttpResult::Success(dispatch_id, response)
+                                                }
+                                                Err(e) => HttpResult::Error(dispatch_id, e.to_string(), Some(sent)),
+                                            }
+                                        }
+                                        Err(e) => HttpResult::Error(dispatch_id, e.to_string(), None),
                                     }
                                 }
-                                state.grpc_requests.push(new_request.clone());
-                                state.selected_request = Some(state.grpc_requests.len() - 1);
-                                let _ = storage.save_grpc_request(&new_request);
-                                // Clear response and input buffers for the new request
-                                state.grpc_response = None;
-                                state.clear_input_buffers();
-                                state.reset_response_scroll();
+                                Err(e) => HttpResult::Error(dispatch_id, format!("OAuth2: {:#}", e), None),
                             }
                         }
-                    }
-                }
-                (KeyCode::Char('e'), KeyModifiers::NONE) => {
-                    if state.focused_panel == Panel::RequestEditor {
-                        match state.protocol_type {
-                            ProtocolType::Http => {
-                                state.load_current_request_to_input();
-                                state.input_mode = InputMode::Editing;
-                                // Start with Name field
-                                state.editor_focused_field = EditorField::Name;
-                            }
-                            ProtocolType::Grpc => {
-                                state.load_current_grpc_request_to_input();
-                                state.input_mode = InputMode::Editing;
-                                // Start with ServerUrl field
-                                state.grpc_editor_focused_field = app::state::GrpcEditorField::ServerUrl;
-                            }
-                        }
-                    } else if state.focused_panel == Panel::Collections {
-                        Action::EditCollection.execute(&mut state);
-                    }
-                }
-                (KeyCode::Char('c'), KeyModifiers::NONE) => {
-                    if state.focused_panel == Panel::Collections {
-                        Action::NewCollection.execute(&mut state);
-                        if let Some(collection) = state.collections.last() {
-                            let _ = storage.save_collection(collection);
-                        }
-                    } else if state.focused_panel == Panel::Response {
-                        Action::CopyResponse.execute(&mut state);
-                    }
-                }
-                (KeyCode::Char('x'), KeyModifiers::NONE) => {
-                    if state.focused_panel == Panel::Collections {
-                        if let Some(idx) = state.selected_collection {
-                            if let Some(collection) = state.collections.get(idx) {
-                                let collection_id = collection.id;
+                        Err(e) => HttpResult::Error(dispatch_id, format!("{:#}", e), None),
+                    };
+                    let _ = tx.send(result).await;
+                });
+                state.track_in_flight(dispatch_id, request_id, ProtocolType::Http, handle.abort_handle());
+            }
+        }
+        ProtocolType::Grpc => {
+            if let Some(request) = state.get_current_grpc_request().map(|r| state.resolve_grpc_request(r)) {
+                state.loading_message = format!("Calling gRPC method {}...", request.method_name);
+                state.reset_response_scroll();
+                state.grpc_response = None;
+
+                let tx = grpc_response_tx.clone();
+                let proto_schema = state.get_selected_proto_schema().cloned();
+                let proxy = state.effective_proxy(request.collection_id).clone();
+                let dispatch_id = Uuid::new_v4();
+                let request_id = request.id;
+
+                let handle = tokio::spawn(async move {
+                    let grpc_client = grpc::client::GrpcClient::with_proxy(proxy);
+                    let result = match grpc_client.execute_unary(&request, proto_schema.as_ref()).await {
+                        Ok(response) => GrpcResult::Success(dispatch_id, response),
+                        Err(e) => GrpcResult::Error(dispatch_id, e.to_string()),
+                    };
+                    let _ = tx.send(result).await;
+                });
+                state.track_in_flight(dispatch_id, request_id, ProtocolType::Grpc, handle.abort_handle());
+            }
+        }
+    }
+}
 
-                                // Remove all HTTP requests belonging to this collection
-                                state.requests.retain(|r| r.collection_id != Some(collection_id));
+fn duplicate_request(state: &mut AppState, storage: &storage::Storage) {
+    let Some(request) = state.selected_request.and_then(|idx| state.requests.get(idx)) else {
+        return;
+    };
+    let mut new_request = request.duplicate(format!("{} (copy)", request.name()));
+    if let Some(collection) = state.selected_collection.and_then(|idx| state.collections.get(idx)) {
+        new_request.set_collection_id(Some(collection.id));
+    }
+    if let Some(folder_id) = state.selected_folder {
+        state.request_folders.insert(new_request.id(), folder_id);
+        let _ = storage.save_request_folder(&new_request.id(), &folder_id);
+    }
+    let _ = storage.save_any_request(&new_request);
+    state.requests.push(new_request);
+    state.selected_request = Some(state.requests.len() - 1);
+    // The copy opens in its own tab on the next sync
+    state.clear_input_buffers();
+}
 
-                                // Remove all gRPC requests belonging to this collection
-                                state.grpc_requests.retain(|r| r.collection_id != Some(collection_id));
+/// Move the selected folder, or the collection when no folder is selected, to the trash
+fn delete_node(state: &mut AppState, storage: &storage::Storage) {
+    let entry = if state.selected_folder.is_some() {
+        state.trash_folder()
+    } else {
+        state.trash_collection()
+    };
+    if let Some(entry) = entry {
+        save_to_trash(storage, entry);
+    }
+}
 
-                                // Delete from storage
-                                let _ = storage.delete_collection(&collection_id);
-                                let _ = storage.delete_requests_by_collection(&collection_id);
-                                let _ = storage.delete_grpc_requests_by_collection(&collection_id);
+/// Store a trash entry and delete what it holds from the stored collections, requests and schemas.
+/// OAuth2 tokens and recent filters stay until the entry is purged.
+fn save_to_trash(storage: &storage::Storage, entry: &TrashEntry) {
+    let _ = storage.save_trash_entry(entry);
+    for collection in &entry.collections {
+        let _ = storage.delete_collection(&collection.id);
+        let _ = storage.delete_sort_index(&collection.id);
+    }
+    for folder in &entry.folders {
+        let _ = storage.delete_folder(&folder.id);
+    }
+    for request in &entry.requests {
+        let _ = storage.delete_any_request(request);
+        let _ = storage.delete_request_folder(&request.id());
+        let _ = storage.delete_sort_index(&request.id());
+    }
+    for (node_id, _) in &entry.node_defaults {
+        let _ = storage.delete_request_defaults(node_id);
+    }
+    for (collection_id, _) in &entry.collection_proxies {
+        let _ = storage.delete_collection_proxy(collection_id);
+    }
+    for schema in &entry.proto_schemas {
+        let _ = storage.delete_proto_schema(&schema.id);
+    }
+}
 
-                                // Delete the collection from state
-                                Action::DeleteCollection.execute(&mut state);
+/// Save what came back from the trash, in the place it was restored to, and drop the stored entry
+fn save_restored(state: &AppState, storage: &storage::Storage, entry: &TrashEntry) {
+    for collection in &entry.collections {
+        let _ = storage.save_collection(collection);
+    }
+    for folder in &entry.folders {
+        let _ = storage.save_folder(folder);
+    }
+    for request in &entry.requests {
+        let _ = storage.save_any_request(request);
+    }
+    for (request_id, folder_id) in &entry.request_folders {
+        let _ = storage.save_request_folder(request_id, folder_id);
+    }
+    for (node_id, defaults) in &entry.node_defaults {
+        let _ = storage.save_request_defaults(node_id, defaults);
+    }
+    for (collection_id, proxy) in &entry.collection_proxies {
+        let _ = storage.save_collection_proxy(collection_id, proxy);
+    }
+    for schema in &entry.proto_schemas {
+        let _ = storage.save_proto_schema(schema);
+    }
 
-                                // Clear responses and input buffers
-                                state.current_response = None;
-                                state.grpc_response = None;
-                                state.clear_input_buffers();
-                                state.reset_response_scroll();
+    // Restored collections go last, a restored request goes to the end of its collection or folder
+    for (request_id, sort_index) in &entry.sort_indices {
+        let _ = storage.save_sort_index(request_id, *sort_index);
+    }
+    if !entry.collections.is_empty() {
+        for (sort_index, collection) in state.collections.iter().enumerate() {
+            let _ = storage.save_sort_index(&collection.id, sort_index as u32);
+        }
+    }
+    if entry.kind == TrashKind::Request {
+        for (request_id, sort_index) in state.node_sort_indices() {
+            let _ = storage.save_sort_index(&request_id, sort_index);
+        }
+    }
+    let _ = storage.delete_trash_entry(&entry.id);
+}
 
-                                // Update selection
-                                state.update_selected_request_for_collection();
-                            }
-                        }
-                    }
-                }
-                (KeyCode::Char('o'), KeyModifiers::NONE) => {
-                    // Open export menu based on current protocol
-                    match state.protocol_type {
-                        ProtocolType::Http => {
-                            Action::OpenCurlExportMenu.execute(&mut state);
-                        }
-                        ProtocolType::Grpc => {
-                            Action::OpenGrpcurlExportMenu.execute(&mut state);
-                        }
-                    }
-                }
-                (KeyCode::Char('i'), KeyModifiers::NONE) => {
-                    Action::OpenImportMenu.execute(&mut state);
-                }
-                (KeyCode::Char('l'), KeyModifiers::NONE) => {
-                    // Open proto loader only in gRPC mode
-                    if state.protocol_type == ProtocolType::Grpc {
-                        state.open_proto_loader();
-                    }
+/// Delete a purged trash entry and the OAuth2 tokens and recent filters of its requests
+fn delete_purged(storage: &storage::Storage, entry: &TrashEntry) {
+    for request in &entry.requests {
+        let _ = storage.delete_oauth2_token(&request.id());
+        let _ = storage.delete_recent_filters(&request.id());
+    }
+    let _ = storage.delete_trash_entry(&entry.id);
+}
+
+/// Save the folders a move changed, and the requests in them that changed collection
+fn save_moved_folders(state: &AppState, storage: &storage::Storage, moved: &[Uuid]) {
+    for folder in state.folders.iter().filter(|f| moved.contains(&f.id)) {
+        let _ = storage.save_folder(folder);
+    }
+    let in_moved = |request_id: &Uuid| state.request_folders.get(request_id).is_some_and(|id| moved.contains(id));
+    for request in state.requests.iter().filter(|r| in_moved(&r.id())) {
+        let _ = storage.save_any_request(request);
+    }
+}
+
+/// Save a pasted request where it went, and the order of the requests there
+fn save_pasted_request(state: &AppState, storage: &storage::Storage, request_id: Uuid) {
+    if let Some(request) = state.requests.iter().find(|r| r.id() == request_id) {
+        let _ = storage.save_any_request(request);
+    }
+    match state.request_folders.get(&request_id) {
+        Some(folder_id) => {
+            let _ = storage.save_request_folder(&request_id, folder_id);
+        }
+        None => {
+            let _ = storage.delete_request_folder(&request_id);
+        }
+    }
+    for (request_id, sort_index) in state.node_sort_indices() {
+        let _ = storage.save_sort_index(&request_id, sort_index);
+    }
+}
+
+/// Save a collection with everything in it, e.g. after duplicating it
+fn save_collection_contents(state: &AppState, storage: &storage::Storage, collection_id: Uuid) {
+    if let Some(collection) = state.collections.iter().find(|c| c.id == collection_id) {
+        let _ = storage.save_collection(collection);
+    }
+    if let Some(proxy) = state.collection_proxies.get(&collection_id) {
+        let _ = storage.save_collection_proxy(&collection_id, proxy);
+    }
+    let folders = state.folders.iter().filter(|f| f.collection_id == collection_id);
+    for folder in folders.clone() {
+        let _ = storage.save_folder(folder);
+    }
+    let nodes = std::iter::once(collection_id).chain(folders.map(|f| f.id));
+    for node_id in nodes {
+        if let Some(defaults) = state.node_defaults.get(&node_id) {
+            let _ = storage.save_request_defaults(&node_id, defaults);
+        }
+    }
+    for request in state.requests.iter().filter(|r| r.collection_id() == Some(collection_id)) {
+        let _ = storage.save_any_request(request);
+        if let Some(folder_id) = state.request_folders.get(&request.id()) {
+            let _ = storage.save_request_folder(&request.id(), folder_id);
+        }
+    }
+}
+
+/// Store which folder a new request went into, if any
+fn save_request_folder(state: &AppState, storage: &storage::Storage, request_id: Uuid) {
+    if let Some(folder_id) = state.request_folders.get(&request_id) {
+        let _ = storage.save_request_folder(&request_id, folder_id);
+    }
+}
+
+/// Edit the focused body or gRPC message in `$VISUAL` / `$EDITOR`, with the TUI suspended meanwhile
+fn edit_in_external_editor(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    state: &mut AppState,
+) -> anyhow::Result<()> {
+    let extension = state.focused_text_extension();
+    let undo_hint = state
+        .keymap
+        .first_key(KeyScope::Editing, Action::UndoEdit)
+        .map_or_else(String::new, |key| format!(" ({} to undo)", key));
+    let Some(editor) = state.focused_text_editor() else {
+        return Ok(());
+    };
+    let text = editor.text();
+    let command = utils::external_editor::editor_command();
+
+    // Hand the terminal over as the shell left it
+    disable_raw_mode()?;
+    if config::Config::global().ui.mouse {
+        terminal.backend_mut().execute(DisableMouseCapture)?;
+    }
+    terminal.backend_mut().execute(DisableBracketedPaste)?;
+    terminal.backend_mut().execute(LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    let edited = utils::external_editor::edit_text(&command, &text, extension);
+
+    terminal.backend_mut().execute(EnterAlternateScreen)?;
+    terminal.backend_mut().execute(EnableBracketedPaste)?;
+    if config::Config::global().ui.mouse {
+        terminal.backend_mut().execute(EnableMouseCapture)?;
+    }
+    enable_raw_mode()?;
+    // The editor drew over the screen, so redraw all of it
+    terminal.clear()?;
+
+    let message = match edited {
+        Ok(edited) if edited == text => format!("No changes from {}", command),
+        Ok(edited) => {
+            editor.replace_text(&edited);
+            format!("Loaded the changes from {}{}", command, undo_hint)
+        }
+        Err(e) => format!("External editor failed: {:#}", e),
+    };
+    state.status_message = Some(message);
+    Ok(())
+}
+
+/// Steps the wheel moves the response or the JSON tree cursor
+const WHEEL_STEP: usize = 3;
+
+/// Clicks focus and select what they land on, the wheel scrolls what is under the pointer
+fn handle_mouse(
+    state: &mut AppState,
+    hits: &HitMap,
+    mouse: MouseEvent,
+    storage: &storage::Storage,
+    response_tx: &mpsc::Sender<HttpResult>,
+    grpc_response_tx: &mpsc::Sender<GrpcResult>,
+) {
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            handle_click(state, hits, (mouse.column, mouse.row), storage, response_tx, grpc_response_tx)
+        }
+        MouseEventKind::ScrollDown => handle_wheel(state, hits.panel_at(mouse.column, mouse.row), true),
+        MouseEventKind::ScrollUp => handle_wheel(state, hits.panel_at(mouse.column, mouse.row), false),
+        _ => {}
+    }
+}
+
+fn handle_click(
+    state: &mut AppState,
+    hits: &HitMap,
+    (column, row): (u16, u16),
+    storage: &storage::Storage,
+    response_tx: &mpsc::Sender<HttpResult>,
+    grpc_response_tx: &mpsc::Sender<GrpcResult>,
+) {
+    if state.show_welcome {
+        state.show_welcome = false;
+        return;
+    }
+    let Some((area, target)) = hits.target_at(column, row) else {
+        return;
+    };
+    state.status_message = None;
+
+    if state.is_popup_open() {
+        handle_popup_click(state, target, storage, response_tx, grpc_response_tx);
+        return;
+    }
+    // A collection name or a search being typed keeps the keyboard until it is finished
+    if state.editing_collection || state.request_search_mode || state.response_filter_mode || state.response_search_mode {
+        return;
+    }
+    // Clicking away from the editor saves the edits, like leaving edit mode with the keyboard
+    let in_editor = matches!(
+        target,
+        Target::Panel(Panel::RequestEditor) | Target::EditorTab(_) | Target::Field(_) | Target::GrpcField(_)
+    );
+    if state.input_mode == InputMode::Editing && !in_editor {
+        finish_editing(state, storage);
+    }
+
+    match target {
+        Target::Panel(panel) => state.focused_panel = panel,
+        Target::RequestTab(idx) => state.switch_to_tab(idx),
+        Target::Collection(row) => {
+            state.focused_panel = Panel::Collections;
+            // A second click on a collection or folder opens or closes it
+            if state.selected_tree_row() == Some(row) {
+                match state.collection_tree().get(row) {
+                    Some(node) if node.expanded => state.collapse_tree_node(),
+                    _ => state.expand_tree_node(),
                 }
-                _ => {}
+            } else {
+                state.select_tree_row(row);
+            }
+        }
+        Target::Request(idx) => {
+            state.focused_panel = Panel::Requests;
+            state.select_request(idx);
+        }
+        Target::EditorTab(tab) => {
+            state.focused_panel = Panel::RequestEditor;
+            if state.input_mode == InputMode::Editing {
+                state.focus_editor_field(tab.field());
+            } else {
+                state.editor_tab = tab;
+            }
+        }
+        Target::Field(field) => click_editor_field(state, field, area, column, row),
+        Target::GrpcField(field) => click_grpc_field(state, field, area, column, row),
+        Target::ResponseTab(tab) => {
+            state.focused_panel = Panel::Response;
+            state.set_response_tab(tab);
+        }
+        Target::PopupRow(_) | Target::Button(_) => {}
+    }
+}
+
+/// Row of a bordered list in `area` that screen `row` falls on
+fn list_row(area: Rect, row: u16) -> Option<usize> {
+    row.checked_sub(area.y + 1).map(usize::from).filter(|&row| row + 2 < area.height as usize)
+}
+
+/// Start editing if needed, focus the clicked field and put the cursor or selection under the click
+fn click_editor_field(state: &mut AppState, field: EditorField, area: Rect, column: u16, row: u16) {
+    let was_focused = state.input_mode == InputMode::Editing && state.editor_focused_field == field;
+    state.focused_panel = Panel::RequestEditor;
+    if state.input_mode != InputMode::Editing {
+        Action::EditRequest.execute(state);
+    }
+    state.focus_editor_field(field);
+
+    match field {
+        EditorField::Name => {
+            state.name_cursor = text_field_cursor(&state.name_input, state.name_cursor, was_focused, area, column);
+        }
+        EditorField::Url => {
+            state.url_cursor = text_field_cursor(&state.url_input, state.url_cursor, was_focused, area, column);
+        }
+        EditorField::Params => {
+            if let Some(row) = list_row(area, row).filter(|&row| row < state.params_input.len()) {
+                state.params_selected = row;
+            }
+        }
+        EditorField::Headers => {
+            if let Some(row) = list_row(area, row).filter(|&row| row < state.headers_input.len()) {
+                state.headers_selected = row;
             }
+        }
+        EditorField::Body => ui::components::text_editor::click(&mut state.body_editor, area, column, row),
+        EditorField::Auth => {
+            // The type row, then one row per field
+            if let Some(row) = list_row(area, row).filter(|&row| row <= state.auth_visible_fields().len()) {
+                state.select_auth_row(row);
             }
         }
-        
-        if state.should_quit {
-            break;
+        EditorField::Method => {}
+    }
+}
+
+fn click_grpc_field(state: &mut AppState, field: GrpcEditorField, area: Rect, column: u16, row: u16) {
+    let was_focused = state.input_mode == InputMode::Editing && state.grpc_editor_focused_field == field;
+    state.focused_panel = Panel::RequestEditor;
+    if state.input_mode != InputMode::Editing {
+        Action::EditRequest.execute(state);
+    }
+    state.kv_edit_mode = app::state::KeyValueEditMode::None;
+    state.grpc_editor_focused_field = field;
+
+    match field {
+        GrpcEditorField::Name => {
+            state.grpc_name_cursor =
+                text_field_cursor(&state.grpc_name_input, state.grpc_name_cursor, was_focused, area, column);
+        }
+        GrpcEditorField::ServerUrl => {
+            state.grpc_server_url_cursor =
+                text_field_cursor(&state.grpc_server_url_input, state.grpc_server_url_cursor, was_focused, area, column);
+        }
+        GrpcEditorField::ServiceName => {
+            state.grpc_service_name_cursor =
+                text_field_cursor(&state.grpc_service_name_input, state.grpc_service_name_cursor, was_focused, area, column);
+        }
+        GrpcEditorField::MethodName => {
+            state.grpc_method_name_cursor =
+                text_field_cursor(&state.grpc_method_name_input, state.grpc_method_name_cursor, was_focused, area, column);
+        }
+        GrpcEditorField::Message => ui::components::text_editor::click(&mut state.grpc_message_editor, area, column, row),
+        GrpcEditorField::Metadata => {
+            if let Some(row) = list_row(area, row).filter(|&row| row < state.grpc_metadata_input.len()) {
+                state.grpc_metadata_selected = row;
+            }
         }
     }
-    
-    disable_raw_mode()?;
-    terminal.backend_mut().execute(LeaveAlternateScreen)?;
-    
-    Ok(())
 }
 
-fn handle_edit_mode(state: &mut AppState, key: KeyEvent, storage: &storage::Storage) {
-    match key.code {
-        KeyCode::Esc => {
-            // If we're in key-value editing mode (editing a param/header key or value),
-            // let the specific handler deal with Esc to exit key-value editing mode
-            // Otherwise, exit the entire edit mode
-            if (state.editor_focused_field == EditorField::Params || 
-                state.editor_focused_field == EditorField::Headers) && 
-               state.kv_edit_mode != app::state::KeyValueEditMode::None {
-                // Let the specific field handler deal with Esc in key-value editing mode
-                match state.editor_focused_field {
-                    EditorField::Params => handle_params_edit(state, key),
-                    EditorField::Headers => handle_headers_edit(state, key),
-                    _ => {}
-                }
-            } else {
-                // Exit the entire edit mode
-                state.save_input_to_request();
-                if let Some(request) = state.get_current_request() {
-                    let _ = storage.save_request(request);
-                }
-                state.input_mode = InputMode::Normal;
-                state.kv_edit_mode = app::state::KeyValueEditMode::None;
-            }
-        }
-        KeyCode::Tab => {
-            // Check if we're in key-value editing mode for params or headers
-            if (state.editor_focused_field == EditorField::Params || 
-                state.editor_focused_field == EditorField::Headers) && 
-               state.kv_edit_mode != app::state::KeyValueEditMode::None {
-                // Let the specific field handler deal with Tab in key-value editing mode
-                match state.editor_focused_field {
-                    EditorField::Params => handle_params_edit(state, key),
-                    EditorField::Headers => handle_headers_edit(state, key),
-                    _ => {}
-                }
-            } else {
-                // Switch between fields in edit mode (forward)
-                state.kv_edit_mode = app::state::KeyValueEditMode::None; // Reset KV edit mode when switching fields
-                state.editor_focused_field = match state.editor_focused_field {
-                    EditorField::Name => EditorField::Method,
-                    EditorField::Method => EditorField::Url,
-                    EditorField::Url => EditorField::Params,
-                    EditorField::Params => EditorField::Headers,
-                    EditorField::Headers => EditorField::Body,
-                    EditorField::Body => EditorField::Auth,
-                    EditorField::Auth => EditorField::Name,
-                };
-                
-                // Update the UI tab to match the focused field
-                state.editor_tab = match state.editor_focused_field {
-                    EditorField::Params => app::state::EditorTab::Params,
-                    EditorField::Headers => app::state::EditorTab::Headers,
-                    EditorField::Body => app::state::EditorTab::Body,
-                    EditorField::Auth => app::state::EditorTab::Auth,
-                    _ => state.editor_tab, // Keep current tab for Name, Method, URL
+/// Buttons press their key in the import, export, proto loader, trash and confirmation popups; rows
+/// select list entries, or run the clicked palette entry
+fn handle_popup_click(
+    state: &mut AppState,
+    target: Target,
+    storage: &storage::Storage,
+    response_tx: &mpsc::Sender<HttpResult>,
+    grpc_response_tx: &mpsc::Sender<GrpcResult>,
+) {
+    use app::state::{ExportMenuStage, ProtoLoaderFocus};
+
+    match target {
+        Target::Button(code) => {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            if state.confirmation.is_some() {
+                handle_confirmation(state, key, storage);
+            } else if state.show_trash {
+                handle_trash(state, key, storage);
+            } else if state.show_export_menu {
+                handle_export_menu(state, key);
+            } else if state.show_import_menu {
+                handle_import_menu(state, key, storage);
+            } else if state.show_proto_loader {
+                // Load works on the path, Delete on the selected schema
+                state.proto_loader_focus = if code == KeyCode::Delete {
+                    ProtoLoaderFocus::SchemaList
+                } else {
+                    ProtoLoaderFocus::Input
                 };
+                handle_proto_loader(state, key, storage);
             }
         }
-        KeyCode::BackTab => {
-            // Check if we're in key-value editing mode for params or headers
-            if (state.editor_focused_field == EditorField::Params || 
-                state.editor_focused_field == EditorField::Headers) && 
-               state.kv_edit_mode != app::state::KeyValueEditMode::None {
-                // Let the specific field handler deal with Shift+Tab in key-value editing mode
-                match state.editor_focused_field {
-                    EditorField::Params => handle_params_edit(state, key),
-                    EditorField::Headers => handle_headers_edit(state, key),
-                    _ => {}
-                }
-            } else {
-                // Switch between fields in edit mode (backward)
-                state.kv_edit_mode = app::state::KeyValueEditMode::None; // Reset KV edit mode when switching fields
-                state.editor_focused_field = match state.editor_focused_field {
-                    EditorField::Name => EditorField::Auth,
-                    EditorField::Method => EditorField::Name,
-                    EditorField::Url => EditorField::Method,
-                    EditorField::Params => EditorField::Url,
-                    EditorField::Headers => EditorField::Params,
-                    EditorField::Body => EditorField::Headers,
-                    EditorField::Auth => EditorField::Body,
-                };
-                
-                // Update the UI tab to match the focused field
-                state.editor_tab = match state.editor_focused_field {
-                    EditorField::Params => app::state::EditorTab::Params,
-                    EditorField::Headers => app::state::EditorTab::Headers,
-                    EditorField::Body => app::state::EditorTab::Body,
-                    EditorField::Auth => app::state::EditorTab::Auth,
-                    _ => state.editor_tab, // Keep current tab for Name, Method, URL
-                };
+        Target::PopupRow(row) if state.show_export_menu => match state.export_menu_stage {
+            ExportMenuStage::SelectingCollection => state.export_selected_collection = Some(row),
+            ExportMenuStage::SelectingRequest => {
+                state.export_selected_request = state.export_request_indices().get(row).copied();
             }
+            ExportMenuStage::ShowingResult => {}
+        },
+        Target::PopupRow(row) if state.show_command_palette => {
+            run_palette_entry(state, storage, row, response_tx, grpc_response_tx);
         }
-        _ => {
-            match state.editor_focused_field {
-                EditorField::Name => handle_name_edit(state, key),
-                EditorField::Method => handle_method_edit(state, key),
-                EditorField::Url => handle_url_edit(state, key),
-                EditorField::Params => handle_params_edit(state, key),
-                EditorField::Headers => handle_headers_edit(state, key),
-                EditorField::Body => handle_body_edit(state, key),
-                EditorField::Auth => handle_auth_edit(state, key),
+        Target::PopupRow(row) if state.show_trash => state.selected_trash = row,
+        Target::PopupRow(row) if state.show_proto_loader => {
+            state.proto_loader_focus = ProtoLoaderFocus::SchemaList;
+            state.selected_proto_schema = Some(row);
+        }
+        _ => {}
+    }
+}
+
+fn handle_wheel(state: &mut AppState, panel: Option<Panel>, down: bool) {
+    if state.show_help {
+        state.help_scroll = if down {
+            state.help_scroll.saturating_add(WHEEL_STEP as u16)
+        } else {
+            state.help_scroll.saturating_sub(WHEEL_STEP as u16)
+        };
+        return;
+    }
+    if state.show_command_palette {
+        let count = state.command_palette.entries(state).len();
+        state.command_palette.move_selection(if down { 1 } else { -1 }, count);
+        return;
+    }
+    if state.is_popup_open() {
+        return;
+    }
+    // The selection stays put while a request, collection name or search is being typed
+    let lists_locked = state.input_mode == InputMode::Editing || state.editing_collection || state.request_search_mode;
+
+    match panel {
+        Some(Panel::Collections) if !lists_locked => {
+            if down { state.next_collection() } else { state.prev_collection() }
+        }
+        Some(Panel::Requests) if !lists_locked => {
+            if down { state.next_request() } else { state.prev_request() }
+        }
+        Some(Panel::Response) => {
+            if state.is_json_tree_shown() {
+                if let Some(tree) = state.json_tree.as_mut() {
+                    tree.move_cursor(if down { WHEEL_STEP as isize } else { -(WHEEL_STEP as isize) });
+                }
+                return;
+            }
+            for _ in 0..WHEEL_STEP {
+                if down { state.scroll_response_down() } else { state.scroll_response_up() }
             }
         }
+        _ => {}
+    }
+}
+
+/// Keys of edit mode that are not bound in the keymap go to the focused field
+fn handle_edit_mode(state: &mut AppState, key: KeyEvent) {
+    match state.editor_focused_field {
+        EditorField::Name => handle_name_edit(state, key),
+        EditorField::Method => handle_method_edit(state, key),
+        EditorField::Url => handle_url_edit(state, key),
+        EditorField::Params => handle_params_edit(state, key),
+        EditorField::Headers => handle_headers_edit(state, key),
+        EditorField::Body => handle_body_edit(state, key),
+        EditorField::Auth => handle_auth_edit(state, key),
     }
 }
 
@@ -941,104 +1464,92 @@ fn handle_headers_edit(state: &mut AppState, key: KeyEvent) {
 }
 
 fn handle_body_edit(state: &mut AppState, key: KeyEvent) {
+    handle_text_editor(&mut state.body_editor, key);
+}
+
+/// Keys of the body and gRPC message editors: clipboard, then plain editing.
+/// Undo, redo and formatting are keymap actions.
+fn handle_text_editor(editor: &mut TextEditor, key: KeyEvent) {
     match (key.code, key.modifiers) {
-        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
-            state.body_input.clear();
-            state.body_cursor = 0;
-        }
-        (KeyCode::Char(c), KeyModifiers::NONE) | (KeyCode::Char(c), KeyModifiers::SHIFT) => {
-            state.body_input.insert(state.body_cursor, c);
-            state.body_cursor += 1;
+        (KeyCode::Char('a'), KeyModifiers::CONTROL) => {
+            editor.select_all();
         }
-        (KeyCode::Backspace, _) => {
-            if state.body_cursor > 0 {
-                state.body_cursor -= 1;
-                state.body_input.remove(state.body_cursor);
-            }
-        }
-        (KeyCode::Delete, _) => {
-            if state.body_cursor < state.body_input.len() {
-                state.body_input.remove(state.body_cursor);
-            }
+        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+            editor.clear();
         }
-        (KeyCode::Left, _) => {
-            if state.body_cursor > 0 {
-                state.body_cursor -= 1;
+        (KeyCode::Char(c @ ('c' | 'x')), KeyModifiers::CONTROL) => {
+            let selected = if c == 'c' { editor.copy() } else { editor.cut() };
+            if let Some(selected) = selected {
+                if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                    let _ = clipboard.set_text(selected);
+                }
             }
         }
-        (KeyCode::Right, _) => {
-            if state.body_cursor < state.body_input.len() {
-                state.body_cursor += 1;
+        (KeyCode::Char('v'), KeyModifiers::CONTROL) => {
+            match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+                Ok(text) => editor.paste(&text),
+                Err(_) => editor.paste_yanked(),
             }
         }
-        (KeyCode::Up, _) => {
-            state.body_cursor = move_cursor_up(&state.body_input, state.body_cursor);
-        }
-        (KeyCode::Down, _) => {
-            state.body_cursor = move_cursor_down(&state.body_input, state.body_cursor);
-        }
-        (KeyCode::Home, _) => {
-            state.body_cursor = move_cursor_to_line_start(&state.body_input, state.body_cursor);
-        }
-        (KeyCode::End, _) => {
-            state.body_cursor = move_cursor_to_line_end(&state.body_input, state.body_cursor);
-        }
-        (KeyCode::Enter, _) => {
-            state.body_input.insert(state.body_cursor, '\n');
-            state.body_cursor += 1;
+        _ => {
+            editor.input(key);
         }
-        _ => {}
     }
 }
 
 fn handle_auth_edit(state: &mut AppState, key: KeyEvent) {
+    let field_idx = state.selected_auth_field();
+    let is_choice = field_idx
+        .map(|idx| matches!(state.auth_kind_input.fields()[idx].kind, models::auth::AuthFieldKind::Choice(_)))
+        .unwrap_or(true);
+
     match (key.code, key.modifiers) {
-        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
-            state.auth_input.clear();
-            state.auth_cursor = 0;
-        }
-        (KeyCode::Char(c), KeyModifiers::NONE) | (KeyCode::Char(c), KeyModifiers::SHIFT) => {
-            state.auth_input.insert(state.auth_cursor, c);
-            state.auth_cursor += 1;
-        }
-        (KeyCode::Backspace, _) => {
-            if state.auth_cursor > 0 {
-                state.auth_cursor -= 1;
-                state.auth_input.remove(state.auth_cursor);
-            }
-        }
-        (KeyCode::Delete, _) => {
-            if state.auth_cursor < state.auth_input.len() {
-                state.auth_input.remove(state.auth_cursor);
-            }
-        }
-        (KeyCode::Left, _) => {
-            if state.auth_cursor > 0 {
-                state.auth_cursor -= 1;
-            }
-        }
-        (KeyCode::Right, _) => {
-            if state.auth_cursor < state.auth_input.len() {
-                state.auth_cursor += 1;
-            }
-        }
         (KeyCode::Up, _) => {
-            state.auth_cursor = move_cursor_up(&state.auth_input, state.auth_cursor);
+            state.select_auth_row(state.auth_selected.saturating_sub(1));
         }
         (KeyCode::Down, _) => {
-            state.auth_cursor = move_cursor_down(&state.auth_input, state.auth_cursor);
+            state.select_auth_row(state.auth_selected + 1);
         }
-        (KeyCode::Home, _) => {
-            state.auth_cursor = move_cursor_to_line_start(&state.auth_input, state.auth_cursor);
+        (KeyCode::Left, _) if is_choice => {
+            state.cycle_auth_option(false);
         }
-        (KeyCode::End, _) => {
-            state.auth_cursor = move_cursor_to_line_end(&state.auth_input, state.auth_cursor);
+        (KeyCode::Right, _) | (KeyCode::Char(' '), _) if is_choice => {
+            state.cycle_auth_option(true);
         }
-        (KeyCode::Enter, _) => {
-            state.auth_input.insert(state.auth_cursor, '\n');
-            state.auth_cursor += 1;
+        _ => {
+            let Some(value) = field_idx.and_then(|idx| state.auth_values_input.get_mut(idx)) else {
+                return;
+            };
+            let cursor = state.auth_cursor.min(value.len());
+            state.auth_cursor = match (key.code, key.modifiers) {
+                (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                    value.clear();
+                    0
+                }
+                (KeyCode::Char(c), KeyModifiers::NONE) | (KeyCode::Char(c), KeyModifiers::SHIFT) => {
+                    value.insert(cursor, c);
+                    cursor + c.len_utf8()
+                }
+                (KeyCode::Backspace, _) => match value[..cursor].chars().next_back() {
+                    Some(c) => {
+                        value.remove(cursor - c.len_utf8());
+                        cursor - c.len_utf8()
+                    }
+                    None => cursor,
+                },
+                (KeyCode::Delete, _) => {
+                    if cursor < value.len() {
+                        value.remove(cursor);
+                    }
+                    cursor
+                }
+                (KeyCode::Left, _) => cursor - value[..cursor].chars().next_back().map_or(0, char::len_utf8),
+                (KeyCode::Right, _) => cursor + value[cursor..].chars().next().map_or(0, char::len_utf8),
+                (KeyCode::Home, _) => 0,
+                (KeyCode::End, _) => value.len(),
+                _ => cursor,
+            };
         }
-        _ => {}
     }
 }
 
@@ -1090,42 +1601,10 @@ fn handle_export_menu(state: &mut AppState, key: KeyEvent) {
                         }
                     }
                 }
-                KeyCode::Enter => {
-                    match state.export_mode {
-                        Some(ExportMode::RequestCurl) => {
-                            // Move to request selection stage
-                            if let Some(collection_idx) = state.export_selected_collection {
-                                if let Some(collection) = state.collections.get(collection_idx) {
-                                    // Find first HTTP request in this collection
-                                    let first_request_idx = state.requests
-                                        .iter()
-                                        .enumerate()
-                                        .find(|(_, r)| r.collection_id == Some(collection.id))
-                                        .map(|(idx, _)| idx);
-
-                                    state.export_selected_request = first_request_idx;
-                                    state.export_menu_stage = ExportMenuStage::SelectingRequest;
-                                }
-                            }
-                        }
-                        Some(ExportMode::GrpcRequestGrpcurl) => {
-                            // Move to request selection stage for gRPC requests
-                            if let Some(collection_idx) = state.export_selected_collection {
-                                if let Some(collection) = state.collections.get(collection_idx) {
-                                    // Find first gRPC request in this collection
-                                    let first_request_idx = state.grpc_requests
-                                        .iter()
-                                        .enumerate()
-                                        .find(|(_, r)| r.collection_id == Some(collection.id))
-                                        .map(|(idx, _)| idx);
-
-                                    state.export_selected_request = first_request_idx;
-                                    state.export_menu_stage = ExportMenuStage::SelectingRequest;
-                                }
-                            }
-                        }
-                        None => {}
-                    }
+                // Move to request selection stage, on the first request of the kind being exported
+                KeyCode::Enter if state.export_mode.is_some() && state.export_selected_collection.is_some() => {
+                    state.export_selected_request = state.export_request_indices().first().copied();
+                    state.export_menu_stage = ExportMenuStage::SelectingRequest;
                 }
                 _ => {}
             }
@@ -1138,70 +1617,18 @@ fn handle_export_menu(state: &mut AppState, key: KeyEvent) {
                     state.export_selected_request = None;
                 }
                 KeyCode::Up | KeyCode::Char('k') => {
-                    if let Some(current_idx) = state.export_selected_request {
-                        if let Some(collection_idx) = state.export_selected_collection {
-                            if let Some(collection) = state.collections.get(collection_idx) {
-                                // Find previous request in this collection based on export mode
-                                let requests_in_collection: Vec<usize> = match state.export_mode {
-                                    Some(ExportMode::RequestCurl) => {
-                                        state.requests
-                                            .iter()
-                                            .enumerate()
-                                            .filter(|(_, r)| r.collection_id == Some(collection.id))
-                                            .map(|(idx, _)| idx)
-                                            .collect()
-                                    }
-                                    Some(ExportMode::GrpcRequestGrpcurl) => {
-                                        state.grpc_requests
-                                            .iter()
-                                            .enumerate()
-                                            .filter(|(_, r)| r.collection_id == Some(collection.id))
-                                            .map(|(idx, _)| idx)
-                                            .collect()
-                                    }
-                                    _ => vec![],
-                                };
-
-                                if let Some(pos) = requests_in_collection.iter().position(|&idx| idx == current_idx) {
-                                    if pos > 0 {
-                                        state.export_selected_request = Some(requests_in_collection[pos - 1]);
-                                    }
-                                }
-                            }
+                    let requests_in_collection = state.export_request_indices();
+                    if let Some(pos) = requests_in_collection.iter().position(|&idx| Some(idx) == state.export_selected_request) {
+                        if pos > 0 {
+                            state.export_selected_request = Some(requests_in_collection[pos - 1]);
                         }
                     }
                 }
                 KeyCode::Down | KeyCode::Char('j') => {
-                    if let Some(current_idx) = state.export_selected_request {
-                        if let Some(collection_idx) = state.export_selected_collection {
-                            if let Some(collection) = state.collections.get(collection_idx) {
-                                // Find next request in this collection based on export mode
-                                let requests_in_collection: Vec<usize> = match state.export_mode {
-                                    Some(ExportMode::RequestCurl) => {
-                                        state.requests
-                                            .iter()
-                                            .enumerate()
-                                            .filter(|(_, r)| r.collection_id == Some(collection.id))
-                                            .map(|(idx, _)| idx)
-                                            .collect()
-                                    }
-                                    Some(ExportMode::GrpcRequestGrpcurl) => {
-                                        state.grpc_requests
-                                            .iter()
-                                            .enumerate()
-                                            .filter(|(_, r)| r.collection_id == Some(collection.id))
-                                            .map(|(idx, _)| idx)
-                                            .collect()
-                                    }
-                                    _ => vec![],
-                                };
-
-                                if let Some(pos) = requests_in_collection.iter().position(|&idx| idx == current_idx) {
-                                    if pos < requests_in_collection.len() - 1 {
-                                        state.export_selected_request = Some(requests_in_collection[pos + 1]);
-                                    }
-                                }
-                            }
+                    let requests_in_collection = state.export_request_indices();
+                    if let Some(pos) = requests_in_collection.iter().position(|&idx| Some(idx) == state.export_selected_request) {
+                        if pos + 1 < requests_in_collection.len() {
+                            state.export_selected_request = Some(requests_in_collection[pos + 1]);
                         }
                     }
                 }
@@ -1242,6 +1669,7 @@ fn handle_import_menu(state: &mut AppState, key: KeyEvent, storage: &storage::St
         (KeyCode::Enter, _) => {
             // Store the counts before import to identify what was imported
             let collections_before = state.collections.len();
+            let folders_before = state.folders.len();
             let requests_before = state.requests.len();
 
             Action::ImportPostmanCollection.execute(state);
@@ -1256,9 +1684,23 @@ fn handle_import_menu(state: &mut AppState, key: KeyEvent, storage: &storage::St
                     }
                 }
 
+                for folder in state.folders.iter().skip(folders_before) {
+                    let _ = storage.save_folder(folder);
+                }
+                let imported_nodes = state.collections.iter().skip(collections_before).map(|c| c.id)
+                    .chain(state.folders.iter().skip(folders_before).map(|f| f.id));
+                for node_id in imported_nodes {
+                    if let Some(defaults) = state.node_defaults.get(&node_id) {
+                        let _ = storage.save_request_defaults(&node_id, defaults);
+                    }
+                }
+
                 // Save all newly imported requests
                 for request in state.requests.iter().skip(requests_before) {
-                    let _ = storage.save_request(request);
+                    let _ = storage.save_any_request(request);
+                    if let Some(folder_id) = state.request_folders.get(&request.id()) {
+                        let _ = storage.save_request_folder(&request.id(), folder_id);
+                    }
                 }
             }
         }
@@ -1566,7 +2008,11 @@ fn handle_collection_edit_mode(state: &mut AppState, key: KeyEvent, storage: &st
         }
         (KeyCode::Enter, _) => {
             state.save_collection_name();
-            if let Some(idx) = state.selected_collection {
+            if let Some(folder_id) = state.selected_folder {
+                if let Some(folder) = state.folders.iter().find(|f| f.id == folder_id) {
+                    let _ = storage.save_folder(folder);
+                }
+            } else if let Some(idx) = state.selected_collection {
                 if let Some(collection) = state.collections.get(idx) {
                     let _ = storage.save_collection(collection);
                 }
@@ -1603,212 +2049,207 @@ fn handle_collection_edit_mode(state: &mut AppState, key: KeyEvent, storage: &st
         (KeyCode::End, _) => {
             state.collection_name_cursor = state.collection_name_input.len();
         }
-        _ => {}
-    }
-}
-
-fn handle_request_search_mode(state: &mut AppState, key: KeyEvent) {
-    match key.code {
-        KeyCode::Enter => {
-            // Apply filter and exit search mode (keep filter active)
-            state.update_request_filter();
-
-            // If there are filtered results, select the first one
-            if !state.filtered_request_indices.is_empty() {
-                state.selected_request = Some(state.filtered_request_indices[0]);
-                state.clear_input_buffers();
+        _ => {}
+    }
+}
+
+fn handle_request_search_mode(state: &mut AppState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            // Apply filter and exit search mode (keep filter active)
+            state.update_request_filter();
+
+            // If there are filtered results, select the first one
+            if !state.filtered_request_indices.is_empty() {
+                state.selected_request = Some(state.filtered_request_indices[0]);
+                state.clear_input_buffers();
+            }
+            // The list goes back to a single folder, the one the picked request is in
+            if let Some(request_id) = state.current_request_id() {
+                state.follow_request_folder(request_id);
+            }
+
+            state.request_search_mode = false;
+        }
+        KeyCode::Esc => {
+            // Cancel search without applying filter
+            Action::ExitRequestSearch.execute(state);
+        }
+        KeyCode::Backspace => {
+            if state.request_search_cursor > 0 {
+                state.request_search_input.remove(state.request_search_cursor - 1);
+                state.request_search_cursor -= 1;
+                // Update filter in real-time as user types
+                state.update_request_filter();
+            }
+        }
+        KeyCode::Left => {
+            if state.request_search_cursor > 0 {
+                state.request_search_cursor -= 1;
+            }
+        }
+        KeyCode::Right => {
+            if state.request_search_cursor < state.request_search_input.len() {
+                state.request_search_cursor += 1;
+            }
+        }
+        KeyCode::Char(c) => {
+            state.request_search_input.insert(state.request_search_cursor, c);
+            state.request_search_cursor += 1;
+            // Update filter in real-time
+            state.update_request_filter();
+        }
+        _ => {}
+    }
+}
+
+fn handle_response_filter_mode(state: &mut AppState, key: KeyEvent, storage: &storage::Storage) {
+    let cursor = state.response_filter_cursor.min(state.response_filter_input.len());
+    let input = &mut state.response_filter_input;
+
+    match (key.code, key.modifiers) {
+        (KeyCode::Enter, _) => {
+            // Keep the filter applied and remember it for this request
+            if let Some(request_id) = state.confirm_response_filter() {
+                if let Some(recent) = state.recent_filters.get(&request_id) {
+                    let _ = storage.save_recent_filters(&request_id, recent);
+                }
+            }
+            return;
+        }
+        (KeyCode::Esc, _) => {
+            state.clear_response_filter();
+            return;
+        }
+        (KeyCode::Up, _) => {
+            state.cycle_recent_filter(true);
+            return;
+        }
+        (KeyCode::Down, _) => {
+            state.cycle_recent_filter(false);
+            return;
+        }
+        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+            input.clear();
+            state.response_filter_cursor = 0;
+        }
+        (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+            input.insert(cursor, c);
+            state.response_filter_cursor = cursor + c.len_utf8();
+        }
+        (KeyCode::Backspace, _) => {
+            if let Some(c) = input[..cursor].chars().next_back() {
+                input.remove(cursor - c.len_utf8());
+                state.response_filter_cursor = cursor - c.len_utf8();
+            }
+        }
+        (KeyCode::Delete, _) if cursor < input.len() => {
+            input.remove(cursor);
+        }
+        (KeyCode::Left, _) => {
+            state.response_filter_cursor = cursor - input[..cursor].chars().next_back().map_or(0, char::len_utf8);
+            return;
+        }
+        (KeyCode::Right, _) => {
+            state.response_filter_cursor = cursor + input[cursor..].chars().next().map_or(0, char::len_utf8);
+            return;
+        }
+        (KeyCode::Home, _) => {
+            state.response_filter_cursor = 0;
+            return;
+        }
+        (KeyCode::End, _) => {
+            state.response_filter_cursor = input.len();
+            return;
+        }
+        _ => return,
+    }
+
+    // Show the result live once typing pauses
+    state.recent_filter_index = None;
+    state.debounce_response_filter();
+}
+
+fn handle_response_search_mode(state: &mut AppState, key: KeyEvent) {
+    let cursor = state.response_search_cursor.min(state.response_search_input.len());
+    let input = &mut state.response_search_input;
+
+    match (key.code, key.modifiers) {
+        (KeyCode::Enter, _) => {
+            // Keep the highlights; n/N move between matches
+            state.response_search_mode = false;
+            return;
+        }
+        (KeyCode::Esc, _) => {
+            state.clear_response_search();
+            return;
+        }
+        (KeyCode::Down, _) => {
+            state.next_search_match();
+            return;
+        }
+        (KeyCode::Up, _) => {
+            state.prev_search_match();
+            return;
+        }
+        (KeyCode::Char('c'), KeyModifiers::ALT) => {
+            state.toggle_search_case_sensitive();
+            return;
+        }
+        (KeyCode::Char('r'), KeyModifiers::ALT) => {
+            state.toggle_search_regex();
+            return;
+        }
+        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+            input.clear();
+            state.response_search_cursor = 0;
+        }
+        (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+            input.insert(cursor, c);
+            state.response_search_cursor = cursor + c.len_utf8();
+        }
+        (KeyCode::Backspace, _) => {
+            if let Some(c) = input[..cursor].chars().next_back() {
+                input.remove(cursor - c.len_utf8());
+                state.response_search_cursor = cursor - c.len_utf8();
             }
-
-            state.request_search_mode = false;
         }
-        KeyCode::Esc => {
-            // Cancel search without applying filter
-            Action::ExitRequestSearch.execute(state);
+        (KeyCode::Delete, _) if cursor < input.len() => {
+            input.remove(cursor);
         }
-        KeyCode::Backspace => {
-            if state.request_search_cursor > 0 {
-                state.request_search_input.remove(state.request_search_cursor - 1);
-                state.request_search_cursor -= 1;
-                // Update filter in real-time as user types
-                state.update_request_filter();
-            }
+        (KeyCode::Left, _) => {
+            state.response_search_cursor = cursor - input[..cursor].chars().next_back().map_or(0, char::len_utf8);
+            return;
         }
-        KeyCode::Left => {
-            if state.request_search_cursor > 0 {
-                state.request_search_cursor -= 1;
-            }
+        (KeyCode::Right, _) => {
+            state.response_search_cursor = cursor + input[cursor..].chars().next().map_or(0, char::len_utf8);
+            return;
         }
-        KeyCode::Right => {
-            if state.request_search_cursor < state.request_search_input.len() {
-                state.request_search_cursor += 1;
-            }
+        (KeyCode::Home, _) => {
+            state.response_search_cursor = 0;
+            return;
         }
-        KeyCode::Char(c) => {
-            state.request_search_input.insert(state.request_search_cursor, c);
-            state.request_search_cursor += 1;
-            // Update filter in real-time
-            state.update_request_filter();
+        (KeyCode::End, _) => {
+            state.response_search_cursor = input.len();
+            return;
         }
-        _ => {}
-    }
-}
-
-// Helper functions for multiline text navigation
-
-/// Move cursor up one line, maintaining column position when possible
-fn move_cursor_up(text: &str, cursor_pos: usize) -> usize {
-    if cursor_pos == 0 {
-        return 0;
-    }
-    
-    // Find the start of the current line
-    let current_line_start = text[..cursor_pos]
-        .rfind('\n')
-        .map(|pos| pos + 1)
-        .unwrap_or(0);
-    
-    // If we're already on the first line, move to the start
-    if current_line_start == 0 {
-        return 0;
-    }
-    
-    // Calculate column position in current line
-    let column = cursor_pos - current_line_start;
-    
-    // Find the start of the previous line
-    let prev_line_start = text[..current_line_start - 1]
-        .rfind('\n')
-        .map(|pos| pos + 1)
-        .unwrap_or(0);
-    
-    // Find the end of the previous line (excluding the newline)
-    let prev_line_end = current_line_start - 1;
-    let prev_line_len = prev_line_end - prev_line_start;
-    
-    // Move to the same column or to the end of the previous line if it's shorter
-    prev_line_start + column.min(prev_line_len)
-}
-
-/// Move cursor down one line, maintaining column position when possible
-fn move_cursor_down(text: &str, cursor_pos: usize) -> usize {
-    if cursor_pos >= text.len() {
-        return text.len();
+        _ => return,
     }
-    
-    // Find the start of the current line
-    let current_line_start = text[..cursor_pos]
-        .rfind('\n')
-        .map(|pos| pos + 1)
-        .unwrap_or(0);
-    
-    // Find the end of the current line
-    let current_line_end = text[cursor_pos..]
-        .find('\n')
-        .map(|pos| cursor_pos + pos)
-        .unwrap_or(text.len());
-    
-    // If we're on the last line, move to the end
-    if current_line_end >= text.len() {
-        return text.len();
-    }
-    
-    // Calculate column position in current line
-    let column = cursor_pos - current_line_start;
-    
-    // The next line starts after the newline
-    let next_line_start = current_line_end + 1;
-    
-    // Find the end of the next line
-    let next_line_end = text[next_line_start..]
-        .find('\n')
-        .map(|pos| next_line_start + pos)
-        .unwrap_or(text.len());
-    
-    let next_line_len = next_line_end - next_line_start;
-    
-    // Move to the same column or to the end of the next line if it's shorter
-    next_line_start + column.min(next_line_len)
-}
-
-/// Move cursor to the start of the current line
-fn move_cursor_to_line_start(text: &str, cursor_pos: usize) -> usize {
-    text[..cursor_pos]
-        .rfind('\n')
-        .map(|pos| pos + 1)
-        .unwrap_or(0)
-}
 
-/// Move cursor to the end of the current line
-fn move_cursor_to_line_end(text: &str, cursor_pos: usize) -> usize {
-    text[cursor_pos..]
-        .find('\n')
-        .map(|pos| cursor_pos + pos)
-        .unwrap_or(text.len())
+    // Search as the query is typed
+    state.refresh_response_search();
 }
 
 // gRPC Edit Mode Handlers
 
-fn handle_grpc_edit_mode(state: &mut AppState, key: KeyEvent, storage: &storage::Storage) {
-    match key.code {
-        KeyCode::Esc => {
-            // Similar to HTTP edit mode handling
-            if state.grpc_editor_focused_field == app::state::GrpcEditorField::Metadata &&
-               state.kv_edit_mode != app::state::KeyValueEditMode::None {
-                handle_grpc_metadata_edit(state, key);
-            } else {
-                // Exit edit mode and save
-                state.save_grpc_input_to_request();
-                if let Some(request) = state.get_current_grpc_request() {
-                    let _ = storage.save_grpc_request(request);
-                }
-                state.input_mode = InputMode::Normal;
-                state.kv_edit_mode = app::state::KeyValueEditMode::None;
-            }
-        }
-        KeyCode::Tab => {
-            if state.grpc_editor_focused_field == app::state::GrpcEditorField::Metadata &&
-               state.kv_edit_mode != app::state::KeyValueEditMode::None {
-                handle_grpc_metadata_edit(state, key);
-            } else {
-                // Switch fields forward
-                state.kv_edit_mode = app::state::KeyValueEditMode::None;
-                state.grpc_editor_focused_field = match state.grpc_editor_focused_field {
-                    app::state::GrpcEditorField::Name => app::state::GrpcEditorField::ServerUrl,
-                    app::state::GrpcEditorField::ServerUrl => app::state::GrpcEditorField::ServiceName,
-                    app::state::GrpcEditorField::ServiceName => app::state::GrpcEditorField::MethodName,
-                    app::state::GrpcEditorField::MethodName => app::state::GrpcEditorField::Message,
-                    app::state::GrpcEditorField::Message => app::state::GrpcEditorField::Metadata,
-                    app::state::GrpcEditorField::Metadata => app::state::GrpcEditorField::Name,
-                };
-            }
-        }
-        KeyCode::BackTab => {
-            if state.grpc_editor_focused_field == app::state::GrpcEditorField::Metadata &&
-               state.kv_edit_mode != app::state::KeyValueEditMode::None {
-                handle_grpc_metadata_edit(state, key);
-            } else {
-                // Switch fields backward
-                state.kv_edit_mode = app::state::KeyValueEditMode::None;
-                state.grpc_editor_focused_field = match state.grpc_editor_focused_field {
-                    app::state::GrpcEditorField::Name => app::state::GrpcEditorField::Metadata,
-                    app::state::GrpcEditorField::ServerUrl => app::state::GrpcEditorField::Name,
-                    app::state::GrpcEditorField::ServiceName => app::state::GrpcEditorField::ServerUrl,
-                    app::state::GrpcEditorField::MethodName => app::state::GrpcEditorField::ServiceName,
-                    app::state::GrpcEditorField::Message => app::state::GrpcEditorField::MethodName,
-                    app::state::GrpcEditorField::Metadata => app::state::GrpcEditorField::Message,
-                };
-            }
-        }
-        _ => {
-            match state.grpc_editor_focused_field {
-                app::state::GrpcEditorField::Name => handle_grpc_name_edit(state, key),
-                app::state::GrpcEditorField::ServerUrl => handle_grpc_server_url_edit(state, key),
-                app::state::GrpcEditorField::ServiceName => handle_grpc_service_name_edit(state, key),
-                app::state::GrpcEditorField::MethodName => handle_grpc_method_name_edit(state, key),
-                app::state::GrpcEditorField::Message => handle_grpc_message_edit(state, key),
-                app::state::GrpcEditorField::Metadata => handle_grpc_metadata_edit(state, key),
-            }
-        }
+fn handle_grpc_edit_mode(state: &mut AppState, key: KeyEvent) {
+    match state.grpc_editor_focused_field {
+        app::state::GrpcEditorField::Name => handle_grpc_name_edit(state, key),
+        app::state::GrpcEditorField::ServerUrl => handle_grpc_server_url_edit(state, key),
+        app::state::GrpcEditorField::ServiceName => handle_grpc_service_name_edit(state, key),
+        app::state::GrpcEditorField::MethodName => handle_grpc_method_name_edit(state, key),
+        app::state::GrpcEditorField::Message => handle_grpc_message_edit(state, key),
+        app::state::GrpcEditorField::Metadata => handle_grpc_metadata_edit(state, key),
     }
 }
 
@@ -1977,60 +2418,7 @@ fn handle_grpc_method_name_edit(state: &mut AppState, key: KeyEvent) {
 }
 
 fn handle_grpc_message_edit(state: &mut AppState, key: KeyEvent) {
-    match (key.code, key.modifiers) {
-        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
-            state.grpc_message_input.clear();
-            state.grpc_message_cursor = 0;
-        }
-        (KeyCode::Char(c), KeyModifiers::NONE) | (KeyCode::Char(c), KeyModifiers::SHIFT) => {
-            state.grpc_message_input.insert(state.grpc_message_cursor, c);
-            state.grpc_message_cursor += 1;
-        }
-        (KeyCode::Backspace, _) => {
-            if state.grpc_message_cursor > 0 {
-                state.grpc_message_cursor -= 1;
-                state.grpc_message_input.remove(state.grpc_message_cursor);
-            }
-        }
-        (KeyCode::Delete, _) => {
-            if state.grpc_message_cursor < state.grpc_message_input.len() {
-                state.grpc_message_input.remove(state.grpc_message_cursor);
-            }
-        }
-        (KeyCode::Left, _) => {
-            if state.grpc_message_cursor > 0 {
-                state.grpc_message_cursor -= 1;
-            }
-        }
-        (KeyCode::Right, _) => {
-            if state.grpc_message_cursor < state.grpc_message_input.len() {
-                state.grpc_message_cursor += 1;
-            }
-        }
-        (KeyCode::Up, _) => {
-            state.grpc_message_cursor = move_cursor_up(&state.grpc_message_input, state.grpc_message_cursor);
-        }
-        (KeyCode::Down, _) => {
-            state.grpc_message_cursor = move_cursor_down(&state.grpc_message_input, state.grpc_message_cursor);
-        }
-        (KeyCode::Home, KeyModifiers::CONTROL) => {
-            state.grpc_message_cursor = 0;
-        }
-        (KeyCode::End, KeyModifiers::CONTROL) => {
-            state.grpc_message_cursor = state.grpc_message_input.len();
-        }
-        (KeyCode::Home, _) => {
-            state.grpc_message_cursor = move_cursor_to_line_start(&state.grpc_message_input, state.grpc_message_cursor);
-        }
-        (KeyCode::End, _) => {
-            state.grpc_message_cursor = move_cursor_to_line_end(&state.grpc_message_input, state.grpc_message_cursor);
-        }
-        (KeyCode::Enter, _) => {
-            state.grpc_message_input.insert(state.grpc_message_cursor, '\n');
-            state.grpc_message_cursor += 1;
-        }
-        _ => {}
-    }
+    handle_text_editor(&mut state.grpc_message_editor, key);
 }
 
 fn handle_grpc_metadata_edit(state: &mut AppState, key: KeyEvent) {
@@ -2122,6 +2510,285 @@ fn handle_grpc_metadata_edit(state: &mut AppState, key: KeyEvent) {
 
 
 
+/// y or Enter runs the confirmed action, n or Esc drops it
+fn handle_confirmation(state: &mut AppState, key: KeyEvent, storage: &storage::Storage) {
+    use app::state::ConfirmAction;
+
+    let Some(confirmation) = state.confirmation.take() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => match confirmation.action {
+            ConfirmAction::DeleteNode => delete_node(state, storage),
+            ConfirmAction::PurgeTrashEntry(id) => {
+                if let Some(entry) = state.purge_from_trash(id) {
+                    delete_purged(storage, &entry);
+                }
+            }
+            ConfirmAction::EmptyTrash => {
+                for entry in state.empty_trash() {
+                    delete_purged(storage, &entry);
+                }
+            }
+        },
+        KeyCode::Char('n') | KeyCode::Esc => {}
+        // Any other key leaves the question up
+        _ => state.confirmation = Some(confirmation),
+    }
+}
+
+/// Move through the trash, restore the selected entry or purge it
+fn handle_trash(state: &mut AppState, key: KeyEvent, storage: &storage::Storage) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => state.close_trash(),
+        KeyCode::Down | KeyCode::Char('j') if state.selected_trash + 1 < state.trash.len() => state.selected_trash += 1,
+        KeyCode::Up | KeyCode::Char('k') => state.selected_trash = state.selected_trash.saturating_sub(1),
+        KeyCode::Enter | KeyCode::Char('r') => {
+            let Some(id) = state.trash.get(state.selected_trash).map(|e| e.id) else {
+                return;
+            };
+            match state.restore_from_trash(id) {
+                Ok(entry) => save_restored(state, storage, &entry),
+                Err(e) => state.status_message = Some(e),
+            }
+        }
+        KeyCode::Char('d') | KeyCode::Delete => state.confirm_purge(),
+        KeyCode::Char('D') => state.confirm_empty_trash(),
+        _ => {}
+    }
+}
+
+/// Typing narrows the palette down, Enter runs the highlighted action or goes to the collection or request
+fn handle_command_palette(
+    state: &mut AppState,
+    key: KeyEvent,
+    storage: &storage::Storage,
+    response_tx: &mpsc::Sender<HttpResult>,
+    grpc_response_tx: &mpsc::Sender<GrpcResult>,
+) {
+    let count = state.command_palette.entries(state).len();
+    let palette = &mut state.command_palette;
+    match (key.code, key.modifiers) {
+        (KeyCode::Esc, _) => state.close_command_palette(),
+        (KeyCode::Enter, _) => {
+            let selected = palette.selected;
+            run_palette_entry(state, storage, selected, response_tx, grpc_response_tx);
+        }
+        (KeyCode::Down | KeyCode::Tab, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => palette.move_selection(1, count),
+        (KeyCode::Up | KeyCode::BackTab, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+            palette.move_selection(-1, count)
+        }
+        (KeyCode::PageDown, _) => palette.move_selection(10, count),
+        (KeyCode::PageUp, _) => palette.move_selection(-10, count),
+        _ => {
+            palette.input(key);
+        }
+    }
+}
+
+/// Close the palette and run its entry at `index`, from the panel an action belongs to
+fn run_palette_entry(
+    state: &mut AppState,
+    storage: &storage::Storage,
+    index: usize,
+    response_tx: &mpsc::Sender<HttpResult>,
+    grpc_response_tx: &mpsc::Sender<GrpcResult>,
+) {
+    use app::command_palette::PaletteItem;
+
+    let Some(entry) = state.command_palette.entries(state).into_iter().nth(index) else {
+        return;
+    };
+    state.close_command_palette();
+    match entry.item {
+        PaletteItem::Action(action, scope) => {
+            if let Some(panel) = scope.panel() {
+                state.focus_panel(panel);
+            }
+            if !perform_action(state, storage, action, response_tx, grpc_response_tx) {
+                state.status_message = Some(format!("{} is not available right now", entry.label));
+            }
+        }
+        PaletteItem::Collection(id) => state.jump_to_collection(id),
+        PaletteItem::Folder(id) => state.jump_to_folder(id),
+        PaletteItem::Request(id) => state.jump_to_request(id),
+    }
+}
+
+fn handle_diff_popup(state: &mut AppState, key: KeyEvent) {
+    use app::state::DiffStage;
+
+    match (state.diff_stage, key.code) {
+        (DiffStage::SelectingResponses, KeyCode::Esc) => state.close_diff_popup(),
+        (DiffStage::SelectingResponses, KeyCode::Up | KeyCode::Char('k')) => state.move_diff_cursor(-1),
+        (DiffStage::SelectingResponses, KeyCode::Down | KeyCode::Char('j')) => state.move_diff_cursor(1),
+        (DiffStage::SelectingResponses, KeyCode::Char('a')) => state.set_diff_side(true),
+        (DiffStage::SelectingResponses, KeyCode::Char('b')) => state.set_diff_side(false),
+        (DiffStage::SelectingResponses, KeyCode::Enter) => state.show_diff(),
+        (DiffStage::ShowingDiff, KeyCode::Esc | KeyCode::Backspace) => {
+            state.diff_stage = DiffStage::SelectingResponses;
+        }
+        (DiffStage::ShowingDiff, KeyCode::Up | KeyCode::Char('k')) => {
+            state.diff_scroll = state.diff_scroll.saturating_sub(1);
+        }
+        (DiffStage::ShowingDiff, KeyCode::Down | KeyCode::Char('j')) => {
+            state.diff_scroll = state.diff_scroll.saturating_add(1);
+        }
+        (DiffStage::ShowingDiff, KeyCode::PageUp) => {
+            state.diff_scroll = state.diff_scroll.saturating_sub(10);
+        }
+        (DiffStage::ShowingDiff, KeyCode::PageDown) => {
+            state.diff_scroll = state.diff_scroll.saturating_add(10);
+        }
+        (_, KeyCode::Char('s')) => state.swap_diff_sides(),
+        _ => {}
+    }
+}
+
+fn handle_defaults_editor(state: &mut AppState, key: KeyEvent, storage: &storage::Storage) {
+    match (key.code, key.modifiers) {
+        (KeyCode::Esc, _) => state.close_defaults_editor(),
+        (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+            if let Some((target, defaults)) = state.apply_defaults_editor() {
+                let result = if defaults.is_empty() {
+                    storage.delete_request_defaults(&target)
+                } else {
+                    storage.save_request_defaults(&target, &defaults)
+                };
+                if let Err(e) = result {
+                    state.status_message = Some(format!("Failed to save defaults: {:#}", e));
+                }
+            }
+        }
+        (KeyCode::Char('z'), KeyModifiers::CONTROL) => {
+            state.defaults_editor.undo();
+        }
+        (KeyCode::Char('y'), KeyModifiers::CONTROL) => {
+            state.defaults_editor.redo();
+        }
+        _ => handle_text_editor(&mut state.defaults_editor, key),
+    }
+}
+
+fn handle_proxy_settings(state: &mut AppState, key: KeyEvent, storage: &storage::Storage) {
+    use app::state::{ProxyField, ProxyScope};
+
+    match (key.code, key.modifiers) {
+        (KeyCode::Esc, _) => {
+            state.close_proxy_settings();
+        }
+        (KeyCode::Enter, _) => {
+            match state.proxy_form.to_settings() {
+                Ok(settings) => {
+                    let result = match (state.proxy_form.scope, settings) {
+                        (ProxyScope::Global, Some(settings)) => {
+                            let result = storage.save_proxy_settings(&settings);
+                            state.proxy_settings = settings;
+                            result
+                        }
+                        (ProxyScope::Collection(id), Some(settings)) => {
+                            let result = storage.save_collection_proxy(&id, &settings);
+                            state.collection_proxies.insert(id, settings);
+                            result
+                        }
+                        (ProxyScope::Collection(id), None) => {
+                            state.collection_proxies.remove(&id);
+                            storage.delete_collection_proxy(&id)
+                        }
+                        (ProxyScope::Global, None) => Ok(()),
+                    };
+                    match result {
+                        Ok(()) => state.close_proxy_settings(),
+                        Err(e) => state.proxy_form.error = Some(format!("{:#}", e)),
+                    }
+                }
+                Err(e) => {
+                    state.proxy_form.error = Some(e);
+                }
+            }
+        }
+        (KeyCode::Tab, _) | (KeyCode::Down, _) => {
+            state.proxy_form.next_field();
+        }
+        (KeyCode::BackTab, _) | (KeyCode::Up, _) => {
+            state.proxy_form.prev_field();
+        }
+        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+            if let Some(text) = state.proxy_form.focused_text_mut() {
+                text.clear();
+                state.proxy_form.cursor = 0;
+            }
+        }
+        _ => {
+            if state.proxy_form.focused_text_mut().is_some() {
+                handle_proxy_text_input(state, key);
+                return;
+            }
+
+            let toggle = matches!(key.code, KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right);
+            if !toggle {
+                return;
+            }
+            let form = &mut state.proxy_form;
+            match form.focused_field {
+                ProxyField::Scope => state.toggle_proxy_scope(),
+                ProxyField::Inherit => form.inherit = !form.inherit,
+                ProxyField::Enabled => form.enabled = !form.enabled,
+                ProxyField::Scheme => form.scheme = form.scheme.next(),
+                ProxyField::UseSystemEnv => form.use_system_env = !form.use_system_env,
+                _ => {}
+            }
+        }
+    }
+}
+
+fn handle_proxy_text_input(state: &mut AppState, key: KeyEvent) {
+    let is_port = state.proxy_form.focused_field == app::state::ProxyField::Port;
+    let mut cursor = state.proxy_form.cursor;
+    let Some(text) = state.proxy_form.focused_text_mut() else {
+        return;
+    };
+    cursor = cursor.min(text.len());
+
+    match key.code {
+        KeyCode::Char(c) if key.modifiers == KeyModifiers::NONE || key.modifiers == KeyModifiers::SHIFT => {
+            if is_port && !c.is_ascii_digit() {
+                return;
+            }
+            text.insert(cursor, c);
+            cursor += c.len_utf8();
+        }
+        KeyCode::Backspace => {
+            if let Some(c) = text[..cursor].chars().next_back() {
+                cursor -= c.len_utf8();
+                text.remove(cursor);
+            }
+        }
+        KeyCode::Delete if cursor < text.len() => {
+            text.remove(cursor);
+        }
+        KeyCode::Left => {
+            if let Some(c) = text[..cursor].chars().next_back() {
+                cursor -= c.len_utf8();
+            }
+        }
+        KeyCode::Right => {
+            if let Some(c) = text[cursor..].chars().next() {
+                cursor += c.len_utf8();
+            }
+        }
+        KeyCode::Home => {
+            cursor = 0;
+        }
+        KeyCode::End => {
+            cursor = text.len();
+        }
+        _ => {}
+    }
+
+    state.proxy_form.cursor = cursor;
+}
+
 fn handle_proto_loader(state: &mut AppState, key: KeyEvent, storage: &storage::Storage) {
     use app::state::ProtoLoaderFocus;
 
@@ -2224,19 +2891,14 @@ fn handle_proto_input(state: &mut AppState, key: KeyEvent, storage: &storage::St
                     // Save to storage
                     let _ = storage.save_proto_schema(&schema_to_save);
 
-                    // Auto-link to current gRPC request if one is selected and we're in gRPC mode
-                    if state.protocol_type == ProtocolType::Grpc {
-                        if let Some(request_idx) = state.selected_request {
-                            if request_idx < state.grpc_requests.len() {
-                                let request = &mut state.grpc_requests[request_idx];
-                                request.proto_source = models::grpc_request::ProtoSource::File {
-                                    proto_path: schema_to_save.source_path.clone().unwrap_or_default(),
-                                    file_descriptor_set: schema_to_save.file_descriptor_set.clone(),
-                                };
-                                // Save updated request
-                                let _ = storage.save_grpc_request(request);
-                            }
-                        }
+                    // Auto-link to the selected request if it is a gRPC one
+                    if let Some(request) = state.get_current_grpc_request_mut() {
+                        request.proto_source = models::grpc_request::ProtoSource::File {
+                            proto_path: schema_to_save.source_path.clone().unwrap_or_default(),
+                            file_descriptor_set: schema_to_save.file_descriptor_set.clone(),
+                        };
+                        // Save updated request
+                        let _ = storage.save_grpc_request(request);
                     }
 
                     // Show brief status message
@@ -2324,13 +2986,11 @@ fn handle_proto_schema_list(state: &mut AppState, key: KeyEvent, storage: &stora
             }
         }
         KeyCode::Char('d') | KeyCode::Delete | KeyCode::Backspace => {
-            // Delete selected proto schema
+            // Move the selected proto schema to the trash
             if let Some(idx) = state.selected_proto_schema {
-                if idx < state.proto_schemas.len() {
-                    let schema = state.proto_schemas.remove(idx);
-
-                    // Delete from storage
-                    let _ = storage.delete_proto_schema(&schema.id);
+                if let Some(entry) = state.trash_proto_schema(idx) {
+                    save_to_trash(storage, entry);
+                    let name = entry.name.clone();
 
                     // Update selection
                     if state.proto_schemas.is_empty() {
@@ -2344,10 +3004,49 @@ fn handle_proto_schema_list(state: &mut AppState, key: KeyEvent, storage: &stora
                     }
 
                     // Show brief status message
-                    state.proto_load_result = Some(format!("Deleted '{}'", schema.name));
+                    state.proto_load_result = Some(format!("Moved '{}' to the trash", name));
                 }
             }
         }
         _ => {}
     }
 }
+
+/// Navigation and folding in the JSON tree view. Returns false for keys it leaves to the main handler.
+fn handle_json_tree(state: &mut AppState, key: KeyEvent) -> bool {
+    let Some(tree) = state.json_tree.as_mut() else {
+        return false;
+    };
+
+    match (key.code, key.modifiers) {
+        (KeyCode::Down | KeyCode::Char('j'), KeyModifiers::NONE) => tree.move_cursor(1),
+        (KeyCode::Up | KeyCode::Char('k'), KeyModifiers::NONE) => tree.move_cursor(-1),
+        (KeyCode::PageDown, _) | (KeyCode::Char('f'), KeyModifiers::CONTROL) => tree.move_cursor(20),
+        (KeyCode::PageUp, _) | (KeyCode::Char('b'), KeyModifiers::CONTROL) => tree.move_cursor(-20),
+        (KeyCode::Home, KeyModifiers::NONE) => tree.cursor = 0,
+        (KeyCode::End, KeyModifiers::NONE) => tree.cursor_to_end(),
+        (KeyCode::Right, KeyModifiers::NONE) => tree.expand_at_cursor(),
+        (KeyCode::Left, KeyModifiers::NONE) => tree.collapse_at_cursor(),
+        (KeyCode::Char(' '), KeyModifiers::NONE) => tree.toggle_at_cursor(),
+        (KeyCode::Char('0'), KeyModifiers::NONE) => tree.expand_all(),
+        (KeyCode::Char(digit @ '1'..='9'), KeyModifiers::NONE) => {
+            tree.expand_to_depth(digit.to_digit(10).unwrap_or(1) as usize);
+        }
+        (KeyCode::Char('c'), KeyModifiers::NONE) => {
+            if let Some(value) = tree.cursor_value_text() {
+                if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                    let _ = clipboard.set_text(value);
+                }
+            }
+        }
+        (KeyCode::Char('C'), KeyModifiers::SHIFT) => {
+            if let Some(path) = tree.cursor_path() {
+                if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                    let _ = clipboard.set_text(path);
+                }
+            }
+        }
+        _ => return false,
+    }
+    true
+}
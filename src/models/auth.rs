@@ -0,0 +1,288 @@
+use crate::models::oauth2::{OAuth2Config, OAuth2GrantType};
+use crate::models::request::{ApiKeyLocation, AuthType};
+
+/// The kinds of authentication that can be picked in the Auth tab
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuthKind {
+    Inherit,
+    None,
+    Bearer,
+    Basic,
+    ApiKey,
+    OAuth2,
+    AwsSigV4,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuthFieldKind {
+    Text,
+    /// Masked when not being edited
+    Secret,
+    /// Cycled with ←/→ instead of typed
+    Choice(&'static [&'static str]),
+}
+
+/// One labelled input of the Auth tab form
+#[derive(Debug)]
+pub struct AuthField {
+    pub label: &'static str,
+    pub kind: AuthFieldKind,
+    /// OAuth2 grant types the field applies to, empty for all
+    pub grants: &'static [OAuth2GrantType],
+}
+
+const API_KEY_LOCATIONS: &[&str] = &["Header", "Query param"];
+
+/// Labels of `OAuth2GrantType::all()`, in the same order
+const GRANT_TYPES: &[&str] = &[
+    "Client credentials",
+    "Password",
+    "Authorization code (PKCE)",
+    "Refresh token",
+];
+
+const fn field(label: &'static str, kind: AuthFieldKind) -> AuthField {
+    AuthField { label, kind, grants: &[] }
+}
+
+const fn grant_field(label: &'static str, kind: AuthFieldKind, grants: &'static [OAuth2GrantType]) -> AuthField {
+    AuthField { label, kind, grants }
+}
+
+const BEARER_FIELDS: &[AuthField] = &[field("Token", AuthFieldKind::Text)];
+
+const BASIC_FIELDS: &[AuthField] = &[
+    field("Username", AuthFieldKind::Text),
+    field("Password", AuthFieldKind::Secret),
+];
+
+const API_KEY_FIELDS: &[AuthField] = &[
+    field("Key", AuthFieldKind::Text),
+    field("Value", AuthFieldKind::Secret),
+    field("Add to", AuthFieldKind::Choice(API_KEY_LOCATIONS)),
+];
+
+const OAUTH2_FIELDS: &[AuthField] = &[
+    field("Grant type", AuthFieldKind::Choice(GRANT_TYPES)),
+    field("Token URL", AuthFieldKind::Text),
+    field("Client ID", AuthFieldKind::Text),
+    field("Client secret", AuthFieldKind::Secret),
+    field("Scope", AuthFieldKind::Text),
+    grant_field("Username", AuthFieldKind::Text, &[OAuth2GrantType::Password]),
+    grant_field("Password", AuthFieldKind::Secret, &[OAuth2GrantType::Password]),
+    grant_field("Authorization URL", AuthFieldKind::Text, &[OAuth2GrantType::AuthorizationCode]),
+    grant_field("Redirect URI", AuthFieldKind::Text, &[OAuth2GrantType::AuthorizationCode]),
+    grant_field("Refresh token", AuthFieldKind::Secret, &[OAuth2GrantType::RefreshToken]),
+];
+
+const AWS_SIGV4_FIELDS: &[AuthField] = &[
+    field("Access key ID", AuthFieldKind::Text),
+    field("Secret access key", AuthFieldKind::Secret),
+    field("Session token", AuthFieldKind::Secret),
+    field("Region", AuthFieldKind::Text),
+    field("Service", AuthFieldKind::Text),
+];
+
+impl AuthKind {
+    pub fn all() -> Vec<AuthKind> {
+        vec![
+            AuthKind::Inherit,
+            AuthKind::None,
+            AuthKind::Bearer,
+            AuthKind::Basic,
+            AuthKind::ApiKey,
+            AuthKind::OAuth2,
+            AuthKind::AwsSigV4,
+        ]
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            AuthKind::Inherit => "Inherit from parent",
+            AuthKind::None => "No auth",
+            AuthKind::Bearer => "Bearer token",
+            AuthKind::Basic => "Basic auth",
+            AuthKind::ApiKey => "API key",
+            AuthKind::OAuth2 => "OAuth 2.0",
+            AuthKind::AwsSigV4 => "AWS Signature V4",
+        }
+    }
+
+    pub fn fields(&self) -> &'static [AuthField] {
+        match self {
+            AuthKind::Inherit | AuthKind::None => &[],
+            AuthKind::Bearer => BEARER_FIELDS,
+            AuthKind::Basic => BASIC_FIELDS,
+            AuthKind::ApiKey => API_KEY_FIELDS,
+            AuthKind::OAuth2 => OAUTH2_FIELDS,
+            AuthKind::AwsSigV4 => AWS_SIGV4_FIELDS,
+        }
+    }
+
+    /// Initial field values after switching to this kind
+    pub fn default_values(&self) -> Vec<String> {
+        match self {
+            AuthKind::OAuth2 => AuthType::OAuth2(OAuth2Config::default()).to_field_values(),
+            // API Gateway is the most common target
+            AuthKind::AwsSigV4 => vec![
+                String::new(),
+                String::new(),
+                String::new(),
+                "us-east-1".to_string(),
+                "execute-api".to_string(),
+            ],
+            _ => self.fields()
+                .iter()
+                .map(|field| match field.kind {
+                    AuthFieldKind::Choice(options) => options[0].to_string(),
+                    _ => String::new(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Indices of the fields that apply given the current values
+    pub fn visible_fields(&self, values: &[String]) -> Vec<usize> {
+        let grant = match self {
+            AuthKind::OAuth2 => Some(parse_grant(values.first().map(String::as_str).unwrap_or_default())),
+            _ => None,
+        };
+        self.fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| match grant {
+                Some(grant) => field.grants.is_empty() || field.grants.contains(&grant),
+                None => true,
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+fn parse_grant(value: &str) -> OAuth2GrantType {
+    OAuth2GrantType::all()
+        .into_iter()
+        .find(|grant| grant.as_str() == value)
+        .unwrap_or(OAuth2GrantType::ClientCredentials)
+}
+
+fn value(values: &[String], idx: usize) -> String {
+    values.get(idx).cloned().unwrap_or_default()
+}
+
+impl AuthType {
+    pub fn kind(&self) -> AuthKind {
+        match self {
+            AuthType::Inherit => AuthKind::Inherit,
+            AuthType::None => AuthKind::None,
+            AuthType::Bearer { .. } => AuthKind::Bearer,
+            AuthType::Basic { .. } => AuthKind::Basic,
+            AuthType::ApiKey { .. } => AuthKind::ApiKey,
+            AuthType::OAuth2(_) => AuthKind::OAuth2,
+            AuthType::AwsSigV4 { .. } => AuthKind::AwsSigV4,
+        }
+    }
+
+    /// Values for the Auth tab form, in the order of `AuthKind::fields`
+    pub fn to_field_values(&self) -> Vec<String> {
+        match self {
+            AuthType::None | AuthType::Inherit => Vec::new(),
+            AuthType::Bearer { token } => vec![token.clone()],
+            AuthType::Basic { username, password } => vec![username.clone(), password.clone()],
+            AuthType::ApiKey { key, value, location } => vec![
+                key.clone(),
+                value.clone(),
+                match location {
+                    ApiKeyLocation::Header => API_KEY_LOCATIONS[0].to_string(),
+                    ApiKeyLocation::QueryParam => API_KEY_LOCATIONS[1].to_string(),
+                },
+            ],
+            AuthType::OAuth2(config) => vec![
+                config.grant_type.as_str().to_string(),
+                config.token_url.clone(),
+                config.client_id.clone(),
+                config.client_secret.clone(),
+                config.scope.clone(),
+                config.username.clone(),
+                config.password.clone(),
+                config.auth_url.clone(),
+                config.redirect_uri.clone(),
+                config.refresh_token.clone(),
+            ],
+            AuthType::AwsSigV4 { access_key, secret_key, session_token, region, service } => vec![
+                access_key.clone(),
+                secret_key.clone(),
+                session_token.clone(),
+                region.clone(),
+                service.clone(),
+            ],
+        }
+    }
+
+    /// Build the auth settings back from the Auth tab form
+    pub fn from_field_values(kind: AuthKind, values: &[String]) -> AuthType {
+        match kind {
+            AuthKind::Inherit => AuthType::Inherit,
+            AuthKind::None => AuthType::None,
+            AuthKind::Bearer => {
+                let token = value(values, 0).trim().to_string();
+                if token.is_empty() {
+                    AuthType::None
+                } else {
+                    AuthType::Bearer { token }
+                }
+            }
+            AuthKind::Basic => AuthType::Basic {
+                username: value(values, 0),
+                password: value(values, 1),
+            },
+            AuthKind::ApiKey => AuthType::ApiKey {
+                key: value(values, 0).trim().to_string(),
+                value: value(values, 1),
+                location: if value(values, 2) == API_KEY_LOCATIONS[1] {
+                    ApiKeyLocation::QueryParam
+                } else {
+                    ApiKeyLocation::Header
+                },
+            },
+            AuthKind::OAuth2 => AuthType::OAuth2(OAuth2Config {
+                grant_type: parse_grant(&value(values, 0)),
+                token_url: value(values, 1).trim().to_string(),
+                client_id: value(values, 2).trim().to_string(),
+                client_secret: value(values, 3),
+                scope: value(values, 4).trim().to_string(),
+                username: value(values, 5),
+                password: value(values, 6),
+                auth_url: value(values, 7).trim().to_string(),
+                redirect_uri: value(values, 8).trim().to_string(),
+                refresh_token: value(values, 9).trim().to_string(),
+            }),
+            AuthKind::AwsSigV4 => AuthType::AwsSigV4 {
+                access_key: value(values, 0).trim().to_string(),
+                secret_key: value(values, 1).trim().to_string(),
+                session_token: value(values, 2).trim().to_string(),
+                region: value(values, 3).trim().to_string(),
+                service: value(values, 4).trim().to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grant_type_choices_match_grant_types() {
+        let AuthFieldKind::Choice(choices) = AuthKind::OAuth2.fields()[0].kind else {
+            panic!("the first OAuth2 field is the grant type");
+        };
+        let grants = OAuth2GrantType::all();
+        let labels: Vec<&str> = grants.iter().map(|grant| grant.as_str()).collect();
+        assert_eq!(choices, labels.as_slice());
+
+        for grant in OAuth2GrantType::all() {
+            assert_eq!(parse_grant(grant.as_str()), grant);
+        }
+    }
+}
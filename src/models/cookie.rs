@@ -0,0 +1,79 @@
+/// A cookie set by a `Set-Cookie` response header
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetCookie {
+    pub name: String,
+    pub value: String,
+    /// Attributes in header order, e.g. `("Path", Some("/"))` or `("HttpOnly", None)`
+    pub attributes: Vec<(String, Option<String>)>,
+}
+
+impl SetCookie {
+    /// `name = value`, then one indented line per attribute
+    pub fn display_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("{} = {}", self.name, self.value)];
+        lines.extend(self.attributes.iter().map(|(name, value)| match value {
+            Some(value) => format!("  {}: {}", name, value),
+            None => format!("  {}", name),
+        }));
+        lines
+    }
+}
+
+/// Parse one `Set-Cookie` header value.
+///
+/// Follows RFC 6265 leniently: the first `name=value` pair is the cookie, everything
+/// after the first `;` are attributes. Returns `None` when there is no `=` in the pair.
+pub fn parse_set_cookie(header: &str) -> Option<SetCookie> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let attributes = parts
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.split_once('=') {
+            Some((attr, value)) => (attr.trim().to_string(), Some(value.trim().to_string())),
+            None => (part.to_string(), None),
+        })
+        .collect();
+
+    Some(SetCookie {
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+        attributes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_attributes_in_order() {
+        let cookie = parse_set_cookie(
+            "session=abc123; Path=/; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Secure; HttpOnly; SameSite=Lax",
+        )
+        .unwrap();
+        assert_eq!(cookie.name, "session");
+        assert_eq!(cookie.value, "abc123");
+        assert_eq!(cookie.attributes.len(), 5);
+        assert_eq!(
+            cookie.attributes[1],
+            ("Expires".to_string(), Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()))
+        );
+        assert_eq!(cookie.attributes[3], ("HttpOnly".to_string(), None));
+        assert_eq!(cookie.display_lines()[..2], ["session = abc123", "  Path: /"]);
+        assert_eq!(cookie.display_lines()[4], "  HttpOnly");
+    }
+
+    #[test]
+    fn quoted_empty_and_invalid_values() {
+        assert_eq!(parse_set_cookie("id=\"q=1\"").unwrap().value, "q=1");
+        assert_eq!(parse_set_cookie("id=; Max-Age=0").unwrap().value, "");
+        assert!(parse_set_cookie("no-equals-sign").is_none());
+        assert!(parse_set_cookie("=value").is_none());
+    }
+}
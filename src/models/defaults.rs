@@ -0,0 +1,226 @@
+use crate::models::request::{AuthType, HttpRequest};
+use crate::models::GrpcRequest;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Settings a collection or folder hands down to the requests inside it
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RequestDefaults {
+    /// Put in front of request URLs without a scheme, e.g. `/users`
+    pub base_url: Option<String>,
+    /// Auth for requests set to inherit, `None` to keep looking further up
+    pub auth: Option<AuthType>,
+    pub headers: BTreeMap<String, String>,
+    /// Values for `{{name}}` in URLs, params, headers, bodies and auth
+    pub variables: BTreeMap<String, String>,
+}
+
+/// Shown in the editor of a collection or folder without defaults yet
+const TEMPLATE: &str = r#"# Defaults for the requests in here; folders below can override them.
+# base_url = "https://api.example.com"
+#
+# [auth.Bearer]
+# token = "{{token}}"
+#
+# [headers]
+# Accept = "application/json"
+#
+# [variables]
+# token = "secret"
+"#;
+
+impl RequestDefaults {
+    pub fn is_empty(&self) -> bool {
+        self.base_url.is_none() && self.auth.is_none() && self.headers.is_empty() && self.variables.is_empty()
+    }
+
+    /// The defaults as TOML for editing, or a commented example when there are none
+    pub fn to_toml(&self) -> String {
+        if self.is_empty() {
+            return TEMPLATE.to_string();
+        }
+        toml::to_string(self).unwrap_or_else(|_| TEMPLATE.to_string())
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let mut defaults: RequestDefaults = toml::from_str(text).context("Invalid defaults")?;
+        defaults.base_url = defaults.base_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+        if matches!(defaults.auth, Some(AuthType::Inherit)) {
+            defaults.auth = None;
+        }
+        Ok(defaults)
+    }
+}
+
+/// What a request gets from the collection and folders it sits in, each value with the
+/// path of the collection or folder it comes from
+#[derive(Clone, Debug, Default)]
+pub struct Inherited {
+    pub base_url: Option<(String, String)>,
+    pub auth: Option<(AuthType, String)>,
+    pub headers: BTreeMap<String, (String, String)>,
+    pub variables: BTreeMap<String, (String, String)>,
+}
+
+impl Inherited {
+    /// Merge the defaults of the collection and the folders down to the request's, outermost
+    /// first, so the closest one wins
+    pub fn from_chain<'a>(chain: impl IntoIterator<Item = (String, &'a RequestDefaults)>) -> Self {
+        let mut inherited = Inherited::default();
+        for (source, defaults) in chain {
+            if let Some(base_url) = &defaults.base_url {
+                inherited.base_url = Some((base_url.clone(), source.clone()));
+            }
+            if let Some(auth) = defaults.auth.as_ref().filter(|auth| !matches!(auth, AuthType::Inherit)) {
+                inherited.auth = Some((auth.clone(), source.clone()));
+            }
+            for (name, value) in &defaults.headers {
+                // Header names are case-insensitive, the closest spelling wins
+                inherited.headers.retain(|existing, _| !existing.eq_ignore_ascii_case(name));
+                inherited.headers.insert(name.clone(), (value.clone(), source.clone()));
+            }
+            for (name, value) in &defaults.variables {
+                inherited.variables.insert(name.clone(), (value.clone(), source.clone()));
+            }
+        }
+        inherited
+    }
+
+    /// Inherited headers the request does not set itself
+    pub fn extra_headers<'a>(&'a self, own: impl Fn(&str) -> bool + 'a) -> impl Iterator<Item = (&'a String, &'a (String, String))> {
+        self.headers.iter().filter(move |(name, _)| !own(name))
+    }
+
+    /// Replace `{{name}}` with the variable's value; unknown names are left as they are
+    pub fn substitute(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let name = &rest[start + 2..start + 2 + len];
+            result.push_str(&rest[..start]);
+            match self.variables.get(name.trim()) {
+                Some((value, _)) => result.push_str(value),
+                None => result.push_str(&rest[start..start + len + 4]),
+            }
+            rest = &rest[start + len + 4..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// The URL with variables filled in and the base URL in front when it has no scheme
+    pub fn resolve_url(&self, url: &str) -> String {
+        let url = self.substitute(url);
+        match &self.base_url {
+            Some((base_url, _)) if !url.contains("://") => {
+                let base_url = self.substitute(base_url);
+                let path = url.trim_start_matches('/');
+                if path.is_empty() {
+                    base_url
+                } else {
+                    format!("{}/{}", base_url.trim_end_matches('/'), path)
+                }
+            }
+            _ => url,
+        }
+    }
+
+    /// The auth a request ends up with, with variables filled in
+    pub fn resolve_auth(&self, auth: &AuthType) -> AuthType {
+        let auth = match auth {
+            AuthType::Inherit => match &self.auth {
+                Some((auth, _)) => auth,
+                None => return AuthType::None,
+            },
+            auth => auth,
+        };
+        let values: Vec<String> = auth.to_field_values().iter().map(|value| self.substitute(value)).collect();
+        AuthType::from_field_values(auth.kind(), &values)
+    }
+
+    /// The request as it is sent and exported
+    pub fn resolve_http(&self, request: &HttpRequest) -> HttpRequest {
+        let mut resolved = request.clone();
+        resolved.url = self.resolve_url(&request.url);
+        resolved.query_params = request.query_params.iter()
+            .map(|(name, value)| (self.substitute(name), self.substitute(value)))
+            .collect();
+        resolved.headers = request.headers.iter()
+            .map(|(name, value)| (self.substitute(name), self.substitute(value)))
+            .collect();
+        for (name, (value, _)) in self.extra_headers(|name| request.headers.keys().any(|own| own.eq_ignore_ascii_case(name))) {
+            resolved.headers.insert(name.clone(), self.substitute(value));
+        }
+        resolved.body = request.body.as_deref().map(|body| self.substitute(body));
+        resolved.auth = self.resolve_auth(&request.auth);
+        resolved
+    }
+
+    /// The gRPC request as it is sent and exported; inherited headers become metadata
+    pub fn resolve_grpc(&self, request: &GrpcRequest) -> GrpcRequest {
+        let mut resolved = request.clone();
+        resolved.server_url = self.substitute(&request.server_url);
+        resolved.message_json = self.substitute(&request.message_json);
+        resolved.metadata = request.metadata.iter()
+            .map(|(name, value)| (self.substitute(name), self.substitute(value)))
+            .collect();
+        for (name, (value, _)) in self.extra_headers(|name| request.metadata.keys().any(|own| own.eq_ignore_ascii_case(name))) {
+            resolved.metadata.insert(name.to_ascii_lowercase(), self.substitute(value));
+        }
+        resolved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::request::HttpMethod;
+
+    #[test]
+    fn requests_inherit_from_the_closest_node() {
+        let collection = RequestDefaults::from_toml(
+            r#"
+            base_url = "https://{{host}}/v1/"
+            [auth.Bearer]
+            token = "{{token}}"
+            [headers]
+            Accept = "application/json"
+            X-Team = "core"
+            [variables]
+            host = "api.test"
+            token = "outer"
+            "#,
+        )
+        .unwrap();
+        let folder = RequestDefaults::from_toml("[headers]\nx-team = \"admin\"\n[variables]\ntoken = \"inner\"\n").unwrap();
+        let inherited = Inherited::from_chain([("Shop".to_string(), &collection), ("Shop/Admin".to_string(), &folder)]);
+
+        let mut request = HttpRequest::new("ban".to_string(), HttpMethod::POST, "/users/{{id}}".to_string());
+        request.auth = AuthType::Inherit;
+        request.headers.insert("accept".to_string(), "text/plain".to_string());
+        let resolved = inherited.resolve_http(&request);
+        assert_eq!(resolved.url, "https://api.test/v1/users/{{id}}");
+        assert!(matches!(resolved.auth, AuthType::Bearer { ref token } if token == "inner"));
+        assert_eq!(resolved.headers.len(), 2);
+        assert_eq!(resolved.headers["accept"], "text/plain");
+        assert_eq!(resolved.headers["x-team"], "admin");
+        assert_eq!(inherited.auth.as_ref().map(|(_, source)| source.as_str()), Some("Shop"));
+
+        // Full URLs and the request's own auth are kept
+        request.url = "http://localhost/{{token}}".to_string();
+        request.auth = AuthType::None;
+        let resolved = inherited.resolve_http(&request);
+        assert_eq!(resolved.url, "http://localhost/inner");
+        assert!(matches!(resolved.auth, AuthType::None));
+
+        assert!(RequestDefaults::default().to_toml().starts_with('#'));
+        let again = RequestDefaults::from_toml(&collection.to_toml()).unwrap();
+        assert_eq!(again.headers, collection.headers);
+        assert_eq!(again.base_url, collection.base_url);
+        assert!(RequestDefaults::from_toml("base_url = ").is_err());
+    }
+}
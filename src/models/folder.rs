@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A folder of requests inside a collection; folders nest through `parent_id`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: Uuid,
+    pub collection_id: Uuid,
+    /// The folder this one sits in, `None` at the top of the collection
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    /// Position among the folders with the same parent
+    pub sort_index: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Folder {
+    pub fn new(name: String, collection_id: Uuid, parent_id: Option<Uuid>) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            collection_id,
+            parent_id,
+            name,
+            description: None,
+            sort_index: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// The folders directly in `parent` (the top of the collection for `None`), in order
+pub fn children(folders: &[Folder], collection_id: Uuid, parent: Option<Uuid>) -> Vec<&Folder> {
+    let mut children: Vec<&Folder> = folders
+        .iter()
+        .filter(|f| f.collection_id == collection_id && f.parent_id == parent)
+        .collect();
+    children.sort_by_key(|f| (f.sort_index, f.created_at));
+    children
+}
+
+/// `sort_index` for a folder added after the existing children of `parent`
+pub fn next_sort_index(folders: &[Folder], collection_id: Uuid, parent: Option<Uuid>) -> u32 {
+    children(folders, collection_id, parent)
+        .last()
+        .map_or(0, |f| f.sort_index.saturating_add(1))
+}
+
+/// The folder and every folder nested in it, parents before their children
+pub fn subtree(folders: &[Folder], id: Uuid) -> Vec<Uuid> {
+    let mut ids = vec![id];
+    let mut next = 0;
+    while let Some(&parent) = ids.get(next) {
+        ids.extend(folders.iter().filter(|f| f.parent_id == Some(parent)).map(|f| f.id));
+        next += 1;
+    }
+    ids
+}
+
+/// The folder's ancestors from the top of the collection down, without the folder itself
+pub fn ancestors(folders: &[Folder], id: Uuid) -> Vec<Uuid> {
+    let mut ancestors = Vec::new();
+    let mut parent = folders.iter().find(|f| f.id == id).and_then(|f| f.parent_id);
+    // Stops at a broken link rather than looping on it
+    while let Some(id) = parent.filter(|id| !ancestors.contains(id)) {
+        ancestors.push(id);
+        parent = folders.iter().find(|f| f.id == id).and_then(|f| f.parent_id);
+    }
+    ancestors.reverse();
+    ancestors
+}
+
+/// Names from the top of the collection down to the folder, e.g. `Users/Admin`
+pub fn path(folders: &[Folder], id: Uuid) -> String {
+    ancestors(folders, id)
+        .into_iter()
+        .chain([id])
+        .filter_map(|id| folders.iter().find(|f| f.id == id))
+        .map(|f| f.name.as_str())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folders_nest_in_order() {
+        let collection = Uuid::new_v4();
+        let mut users = Folder::new("Users".to_string(), collection, None);
+        users.sort_index = 1;
+        let mut orders = Folder::new("Orders".to_string(), collection, None);
+        orders.sort_index = 0;
+        let admin = Folder::new("Admin".to_string(), collection, Some(users.id));
+        let roles = Folder::new("Roles".to_string(), collection, Some(admin.id));
+        let other = Folder::new("Other".to_string(), Uuid::new_v4(), None);
+        let folders = vec![users.clone(), orders.clone(), admin.clone(), roles.clone(), other];
+
+        let top: Vec<&str> = children(&folders, collection, None).iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(top, vec!["Orders", "Users"]);
+        assert_eq!(next_sort_index(&folders, collection, None), 2);
+        assert_eq!(next_sort_index(&folders, collection, Some(roles.id)), 0);
+
+        assert_eq!(subtree(&folders, users.id), vec![users.id, admin.id, roles.id]);
+        assert_eq!(ancestors(&folders, roles.id), vec![users.id, admin.id]);
+        assert_eq!(path(&folders, roles.id), "Users/Admin/Roles");
+        assert_eq!(path(&folders, orders.id), "Orders");
+    }
+}
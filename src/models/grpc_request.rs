@@ -1,3 +1,4 @@
+use crate::config::Config;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -71,7 +72,7 @@ impl GrpcRequest {
             message_json: "{}".to_string(),
             metadata: HashMap::new(),
             use_tls: false,
-            timeout_seconds: Some(30),
+            timeout_seconds: Some(Config::global().grpc.timeout_seconds),
             collection_id: None,
             created_at: now,
             updated_at: now,
@@ -47,6 +47,18 @@ impl GrpcResponse {
         }
     }
 
+    /// Placeholder response for a call the user aborted before it completed
+    pub fn cancelled(request_id: Uuid, duration_ms: u64) -> Self {
+        Self {
+            status: GrpcStatus {
+                code: 1, // CANCELLED
+                message: format!("Call cancelled after {}ms", duration_ms),
+            },
+            duration_ms,
+            ..Self::new(request_id)
+        }
+    }
+
     pub fn is_ok(&self) -> bool {
         self.status.code == 0
     }
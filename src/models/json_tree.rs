@@ -0,0 +1,388 @@
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Foldable view over one or more JSON documents (a gRPC stream has one per message)
+#[derive(Clone, Debug)]
+pub struct JsonTree {
+    roots: Vec<Value>,
+    /// Collapsed containers, keyed by root index and path
+    collapsed: HashSet<(usize, String)>,
+    pub cursor: usize,
+    /// Visible rows, rebuilt only when the fold state changes
+    rows: Vec<RowEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonRowKind {
+    /// First line of an object or array, with its child count
+    Open { children: usize, collapsed: bool },
+    /// Closing bracket of an expanded object or array
+    Close,
+    Scalar,
+}
+
+/// One visible line of the tree
+#[derive(Clone, Debug)]
+pub struct JsonRow<'a> {
+    pub root: usize,
+    pub depth: usize,
+    /// Member name when the value sits in an object
+    pub key: Option<&'a str>,
+    /// JSONPath-style location, e.g. `$.items[2].name`
+    pub path: &'a str,
+    pub value: &'a Value,
+    pub kind: JsonRowKind,
+    /// Whether a comma follows, i.e. the value is not the last in its parent
+    pub comma: bool,
+}
+
+/// Cached form of a row that locates its value by JSON pointer instead of borrowing it
+#[derive(Clone, Debug)]
+struct RowEntry {
+    root: usize,
+    depth: usize,
+    key: Option<String>,
+    path: String,
+    pointer: String,
+    kind: JsonRowKind,
+    comma: bool,
+}
+
+impl JsonRow<'_> {
+    pub fn open_bracket(&self) -> &'static str {
+        if self.value.is_array() { "[" } else { "{" }
+    }
+
+    pub fn close_bracket(&self) -> &'static str {
+        if self.value.is_array() { "]" } else { "}" }
+    }
+}
+
+impl JsonTree {
+    pub fn new(roots: Vec<Value>) -> Self {
+        let mut tree = Self {
+            roots,
+            collapsed: HashSet::new(),
+            cursor: 0,
+            rows: Vec::new(),
+        };
+        tree.rebuild_rows();
+        tree
+    }
+
+    fn rebuild_rows(&mut self) {
+        let mut rows = Vec::new();
+        for (root, value) in self.roots.iter().enumerate() {
+            self.push_rows(&mut rows, root, 0, None, "$".to_string(), String::new(), value, false);
+        }
+        self.rows = rows;
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push_rows(
+        &self,
+        rows: &mut Vec<RowEntry>,
+        root: usize,
+        depth: usize,
+        key: Option<&str>,
+        path: String,
+        pointer: String,
+        value: &Value,
+        comma: bool,
+    ) {
+        let key = key.map(str::to_string);
+        let children = match value {
+            Value::Object(map) => map.len(),
+            Value::Array(items) => items.len(),
+            _ => {
+                rows.push(RowEntry { root, depth, key, path, pointer, kind: JsonRowKind::Scalar, comma });
+                return;
+            }
+        };
+
+        // Empty containers are shown inline as {} or []
+        let collapsed = children > 0 && self.collapsed.contains(&(root, path.clone()));
+        rows.push(RowEntry {
+            root,
+            depth,
+            key,
+            path: path.clone(),
+            pointer: pointer.clone(),
+            kind: JsonRowKind::Open { children, collapsed },
+            comma,
+        });
+        if collapsed || children == 0 {
+            return;
+        }
+
+        match value {
+            Value::Object(map) => {
+                for (idx, (child_key, child)) in map.iter().enumerate() {
+                    let child_path = member_path(&path, child_key);
+                    let child_pointer = format!("{}/{}", pointer, child_key.replace('~', "~0").replace('/', "~1"));
+                    self.push_rows(rows, root, depth + 1, Some(child_key), child_path, child_pointer, child, idx + 1 < children);
+                }
+            }
+            Value::Array(items) => {
+                for (idx, child) in items.iter().enumerate() {
+                    let child_path = format!("{}[{}]", path, idx);
+                    let child_pointer = format!("{}/{}", pointer, idx);
+                    self.push_rows(rows, root, depth + 1, None, child_path, child_pointer, child, idx + 1 < children);
+                }
+            }
+            _ => {}
+        }
+
+        rows.push(RowEntry { root, depth, key: None, path, pointer, kind: JsonRowKind::Close, comma });
+    }
+
+    /// The visible row at `idx`
+    pub fn row(&self, idx: usize) -> Option<JsonRow<'_>> {
+        let entry = self.rows.get(idx)?;
+        Some(JsonRow {
+            root: entry.root,
+            depth: entry.depth,
+            key: entry.key.as_deref(),
+            path: &entry.path,
+            value: self.roots.get(entry.root)?.pointer(&entry.pointer)?,
+            kind: entry.kind.clone(),
+            comma: entry.comma,
+        })
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cursor_row(&self) -> Option<JsonRow<'_>> {
+        self.row(self.cursor)
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let last = self.row_count().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn cursor_to_end(&mut self) {
+        self.cursor = self.row_count().saturating_sub(1);
+    }
+
+    /// Unfold the node under the cursor, or step into it when already unfolded
+    pub fn expand_at_cursor(&mut self) {
+        let Some(row) = self.cursor_row() else { return };
+        let key = (row.root, row.path.to_string());
+        match row.kind {
+            JsonRowKind::Open { collapsed: true, .. } => {
+                self.collapsed.remove(&key);
+                self.rebuild_rows();
+            }
+            JsonRowKind::Open { children, .. } if children > 0 => self.move_cursor(1),
+            _ => {}
+        }
+    }
+
+    /// Fold the node under the cursor, or jump to its parent
+    pub fn collapse_at_cursor(&mut self) {
+        let Some(row) = self.cursor_row() else { return };
+        let (root, path, depth) = (row.root, row.path.to_string(), row.depth);
+        match row.kind {
+            JsonRowKind::Open { children, collapsed: false } if children > 0 => {
+                self.collapsed.insert((root, path));
+                self.rebuild_rows();
+            }
+            JsonRowKind::Close => {
+                self.collapsed.insert((root, path.clone()));
+                self.rebuild_rows();
+                self.cursor = self.find_row(root, &path).unwrap_or(self.cursor);
+            }
+            _ => {
+                if let Some(parent_depth) = depth.checked_sub(1) {
+                    self.cursor = self.rows[..self.cursor]
+                        .iter()
+                        .rposition(|r| r.depth == parent_depth && matches!(r.kind, JsonRowKind::Open { .. }))
+                        .unwrap_or(self.cursor);
+                }
+            }
+        }
+    }
+
+    pub fn toggle_at_cursor(&mut self) {
+        match self.cursor_row().map(|row| row.kind) {
+            Some(JsonRowKind::Open { collapsed: true, .. }) => self.expand_at_cursor(),
+            Some(JsonRowKind::Open { .. } | JsonRowKind::Close) => self.collapse_at_cursor(),
+            _ => {}
+        }
+    }
+
+    /// Show nodes down to `depth` levels below the root and fold everything deeper
+    pub fn expand_to_depth(&mut self, depth: usize) {
+        let selected = self.cursor_row().map(|row| (row.root, row.path.to_string()));
+
+        self.collapsed.clear();
+        self.rebuild_rows();
+        let deeper: Vec<(usize, String)> = self.rows
+            .iter()
+            .filter(|row| row.depth >= depth && matches!(row.kind, JsonRowKind::Open { children, .. } if children > 0))
+            .map(|row| (row.root, row.path.clone()))
+            .collect();
+        self.collapsed.extend(deeper);
+        self.rebuild_rows();
+        self.reselect(selected);
+    }
+
+    pub fn expand_all(&mut self) {
+        let selected = self.cursor_row().map(|row| (row.root, row.path.to_string()));
+        self.collapsed.clear();
+        self.rebuild_rows();
+        self.reselect(selected);
+    }
+
+    /// Keep the cursor on the same node, or its closest visible ancestor
+    fn reselect(&mut self, selected: Option<(usize, String)>) {
+        let Some((root, mut path)) = selected else { return };
+        loop {
+            if let Some(idx) = self.find_row(root, &path) {
+                self.cursor = idx;
+                return;
+            }
+            match parent_path(&path) {
+                Some(parent) => path = parent,
+                None => {
+                    self.cursor = 0;
+                    return;
+                }
+            }
+        }
+    }
+
+    fn find_row(&self, root: usize, path: &str) -> Option<usize> {
+        self.rows.iter().position(|r| r.root == root && r.path == path)
+    }
+
+    /// The value under the cursor, strings without their quotes
+    pub fn cursor_value_text(&self) -> Option<String> {
+        let row = self.cursor_row()?;
+        Some(match row.value {
+            Value::String(s) => s.clone(),
+            Value::Object(_) | Value::Array(_) => serde_json::to_string_pretty(row.value).ok()?,
+            other => other.to_string(),
+        })
+    }
+
+    pub fn cursor_path(&self) -> Option<String> {
+        self.cursor_row().map(|row| row.path.to_string())
+    }
+}
+
+/// Path of an object member: `.name` for plain identifiers, `["odd key"]` otherwise
+pub fn member_path(parent: &str, key: &str) -> String {
+    let is_identifier = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        format!("{}.{}", parent, key)
+    } else {
+        format!("{}[{}]", parent, Value::String(key.to_string()))
+    }
+}
+
+/// Strip the last `.name`, `[n]` or `["key"]` segment of a path
+fn parent_path(path: &str) -> Option<String> {
+    if path == "$" {
+        return None;
+    }
+    let cut = if path.ends_with(']') {
+        // A quoted key may itself contain brackets, so search from the opening quote
+        match path.rfind("[\"") {
+            Some(idx) if path.ends_with("\"]") => idx,
+            _ => path.rfind('[')?,
+        }
+    } else {
+        path.rfind('.')?
+    };
+    Some(path[..cut].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> JsonTree {
+        JsonTree::new(vec![json!({
+            "name": "nexus",
+            "tags": ["tui", "http"],
+            "owner": {"login": "octo", "odd key": {"x": 1}},
+            "empty": []
+        })])
+    }
+
+    fn rows(tree: &JsonTree) -> Vec<JsonRow<'_>> {
+        (0..tree.row_count()).filter_map(|idx| tree.row(idx)).collect()
+    }
+
+    fn lines(tree: &JsonTree) -> Vec<String> {
+        rows(tree)
+            .iter()
+            .map(|row| {
+                let body = match &row.kind {
+                    JsonRowKind::Open { children, collapsed: true } => format!("{{{}}}", children),
+                    JsonRowKind::Open { children: 0, .. } => format!("{}{}", row.open_bracket(), row.close_bracket()),
+                    JsonRowKind::Open { .. } => row.open_bracket().to_string(),
+                    JsonRowKind::Close => row.close_bracket().to_string(),
+                    JsonRowKind::Scalar => row.value.to_string(),
+                };
+                format!("{}{}{}", "  ".repeat(row.depth), row.key.map(|k| format!("{}: ", k)).unwrap_or_default(), body)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn paths_use_jsonpath_syntax() {
+        let tree = sample();
+        let paths: Vec<String> = rows(&tree).into_iter().map(|row| row.path.to_string()).collect();
+        assert!(paths.contains(&"$.tags[1]".to_string()));
+        assert!(paths.contains(&"$.owner[\"odd key\"].x".to_string()));
+        assert_eq!(parent_path("$.owner[\"odd key\"].x").as_deref(), Some("$.owner[\"odd key\"]"));
+        assert_eq!(parent_path("$.owner[\"odd key\"]").as_deref(), Some("$.owner"));
+        assert_eq!(parent_path("$.tags[1]").as_deref(), Some("$.tags"));
+
+        // Keys with JSON pointer metacharacters still resolve to their value
+        let tree = JsonTree::new(vec![json!({"a/b~c": [true]})]);
+        let idx = tree.find_row(0, "$[\"a/b~c\"][0]").unwrap();
+        assert_eq!(tree.row(idx).unwrap().value, &json!(true));
+    }
+
+    #[test]
+    fn folding_shows_child_counts() {
+        let mut tree = sample();
+        tree.expand_to_depth(1);
+        assert_eq!(
+            lines(&tree),
+            vec!["{", "  empty: []", "  name: \"nexus\"", "  owner: {2}", "  tags: {2}", "}"]
+        );
+
+        // Unfold the owner object from the cursor and step into it
+        tree.cursor = 3;
+        tree.expand_at_cursor();
+        tree.expand_at_cursor();
+        assert_eq!(tree.cursor_path().as_deref(), Some("$.owner.login"));
+        assert_eq!(tree.cursor_value_text().as_deref(), Some("octo"));
+
+        // Going left from a member jumps to its parent, then folds it
+        tree.collapse_at_cursor();
+        assert_eq!(tree.cursor_path().as_deref(), Some("$.owner"));
+        tree.collapse_at_cursor();
+        assert_eq!(tree.row_count(), 6);
+    }
+
+    #[test]
+    fn changing_depth_keeps_cursor_on_nearest_visible_node() {
+        let mut tree = sample();
+        tree.cursor = tree.find_row(0, "$.owner[\"odd key\"].x").unwrap();
+        tree.expand_to_depth(1);
+        assert_eq!(tree.cursor_path().as_deref(), Some("$.owner"));
+        tree.expand_all();
+        assert_eq!(tree.cursor_path().as_deref(), Some("$.owner"));
+        assert_eq!(tree.row_count(), 14);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+
+/// Narrowest share a panel can be resized to, in percent
+pub const MIN_PANEL_WIDTH: u16 = 5;
+
+/// How far one grow or shrink step moves a panel border, in percent
+pub const RESIZE_STEP: i16 = 5;
+
+/// Terminals narrower than this stack the panels in the `Auto` arrangement
+pub const NARROW_COLUMNS: u16 = 100;
+
+/// How the collections, requests, editor and response panels share the screen, kept between sessions
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PanelLayout {
+    /// Share of each panel in percent, in panel order; hidden panels keep theirs for when they come back
+    pub widths: [u16; 4],
+    pub show_collections: bool,
+    pub show_requests: bool,
+    pub arrangement: Arrangement,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Arrangement {
+    /// Side by side, stacked when the terminal is narrow
+    Auto,
+    Columns,
+    Stacked,
+}
+
+impl Arrangement {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Arrangement::Auto => "auto",
+            Arrangement::Columns => "side by side",
+            Arrangement::Stacked => "stacked",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Arrangement::Auto => Arrangement::Columns,
+            Arrangement::Columns => Arrangement::Stacked,
+            Arrangement::Stacked => Arrangement::Auto,
+        }
+    }
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            widths: Config::global().ui.panel_widths,
+            show_collections: true,
+            show_requests: true,
+            arrangement: Arrangement::Auto,
+        }
+    }
+}
+
+impl PanelLayout {
+    /// Whether the panel at `index` (collections, requests, editor, response) is shown
+    pub fn is_visible(&self, index: usize) -> bool {
+        match index {
+            0 => self.show_collections,
+            1 => self.show_requests,
+            _ => true,
+        }
+    }
+
+    /// Whether a screen `width` columns wide shows the panels on top of each other
+    pub fn is_stacked(&self, width: u16) -> bool {
+        match self.arrangement {
+            Arrangement::Auto => width < NARROW_COLUMNS,
+            Arrangement::Columns => false,
+            Arrangement::Stacked => true,
+        }
+    }
+
+    /// Grow (positive `delta`) or shrink the panel at `index` by moving its border with the next
+    /// visible panel, or the previous one for the last panel. Returns whether anything changed.
+    pub fn resize(&mut self, index: usize, delta: i16) -> bool {
+        let neighbor = (index + 1..self.widths.len())
+            .find(|&i| self.is_visible(i))
+            .or_else(|| (0..index).rev().find(|&i| self.is_visible(i)));
+        let Some(neighbor) = neighbor else {
+            return false;
+        };
+
+        // Limit the step so neither panel ends up under the minimum
+        let delta = if delta > 0 {
+            delta.min(self.widths[neighbor].saturating_sub(MIN_PANEL_WIDTH) as i16)
+        } else {
+            delta.max(-(self.widths[index].saturating_sub(MIN_PANEL_WIDTH) as i16))
+        };
+        if delta == 0 {
+            return false;
+        }
+        self.widths[index] = self.widths[index].saturating_add_signed(delta);
+        self.widths[neighbor] = self.widths[neighbor].saturating_add_signed(-delta);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resizing_moves_the_border_with_the_next_visible_panel() {
+        let mut layout = PanelLayout { widths: [15, 20, 35, 30], ..PanelLayout::default() };
+
+        assert!(layout.resize(2, 5));
+        assert_eq!(layout.widths, [15, 20, 40, 25]);
+
+        // The last panel borrows from the one before it
+        assert!(layout.resize(3, -10));
+        assert_eq!(layout.widths, [15, 20, 50, 15]);
+
+        // Hidden panels are skipped and keep their share
+        layout.show_requests = false;
+        assert!(layout.resize(0, 5));
+        assert_eq!(layout.widths, [20, 20, 45, 15]);
+
+        // Steps stop at the minimum width
+        assert!(layout.resize(2, 20));
+        assert_eq!(layout.widths, [20, 20, 55, 5]);
+        assert!(!layout.resize(2, 5));
+        assert_eq!(layout.widths.iter().sum::<u16>(), 100);
+    }
+}
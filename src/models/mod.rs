@@ -1,6 +1,15 @@
 pub mod request;
 pub mod response;
 pub mod collection;
+pub mod folder;
+pub mod defaults;
+pub mod proxy;
+pub mod auth;
+pub mod oauth2;
+pub mod json_tree;
+pub mod cookie;
+pub mod layout;
+pub mod trash;
 
 // gRPC models
 pub mod grpc_request;
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+/// Refresh tokens that expire within this many seconds instead of sending a request that will fail
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OAuth2GrantType {
+    ClientCredentials,
+    Password,
+    AuthorizationCode,
+    RefreshToken,
+}
+
+impl OAuth2GrantType {
+    pub fn all() -> Vec<OAuth2GrantType> {
+        vec![
+            OAuth2GrantType::ClientCredentials,
+            OAuth2GrantType::Password,
+            OAuth2GrantType::AuthorizationCode,
+            OAuth2GrantType::RefreshToken,
+        ]
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            OAuth2GrantType::ClientCredentials => "Client credentials",
+            OAuth2GrantType::Password => "Password",
+            OAuth2GrantType::AuthorizationCode => "Authorization code (PKCE)",
+            OAuth2GrantType::RefreshToken => "Refresh token",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OAuth2Config {
+    pub grant_type: OAuth2GrantType,
+    pub token_url: String,
+    pub client_id: String,
+    /// Empty for public clients
+    pub client_secret: String,
+    pub scope: String,
+    /// Password grant only
+    pub username: String,
+    pub password: String,
+    /// Authorization code grant only
+    pub auth_url: String,
+    pub redirect_uri: String,
+    /// Refresh token grant only: the token to start from
+    pub refresh_token: String,
+}
+
+impl Default for OAuth2Config {
+    fn default() -> Self {
+        Self {
+            grant_type: OAuth2GrantType::ClientCredentials,
+            token_url: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            scope: String::new(),
+            username: String::new(),
+            password: String::new(),
+            auth_url: String::new(),
+            redirect_uri: "http://127.0.0.1:8765/callback".to_string(),
+            refresh_token: String::new(),
+        }
+    }
+}
+
+impl OAuth2Config {
+    /// Identifies the settings a token was issued for, so editing them invalidates the cached token
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "{:?}|{}|{}|{}|{}|{}",
+            self.grant_type, self.token_url, self.client_id, self.scope, self.username, self.auth_url
+        )
+    }
+}
+
+/// An access token obtained from a token endpoint
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OAuth2Token {
+    pub access_token: String,
+    pub token_type: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub scope: Option<String>,
+    pub config_fingerprint: String,
+    pub obtained_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl OAuth2Token {
+    /// Whether the token can be sent as is. Tokens without an expiry are trusted until rejected.
+    pub fn is_usable_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        match self.expires_at {
+            Some(expires_at) => expires_at - chrono::Duration::seconds(EXPIRY_MARGIN_SECS) > now,
+            None => true,
+        }
+    }
+
+    pub fn matches(&self, config: &OAuth2Config) -> bool {
+        self.config_fingerprint == config.fingerprint()
+    }
+
+    /// Short human readable status, e.g. "valid, expires in 42m"
+    pub fn status_at(&self, now: chrono::DateTime<chrono::Utc>) -> String {
+        let refresh_note = if self.refresh_token.is_some() { ", refreshable" } else { "" };
+        match self.expires_at {
+            Some(expires_at) if expires_at > now => {
+                let remaining = (expires_at - now).num_seconds();
+                let remaining = if remaining >= 3600 {
+                    format!("{}h{}m", remaining / 3600, (remaining % 3600) / 60)
+                } else if remaining >= 60 {
+                    format!("{}m", remaining / 60)
+                } else {
+                    format!("{}s", remaining)
+                };
+                format!("valid, expires in {}{}", remaining, refresh_note)
+            }
+            Some(_) => format!("expired{}", refresh_note),
+            None => format!("valid, no expiry{}", refresh_note),
+        }
+    }
+}
@@ -1,9 +1,16 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use super::{request::HttpRequest, response::HttpResponse, GrpcRequest, GrpcResponse};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProtocolType {
+    Http,
+    Grpc,
+}
+
 /// Unified request type supporting multiple protocols
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[allow(dead_code)]
 pub enum RequestType {
     Http(HttpRequest),
     Grpc(GrpcRequest),
@@ -17,8 +24,14 @@ pub enum ResponseType {
     Grpc(GrpcResponse),
 }
 
-#[allow(dead_code)]
 impl RequestType {
+    pub fn id(&self) -> Uuid {
+        match self {
+            RequestType::Http(req) => req.id,
+            RequestType::Grpc(req) => req.id,
+        }
+    }
+
     pub fn name(&self) -> &str {
         match self {
             RequestType::Http(req) => &req.name,
@@ -26,14 +39,74 @@ impl RequestType {
         }
     }
 
+    pub fn protocol(&self) -> ProtocolType {
+        match self {
+            RequestType::Http(_) => ProtocolType::Http,
+            RequestType::Grpc(_) => ProtocolType::Grpc,
+        }
+    }
+
+    pub fn collection_id(&self) -> Option<Uuid> {
+        match self {
+            RequestType::Http(req) => req.collection_id,
+            RequestType::Grpc(req) => req.collection_id,
+        }
+    }
+
+    pub fn set_collection_id(&mut self, collection_id: Option<Uuid>) {
+        match self {
+            RequestType::Http(req) => req.collection_id = collection_id,
+            RequestType::Grpc(req) => req.collection_id = collection_id,
+        }
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        match self {
+            RequestType::Http(req) => req.created_at,
+            RequestType::Grpc(req) => req.created_at,
+        }
+    }
+
+    /// A copy under a new id and name, created now
+    pub fn duplicate(&self, name: String) -> RequestType {
+        let now = Utc::now();
+        let mut copy = self.clone();
+        match &mut copy {
+            RequestType::Http(req) => {
+                req.id = Uuid::new_v4();
+                req.name = name;
+                req.created_at = now;
+                req.updated_at = now;
+            }
+            RequestType::Grpc(req) => {
+                req.id = Uuid::new_v4();
+                req.name = name;
+                req.created_at = now;
+                req.updated_at = now;
+            }
+        }
+        copy
+    }
+
+    #[allow(dead_code)]
     pub fn is_http(&self) -> bool {
         matches!(self, RequestType::Http(_))
     }
 
+    #[allow(dead_code)]
     pub fn is_grpc(&self) -> bool {
         matches!(self, RequestType::Grpc(_))
     }
 
+    /// Whether the name, method or address contains `needle`, which is lowercase
+    pub fn matches_search(&self, needle: &str) -> bool {
+        let fields = match self {
+            RequestType::Http(req) => vec![req.name.as_str(), req.url.as_str(), req.method.as_str()],
+            RequestType::Grpc(req) => vec![req.name.as_str(), req.server_url.as_str(), req.service_name.as_str(), req.method_name.as_str()],
+        };
+        fields.iter().any(|field| field.to_lowercase().contains(needle))
+    }
+
     pub fn as_http(&self) -> Option<&HttpRequest> {
         match self {
             RequestType::Http(req) => Some(req),
@@ -47,6 +120,20 @@ impl RequestType {
             _ => None,
         }
     }
+
+    pub fn as_http_mut(&mut self) -> Option<&mut HttpRequest> {
+        match self {
+            RequestType::Http(req) => Some(req),
+            _ => None,
+        }
+    }
+
+    pub fn as_grpc_mut(&mut self) -> Option<&mut GrpcRequest> {
+        match self {
+            RequestType::Grpc(req) => Some(req),
+            _ => None,
+        }
+    }
 }
 
 #[allow(dead_code)]
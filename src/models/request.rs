@@ -1,3 +1,6 @@
+use crate::config::Config;
+use crate::models::oauth2::OAuth2Config;
+use crate::models::proxy::ProxySettings;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -46,6 +49,17 @@ pub enum AuthType {
     Bearer { token: String },
     Basic { username: String, password: String },
     ApiKey { key: String, value: String, location: ApiKeyLocation },
+    OAuth2(OAuth2Config),
+    AwsSigV4 {
+        access_key: String,
+        secret_key: String,
+        /// Only set for temporary credentials
+        session_token: String,
+        region: String,
+        service: String,
+    },
+    /// Use the auth of the folder or collection the request is in
+    Inherit,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -78,6 +92,7 @@ pub struct HttpRequest {
 impl HttpRequest {
     pub fn new(name: String, method: HttpMethod, url: String) -> Self {
         let now = chrono::Utc::now();
+        let defaults = &Config::global().http;
         Self {
             id: Uuid::new_v4(),
             name,
@@ -86,10 +101,10 @@ impl HttpRequest {
             headers: HashMap::new(),
             query_params: HashMap::new(),
             body: None,
-            auth: AuthType::None,
-            timeout_seconds: Some(30),
-            follow_redirects: true,
-            verify_ssl: true,
+            auth: AuthType::Inherit,
+            timeout_seconds: Some(defaults.timeout_seconds),
+            follow_redirects: defaults.follow_redirects,
+            verify_ssl: defaults.verify_tls,
             collection_id: None,
             created_at: now,
             updated_at: now,
@@ -125,6 +140,11 @@ impl HttpRequest {
         }
     }
     
+    /// Generate the curl command with flags that route it through the same proxy as the app
+    pub fn to_curl_with_proxy(&self, proxy: &ProxySettings) -> String {
+        format!("{}{}", self.to_curl(), proxy.curl_args())
+    }
+
     // Generate curl command equivalent for this request
     pub fn to_curl(&self) -> String {
         let mut curl = format!("curl -X {} '{}'", self.method.as_str(), self.full_url());
@@ -152,7 +172,19 @@ impl HttpRequest {
                     }
                 }
             }
-            AuthType::None => {}
+            AuthType::OAuth2(_) => {
+                // The token is only known at send time
+                curl.push_str(" \\\n  -H \"Authorization: Bearer $ACCESS_TOKEN\"");
+            }
+            AuthType::AwsSigV4 { access_key, secret_key, session_token, region, service } => {
+                let escape = |value: &str| value.replace('\'', "'\\''");
+                curl.push_str(&format!(" \\\n  --aws-sigv4 'aws:amz:{}:{}'", escape(region), escape(service)));
+                curl.push_str(&format!(" \\\n  -u '{}:{}'", escape(access_key), escape(secret_key)));
+                if !session_token.is_empty() {
+                    curl.push_str(&format!(" \\\n  -H 'x-amz-security-token: {}'", escape(session_token)));
+                }
+            }
+            AuthType::None | AuthType::Inherit => {}
         }
         
         // Add body if present
@@ -175,3 +207,22 @@ impl Default for HttpRequest {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curl_export_escapes_aws_credentials() {
+        let mut request = HttpRequest::new("s3".to_string(), HttpMethod::GET, "https://s3.amazonaws.com/".to_string());
+        request.auth = AuthType::AwsSigV4 {
+            access_key: "AKID".to_string(),
+            secret_key: "a'b".to_string(),
+            session_token: "tok'en".to_string(),
+            region: "us-east-1".to_string(),
+            service: "s3".to_string(),
+        };
+        let curl = request.to_curl();
+        assert!(curl.contains("-u 'AKID:a'\\''b'"), "{}", curl);
+        assert!(curl.contains("-H 'x-amz-security-token: tok'\\''en'"), "{}", curl);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::utils::binary::hex_dump;
+use crate::utils::body_format::{format_body, BodyFormat};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -15,44 +17,200 @@ pub struct HttpResponse {
     pub size_bytes: usize,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub error: Option<String>,
+    /// Set only on the placeholder for a request the user aborted
+    pub cancelled: bool,
+    /// e.g. "HTTP/1.1", empty when no response was received
+    pub http_version: String,
+    /// Headers as received, including repeated ones like `Set-Cookie`
+    pub raw_headers: Vec<(String, String)>,
+    /// The request as it went out, after auth was applied
+    pub sent_request: Option<SentRequest>,
+}
+
+/// Snapshot of the final request handed to the HTTP client
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SentRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl SentRequest {
+    pub fn to_raw(&self) -> String {
+        let mut raw = format!("{} {}\n", self.method, self.url);
+        for (name, value) in &self.headers {
+            raw.push_str(&format!("{}: {}\n", name, value));
+        }
+        if let Some(body) = &self.body {
+            raw.push('\n');
+            raw.push_str(body);
+        }
+        raw
+    }
 }
 
 impl HttpResponse {
+    /// Placeholder response for a request the user aborted before it completed
+    pub fn cancelled(request_id: Uuid, duration_ms: u64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            request_id,
+            status_code: 0,
+            status_text: "Cancelled".to_string(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            body_text: Some(format!("Request cancelled after {}ms", duration_ms)),
+            duration_ms,
+            size_bytes: 0,
+            timestamp: chrono::Utc::now(),
+            error: Some("Request cancelled by user".to_string()),
+            cancelled: true,
+            http_version: String::new(),
+            raw_headers: Vec::new(),
+            sent_request: None,
+        }
+    }
+
+    /// Placeholder response for a request that failed before a response arrived
+    pub fn failed(message: String, sent_request: Option<SentRequest>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            request_id: Uuid::new_v4(),
+            status_code: 0,
+            status_text: "Request Failed".to_string(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            body_text: Some(message),
+            duration_ms: 0,
+            size_bytes: 0,
+            timestamp: chrono::Utc::now(),
+            error: None,
+            cancelled: false,
+            http_version: String::new(),
+            raw_headers: Vec::new(),
+            sent_request,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
     
     pub fn get_content_type(&self) -> Option<&String> {
         self.headers.get("content-type")
             .or_else(|| self.headers.get("Content-Type"))
     }
     
-    pub fn is_json(&self) -> bool {
+    /// Format announced by the `Content-Type` header, plain text without one.
+    /// Bodies that aren't UTF-8 are shown as a hex dump.
+    pub fn body_format(&self) -> BodyFormat {
+        if self.is_binary() {
+            return BodyFormat::Hex;
+        }
         self.get_content_type()
-            .map(|ct| ct.contains("application/json"))
-            .unwrap_or(false)
+            .map_or(BodyFormat::Text, |ct| BodyFormat::from_content_type(ct))
     }
-    
-    pub fn formatted_body(&self) -> String {
-        if let Some(text) = &self.body_text {
-            if self.is_json() {
-                if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
-                    return serde_json::to_string_pretty(&value)
-                        .unwrap_or_else(|_| text.clone());
-                }
-            }
-            text.clone()
-        } else {
-            format!("<binary data, {} bytes>", self.size_bytes)
+
+    pub fn is_binary(&self) -> bool {
+        self.body_text.is_none() && !self.body.is_empty()
+    }
+
+    /// The body pretty-printed as `format`, or a placeholder for binary data
+    pub fn formatted_body(&self, format: BodyFormat) -> String {
+        match &self.body_text {
+            Some(text) => format_body(format, text),
+            None if format == BodyFormat::Hex => hex_dump(&self.body),
+            None => format!("<binary data, {} bytes>", self.size_bytes),
         }
     }
     
-    pub fn status_color(&self) -> ratatui::style::Color {
-        use ratatui::style::Color;
-        match self.status_code {
-            200..=299 => Color::Green,
-            300..=399 => Color::Blue,
-            400..=499 => Color::Yellow,
-            500..=599 => Color::Red,
-            _ => Color::White,
+    /// Status line, headers and body as received, without any pretty-printing
+    pub fn raw_text(&self) -> String {
+        let mut raw = format!("{} {}\n", self.http_version, self.status_text);
+        for (name, value) in &self.raw_headers {
+            raw.push_str(&format!("{}: {}\n", name, value));
+        }
+        raw.push('\n');
+        match &self.body_text {
+            Some(text) => raw.push_str(text),
+            None if self.body.is_empty() => {}
+            None => raw.push_str(&format!("<binary data, {} bytes>", self.size_bytes)),
         }
+        raw
+    }
+
+    /// Values of every `Set-Cookie` header, in the order received
+    pub fn set_cookie_headers(&self) -> impl Iterator<Item = &str> {
+        self.raw_headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("set-cookie"))
+            .map(|(_, value)| value.as_str())
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(raw_headers: Vec<(&str, &str)>, body: &[u8]) -> HttpResponse {
+        let mut response = HttpResponse::failed(String::new(), None);
+        response.status_code = 200;
+        response.status_text = "200 OK".to_string();
+        response.http_version = "HTTP/1.1".to_string();
+        response.raw_headers = raw_headers.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        response.body = body.to_vec();
+        response.size_bytes = body.len();
+        response.body_text = String::from_utf8(body.to_vec()).ok();
+        response
+    }
+
+    #[test]
+    fn raw_text_keeps_header_order_and_repeats() {
+        let response = response(
+            vec![("set-cookie", "a=1"), ("content-type", "text/plain"), ("set-cookie", "b=2; Path=/")],
+            b"hello",
+        );
+        assert_eq!(
+            response.raw_text(),
+            "HTTP/1.1 200 OK\nset-cookie: a=1\ncontent-type: text/plain\nset-cookie: b=2; Path=/\n\nhello"
+        );
+        assert_eq!(response.set_cookie_headers().collect::<Vec<_>>(), vec!["a=1", "b=2; Path=/"]);
+
+        let binary = self::response(vec![], &[0xff, 0xfe, 0x00]);
+        assert_eq!(binary.raw_text(), "HTTP/1.1 200 OK\n\n<binary data, 3 bytes>");
+    }
+
+    #[test]
+    fn binary_bodies_are_hex_dumped() {
+        let png = response(vec![], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(png.body_format(), BodyFormat::Hex);
+        assert!(png.formatted_body(BodyFormat::Hex).starts_with("00000000  89 50 4e 47"));
+        assert_eq!(png.formatted_body(BodyFormat::Json), "<binary data, 8 bytes>");
+
+        let mut text = response(vec![], b"{}");
+        text.headers.insert("content-type".to_string(), "application/json".to_string());
+        assert_eq!(text.body_format(), BodyFormat::Json);
+    }
+
+    #[test]
+    fn cancelled_is_an_explicit_marker() {
+        assert!(HttpResponse::cancelled(Uuid::new_v4(), 12).is_cancelled());
+
+        let mut failed = HttpResponse::failed("timed out".to_string(), None);
+        failed.error = Some("Request cancelled by user".to_string());
+        assert!(!failed.is_cancelled());
+    }
+
+    #[test]
+    fn sent_request_to_raw() {
+        let sent = SentRequest {
+            method: "POST".to_string(),
+            url: "https://example.com/items".to_string(),
+            headers: vec![("authorization".to_string(), "Bearer t".to_string())],
+            body: Some("{}".to_string()),
+        };
+        assert_eq!(sent.to_raw(), "POST https://example.com/items\nauthorization: Bearer t\n\n{}");
+    }
+}
@@ -0,0 +1,98 @@
+use crate::models::{
+    collection::Collection, defaults::RequestDefaults, folder::Folder, protocol::RequestType, proxy::ProxySettings,
+    ProtoSchema,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What was deleted, as shown in the trash
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TrashKind {
+    Collection,
+    Folder,
+    Request,
+    ProtoSchema,
+}
+
+impl TrashKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TrashKind::Collection => "Collection",
+            TrashKind::Folder => "Folder",
+            TrashKind::Request => "Request",
+            TrashKind::ProtoSchema => "Proto schema",
+        }
+    }
+}
+
+/// Everything one delete removed, kept until it is restored or purged
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: Uuid,
+    pub kind: TrashKind,
+    /// Name of the deleted collection, folder, request or schema
+    pub name: String,
+    pub deleted_at: DateTime<Utc>,
+    pub collections: Vec<Collection>,
+    pub folders: Vec<Folder>,
+    pub requests: Vec<RequestType>,
+    /// Folder of each request that was not at the top of its collection
+    pub request_folders: Vec<(Uuid, Uuid)>,
+    /// Position of each request in its collection or folder, for requests deleted with it
+    pub sort_indices: Vec<(Uuid, u32)>,
+    pub node_defaults: Vec<(Uuid, RequestDefaults)>,
+    pub collection_proxies: Vec<(Uuid, ProxySettings)>,
+    pub proto_schemas: Vec<ProtoSchema>,
+}
+
+impl TrashEntry {
+    pub fn new(kind: TrashKind, name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            name,
+            deleted_at: Utc::now(),
+            collections: Vec::new(),
+            folders: Vec::new(),
+            requests: Vec::new(),
+            request_folders: Vec::new(),
+            sort_indices: Vec::new(),
+            node_defaults: Vec::new(),
+            collection_proxies: Vec::new(),
+            proto_schemas: Vec::new(),
+        }
+    }
+
+    /// e.g. `Collection "Shop"`
+    pub fn title(&self) -> String {
+        format!("{} \"{}\"", self.kind.label(), self.name)
+    }
+
+    /// What was deleted along with it, e.g. `2 folders, 5 requests`; empty for a single item
+    pub fn summary(&self) -> String {
+        let folders = match self.kind {
+            TrashKind::Folder => self.folders.len().saturating_sub(1),
+            _ => self.folders.len(),
+        };
+        let requests = match self.kind {
+            TrashKind::Request => 0,
+            _ => self.requests.len(),
+        };
+        describe_contents(folders, requests)
+    }
+}
+
+/// `2 folders, 1 request`, leaving out what there is none of
+pub fn describe_contents(folders: usize, requests: usize) -> String {
+    let count = |n: usize, noun: &str| match n {
+        0 => None,
+        1 => Some(format!("1 {}", noun)),
+        n => Some(format!("{} {}s", n, noun)),
+    };
+    [count(folders, "folder"), count(requests, "request")]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+}
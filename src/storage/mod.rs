@@ -2,12 +2,27 @@ use anyhow::{Context, Result};
 use sled::Db;
 use std::path::PathBuf;
 
-use crate::models::{collection::Collection, request::HttpRequest, GrpcRequest, ProtoSchema};
+use crate::config::Config;
+use crate::models::{collection::Collection, defaults::RequestDefaults, folder::Folder, layout::PanelLayout, oauth2::OAuth2Token, protocol::RequestType, proxy::ProxySettings, request::HttpRequest, trash::TrashEntry, GrpcRequest, ProtoSchema};
+use std::collections::HashMap;
+use uuid::Uuid;
 
 const COLLECTIONS_TREE: &str = "collections";
 const REQUESTS_TREE: &str = "requests";
 const GRPC_REQUESTS_TREE: &str = "grpc_requests";
 const PROTO_SCHEMAS_TREE: &str = "proto_schemas";
+const SETTINGS_TREE: &str = "settings";
+const COLLECTION_PROXIES_TREE: &str = "collection_proxies";
+const OAUTH2_TOKENS_TREE: &str = "oauth2_tokens";
+const RESPONSE_FILTERS_TREE: &str = "response_filters";
+const FOLDERS_TREE: &str = "folders";
+const REQUEST_FOLDERS_TREE: &str = "request_folders";
+const REQUEST_DEFAULTS_TREE: &str = "request_defaults";
+const SORT_INDICES_TREE: &str = "sort_indices";
+const TRASH_TREE: &str = "trash";
+
+const PROXY_SETTINGS_KEY: &str = "proxy";
+const PANEL_LAYOUT_KEY: &str = "layout";
 
 pub struct Storage {
     db: Db,
@@ -27,10 +42,7 @@ impl Storage {
     }
     
     fn get_data_dir() -> Result<PathBuf> {
-        let data_dir = dirs::data_dir()
-            .context("Failed to get data directory")?
-            .join("nexus");
-        Ok(data_dir)
+        Config::global().paths.data_dir()
     }
     
     pub fn save_collection(&self, collection: &Collection) -> Result<()> {
@@ -129,27 +141,94 @@ impl Storage {
         Ok(())
     }
     
-    pub fn delete_requests_by_collection(&self, collection_id: &uuid::Uuid) -> Result<()> {
-        let tree = self.db.open_tree(REQUESTS_TREE)
-            .context("Failed to open requests tree")?;
-        
-        let mut keys_to_delete = Vec::new();
-        
+    // Folder storage methods
+
+    pub fn save_folder(&self, folder: &Folder) -> Result<()> {
+        let tree = self.db.open_tree(FOLDERS_TREE)
+            .context("Failed to open folders tree")?;
+
+        let value = bincode::serialize(folder)
+            .context("Failed to serialize folder")?;
+
+        tree.insert(folder.id.as_bytes(), value)
+            .context("Failed to save folder")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    pub fn load_folders(&self) -> Result<Vec<Folder>> {
+        let tree = self.db.open_tree(FOLDERS_TREE)
+            .context("Failed to open folders tree")?;
+
+        let mut folders = Vec::new();
+
         for result in tree.iter() {
-            let (key, value) = result.context("Failed to iterate requests")?;
-            let request: HttpRequest = bincode::deserialize(&value)
-                .context("Failed to deserialize request")?;
-            
-            if request.collection_id == Some(*collection_id) {
-                keys_to_delete.push(key.to_vec());
-            }
+            let (_, value) = result.context("Failed to iterate folders")?;
+            let folder: Folder = bincode::deserialize(&value)
+                .context("Failed to deserialize folder")?;
+            folders.push(folder);
         }
-        
-        for key in keys_to_delete {
-            tree.remove(key)
-                .context("Failed to delete request")?;
+
+        folders.sort_by(|a, b| a.sort_index.cmp(&b.sort_index).then(a.created_at.cmp(&b.created_at)));
+
+        Ok(folders)
+    }
+
+    pub fn delete_folder(&self, id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(FOLDERS_TREE)
+            .context("Failed to open folders tree")?;
+
+        tree.remove(id.as_bytes())
+            .context("Failed to delete folder")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    /// Put a request in a folder; requests without an entry sit at the top of their collection
+    pub fn save_request_folder(&self, request_id: &Uuid, folder_id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(REQUEST_FOLDERS_TREE)
+            .context("Failed to open request folders tree")?;
+
+        tree.insert(request_id.as_bytes(), folder_id.as_bytes().as_slice())
+            .context("Failed to save request folder")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    pub fn load_request_folders(&self) -> Result<HashMap<Uuid, Uuid>> {
+        let tree = self.db.open_tree(REQUEST_FOLDERS_TREE)
+            .context("Failed to open request folders tree")?;
+
+        let mut request_folders = HashMap::new();
+
+        for result in tree.iter() {
+            let (key, value) = result.context("Failed to iterate request folders")?;
+            let request_id = Uuid::from_slice(&key)
+                .context("Invalid request folder key")?;
+            let folder_id = Uuid::from_slice(&value)
+                .context("Invalid request folder value")?;
+            request_folders.insert(request_id, folder_id);
         }
-        
+
+        Ok(request_folders)
+    }
+
+    pub fn delete_request_folder(&self, request_id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(REQUEST_FOLDERS_TREE)
+            .context("Failed to open request folders tree")?;
+
+        tree.remove(request_id.as_bytes())
+            .context("Failed to delete request folder")?;
+
         self.db.flush()
             .context("Failed to flush database")?;
 
@@ -208,34 +287,81 @@ impl Storage {
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub fn delete_grpc_requests_by_collection(&self, collection_id: &uuid::Uuid) -> Result<()> {
-        let tree = self.db.open_tree(GRPC_REQUESTS_TREE)
-            .context("Failed to open gRPC requests tree")?;
+    /// Position of a collection among the collections, or of a request in its collection or folder
+    pub fn save_sort_index(&self, id: &Uuid, sort_index: u32) -> Result<()> {
+        let tree = self.db.open_tree(SORT_INDICES_TREE)
+            .context("Failed to open sort indices tree")?;
 
-        let mut keys_to_delete = Vec::new();
+        tree.insert(id.as_bytes(), &sort_index.to_be_bytes())
+            .context("Failed to save sort index")?;
 
-        for result in tree.iter() {
-            let (key, value) = result.context("Failed to iterate gRPC requests")?;
-            let request: GrpcRequest = bincode::deserialize(&value)
-                .context("Failed to deserialize gRPC request")?;
+        self.db.flush()
+            .context("Failed to flush database")?;
 
-            if request.collection_id == Some(*collection_id) {
-                keys_to_delete.push(key.to_vec());
-            }
-        }
+        Ok(())
+    }
+
+    /// Sort indices by collection or request id; those without one go after the others
+    pub fn load_sort_indices(&self) -> Result<HashMap<Uuid, u32>> {
+        let tree = self.db.open_tree(SORT_INDICES_TREE)
+            .context("Failed to open sort indices tree")?;
+
+        let mut sort_indices = HashMap::new();
 
-        for key in keys_to_delete {
-            tree.remove(key)
-                .context("Failed to delete gRPC request")?;
+        for result in tree.iter() {
+            let (key, value) = result.context("Failed to iterate sort indices")?;
+            let id = Uuid::from_slice(&key)
+                .context("Invalid sort index key")?;
+            let bytes: [u8; 4] = value.as_ref().try_into()
+                .context("Invalid sort index value")?;
+            sort_indices.insert(id, u32::from_be_bytes(bytes));
         }
 
+        Ok(sort_indices)
+    }
+
+    pub fn delete_sort_index(&self, id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(SORT_INDICES_TREE)
+            .context("Failed to open sort indices tree")?;
+
+        tree.remove(id.as_bytes())
+            .context("Failed to delete sort index")?;
+
         self.db.flush()
             .context("Failed to flush database")?;
 
         Ok(())
     }
 
+    // Both kinds of request, each kind is kept in its own tree
+
+    pub fn save_any_request(&self, request: &RequestType) -> Result<()> {
+        match request {
+            RequestType::Http(request) => self.save_request(request),
+            RequestType::Grpc(request) => self.save_grpc_request(request),
+        }
+    }
+
+    /// HTTP and gRPC requests in one list, in the order they were created
+    pub fn load_all_requests(&self) -> Result<Vec<RequestType>> {
+        let mut requests: Vec<RequestType> = self.load_requests()?
+            .into_iter()
+            .map(RequestType::Http)
+            .chain(self.load_grpc_requests()?.into_iter().map(RequestType::Grpc))
+            .collect();
+
+        requests.sort_by_key(RequestType::created_at);
+
+        Ok(requests)
+    }
+
+    pub fn delete_any_request(&self, request: &RequestType) -> Result<()> {
+        match request {
+            RequestType::Http(request) => self.delete_request(&request.id),
+            RequestType::Grpc(request) => self.delete_grpc_request(&request.id),
+        }
+    }
+
     // Proto schema storage methods
 
     #[allow(dead_code)]
@@ -287,5 +413,309 @@ impl Storage {
 
         Ok(())
     }
-}
 
+    pub fn save_panel_layout(&self, layout: &PanelLayout) -> Result<()> {
+        let tree = self.db.open_tree(SETTINGS_TREE)
+            .context("Failed to open settings tree")?;
+
+        let value = bincode::serialize(layout)
+            .context("Failed to serialize panel layout")?;
+
+        tree.insert(PANEL_LAYOUT_KEY, value)
+            .context("Failed to save panel layout")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    pub fn load_panel_layout(&self) -> Result<Option<PanelLayout>> {
+        let tree = self.db.open_tree(SETTINGS_TREE)
+            .context("Failed to open settings tree")?;
+
+        match tree.get(PANEL_LAYOUT_KEY).context("Failed to read panel layout")? {
+            Some(value) => {
+                let layout = bincode::deserialize(&value)
+                    .context("Failed to deserialize panel layout")?;
+                Ok(Some(layout))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // Proxy settings storage methods
+
+    pub fn save_proxy_settings(&self, settings: &ProxySettings) -> Result<()> {
+        let tree = self.db.open_tree(SETTINGS_TREE)
+            .context("Failed to open settings tree")?;
+
+        let value = bincode::serialize(settings)
+            .context("Failed to serialize proxy settings")?;
+
+        tree.insert(PROXY_SETTINGS_KEY, value)
+            .context("Failed to save proxy settings")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    pub fn load_proxy_settings(&self) -> Result<Option<ProxySettings>> {
+        let tree = self.db.open_tree(SETTINGS_TREE)
+            .context("Failed to open settings tree")?;
+
+        match tree.get(PROXY_SETTINGS_KEY).context("Failed to read proxy settings")? {
+            Some(value) => {
+                let settings = bincode::deserialize(&value)
+                    .context("Failed to deserialize proxy settings")?;
+                Ok(Some(settings))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_collection_proxy(&self, collection_id: &Uuid, settings: &ProxySettings) -> Result<()> {
+        let tree = self.db.open_tree(COLLECTION_PROXIES_TREE)
+            .context("Failed to open collection proxies tree")?;
+
+        let value = bincode::serialize(settings)
+            .context("Failed to serialize collection proxy")?;
+
+        tree.insert(collection_id.as_bytes(), value)
+            .context("Failed to save collection proxy")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    pub fn load_collection_proxies(&self) -> Result<HashMap<Uuid, ProxySettings>> {
+        let tree = self.db.open_tree(COLLECTION_PROXIES_TREE)
+            .context("Failed to open collection proxies tree")?;
+
+        let mut proxies = HashMap::new();
+
+        for result in tree.iter() {
+            let (key, value) = result.context("Failed to iterate collection proxies")?;
+            let collection_id = Uuid::from_slice(&key)
+                .context("Invalid collection proxy key")?;
+            let settings: ProxySettings = bincode::deserialize(&value)
+                .context("Failed to deserialize collection proxy")?;
+            proxies.insert(collection_id, settings);
+        }
+
+        Ok(proxies)
+    }
+
+    pub fn delete_collection_proxy(&self, collection_id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(COLLECTION_PROXIES_TREE)
+            .context("Failed to open collection proxies tree")?;
+
+        tree.remove(collection_id.as_bytes())
+            .context("Failed to delete collection proxy")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    /// Defaults of a collection or folder, keyed by its id
+    pub fn save_request_defaults(&self, node_id: &Uuid, defaults: &RequestDefaults) -> Result<()> {
+        let tree = self.db.open_tree(REQUEST_DEFAULTS_TREE)
+            .context("Failed to open request defaults tree")?;
+
+        let value = bincode::serialize(defaults)
+            .context("Failed to serialize request defaults")?;
+
+        tree.insert(node_id.as_bytes(), value)
+            .context("Failed to save request defaults")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    pub fn load_request_defaults(&self) -> Result<HashMap<Uuid, RequestDefaults>> {
+        let tree = self.db.open_tree(REQUEST_DEFAULTS_TREE)
+            .context("Failed to open request defaults tree")?;
+
+        let mut defaults = HashMap::new();
+
+        for result in tree.iter() {
+            let (key, value) = result.context("Failed to iterate request defaults")?;
+            let node_id = Uuid::from_slice(&key)
+                .context("Invalid request defaults key")?;
+            let node_defaults: RequestDefaults = bincode::deserialize(&value)
+                .context("Failed to deserialize request defaults")?;
+            defaults.insert(node_id, node_defaults);
+        }
+
+        Ok(defaults)
+    }
+
+    pub fn delete_request_defaults(&self, node_id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(REQUEST_DEFAULTS_TREE)
+            .context("Failed to open request defaults tree")?;
+
+        tree.remove(node_id.as_bytes())
+            .context("Failed to delete request defaults")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    // OAuth2 token storage methods
+
+    pub fn save_oauth2_token(&self, request_id: &Uuid, token: &OAuth2Token) -> Result<()> {
+        let tree = self.db.open_tree(OAUTH2_TOKENS_TREE)
+            .context("Failed to open OAuth2 tokens tree")?;
+
+        let value = bincode::serialize(token)
+            .context("Failed to serialize OAuth2 token")?;
+
+        tree.insert(request_id.as_bytes(), value)
+            .context("Failed to save OAuth2 token")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    pub fn load_oauth2_tokens(&self) -> Result<HashMap<Uuid, OAuth2Token>> {
+        let tree = self.db.open_tree(OAUTH2_TOKENS_TREE)
+            .context("Failed to open OAuth2 tokens tree")?;
+
+        let mut tokens = HashMap::new();
+
+        for result in tree.iter() {
+            let (key, value) = result.context("Failed to iterate OAuth2 tokens")?;
+            let request_id = Uuid::from_slice(&key)
+                .context("Invalid OAuth2 token key")?;
+            let token: OAuth2Token = bincode::deserialize(&value)
+                .context("Failed to deserialize OAuth2 token")?;
+            tokens.insert(request_id, token);
+        }
+
+        Ok(tokens)
+    }
+
+    pub fn delete_oauth2_token(&self, request_id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(OAUTH2_TOKENS_TREE)
+            .context("Failed to open OAuth2 tokens tree")?;
+
+        tree.remove(request_id.as_bytes())
+            .context("Failed to delete OAuth2 token")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    // Recent response filter storage methods
+
+    pub fn save_recent_filters(&self, request_id: &Uuid, filters: &[String]) -> Result<()> {
+        let tree = self.db.open_tree(RESPONSE_FILTERS_TREE)
+            .context("Failed to open response filters tree")?;
+
+        let value = bincode::serialize(filters)
+            .context("Failed to serialize response filters")?;
+
+        tree.insert(request_id.as_bytes(), value)
+            .context("Failed to save response filters")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    pub fn load_recent_filters(&self) -> Result<HashMap<Uuid, Vec<String>>> {
+        let tree = self.db.open_tree(RESPONSE_FILTERS_TREE)
+            .context("Failed to open response filters tree")?;
+
+        let mut filters = HashMap::new();
+
+        for result in tree.iter() {
+            let (key, value) = result.context("Failed to iterate response filters")?;
+            let request_id = Uuid::from_slice(&key)
+                .context("Invalid response filters key")?;
+            let recent: Vec<String> = bincode::deserialize(&value)
+                .context("Failed to deserialize response filters")?;
+            filters.insert(request_id, recent);
+        }
+
+        Ok(filters)
+    }
+
+    pub fn delete_recent_filters(&self, request_id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(RESPONSE_FILTERS_TREE)
+            .context("Failed to open response filters tree")?;
+
+        tree.remove(request_id.as_bytes())
+            .context("Failed to delete response filters")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    // Trash storage methods
+
+    pub fn save_trash_entry(&self, entry: &TrashEntry) -> Result<()> {
+        let tree = self.db.open_tree(TRASH_TREE)
+            .context("Failed to open trash tree")?;
+
+        let value = bincode::serialize(entry)
+            .context("Failed to serialize trash entry")?;
+
+        tree.insert(entry.id.as_bytes(), value)
+            .context("Failed to save trash entry")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+
+    /// Deleted items, the most recently deleted first
+    pub fn load_trash(&self) -> Result<Vec<TrashEntry>> {
+        let tree = self.db.open_tree(TRASH_TREE)
+            .context("Failed to open trash tree")?;
+
+        let mut entries = Vec::new();
+
+        for result in tree.iter() {
+            let (_, value) = result.context("Failed to iterate trash")?;
+            let entry: TrashEntry = bincode::deserialize(&value)
+                .context("Failed to deserialize trash entry")?;
+            entries.push(entry);
+        }
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+
+        Ok(entries)
+    }
+
+    pub fn delete_trash_entry(&self, id: &Uuid) -> Result<()> {
+        let tree = self.db.open_tree(TRASH_TREE)
+            .context("Failed to open trash tree")?;
+
+        tree.remove(id.as_bytes())
+            .context("Failed to delete trash entry")?;
+
+        self.db.flush()
+            .context("Failed to flush database")?;
+
+        Ok(())
+    }
+}
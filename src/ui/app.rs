@@ -1,4 +1,5 @@
-use crate::app::state::{AppState, ProtocolType};
+use crate::app::state::{AppState, Panel, ProtocolType};
+use crate::utils::body_format::BodyFormat;
 use crate::ui::{
     components::{
         collection_list::CollectionList,
@@ -7,78 +8,168 @@ use crate::ui::{
         grpc_editor::GrpcEditor,
         response_viewer::ResponseViewer,
         statusbar::StatusBar,
+        tab_bar::TabBar,
         help_popup::HelpPopup,
         welcome_popup::WelcomePopup,
         export_popup::ExportPopup,
         import_popup::ImportPopup,
         proto_loader_popup::ProtoLoaderPopup,
+        proxy_settings_popup::ProxySettingsPopup,
+        defaults_popup::DefaultsPopup,
+        diff_popup::DiffPopup,
+        command_palette::CommandPalettePopup,
+        trash_popup::TrashPopup,
+        confirm_popup::ConfirmPopup,
     },
+    hit_map::{HitMap, Target},
     layout::Layout,
 };
-use ratatui::{Frame, layout::{Constraint, Direction, Layout as RatatuiLayout, Rect}};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout as RatatuiLayout, Rect},
+    text::Line,
+    widgets::{Paragraph, Wrap},
+};
 
 pub struct UI;
 
 impl UI {
-    pub fn draw(frame: &mut Frame, state: &mut AppState) {
-        let layout = Layout::new(frame.area());
+    /// Draw the whole screen and return where the clickable parts ended up
+    pub fn draw(frame: &mut Frame, state: &mut AppState) -> HitMap {
+        let zoomed = state.zoomed.then(|| state.focused_panel.index());
+        let layout = Layout::new(frame.area(), &state.panel_layout, zoomed);
+        let mut hits = HitMap::default();
+        for (panel, area) in Panel::ALL.into_iter().zip([layout.collections, layout.requests, layout.editor, layout.response]) {
+            hits.push(area, Target::Panel(panel));
+        }
 
-        Self::draw_collections(frame, layout.collections, state);
-        Self::draw_requests(frame, layout.requests, state);
-        Self::draw_editor(frame, layout.editor, state);
-        Self::draw_response(frame, layout.response, state);
+        Self::draw_tabs(frame, layout.tabs, state, &mut hits);
+        if !layout.collections.is_empty() {
+            Self::draw_collections(frame, layout.collections, state, &mut hits);
+        }
+        if !layout.requests.is_empty() {
+            Self::draw_requests(frame, layout.requests, state, &mut hits);
+        }
+        if !layout.editor.is_empty() {
+            Self::draw_editor(frame, layout.editor, state, &mut hits);
+        }
+        if !layout.response.is_empty() {
+            Self::draw_response(frame, layout.response, state, &mut hits);
+        }
         Self::draw_statusbar(frame, layout.statusbar, state);
         
+        // A popup covers the panels, only its own parts take clicks
+        if state.is_popup_open() {
+            hits.clear();
+        }
+
         if state.show_welcome {
             Self::draw_welcome(frame, state);
         } else if state.show_export_menu {
-            Self::draw_export_menu(frame, state);
+            Self::draw_export_menu(frame, state, &mut hits);
         } else if state.show_import_menu {
-            Self::draw_import_menu(frame, state);
+            Self::draw_import_menu(frame, state, &mut hits);
         } else if state.show_proto_loader {
-            Self::draw_proto_loader(frame, state);
+            Self::draw_proto_loader(frame, state, &mut hits);
+        } else if state.show_proxy_settings {
+            Self::draw_proxy_settings(frame, state);
+        } else if state.show_defaults_editor {
+            Self::draw_defaults_editor(frame, state);
+        } else if state.show_diff_popup {
+            Self::draw_diff_popup(frame, state);
+        } else if state.show_command_palette {
+            Self::draw_command_palette(frame, state, &mut hits);
+        } else if state.show_trash {
+            Self::draw_trash(frame, state, &mut hits);
         } else if state.show_help {
             Self::draw_help(frame, state);
         }
+        // The question goes over whatever asked it, e.g. the trash
+        if state.confirmation.is_some() {
+            hits.clear();
+            Self::draw_confirmation(frame, state, &mut hits);
+        }
+        hits
     }
     
-    fn draw_collections(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    fn draw_tabs(frame: &mut Frame, area: Rect, state: &mut AppState, hits: &mut HitMap) {
+        let component = TabBar::new(state);
+        component.hit_targets(area, hits);
+        frame.render_widget(component, area);
+    }
+
+    fn draw_collections(frame: &mut Frame, area: Rect, state: &mut AppState, hits: &mut HitMap) {
         let component = CollectionList::new(state);
+        component.hit_targets(area, hits);
         frame.render_widget(component, area);
     }
 
-    fn draw_requests(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    fn draw_requests(frame: &mut Frame, area: Rect, state: &mut AppState, hits: &mut HitMap) {
         let component = RequestList::new(state);
         frame.render_widget(component, area);
+        // After rendering, which scrolls the selection into view
+        RequestList::new(state).hit_targets(area, hits);
     }
 
-    fn draw_editor(frame: &mut Frame, area: Rect, state: &mut AppState) {
-        match state.protocol_type {
+    fn draw_editor(frame: &mut Frame, area: Rect, state: &mut AppState, hits: &mut HitMap) {
+        match state.protocol_type() {
             ProtocolType::Http => {
                 let component = RequestEditor::new(state);
+                component.hit_targets(area, hits);
                 frame.render_widget(component, area);
             }
             ProtocolType::Grpc => {
                 let component = GrpcEditor::new(state);
+                component.hit_targets(area, hits);
                 frame.render_widget(component, area);
             }
         }
     }
 
-    fn draw_response(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    fn draw_response(frame: &mut Frame, area: Rect, state: &mut AppState, hits: &mut HitMap) {
+        if state.response_search_jump && state.is_response_body_shown() {
+            state.response_search_jump = false;
+            Self::scroll_to_search_match(area, state);
+        }
         let component = ResponseViewer::new(state);
+        component.hit_targets(area, hits);
         frame.render_widget(component, area);
     }
 
+    /// Scroll the response so the current search match is near the top, accounting for wrapping
+    fn scroll_to_search_match(area: Rect, state: &mut AppState) {
+        let Some(line) = state.response_search_matches.get(state.response_search_current).map(|m| m.line) else {
+            return;
+        };
+        // Hex dumps are not wrapped, one line is one row
+        if state.response_body_format() == BodyFormat::Hex {
+            state.response_scroll = line.saturating_sub(2).min(u16::MAX as usize) as u16;
+            return;
+        }
+
+        let text = state.response_display_text().unwrap_or_default();
+        let before: Vec<Line> = text.lines().take(line).map(Line::from).collect();
+
+        // Body width inside the borders. `line_count` comes from ratatui's
+        // `unstable-rendered-line-info` feature; it is the only way to count rows
+        // with exactly the word wrapping the viewer renders with.
+        let width = area.width.saturating_sub(2);
+        let rows = Paragraph::new(before).wrap(Wrap { trim: false }).line_count(width);
+        state.response_scroll = rows.saturating_sub(2).min(u16::MAX as usize) as u16;
+    }
+
     fn draw_statusbar(frame: &mut Frame, area: Rect, state: &mut AppState) {
         let component = StatusBar::new(state);
         frame.render_widget(component, area);
     }
 
-    fn draw_help(frame: &mut Frame, _state: &mut AppState) {
-        let component = HelpPopup::new();
+    fn draw_help(frame: &mut Frame, state: &mut AppState) {
         let area = Self::centered_rect(frame.area(), 60, 80);
-        frame.render_widget(component, area);
+        let component = HelpPopup::new(&state.keymap);
+        // Stop scrolling once the last line is at the bottom of the popup
+        let max_scroll = component.lines().len().saturating_sub(area.height.saturating_sub(2) as usize);
+        state.help_scroll = state.help_scroll.min(max_scroll.min(u16::MAX as usize) as u16);
+        frame.render_widget(HelpPopup::new(&state.keymap).scroll(state.help_scroll), area);
     }
 
     fn draw_welcome(frame: &mut Frame, _state: &mut AppState) {
@@ -87,21 +178,66 @@ impl UI {
         frame.render_widget(component, area);
     }
 
-    fn draw_export_menu(frame: &mut Frame, state: &mut AppState) {
+    fn draw_export_menu(frame: &mut Frame, state: &mut AppState, hits: &mut HitMap) {
         let component = ExportPopup::new(state);
         let area = Self::centered_rect(frame.area(), 60, 50);
+        component.hit_targets(area, hits);
         frame.render_widget(component, area);
     }
 
-    fn draw_import_menu(frame: &mut Frame, state: &mut AppState) {
+    fn draw_import_menu(frame: &mut Frame, state: &mut AppState, hits: &mut HitMap) {
         let component = ImportPopup::new(state);
         let area = Self::centered_rect(frame.area(), 70, 40);
+        component.hit_targets(area, hits);
         frame.render_widget(component, area);
     }
 
-    fn draw_proto_loader(frame: &mut Frame, state: &mut AppState) {
+    fn draw_proto_loader(frame: &mut Frame, state: &mut AppState, hits: &mut HitMap) {
         let component = ProtoLoaderPopup::new(state);
         let area = Self::centered_rect(frame.area(), 80, 50);
+        component.hit_targets(area, hits);
+        frame.render_widget(component, area);
+    }
+
+    fn draw_proxy_settings(frame: &mut Frame, state: &mut AppState) {
+        let component = ProxySettingsPopup::new(state);
+        let area = Self::centered_rect(frame.area(), 70, 60);
+        frame.render_widget(component, area);
+    }
+
+    fn draw_defaults_editor(frame: &mut Frame, state: &mut AppState) {
+        let component = DefaultsPopup::new(state);
+        let area = Self::centered_rect(frame.area(), 70, 70);
+        frame.render_widget(component, area);
+    }
+
+    fn draw_diff_popup(frame: &mut Frame, state: &mut AppState) {
+        let component = DiffPopup::new(state);
+        let area = Self::centered_rect(frame.area(), 90, 85);
+        frame.render_widget(component, area);
+    }
+
+    fn draw_command_palette(frame: &mut Frame, state: &mut AppState, hits: &mut HitMap) {
+        let component = CommandPalettePopup::new(state);
+        let area = Self::centered_rect(frame.area(), 70, 60);
+        component.hit_targets(area, hits);
+        frame.render_widget(component, area);
+    }
+
+    fn draw_trash(frame: &mut Frame, state: &mut AppState, hits: &mut HitMap) {
+        let component = TrashPopup::new(state);
+        let area = Self::centered_rect(frame.area(), 70, 60);
+        component.hit_targets(area, hits);
+        frame.render_widget(component, area);
+    }
+
+    fn draw_confirmation(frame: &mut Frame, state: &mut AppState, hits: &mut HitMap) {
+        let Some(confirmation) = &state.confirmation else {
+            return;
+        };
+        let component = ConfirmPopup::new(confirmation);
+        let area = component.area(frame.area());
+        component.hit_targets(area, hits);
         frame.render_widget(component, area);
     }
 
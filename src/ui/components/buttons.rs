@@ -0,0 +1,59 @@
+use crate::ui::hit_map::{HitMap, Target};
+use crate::ui::theme::Theme;
+use crossterm::event::KeyCode;
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+/// Cells between two buttons
+const SPACING: u16 = 2;
+
+/// A centered row of popup buttons such as `[ Import ]`, clicking one presses its key
+pub struct ButtonRow {
+    buttons: Vec<(&'static str, KeyCode)>,
+}
+
+impl ButtonRow {
+    pub fn new(buttons: Vec<(&'static str, KeyCode)>) -> Self {
+        Self { buttons }
+    }
+
+    /// The last row inside the borders of a popup drawn in `area`
+    pub fn bottom_of(area: Rect) -> Rect {
+        Rect {
+            x: area.x + 1,
+            y: area.bottom().saturating_sub(2),
+            width: area.width.saturating_sub(2),
+            height: area.height.saturating_sub(2).min(1),
+        }
+    }
+
+    /// Where each button is drawn in `area`
+    fn areas(&self, area: Rect) -> Vec<Rect> {
+        let widths: Vec<u16> = self.buttons.iter().map(|(label, _)| label.chars().count() as u16 + 4).collect();
+        let total = widths.iter().sum::<u16>() + SPACING * widths.len().saturating_sub(1) as u16;
+        let mut x = area.x + area.width.saturating_sub(total) / 2;
+        widths
+            .into_iter()
+            .map(|width| {
+                let button = Rect::new(x, area.y, width, area.height.min(1)).intersection(area);
+                x = x.saturating_add(width + SPACING);
+                button
+            })
+            .collect()
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        for (button, (_, key)) in self.areas(area).into_iter().zip(&self.buttons) {
+            hits.push(button, Target::Button(*key));
+        }
+    }
+}
+
+impl Widget for ButtonRow {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for (button, (label, _)) in self.areas(area).into_iter().zip(&self.buttons) {
+            if !button.is_empty() {
+                buf.set_stringn(button.x, button.y, format!("[ {} ]", label), button.width as usize, Theme::accent());
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app::state::{AppState, Panel};
+use crate::ui::hit_map::{HitMap, Target};
 use crate::ui::theme::Theme;
 use ratatui::{
     layout::Rect,
@@ -14,6 +15,21 @@ impl<'a> CollectionList<'a> {
     pub fn new(state: &'a AppState) -> Self {
         Self { state }
     }
+
+    /// First tree row shown, so the selected collection or folder stays in view
+    fn first_shown(&self, inner: Rect) -> usize {
+        let selected = self.state.selected_tree_row().unwrap_or(0);
+        selected.saturating_sub((inner.height as usize).saturating_sub(1))
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        let inner = Block::bordered().inner(area);
+        let first = self.first_shown(inner);
+        let rows = self.state.collection_tree().len();
+        for row in 0..rows.saturating_sub(first).min(inner.height as usize) {
+            hits.push(Rect { y: inner.y + row as u16, height: 1, ..inner }, Target::Collection(first + row));
+        }
+    }
 }
 
 impl<'a> Widget for CollectionList<'a> {
@@ -26,29 +42,45 @@ impl<'a> Widget for CollectionList<'a> {
             Theme::unfocused_border()
         };
         
-        let title = "Collections";
+        let title = match self.state.moving_folder {
+            Some(_) => "Collections (moving: select a place, m: drop, Esc: cancel)",
+            None => "Collections",
+        };
         
         let block = Block::default()
             .title(title)
             .borders(Borders::ALL)
             .border_style(border_style)
             .border_type(BorderType::Rounded);
+        let first = self.first_shown(block.inner(area));
+        let selected_row = self.state.selected_tree_row();
         
         let items: Vec<ListItem> = self
             .state
-            .collections
-            .iter()
+            .collection_tree()
+            .into_iter()
             .enumerate()
-            .map(|(idx, collection)| {
-                let is_selected = Some(idx) == self.state.selected_collection;
+            .skip(first)
+            .map(|(idx, row)| {
+                let is_selected = Some(idx) == selected_row;
                 let is_editing = is_selected && self.state.editing_collection;
+                let is_moving = row.folder.is_some() && row.folder == self.state.moving_folder;
                 
                 let style = if is_selected {
                     Theme::selected()
+                } else if is_moving {
+                    Theme::dim()
                 } else {
                     Theme::default()
                 };
                 
+                let marker = match (row.has_children, row.expanded) {
+                    (false, _) => "  ",
+                    (true, false) => "▸ ",
+                    (true, true) => "▾ ",
+                };
+                let indent = Span::raw(format!("{}{}", "  ".repeat(row.depth), marker));
+                
                 let text = if is_editing {
                     let display_text = &self.state.collection_name_input;
                     let cursor_pos = self.state.collection_name_cursor;
@@ -57,13 +89,21 @@ impl<'a> Widget for CollectionList<'a> {
                     let after = display_text.chars().skip(cursor_pos + 1).collect::<String>();
                     
                     Line::from(vec![
+                        indent,
                         Span::raw(before),
                         Span::styled(cursor_char.to_string(), Theme::selected()),
                         Span::raw(after),
                     ])
                 } else {
+                    let name = match row.folder {
+                        Some(folder_id) => self.state.folders.iter()
+                            .find(|f| f.id == folder_id)
+                            .map_or("", |f| f.name.as_str()),
+                        None => self.state.collections[row.collection].name.as_str(),
+                    };
                     Line::from(vec![
-                        Span::styled(&collection.name, style),
+                        indent,
+                        Span::styled(name, style),
                     ])
                 };
                 
@@ -76,4 +116,3 @@ impl<'a> Widget for CollectionList<'a> {
         Widget::render(list, area, buf);
     }
 }
-
@@ -0,0 +1,136 @@
+use crate::app::command_palette::PaletteEntry;
+use crate::app::state::AppState;
+use crate::ui::hit_map::{HitMap, Target};
+use crate::ui::theme::Theme;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+/// Width of the column naming what an entry is, `Collection` plus a space
+const KIND_WIDTH: usize = 11;
+
+pub struct CommandPalettePopup<'a> {
+    state: &'a AppState,
+    entries: Vec<PaletteEntry>,
+}
+
+impl<'a> CommandPalettePopup<'a> {
+    pub fn new(state: &'a AppState) -> Self {
+        let entries = state.command_palette.entries(state);
+        Self { state, entries }
+    }
+
+    /// Query input, result list and help line inside the borders
+    fn layout(inner: Rect) -> [Rect; 3] {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Fill(1), Constraint::Length(1)])
+            .split(inner);
+        [chunks[0], chunks[1], chunks[2]]
+    }
+
+    /// First entry shown, so the highlighted one stays in view
+    fn first_shown(&self, list: Rect) -> usize {
+        let selected = self.state.command_palette.selected.min(self.entries.len().saturating_sub(1));
+        selected.saturating_sub((list.height as usize).saturating_sub(1))
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        let [_, list, _] = Self::layout(Block::bordered().inner(area));
+        let first = self.first_shown(list);
+        for row in 0..self.entries.len().saturating_sub(first).min(list.height as usize) {
+            hits.push(Rect { y: list.y + row as u16, height: 1, ..list }, Target::PopupRow(first + row));
+        }
+    }
+
+    fn render_input(&self, area: Rect, buf: &mut Buffer) {
+        let palette = &self.state.command_palette;
+        let block = Block::default()
+            .title(format!("Search actions, collections and requests ({})", self.entries.len()))
+            .borders(Borders::ALL)
+            .border_style(Theme::selected())
+            .border_type(BorderType::Rounded);
+
+        // Keep the cursor in view, counting in characters
+        let cursor = palette.query[..palette.cursor.min(palette.query.len())].chars().count();
+        let available_width = area.width.saturating_sub(4) as usize;
+        let scroll_offset = cursor.saturating_sub(available_width.saturating_sub(1));
+        let visible: Vec<char> = palette.query.chars().skip(scroll_offset).take(available_width).collect();
+        let visible_cursor = cursor - scroll_offset;
+
+        let before: String = visible.iter().take(visible_cursor).collect();
+        let cursor_char = visible.get(visible_cursor).copied().unwrap_or(' ');
+        let after: String = visible.iter().skip(visible_cursor + 1).collect();
+        let line = Line::from(vec![
+            Span::styled("> ", Theme::accent()),
+            Span::raw(before),
+            Span::styled(cursor_char.to_string(), Theme::selected()),
+            Span::raw(after),
+        ]);
+        Paragraph::new(line).block(block).render(area, buf);
+    }
+
+    fn render_entry(entry: &PaletteEntry, selected: bool, area: Rect, buf: &mut Buffer) {
+        let base = if selected { Theme::selected() } else { Style::default() };
+        buf.set_style(area, base);
+
+        // The keys go on the right, the label and detail get what is left
+        let keys = entry.keys.as_deref().unwrap_or_default();
+        let keys_width = keys.chars().count() as u16;
+        if keys_width > 0 && keys_width + 1 < area.width {
+            buf.set_string(area.right() - keys_width - 1, area.y, keys, base.patch(Theme::accent()));
+        }
+        let text_width = if keys_width == 0 { area.width } else { area.width.saturating_sub(keys_width + 2) };
+
+        let mut spans = vec![
+            Span::styled(if selected { "▶ " } else { "  " }, base),
+            Span::styled(format!("{:<width$}", entry.kind, width = KIND_WIDTH), base.patch(Theme::dim())),
+        ];
+        let matched = base.patch(Theme::emphasis());
+        for (idx, c) in entry.label.chars().enumerate() {
+            let style = if entry.matched.contains(&idx) { matched } else { base };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+        if !entry.detail.is_empty() {
+            spans.push(Span::styled(format!("  {}", entry.detail), base.patch(Theme::dim())));
+        }
+        Line::from(spans).render(Rect { width: text_width, ..area }, buf);
+    }
+}
+
+impl<'a> Widget for CommandPalettePopup<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title("Command Palette")
+            .borders(Borders::ALL)
+            .border_style(Theme::focused_border())
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [input, list, help] = Self::layout(inner);
+        self.render_input(input, buf);
+
+        if self.entries.is_empty() {
+            Paragraph::new(Line::styled("  No matches", Theme::dim())).render(list, buf);
+        }
+        let first = self.first_shown(list);
+        let selected = self.state.command_palette.selected;
+        for (row, (idx, entry)) in self.entries.iter().enumerate().skip(first).take(list.height as usize).enumerate() {
+            let row_area = Rect { y: list.y + row as u16, height: 1, ..list };
+            Self::render_entry(entry, idx == selected, row_area, buf);
+        }
+
+        Paragraph::new(Line::styled(
+            " Type to search | ↑/↓: move | Enter: run / go to | Esc: close",
+            Theme::dim(),
+        ))
+        .render(help, buf);
+    }
+}
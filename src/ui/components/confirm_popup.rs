@@ -0,0 +1,67 @@
+use crate::app::state::Confirmation;
+use crate::ui::components::buttons::ButtonRow;
+use crate::ui::hit_map::HitMap;
+use crate::ui::theme::Theme;
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+/// Yes / no question before a bulk delete or a purge
+pub struct ConfirmPopup<'a> {
+    confirmation: &'a Confirmation,
+}
+
+impl<'a> ConfirmPopup<'a> {
+    pub fn new(confirmation: &'a Confirmation) -> Self {
+        Self { confirmation }
+    }
+
+    /// A box around the message, centered in `area`
+    pub fn area(&self, area: Rect) -> Rect {
+        let width = (self.confirmation.message.chars().count() as u16 + 6).clamp(40, 72).min(area.width);
+        let height = 7.min(area.height);
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    fn buttons() -> ButtonRow {
+        ButtonRow::new(vec![("Yes", KeyCode::Char('y')), ("No", KeyCode::Char('n'))])
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        Self::buttons().hit_targets(ButtonRow::bottom_of(area), hits);
+    }
+}
+
+impl<'a> Widget for ConfirmPopup<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title("Confirm")
+            .borders(Borders::ALL)
+            .border_style(Theme::focused_border())
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        Paragraph::new(vec![
+            Line::from(""),
+            Line::from(self.confirmation.message.clone()),
+            Line::styled("y/Enter: yes | n/Esc: no", Theme::dim()),
+        ])
+        .wrap(Wrap { trim: true })
+        .centered()
+        .render(inner, buf);
+
+        Self::buttons().render(ButtonRow::bottom_of(area), buf);
+    }
+}
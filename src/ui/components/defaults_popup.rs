@@ -0,0 +1,63 @@
+use crate::app::state::AppState;
+use crate::ui::components::text_editor::TextEditorView;
+use crate::ui::theme::Theme;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+/// Edits the auth, headers, base URL and variables a collection or folder hands down, as TOML
+pub struct DefaultsPopup<'a> {
+    state: &'a AppState,
+}
+
+impl<'a> DefaultsPopup<'a> {
+    pub fn new(state: &'a AppState) -> Self {
+        Self { state }
+    }
+}
+
+impl<'a> Widget for DefaultsPopup<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(format!("Defaults for {}", self.state.defaults_target_label()))
+            .borders(Borders::ALL)
+            .border_style(Theme::focused_border())
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let error_height = if self.state.defaults_error.is_some() { 2 } else { 0 };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Length(error_height), Constraint::Length(2)])
+            .split(inner);
+
+        let editor_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Theme::selected())
+            .border_type(BorderType::Rounded);
+        TextEditorView::new(&self.state.defaults_editor, editor_block)
+            .focused(true)
+            .render(chunks[0], buf);
+
+        if let Some(error) = &self.state.defaults_error {
+            Paragraph::new(Line::styled(error.as_str(), Theme::error()))
+                .wrap(Wrap { trim: true })
+                .render(chunks[1], buf);
+        }
+
+        Paragraph::new(vec![
+            Line::styled(
+                " Requests with auth \"Inherit from parent\" use [auth]; {{name}} takes a variable",
+                Theme::dim(),
+            ),
+            Line::styled(" Ctrl+S: save | Ctrl+Z / Ctrl+Y: undo / redo | Esc: cancel", Theme::dim()),
+        ])
+        .render(chunks[2], buf);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::app::state::{AppState, DiffStage};
+use crate::ui::theme::Theme;
+use crate::utils::diff::ReportLineKind;
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+    style::Style,
+};
+
+pub struct DiffPopup<'a> {
+    state: &'a AppState,
+}
+
+impl<'a> DiffPopup<'a> {
+    pub fn new(state: &'a AppState) -> Self {
+        Self { state }
+    }
+
+    fn render_selection(&self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let state = self.state;
+        let block = Block::default()
+            .title("Compare Responses")
+            .borders(Borders::ALL)
+            .border_style(Theme::focused_border())
+            .border_type(BorderType::Rounded);
+
+        let mut lines = vec![
+            Line::from(format!("  A: {}", state.diff_side_label(state.diff_a))),
+            Line::from(format!("  B: {}", state.diff_side_label(state.diff_b))),
+            Line::from(""),
+        ];
+
+        let pinned_id = state.pinned_response.as_ref().map(|pinned| pinned.response.id);
+        for (idx, entry) in state.diff_candidates().into_iter().enumerate() {
+            let id = Some(entry.response.id);
+            let side = match (id == state.diff_a, id == state.diff_b) {
+                (true, true) => "AB",
+                (true, false) => "A ",
+                (false, true) => " B",
+                (false, false) => "  ",
+            };
+            let pinned = if id == pinned_id { " [pinned]" } else { "" };
+            let text = format!(
+                " {} {}  {:<28} {:<24} {:>6}ms {:>8} bytes{}",
+                side,
+                entry.response.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S"),
+                entry.request_name,
+                entry.response.status_text,
+                entry.response.duration_ms,
+                entry.response.size_bytes,
+                pinned
+            );
+            let style = if idx == state.diff_cursor { Theme::selected() } else { Style::default() };
+            lines.push(Line::styled(text, style));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::styled(
+            "  ↑/↓: move | a / b: use as A / B | s: swap | Enter: compare | Esc: close",
+            Theme::dim(),
+        ));
+
+        // Keep the cursor visible, it is below the three summary lines
+        let visible = area.height.saturating_sub(2) as usize;
+        let scroll = (state.diff_cursor + 4).saturating_sub(visible);
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .render(area, buf);
+    }
+
+    fn render_diff(&self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let state = self.state;
+        let block = Block::default()
+            .title(format!(
+                "A: {}  →  B: {} [↑/↓ scroll, s: swap, Esc: back]",
+                state.diff_side_label(state.diff_a),
+                state.diff_side_label(state.diff_b)
+            ))
+            .borders(Borders::ALL)
+            .border_style(Theme::focused_border())
+            .border_type(BorderType::Rounded);
+
+        let lines: Vec<Line> = state
+            .diff_report
+            .iter()
+            .map(|line| {
+                let style = match line.kind {
+                    ReportLineKind::Heading => Theme::heading(),
+                    ReportLineKind::Same => Style::default(),
+                    ReportLineKind::Added => Theme::success(),
+                    ReportLineKind::Removed => Theme::error(),
+                    ReportLineKind::Changed => Theme::warning(),
+                    ReportLineKind::Note => Theme::dim(),
+                };
+                Line::from(Span::styled(line.text.clone(), style))
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((state.diff_scroll, 0))
+            .render(area, buf);
+    }
+}
+
+impl<'a> Widget for DiffPopup<'a> {
+    fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        Clear.render(area, buf);
+
+        match self.state.diff_stage {
+            DiffStage::SelectingResponses => self.render_selection(area, buf),
+            DiffStage::ShowingDiff => self.render_diff(area, buf),
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::app::state::{AppState, ExportMode, ExportMenuStage};
+use crate::ui::components::buttons::ButtonRow;
+use crate::ui::hit_map::{HitMap, Target};
 use crate::ui::theme::Theme;
+use crossterm::event::KeyCode;
 use ratatui::{
     layout::Rect,
     text::Line,
@@ -15,6 +18,55 @@ impl<'a> ExportPopup<'a> {
     pub fn new(state: &'a AppState) -> Self {
         Self { state }
     }
+
+    fn buttons(&self) -> ButtonRow {
+        match self.state.export_menu_stage {
+            ExportMenuStage::ShowingResult => {
+                ButtonRow::new(vec![("Copy filename", KeyCode::Char('c')), ("Close", KeyCode::Enter)])
+            }
+            ExportMenuStage::SelectingCollection if self.state.collections.is_empty() => {
+                ButtonRow::new(vec![("Close", KeyCode::Esc)])
+            }
+            ExportMenuStage::SelectingCollection => {
+                ButtonRow::new(vec![("Next", KeyCode::Enter), ("Cancel", KeyCode::Esc)])
+            }
+            ExportMenuStage::SelectingRequest if self.state.export_request_indices().is_empty() => {
+                ButtonRow::new(vec![("Back", KeyCode::Esc)])
+            }
+            ExportMenuStage::SelectingRequest => {
+                ButtonRow::new(vec![("Export", KeyCode::Enter), ("Back", KeyCode::Esc)])
+            }
+        }
+    }
+
+    /// Rows of the collection or request list, above the buttons
+    fn list_area(area: Rect) -> Rect {
+        let inner = Block::bordered().inner(area);
+        Rect { height: inner.height.saturating_sub(1), ..inner }
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        let rows = match self.state.export_menu_stage {
+            ExportMenuStage::ShowingResult => 0,
+            ExportMenuStage::SelectingCollection => self.state.collections.len(),
+            ExportMenuStage::SelectingRequest => self.state.export_request_indices().len(),
+        };
+        let list = Self::list_area(area);
+        for row in 0..rows.min(list.height as usize) {
+            hits.push(Rect { y: list.y + row as u16, height: 1, ..list }, Target::PopupRow(row));
+        }
+        self.buttons().hit_targets(ButtonRow::bottom_of(area), hits);
+    }
+
+    fn render_list(items: Vec<ListItem>, title: &str, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Theme::focused_border())
+            .border_type(BorderType::Rounded)
+            .render(area, buf);
+        Widget::render(List::new(items), Self::list_area(area), buf);
+    }
 }
 
 impl<'a> Widget for ExportPopup<'a> {
@@ -32,6 +84,7 @@ impl<'a> Widget for ExportPopup<'a> {
                 self.render_request_selection(area, buf);
             }
         }
+        self.buttons().render(ButtonRow::bottom_of(area), buf);
     }
 }
 
@@ -79,7 +132,7 @@ impl<'a> ExportPopup<'a> {
                 
                 let request_count = self.state.requests
                     .iter()
-                    .filter(|r| r.collection_id == Some(collection.id))
+                    .filter(|r| Some(r.protocol()) == self.state.export_protocol() && r.collection_id() == Some(collection.id))
                     .count();
                 
                 let text = format!("{} ({} requests)", collection.name, request_count);
@@ -103,14 +156,7 @@ impl<'a> ExportPopup<'a> {
             ]).block(block);
             Widget::render(paragraph, area, buf);
         } else {
-            let list = List::new(items).block(
-                Block::default()
-                    .title(format!("{} - Use ↑↓ to select, Enter to continue, Esc to cancel", title))
-                    .borders(Borders::ALL)
-                    .border_style(Theme::focused_border())
-                    .border_type(BorderType::Rounded)
-            );
-            Widget::render(list, area, buf);
+            Self::render_list(items, &format!("{} - Use ↑↓ to select, Enter to continue, Esc to cancel", title), area, buf);
         }
     }
     
@@ -134,14 +180,13 @@ impl<'a> ExportPopup<'a> {
             .border_type(BorderType::Rounded);
 
         if let Some(collection_idx) = self.state.export_selected_collection {
-            if let Some(collection) = self.state.collections.get(collection_idx) {
+            if self.state.collections.get(collection_idx).is_some() {
                 match self.state.export_mode {
                     Some(ExportMode::RequestCurl) => {
                         // Handle HTTP requests
-                        let requests_in_collection: Vec<_> = self.state.requests
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, r)| r.collection_id == Some(collection.id))
+                        let requests_in_collection: Vec<_> = self.state.export_request_indices()
+                            .into_iter()
+                            .filter_map(|idx| Some((idx, self.state.requests.get(idx)?.as_http()?)))
                             .collect();
 
                         if requests_in_collection.is_empty() {
@@ -169,22 +214,14 @@ impl<'a> ExportPopup<'a> {
                                 })
                                 .collect();
 
-                            let list = List::new(items).block(
-                                Block::default()
-                                    .title(nav_title)
-                                    .borders(Borders::ALL)
-                                    .border_style(Theme::focused_border())
-                                    .border_type(BorderType::Rounded)
-                            );
-                            Widget::render(list, area, buf);
+                            Self::render_list(items, nav_title, area, buf);
                         }
                     }
                     Some(ExportMode::GrpcRequestGrpcurl) => {
                         // Handle gRPC requests
-                        let requests_in_collection: Vec<_> = self.state.grpc_requests
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, r)| r.collection_id == Some(collection.id))
+                        let requests_in_collection: Vec<_> = self.state.export_request_indices()
+                            .into_iter()
+                            .filter_map(|idx| Some((idx, self.state.requests.get(idx)?.as_grpc()?)))
                             .collect();
 
                         if requests_in_collection.is_empty() {
@@ -212,14 +249,7 @@ impl<'a> ExportPopup<'a> {
                                 })
                                 .collect();
 
-                            let list = List::new(items).block(
-                                Block::default()
-                                    .title(nav_title)
-                                    .borders(Borders::ALL)
-                                    .border_style(Theme::focused_border())
-                                    .border_type(BorderType::Rounded)
-                            );
-                            Widget::render(list, area, buf);
+                            Self::render_list(items, nav_title, area, buf);
                         }
                     }
                     _ => {
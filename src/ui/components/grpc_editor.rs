@@ -1,4 +1,9 @@
+use crate::app::actions::Action;
+use crate::app::keymap::KeyScope;
 use crate::app::state::{AppState, InputMode, Panel, GrpcEditorField, KeyValueEditMode};
+use crate::ui::components::request_editor::inherited_header_lines;
+use crate::ui::components::text_editor::TextEditorView;
+use crate::ui::hit_map::{HitMap, Target};
 use crate::ui::theme::Theme;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -6,6 +11,7 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Widget, Wrap},
     style::Style,
 };
+use std::rc::Rc;
 
 pub struct GrpcEditor<'a> {
     state: &'a AppState,
@@ -28,16 +34,32 @@ impl<'a> Widget for GrpcEditor<'a> {
             Theme::unfocused_border()
         };
 
+        let keymap = &self.state.keymap;
         let proto_indicator = if let Some(schema) = self.state.get_selected_proto_schema() {
             format!(" | Proto: {}", schema.name)
         } else {
-            " | No proto loaded (Press 'l')".to_string()
+            match keymap.first_key(KeyScope::Global, Action::OpenProtoLoader) {
+                Some(key) => format!(" | No proto loaded ({}: load)", key),
+                None => " | No proto loaded".to_string(),
+            }
         };
 
         let title = if is_editing {
-            format!("gRPC Request Editor [EDITING - ESC to save, Tab/Shift+Tab to switch fields]{}", proto_indicator)
+            format!(
+                "gRPC Request Editor (EDITING){}{}",
+                keymap.title_hints(KeyScope::Editing, &[
+                    (Action::FinishEditing, "save"),
+                    (Action::NextField, "next field"),
+                    (Action::PrevField, "previous field"),
+                ]),
+                proto_indicator
+            )
         } else {
-            format!("gRPC Request Editor [Press 'e' to edit]{}", proto_indicator)
+            format!(
+                "gRPC Request Editor{}{}",
+                keymap.title_hints(KeyScope::Editor, &[(Action::EditRequest, "edit")]),
+                proto_indicator
+            )
         };
 
         let block = Block::default()
@@ -49,17 +71,7 @@ impl<'a> Widget for GrpcEditor<'a> {
         let inner_area = block.inner(area);
         block.render(area, buf);
 
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Name
-                Constraint::Length(3), // Server URL
-                Constraint::Length(3), // Service Name
-                Constraint::Length(3), // Method Name
-                Constraint::Length(10), // Message (JSON)
-                Constraint::Min(0),     // Metadata
-            ])
-            .split(inner_area);
+        let chunks = Self::layout(inner_area);
 
         if let Some(request) = self.state.get_current_grpc_request() {
             self.render_name_field(chunks[0], buf, request, is_editing);
@@ -77,6 +89,39 @@ impl<'a> Widget for GrpcEditor<'a> {
 }
 
 impl<'a> GrpcEditor<'a> {
+    /// The fields from top to bottom, as laid out by `layout`
+    const FIELDS: [GrpcEditorField; 6] = [
+        GrpcEditorField::Name,
+        GrpcEditorField::ServerUrl,
+        GrpcEditorField::ServiceName,
+        GrpcEditorField::MethodName,
+        GrpcEditorField::Message,
+        GrpcEditorField::Metadata,
+    ];
+
+    fn layout(inner_area: Rect) -> Rc<[Rect]> {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Name
+                Constraint::Length(3), // Server URL
+                Constraint::Length(3), // Service Name
+                Constraint::Length(3), // Method Name
+                Constraint::Length(10), // Message (JSON)
+                Constraint::Min(0),     // Metadata
+            ])
+            .split(inner_area)
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        if self.state.get_current_grpc_request().is_none() {
+            return;
+        }
+        for (chunk, field) in Self::layout(Block::bordered().inner(area)).iter().zip(Self::FIELDS) {
+            hits.push(*chunk, Target::GrpcField(field));
+        }
+    }
+
     fn render_text_field(
         &self,
         area: Rect,
@@ -221,23 +266,29 @@ impl<'a> GrpcEditor<'a> {
         let is_focused = is_editing && self.state.grpc_editor_focused_field == GrpcEditorField::Message;
 
         let block = Block::default()
-            .title("Message (JSON)")
+            .title(if is_focused {
+                format!("Message (JSON){}", self.state.keymap.title_hints(KeyScope::Editing, &[
+                    (Action::UndoEdit, "undo"),
+                    (Action::RedoEdit, "redo"),
+                    (Action::FormatBody, "format"),
+                    (Action::OpenExternalEditor, "$EDITOR"),
+                ]))
+            } else {
+                "Message (JSON)".to_string()
+            })
             .borders(Borders::ALL)
             .border_style(if is_focused { Theme::selected() } else { Theme::unfocused_border() })
             .border_type(BorderType::Rounded);
 
-        let mut display_text = if is_editing {
-            self.state.grpc_message_input.clone()
-        } else {
-            request.message_json.clone()
-        };
-
-        if is_editing && is_focused {
-            let cursor_pos = self.state.grpc_message_cursor.min(display_text.len());
-            display_text.insert(cursor_pos, '▌');
+        if is_editing {
+            TextEditorView::new(&self.state.grpc_message_editor, block)
+                .focused(is_focused)
+                .json(true)
+                .render(area, buf);
+            return;
         }
 
-        Paragraph::new(display_text)
+        Paragraph::new(request.message_json.clone())
             .wrap(Wrap { trim: false })
             .block(block)
             .render(area, buf);
@@ -294,27 +345,27 @@ impl<'a> GrpcEditor<'a> {
                 })
                 .collect();
 
-            if items.is_empty() {
+            let inherited = inherited_header_lines(self.state, self.state.grpc_metadata_input.iter().map(|(key, _)| key));
+            if items.is_empty() && inherited.is_empty() {
                 Paragraph::new("No metadata (press + to add)")
                     .block(block)
                     .render(area, buf);
             } else {
-                List::new(items)
+                List::new(items.into_iter().chain(inherited.into_iter().map(ListItem::new)))
                     .block(block)
                     .render(area, buf);
             }
         } else {
-            let content = if request.metadata.is_empty() {
-                "No metadata".to_string()
-            } else {
-                request.metadata
-                    .iter()
-                    .map(|(k, v)| format!("{}: {}", k, v))
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            };
-
-            Paragraph::new(content).block(block).render(area, buf);
+            let mut lines: Vec<Line> = request.metadata
+                .iter()
+                .map(|(k, v)| Line::from(format!("{}: {}", k, v)))
+                .collect();
+            lines.extend(inherited_header_lines(self.state, request.metadata.keys()));
+            if lines.is_empty() {
+                lines.push(Line::from("No metadata"));
+            }
+
+            Paragraph::new(lines).block(block).render(area, buf);
         }
     }
 }
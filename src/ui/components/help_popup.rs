@@ -1,71 +1,93 @@
-use crate::ui::theme::Theme;
+use crate::app::actions::Action;
+use crate::app::keymap::{KeyScope, Keymap};
+use crate::ui::theme::{Palette, Theme};
 use ratatui::{
     layout::Rect,
     text::Line,
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
 };
 
-pub struct HelpPopup;
+/// Lists the bindings of the active keymap, followed by the keys that cannot be rebound
+pub struct HelpPopup<'a> {
+    keymap: &'a Keymap,
+    scroll: u16,
+}
+
+impl<'a> HelpPopup<'a> {
+    pub fn new(keymap: &'a Keymap) -> Self {
+        Self { keymap, scroll: 0 }
+    }
+
+    pub fn scroll(mut self, scroll: u16) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![String::new()];
+        for scope in KeyScope::ALL {
+            let entries = self.keymap.help_entries(scope);
+            if entries.is_empty() {
+                continue;
+            }
+            lines.push(format!("  {}:", scope.title()));
+            for (keys, description) in entries {
+                lines.push(format!("    {:<18} - {}", keys, description));
+            }
+            lines.push(String::new());
+        }
 
-impl HelpPopup {
-    pub fn new() -> Self {
-        Self
+        lines.extend(
+            [
+                "  Editing fields (in edit mode):",
+                "    Name/URL/Body/Auth: Type to edit, arrows to move cursor",
+                "    Method: ←→ or ↑↓ to cycle through methods",
+                "    Params/Headers: ↑↓ to navigate, + to add, - to delete,",
+                "          Enter to edit, Tab between key and value, Esc to stop",
+                "    Auth: ↑↓ to pick a field, ←→/Space to cycle type or option",
+                "    Body: Shift+arrows select, Ctrl+A select all, Ctrl+C/X/V clipboard,",
+                "          Ctrl+U clear",
+                "",
+                "  JSON tree view (in response):",
+                "    ←→ / Space        - Fold / unfold",
+                "    1-9 / 0           - Show N levels / everything",
+                "    c / C             - Copy value / path under cursor",
+                "",
+                "  Mouse:",
+                "    Click             - Focus a panel, select, switch tabs, edit a field",
+                "    Wheel             - Move through lists, scroll the response",
+                "",
+            ]
+            .map(String::from),
+        );
+
+        if let Some(path) = Keymap::config_path() {
+            lines.push(format!("  Keys can be changed in {}", path.display()));
+        }
+        if let Some(path) = Palette::config_path() {
+            lines.push(format!("  Colors can be changed in {}", path.display()));
+        }
+        let close = self.keymap.keys_label(KeyScope::Global, Action::ToggleHelp);
+        lines.push(format!("  ↑↓ to scroll, {} to close", close.map_or("Esc".to_string(), |keys| keys + " / Esc")));
+        lines.push(String::new());
+        lines
     }
 }
 
-impl Widget for HelpPopup {
+impl Widget for HelpPopup<'_> {
     fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
         Clear.render(area, buf);
-        
+
         let block = Block::default()
             .title("Help")
             .borders(Borders::ALL)
             .border_style(Theme::focused_border())
             .border_type(BorderType::Rounded);
-        
-        let help_text = vec![
-            "",
-            "  Navigation:",
-            "    Tab / Shift+Tab   - Switch between panels",
-            "    j / Down          - Move down",
-            "    k / Up            - Move up",
-            "    t                 - Next tab (in editor)",
-            "",
-            "  Actions:",
-            "    Enter             - Send request",
-            "    e                 - Enter edit mode (in editor)",
-            "    Esc               - Save & exit edit mode",
-            "    Tab               - Switch fields (in edit mode)",
-            "    n                 - New HTTP request",
-            "    g                 - New gRPC request",
-            "    p                 - Toggle protocol (HTTP/gRPC)",
-            "    d                 - Delete request",
-            "    y                 - Duplicate request",
-            "    c                 - New collection (in collections)",
-            "    x                 - Delete collection (in collections)",
-            "    o                 - Export request (curl/grpcurl based on protocol)",
-            "",
-            "  Editing (when in edit mode):",
-            "    Name/URL/Body/Auth: Type to edit, arrows to move cursor",
-            "    Method: ←→ or ↑↓ to cycle through methods",
-            "    Params/Headers: ↑↓ to navigate, + to add, - to delete",
-            "",
-            "  Other:",
-            "    ?                 - Toggle this help",
-            "    q / Ctrl+C        - Quit",
-            "",
-            "  Press ? to close",
-            "",
-        ];
-        
-        let lines: Vec<Line> = help_text
-            .into_iter()
-            .map(|s| Line::from(s))
-            .collect();
-        
-        let paragraph = Paragraph::new(lines).block(block);
-        
+
+        let lines: Vec<Line> = self.lines().into_iter().map(Line::from).collect();
+
+        let paragraph = Paragraph::new(lines).block(block).scroll((self.scroll, 0));
+
         Widget::render(paragraph, area, buf);
     }
 }
-
@@ -1,5 +1,8 @@
 use crate::app::state::AppState;
+use crate::ui::components::buttons::ButtonRow;
+use crate::ui::hit_map::HitMap;
 use crate::ui::theme::Theme;
+use crossterm::event::KeyCode;
 use ratatui::{
     layout::Rect,
     text::Line,
@@ -14,6 +17,18 @@ impl<'a> ImportPopup<'a> {
     pub fn new(state: &'a AppState) -> Self {
         Self { state }
     }
+
+    fn buttons(&self) -> ButtonRow {
+        if self.state.import_result_message.is_some() {
+            ButtonRow::new(vec![("Close", KeyCode::Enter)])
+        } else {
+            ButtonRow::new(vec![("Import", KeyCode::Enter), ("Cancel", KeyCode::Esc)])
+        }
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        self.buttons().hit_targets(ButtonRow::bottom_of(area), hits);
+    }
 }
 
 impl<'a> Widget for ImportPopup<'a> {
@@ -25,6 +40,7 @@ impl<'a> Widget for ImportPopup<'a> {
         } else {
             self.render_file_input(area, buf);
         }
+        self.buttons().render(ButtonRow::bottom_of(area), buf);
     }
 }
 
@@ -4,9 +4,18 @@ pub mod request_editor;
 pub mod grpc_editor;
 pub mod response_viewer;
 pub mod statusbar;
+pub mod tab_bar;
 pub mod help_popup;
 pub mod welcome_popup;
 pub mod export_popup;
 pub mod import_popup;
 pub mod proto_loader_popup;
+pub mod proxy_settings_popup;
+pub mod defaults_popup;
 
+pub mod diff_popup;
+pub mod text_editor;
+pub mod buttons;
+pub mod command_palette;
+pub mod trash_popup;
+pub mod confirm_popup;
@@ -1,11 +1,15 @@
 use crate::app::state::AppState;
+use crate::ui::components::buttons::ButtonRow;
+use crate::ui::hit_map::{HitMap, Target};
 use crate::ui::theme::Theme;
+use crossterm::event::KeyCode;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
-    style::{Color, Style},
+    style::Style,
 };
+use std::rc::Rc;
 
 pub struct ProtoLoaderPopup<'a> {
     state: &'a AppState,
@@ -15,6 +19,42 @@ impl<'a> ProtoLoaderPopup<'a> {
     pub fn new(state: &'a AppState) -> Self {
         Self { state }
     }
+
+    fn layout(inner_area: Rect) -> Rc<[Rect]> {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(6),  // Instructions
+                Constraint::Length(3),  // File path input
+                Constraint::Length(2),  // Status message (if any)
+                Constraint::Min(0),     // Loaded schemas
+                Constraint::Length(1),  // Buttons
+            ])
+            .split(inner_area)
+    }
+
+    fn has_error(&self) -> bool {
+        self.state.proto_load_result.as_ref().is_some_and(|result| result.contains("Error") || result.contains("Failed"))
+    }
+
+    fn buttons(&self) -> ButtonRow {
+        if self.has_error() {
+            ButtonRow::new(vec![("Try again", KeyCode::Enter), ("Close", KeyCode::Esc)])
+        } else if self.state.proto_schemas.is_empty() {
+            ButtonRow::new(vec![("Load", KeyCode::Enter), ("Close", KeyCode::Esc)])
+        } else {
+            ButtonRow::new(vec![("Load", KeyCode::Enter), ("Delete", KeyCode::Delete), ("Close", KeyCode::Esc)])
+        }
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        let chunks = Self::layout(Block::bordered().inner(area));
+        let list = Block::bordered().inner(chunks[3]);
+        for row in 0..self.state.proto_schemas.len().min(list.height as usize) {
+            hits.push(Rect { y: list.y + row as u16, height: 1, ..list }, Target::PopupRow(row));
+        }
+        self.buttons().hit_targets(chunks[4], hits);
+    }
 }
 
 impl<'a> Widget for ProtoLoaderPopup<'a> {
@@ -31,22 +71,14 @@ impl<'a> Widget for ProtoLoaderPopup<'a> {
         let inner_area = block.inner(area);
         block.render(area, buf);
 
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(6),  // Instructions
-                Constraint::Length(3),  // File path input
-                Constraint::Length(2),  // Status message (if any)
-                Constraint::Min(0),     // Loaded schemas
-            ])
-            .split(inner_area);
+        let chunks = Self::layout(inner_area);
 
         // Instructions
         let instructions = vec![
             Line::from(""),
-            Line::from(Span::styled("Enter the path to your proto descriptor file (.pb)", Style::default().fg(Color::Cyan))),
+            Line::from(Span::styled("Enter the path to your proto descriptor file (.pb)", Theme::accent())),
             Line::from(""),
-            Line::from(Span::styled("Generate with:", Style::default().fg(Color::Cyan))),
+            Line::from(Span::styled("Generate with:", Theme::accent())),
             Line::from("  protoc --descriptor_set_out=service.pb --include_imports service.proto"),
         ];
 
@@ -97,9 +129,9 @@ impl<'a> Widget for ProtoLoaderPopup<'a> {
         // Status message area (non-blocking)
         if let Some(result) = &self.state.proto_load_result {
             let result_style = if result.contains("Error") || result.contains("Failed") {
-                Style::default().fg(Color::Red)
+                Theme::error()
             } else {
-                Style::default().fg(Color::Green)
+                Theme::success()
             };
 
             Paragraph::new(result.clone())
@@ -135,7 +167,7 @@ impl<'a> Widget for ProtoLoaderPopup<'a> {
                                 if service_count == 1 { "" } else { "s" }
                             ),
                             if is_selected {
-                                Style::default().fg(Color::Yellow)
+                                Theme::warning()
                             } else {
                                 Style::default()
                             }
@@ -149,6 +181,8 @@ impl<'a> Widget for ProtoLoaderPopup<'a> {
                 .render(chunks[3], buf);
         }
 
+        self.buttons().render(chunks[4], buf);
+
         // Help text at bottom
         let help_text = if self.state.proto_load_result.is_some() {
             "Press Esc to close | Enter to try again"
@@ -166,7 +200,7 @@ impl<'a> Widget for ProtoLoaderPopup<'a> {
         };
 
         let help_line = Line::from(vec![
-            Span::styled(help_text, Style::default().fg(Color::DarkGray)),
+            Span::styled(help_text, Theme::dim()),
         ]);
 
         let help_area = Rect {
@@ -0,0 +1,110 @@
+use crate::app::state::{AppState, ProxyField, ProxyForm};
+use crate::ui::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+pub struct ProxySettingsPopup<'a> {
+    state: &'a AppState,
+}
+
+impl<'a> ProxySettingsPopup<'a> {
+    pub fn new(state: &'a AppState) -> Self {
+        Self { state }
+    }
+
+    fn checkbox(value: bool) -> String {
+        if value { "[x]".to_string() } else { "[ ]".to_string() }
+    }
+
+    fn text_value(form: &ProxyForm, field: ProxyField, value: &str) -> String {
+        let shown = if field == ProxyField::Password {
+            "*".repeat(value.chars().count())
+        } else {
+            value.to_string()
+        };
+        if form.focused_field != field {
+            return shown;
+        }
+        // The cursor is a byte offset into the unmasked value
+        let cursor = value[..form.cursor.min(value.len())].chars().count();
+        let before: String = shown.chars().take(cursor).collect();
+        let after: String = shown.chars().skip(cursor).collect();
+        format!("{}│{}", before, after)
+    }
+
+    fn field_line(&self, field: ProxyField) -> Line<'static> {
+        let form = &self.state.proxy_form;
+        let (label, value) = match field {
+            ProxyField::Scope => ("Scope", format!("◀ {} ▶", self.state.proxy_scope_label())),
+            ProxyField::Inherit => ("Use global settings", Self::checkbox(form.inherit)),
+            ProxyField::Enabled => ("Use proxy", Self::checkbox(form.enabled)),
+            ProxyField::Scheme => ("Type", format!("◀ {} ▶", form.scheme.as_str().to_uppercase())),
+            ProxyField::Host => ("Host", Self::text_value(form, field, &form.host)),
+            ProxyField::Port => ("Port", Self::text_value(form, field, &form.port)),
+            ProxyField::Username => ("Username", Self::text_value(form, field, &form.username)),
+            ProxyField::Password => ("Password", Self::text_value(form, field, &form.password)),
+            ProxyField::NoProxy => ("No proxy for", Self::text_value(form, field, &form.no_proxy)),
+            ProxyField::UseSystemEnv => ("Use system proxy env vars", Self::checkbox(form.use_system_env)),
+        };
+
+        let label_style = if form.focused_field == field {
+            Theme::selected()
+        } else {
+            Theme::accent()
+        };
+
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(format!("{:<26}", label), label_style),
+            Span::raw(" "),
+            Span::raw(value),
+        ])
+    }
+}
+
+impl<'a> Widget for ProxySettingsPopup<'a> {
+    fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title("Proxy Settings")
+            .borders(Borders::ALL)
+            .border_style(Theme::focused_border())
+            .border_type(BorderType::Rounded);
+
+        let form = &self.state.proxy_form;
+        let mut lines = vec![Line::from("")];
+
+        for field in ProxyField::ALL {
+            if form.is_field_visible(field) {
+                lines.push(self.field_line(field));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "  No proxy for: comma-separated hosts, e.g. localhost, .corp.example.com, 10.0.0.1",
+            Theme::dim(),
+        )));
+        lines.push(Line::from(Span::styled(
+            "  System env vars (HTTP_PROXY, HTTPS_PROXY, ALL_PROXY, NO_PROXY) apply when no proxy is set",
+            Theme::dim(),
+        )));
+        lines.push(Line::from(""));
+
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(format!("  Error: {}", error), Theme::error())));
+            lines.push(Line::from(""));
+        }
+
+        lines.push(Line::from("  Tab/↑↓: move | Space/←→: toggle | Enter: save | Esc: cancel"));
+
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        Widget::render(paragraph, area, buf);
+    }
+}
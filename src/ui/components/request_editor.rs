@@ -1,4 +1,9 @@
+use crate::app::actions::Action;
+use crate::app::keymap::KeyScope;
 use crate::app::state::{AppState, EditorTab, InputMode, Panel, EditorField, KeyValueEditMode};
+use crate::models::auth::{AuthFieldKind, AuthKind};
+use crate::ui::components::text_editor::TextEditorView;
+use crate::ui::hit_map::{tab_areas, HitMap, Target};
 use crate::ui::theme::Theme;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -28,10 +33,18 @@ impl<'a> Widget for RequestEditor<'a> {
             Theme::unfocused_border()
         };
         
+        let keymap = &self.state.keymap;
         let title = if is_editing {
-            "Request Editor [EDITING - ESC to save, Tab/Shift+Tab to switch fields]"
+            format!(
+                "Request Editor (EDITING){}",
+                keymap.title_hints(KeyScope::Editing, &[
+                    (Action::FinishEditing, "save"),
+                    (Action::NextField, "next field"),
+                    (Action::PrevField, "previous field"),
+                ])
+            )
         } else {
-            "Request Editor [Press 'e' to edit]"
+            format!("Request Editor{}", keymap.title_hints(KeyScope::Editor, &[(Action::EditRequest, "edit")]))
         };
         
         let block = Block::default()
@@ -43,47 +56,26 @@ impl<'a> Widget for RequestEditor<'a> {
         let inner_area = block.inner(area);
         block.render(area, buf);
         
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Name + Method
-                Constraint::Length(3), // URL
-                Constraint::Length(1), // Tabs
-                Constraint::Min(0),    // Content
-            ])
-            .split(inner_area);
-        
         if let Some(request) = self.state.get_current_request() {
-            // Name and Method row
-            let name_method_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-                .split(chunks[0]);
-            
-            self.render_name_field(name_method_chunks[0], buf, request, is_editing);
-            self.render_method_field(name_method_chunks[1], buf, request, is_editing);
-            
-            // URL field
-            self.render_url_field(chunks[1], buf, request, is_editing);
+            let [name, method, url, tabs, content] = Self::layout(inner_area);
+
+            self.render_name_field(name, buf, request, is_editing);
+            self.render_method_field(method, buf, request, is_editing);
+            self.render_url_field(url, buf, request, is_editing);
             
             // Tabs
-            let tabs = Tabs::new(vec!["Params", "Headers", "Body", "Auth"])
-                .select(match self.state.editor_tab {
-                    EditorTab::Params => 0,
-                    EditorTab::Headers => 1,
-                    EditorTab::Body => 2,
-                    EditorTab::Auth => 3,
-                })
+            let tabs_widget = Tabs::new(EditorTab::ALL.iter().map(|tab| tab.as_str()).collect::<Vec<_>>())
+                .select(self.state.editor_tab.index())
                 .style(Theme::default())
                 .highlight_style(Theme::selected());
-            tabs.render(chunks[2], buf);
+            tabs_widget.render(tabs, buf);
             
             // Content area
             match self.state.editor_tab {
-                EditorTab::Params => self.render_params_content(chunks[3], buf, request, is_editing),
-                EditorTab::Headers => self.render_headers_content(chunks[3], buf, request, is_editing),
-                EditorTab::Body => self.render_body_content(chunks[3], buf, request, is_editing),
-                EditorTab::Auth => self.render_auth_content(chunks[3], buf, request, is_editing),
+                EditorTab::Params => self.render_params_content(content, buf, request, is_editing),
+                EditorTab::Headers => self.render_headers_content(content, buf, request, is_editing),
+                EditorTab::Body => self.render_body_content(content, buf, request, is_editing),
+                EditorTab::Auth => self.render_auth_content(content, buf, request, is_editing),
             }
         } else {
             let no_request = Paragraph::new("No request selected")
@@ -93,7 +85,54 @@ impl<'a> Widget for RequestEditor<'a> {
     }
 }
 
+/// Headers the current request inherits and does not set itself, dimmed, with where they come from
+pub fn inherited_header_lines<'k>(state: &AppState, own: impl Iterator<Item = &'k String> + Clone) -> Vec<Line<'static>> {
+    let inherited = state.current_inherited();
+    inherited
+        .extra_headers(|name| own.clone().any(|own| own.eq_ignore_ascii_case(name)))
+        .map(|(name, (value, source))| {
+            Line::from(vec![
+                Span::styled(format!("{}: {}", name, value), Theme::dim()),
+                Span::styled(format!("  (from {})", source), Theme::dim()),
+            ])
+        })
+        .collect()
+}
+
 impl<'a> RequestEditor<'a> {
+    /// Name, method, URL, tab titles and tab content inside the editor's borders
+    fn layout(inner_area: Rect) -> [Rect; 5] {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Name + Method
+                Constraint::Length(3), // URL
+                Constraint::Length(1), // Tabs
+                Constraint::Min(0),    // Content
+            ])
+            .split(inner_area);
+        let name_method_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+            .split(chunks[0]);
+        [name_method_chunks[0], name_method_chunks[1], chunks[1], chunks[2], chunks[3]]
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        if self.state.get_current_request().is_none() {
+            return;
+        }
+        let [name, method, url, tabs, content] = Self::layout(Block::bordered().inner(area));
+        hits.push(name, Target::Field(EditorField::Name));
+        hits.push(method, Target::Field(EditorField::Method));
+        hits.push(url, Target::Field(EditorField::Url));
+        let titles: Vec<&str> = EditorTab::ALL.iter().map(|tab| tab.as_str()).collect();
+        for (title, tab) in tab_areas(tabs, &titles).into_iter().zip(EditorTab::ALL) {
+            hits.push(title, Target::EditorTab(tab));
+        }
+        hits.push(content, Target::Field(self.state.editor_tab.field()));
+    }
+
     fn render_name_field(&self, area: Rect, buf: &mut ratatui::buffer::Buffer, request: &crate::models::request::HttpRequest, is_editing: bool) {
         let is_focused = is_editing && self.state.editor_focused_field == EditorField::Name;
         
@@ -175,8 +214,13 @@ impl<'a> RequestEditor<'a> {
     fn render_url_field(&self, area: Rect, buf: &mut ratatui::buffer::Buffer, request: &crate::models::request::HttpRequest, is_editing: bool) {
         let is_focused = is_editing && self.state.editor_focused_field == EditorField::Url;
         
+        // With a base URL or variables the URL sent differs from the one typed
+        let url = if is_editing { &self.state.url_input } else { &request.url };
+        let resolved = self.state.current_inherited().resolve_url(url);
+        let title = if resolved == *url { "URL".to_string() } else { format!("URL → {}", resolved) };
+        
         let block = Block::default()
-            .title("URL")
+            .title(title)
             .borders(Borders::ALL)
             .border_style(if is_focused { Theme::selected() } else { Theme::unfocused_border() })
             .border_type(BorderType::Rounded);
@@ -340,27 +384,27 @@ impl<'a> RequestEditor<'a> {
                 })
                 .collect();
             
-            if items.is_empty() {
+            let inherited = inherited_header_lines(self.state, self.state.headers_input.iter().map(|(key, _)| key));
+            if items.is_empty() && inherited.is_empty() {
                 Paragraph::new("No headers (press + to add)")
                     .block(block)
                     .render(area, buf);
             } else {
-                List::new(items)
+                List::new(items.into_iter().chain(inherited.into_iter().map(ListItem::new)))
                     .block(block)
                     .render(area, buf);
             }
         } else {
-            let content = if request.headers.is_empty() {
-                "No headers".to_string()
-            } else {
-                request.headers
-                    .iter()
-                    .map(|(k, v)| format!("{}: {}", k, v))
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            };
-            
-            Paragraph::new(content).block(block).render(area, buf);
+            let mut lines: Vec<Line> = request.headers
+                .iter()
+                .map(|(k, v)| Line::from(format!("{}: {}", k, v)))
+                .collect();
+            lines.extend(inherited_header_lines(self.state, request.headers.keys()));
+            if lines.is_empty() {
+                lines.push(Line::from("No headers"));
+            }
+
+            Paragraph::new(lines).block(block).render(area, buf);
         }
     }
     
@@ -368,25 +412,33 @@ impl<'a> RequestEditor<'a> {
         let is_focused = is_editing && self.state.editor_focused_field == EditorField::Body;
         
         let block = Block::default()
-            .title("Body")
+            .title(if is_focused {
+                format!("Body{}", self.state.keymap.title_hints(KeyScope::Editing, &[
+                    (Action::UndoEdit, "undo"),
+                    (Action::RedoEdit, "redo"),
+                    (Action::FormatBody, "format JSON"),
+                    (Action::OpenExternalEditor, "$EDITOR"),
+                ]))
+            } else {
+                "Body".to_string()
+            })
             .borders(Borders::ALL)
             .border_style(if is_focused { Theme::selected() } else { Theme::unfocused_border() })
             .border_type(BorderType::Rounded);
         
-        let mut display_text = if is_editing {
-            self.state.body_input.clone()
-        } else {
-            request
-                .body
-                .clone()
-                .unwrap_or_else(|| "No body".to_string())
-        };
-        
-        if is_editing && is_focused {
-            let cursor_pos = self.state.body_cursor.min(display_text.len());
-            display_text.insert(cursor_pos, '▌');
+        if is_editing {
+            TextEditorView::new(&self.state.body_editor, block)
+                .focused(is_focused)
+                .json(self.state.is_body_input_json())
+                .render(area, buf);
+            return;
         }
-        
+
+        let display_text = request
+            .body
+            .clone()
+            .unwrap_or_else(|| "No body".to_string());
+
         Paragraph::new(display_text)
             .wrap(Wrap { trim: false })
             .block(block)
@@ -397,34 +449,101 @@ impl<'a> RequestEditor<'a> {
         let is_focused = is_editing && self.state.editor_focused_field == EditorField::Auth;
         
         let block = Block::default()
-            .title("Authentication (Bearer Token)")
+            .title(if is_focused { "Authentication [↑↓: field, ←→: change type/option]" } else { "Authentication" })
             .borders(Borders::ALL)
             .border_style(if is_focused { Theme::selected() } else { Theme::unfocused_border() })
             .border_type(BorderType::Rounded);
         
-        let mut display_text = if is_editing {
-            self.state.auth_input.clone()
+        let (kind, values) = if is_editing {
+            (self.state.auth_kind_input, self.state.auth_values_input.clone())
         } else {
-            match &request.auth {
-                crate::models::request::AuthType::None => "No authentication".to_string(),
-                crate::models::request::AuthType::Bearer { token } => {
-                    format!("Bearer: {}", token)
-                }
-                crate::models::request::AuthType::Basic { username, password } => {
-                    format!("Basic: {} / {}", username, password)
-                }
-                crate::models::request::AuthType::ApiKey { key, value, location } => {
-                    format!("API Key: {} = {} ({:?})", key, value, location)
-                }
+            (request.auth.kind(), request.auth.to_field_values())
+        };
+        let fields = kind.fields();
+        
+        let row_style = |row: usize| {
+            if is_focused && self.state.auth_selected == row {
+                Theme::selected()
+            } else {
+                Theme::accent()
             }
         };
         
-        if is_editing && is_focused {
-            let cursor_pos = self.state.auth_cursor.min(display_text.len());
-            display_text.insert(cursor_pos, '▌');
+        let mut lines = vec![Line::from(vec![
+            Span::styled(format!("{:<18}", "Type"), row_style(0)),
+            Span::raw(" "),
+            Span::raw(if is_focused { format!("◀ {} ▶", kind.as_str()) } else { kind.as_str().to_string() }),
+        ])];
+        
+        for (row, field_idx) in kind.visible_fields(&values).into_iter().enumerate() {
+            let field = &fields[field_idx];
+            let value = values.get(field_idx).cloned().unwrap_or_default();
+            let is_selected = is_focused && self.state.auth_selected == row + 1;
+            
+            let display = match field.kind {
+                AuthFieldKind::Choice(_) if is_selected => format!("◀ {} ▶", value),
+                AuthFieldKind::Secret if !is_selected => "•".repeat(value.chars().count().min(24)),
+                _ => value.clone(),
+            };
+            let display = if is_selected && !matches!(field.kind, AuthFieldKind::Choice(_)) {
+                let cursor = self.state.auth_cursor.min(display.len());
+                format!("{}▌{}", &display[..cursor], &display[cursor..])
+            } else {
+                display
+            };
+            
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<18}", field.label), row_style(row + 1)),
+                Span::raw(" "),
+                Span::raw(display),
+            ]));
         }
         
-        Paragraph::new(display_text)
+        let inherited = self.state.current_inherited();
+        if kind == AuthKind::Inherit {
+            lines.push(Line::from(""));
+            match &inherited.auth {
+                Some((auth, source)) => {
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("{:<18}", "From"), Theme::accent()),
+                        Span::raw(" "),
+                        Span::styled(format!("{} ({})", source, auth.kind().as_str()), Theme::dim()),
+                    ]));
+                    let auth_kind = auth.kind();
+                    let auth_values = auth.to_field_values();
+                    for field_idx in auth_kind.visible_fields(&auth_values) {
+                        let field = &auth_kind.fields()[field_idx];
+                        let value = auth_values.get(field_idx).cloned().unwrap_or_default();
+                        let display = match field.kind {
+                            AuthFieldKind::Secret => "•".repeat(value.chars().count().min(24)),
+                            _ => value,
+                        };
+                        lines.push(Line::from(vec![
+                            Span::styled(format!("{:<18}", field.label), Theme::dim()),
+                            Span::raw(" "),
+                            Span::styled(display, Theme::dim()),
+                        ]));
+                    }
+                }
+                None => lines.push(Line::styled("No auth set on the collection or its folders", Theme::dim())),
+            }
+        }
+        
+        if let crate::models::request::AuthType::OAuth2(config) = &inherited.resolve_auth(&request.auth) {
+            let status = match self.state.oauth2_tokens.get(&request.id) {
+                Some(token) if token.matches(config) => token.status_at(chrono::Utc::now()),
+                _ => "none, fetched on next send".to_string(),
+            };
+            let status_style = if status.starts_with("valid") { Theme::success() } else { Theme::warning() };
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<18}", "Token"), Theme::accent()),
+                Span::raw(" "),
+                Span::styled(status, status_style),
+            ]));
+        }
+        
+        Paragraph::new(lines)
             .wrap(Wrap { trim: false })
             .block(block)
             .render(area, buf);
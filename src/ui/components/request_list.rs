@@ -1,27 +1,13 @@
-use crate::app::state::{AppState, Panel, ProtocolType};
+use crate::app::state::{AppState, Panel};
+use crate::ui::hit_map::{HitMap, Target};
 use crate::ui::theme::Theme;
-use crate::models::request::{HttpMethod, HttpRequest};
-use crate::models::GrpcRequest;
+use crate::models::protocol::RequestType;
+use crate::models::request::HttpMethod;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Widget},
 };
-use uuid::Uuid;
-
-enum RequestItem<'a> {
-    Http(&'a HttpRequest),
-    Grpc(&'a GrpcRequest),
-}
-
-impl<'a> RequestItem<'a> {
-    fn id(&self) -> Uuid {
-        match self {
-            RequestItem::Http(req) => req.id,
-            RequestItem::Grpc(req) => req.id,
-        }
-    }
-}
 
 pub struct RequestList<'a> {
     state: &'a mut AppState,
@@ -32,6 +18,27 @@ impl<'a> RequestList<'a> {
         Self { state }
     }
 
+    /// Rows the list has room for, less when the search input takes the bottom
+    fn visible_height(state: &AppState, area: Rect) -> usize {
+        if state.request_search_mode {
+            area.height.saturating_sub(5) as usize // Reserve 3 lines for search input
+        } else {
+            area.height.saturating_sub(2) as usize
+        }
+    }
+
+    /// Make each shown request clickable, call after rendering as that scrolls the list
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        let inner = Block::bordered().inner(area);
+        let rows = self.state.visible_request_indices()
+            .into_iter()
+            .skip(self.state.request_list_scroll as usize)
+            .take(Self::visible_height(self.state, area));
+        for (row, idx) in rows.enumerate() {
+            hits.push(Rect { y: inner.y + row as u16, height: 1, ..inner }, Target::Request(idx));
+        }
+    }
+
     fn method_style(method: &HttpMethod) -> ratatui::style::Style {
         match method {
             HttpMethod::GET => Theme::method_get(),
@@ -69,61 +76,22 @@ impl<'a> Widget for RequestList<'a> {
             ""
         };
 
-        let protocol_indicator = match self.state.protocol_type {
-            ProtocolType::Http => format!("HTTP Requests{}{}", position_indicator, search_indicator),
-            ProtocolType::Grpc => format!("gRPC Requests{}{}", position_indicator, search_indicator),
+        // Search covers the whole collection, otherwise the list shows one folder
+        let folder_indicator = match self.state.selected_folder_path() {
+            Some(path) if !self.state.request_search_mode => format!(" in {}", path),
+            _ => String::new(),
         };
 
+        let title = format!("Requests{}{}{}", folder_indicator, position_indicator, search_indicator);
+
         let block = Block::default()
-            .title(protocol_indicator.clone())
+            .title(title.clone())
             .borders(Borders::ALL)
             .border_style(border_style)
             .border_type(BorderType::Rounded);
 
-        let selected_collection_id = self.state.selected_collection
-            .and_then(|idx| self.state.collections.get(idx))
-            .map(|c| c.id);
-
-        // Calculate visible height (area minus borders and search input if active)
-        let visible_height = if self.state.request_search_mode {
-            area.height.saturating_sub(5) as usize // Reserve 3 lines for search input
-        } else {
-            area.height.saturating_sub(2) as usize
-        };
-
-        // First pass: collect indices and IDs of visible requests
-        let visible_request_indices: Vec<usize> = if self.state.request_search_mode && !self.state.request_search_input.is_empty() {
-            // Use filtered indices when searching
-            self.state.filtered_request_indices.clone()
-        } else {
-            // Use all requests matching collection filter
-            match self.state.protocol_type {
-                ProtocolType::Http => {
-                    self.state.requests.iter().enumerate()
-                        .filter(|(_, request)| {
-                            match (selected_collection_id, request.collection_id) {
-                                (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                                (None, None) => true,
-                                _ => false,
-                            }
-                        })
-                        .map(|(idx, _)| idx)
-                        .collect()
-                }
-                ProtocolType::Grpc => {
-                    self.state.grpc_requests.iter().enumerate()
-                        .filter(|(_, request)| {
-                            match (selected_collection_id, request.collection_id) {
-                                (Some(selected_id), Some(request_id)) => selected_id == request_id,
-                                (None, None) => true,
-                                _ => false,
-                            }
-                        })
-                        .map(|(idx, _)| idx)
-                        .collect()
-                }
-            }
-        };
+        let visible_height = Self::visible_height(self.state, area);
+        let visible_request_indices = self.state.visible_request_indices();
 
         // Find current position in the visible list by checking against selected_request
         let current_position = if let Some(selected_idx) = self.state.selected_request {
@@ -145,80 +113,33 @@ impl<'a> Widget for RequestList<'a> {
             }
         }
 
-        // Now build the all_requests vector using the indices
-        let all_requests: Vec<RequestItem> = match self.state.protocol_type {
-            ProtocolType::Http => {
-                visible_request_indices.iter()
-                    .filter_map(|&idx| self.state.requests.get(idx))
-                    .map(|r| RequestItem::Http(r))
-                    .collect()
-            }
-            ProtocolType::Grpc => {
-                visible_request_indices.iter()
-                    .filter_map(|&idx| self.state.grpc_requests.get(idx))
-                    .map(|r| RequestItem::Grpc(r))
-                    .collect()
-            }
-        };
-
-        // Determine selected request ID
-        let selected_request_id = match self.state.protocol_type {
-            ProtocolType::Http => {
-                self.state.selected_request
-                    .and_then(|idx| self.state.requests.get(idx))
-                    .map(|r| r.id)
-            }
-            ProtocolType::Grpc => {
-                self.state.selected_request
-                    .and_then(|idx| self.state.grpc_requests.get(idx))
-                    .map(|r| r.id)
-            }
-        };
+        let selected_request_id = self.state.selected_request
+            .and_then(|idx| self.state.requests.get(idx))
+            .map(RequestType::id);
 
-        // Generate all list items
-        let all_items: Vec<ListItem> = all_requests
+        // Generate all list items, HTTP and gRPC mixed in list order
+        let all_items: Vec<ListItem> = visible_request_indices
             .iter()
-            .map(|request_item| {
-                let is_selected = Some(request_item.id()) == selected_request_id;
-
-                match request_item {
-                    RequestItem::Http(request) => {
-                        let method_str = format!("{:7}", request.method.as_str());
-
-                        let line = if is_selected {
-                            Line::from(vec![
-                                Span::raw("[H] "),
-                                Span::styled(method_str.clone(), Self::method_style(&request.method)),
-                                Span::raw(" "),
-                                Span::styled(request.name.clone(), Theme::selected()),
-                            ])
-                        } else {
-                            Line::from(vec![
-                                Span::raw("[H] "),
-                                Span::styled(method_str, Self::method_style(&request.method)),
-                                Span::raw(" "),
-                                Span::raw(request.name.clone()),
-                            ])
-                        };
-
-                        ListItem::new(line)
-                    }
-                    RequestItem::Grpc(request) => {
-                        let line = if is_selected {
-                            Line::from(vec![
-                                Span::styled("[G] ", Theme::method_post()), // Use a color for gRPC
-                                Span::styled(request.name.clone(), Theme::selected()),
-                            ])
-                        } else {
-                            Line::from(vec![
-                                Span::styled("[G] ", Theme::method_post()),
-                                Span::raw(request.name.clone()),
-                            ])
-                        };
-
-                        ListItem::new(line)
-                    }
-                }
+            .filter_map(|&idx| self.state.requests.get(idx))
+            .map(|request| {
+                let is_selected = Some(request.id()) == selected_request_id;
+                // gRPC rows fill the method column so the names line up
+                let (badge, method) = match request {
+                    RequestType::Http(request) => (
+                        Span::raw("[H] "),
+                        Span::styled(format!("{:7}", request.method.as_str()), Self::method_style(&request.method)),
+                    ),
+                    RequestType::Grpc(_) => (
+                        Span::styled("[G] ", Theme::method_post()),
+                        Span::styled(format!("{:7}", "RPC"), Theme::method_other()),
+                    ),
+                };
+                let name = if is_selected {
+                    Span::styled(request.name().to_string(), Theme::selected())
+                } else {
+                    Span::raw(request.name().to_string())
+                };
+                ListItem::new(Line::from(vec![badge, method, Span::raw(" "), name]))
             })
             .collect();
 
@@ -243,7 +164,7 @@ impl<'a> Widget for RequestList<'a> {
 
             // Render list in top chunk (without bottom border)
             let list_block = Block::default()
-                .title(protocol_indicator)
+                .title(title)
                 .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
                 .border_style(border_style)
                 .border_type(BorderType::Rounded);
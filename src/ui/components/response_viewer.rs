@@ -1,11 +1,21 @@
-use crate::app::state::{AppState, Panel, ProtocolType};
+use crate::app::actions::Action;
+use crate::app::keymap::KeyScope;
+use crate::app::state::{AppState, Panel, ProtocolType, ResponseTab};
+use crate::models::cookie::parse_set_cookie;
+use crate::models::json_tree::{JsonRow, JsonRowKind, JsonTree};
+use crate::models::response::HttpResponse;
+use crate::utils::binary::sniff;
+use crate::utils::body_format::BodyFormat;
+use crate::utils::json_filter::FilterSyntax;
+use crate::ui::hit_map::{tab_areas, HitMap, Target};
 use crate::ui::theme::Theme;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Paragraph, Widget, Wrap},
-    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, Paragraph, Tabs, Widget, Wrap},
+    style::Style,
 };
+use std::rc::Rc;
 
 pub struct ResponseViewer<'a> {
     state: &'a AppState,
@@ -62,8 +72,8 @@ impl<'a> ResponseViewer<'a> {
                             }
                         }
                         
-                        let color = if is_key { Color::White } else { Color::Gray };
-                        spans.push(Span::styled(string_content, Style::default().fg(color)));
+                        let style = if is_key { Theme::json_key() } else { Theme::json_string() };
+                        spans.push(Span::styled(string_content, style));
                     }
                     't' | 'f' => {
                         if !current.is_empty() {
@@ -82,7 +92,7 @@ impl<'a> ResponseViewer<'a> {
                         }
                         
                         if word == "true" || word == "false" {
-                            spans.push(Span::styled(word, Style::default().fg(Color::Blue)));
+                            spans.push(Span::styled(word, Theme::json_boolean()));
                         } else {
                             spans.push(Span::raw(word));
                         }
@@ -104,7 +114,7 @@ impl<'a> ResponseViewer<'a> {
                         }
                         
                         if word == "null" {
-                            spans.push(Span::styled(word, Style::default().fg(Color::DarkGray)));
+                            spans.push(Span::styled(word, Theme::json_null()));
                         } else {
                             spans.push(Span::raw(word));
                         }
@@ -129,7 +139,7 @@ impl<'a> ResponseViewer<'a> {
                             spans.push(Span::raw(current.clone()));
                             current.clear();
                         }
-                        spans.push(Span::styled(number, Style::default().fg(Color::Cyan)));
+                        spans.push(Span::styled(number, Theme::json_number()));
                     }
                     _ => {
                         current.push(ch);
@@ -150,6 +160,646 @@ impl<'a> ResponseViewer<'a> {
         
         lines
     }
+
+    /// Tags in the accent color, attribute names like JSON keys and values like JSON strings, comments dimmed
+    fn colorize_markup(text: &str) -> Vec<Line<'static>> {
+        let comment = Theme::dim();
+        let mut lines = Vec::new();
+        let mut in_comment = false;
+        // Closing tag of the script or style element being shown as is
+        let mut raw_until: Option<String> = None;
+
+        for line in text.lines() {
+            let mut spans = Vec::new();
+            let mut rest = line;
+
+            while !rest.is_empty() {
+                if let Some(close) = &raw_until {
+                    let end = rest.to_ascii_lowercase().find(close.as_str()).unwrap_or(rest.len());
+                    if end < rest.len() {
+                        raw_until = None;
+                    }
+                    spans.push(Span::raw(rest[..end].to_string()));
+                    rest = &rest[end..];
+                } else if in_comment || rest.starts_with("<!--") {
+                    let end = rest.find("-->").map_or(rest.len(), |idx| idx + 3);
+                    in_comment = !rest[..end].ends_with("-->");
+                    spans.push(Span::styled(rest[..end].to_string(), comment));
+                    rest = &rest[end..];
+                } else if rest.starts_with('<') {
+                    let end = Self::markup_tag_end(rest);
+                    let tag = rest[..end].to_ascii_lowercase();
+                    if ["<script", "<style"].iter().any(|open| tag.starts_with(open) && !tag.ends_with("/>")) {
+                        raw_until = Some(format!("</{}", &tag[1..].split(|c: char| c.is_whitespace() || c == '>').next().unwrap_or_default()));
+                    }
+                    spans.extend(Self::markup_tag_spans(&rest[..end]));
+                    rest = &rest[end..];
+                } else {
+                    let end = rest.find('<').unwrap_or(rest.len());
+                    spans.push(Span::raw(rest[..end].to_string()));
+                    rest = &rest[end..];
+                }
+            }
+
+            lines.push(Line::from(spans));
+        }
+
+        lines
+    }
+
+    /// End of the tag starting the text, the end of the line when it continues on the next one
+    fn markup_tag_end(text: &str) -> usize {
+        let mut quote = None;
+        for (idx, ch) in text.char_indices() {
+            match (quote, ch) {
+                (None, '"' | '\'') => quote = Some(ch),
+                (Some(q), _) if ch == q => quote = None,
+                (None, '>') => return idx + 1,
+                _ => {}
+            }
+        }
+        text.len()
+    }
+
+    fn markup_tag_spans(tag: &str) -> Vec<Span<'static>> {
+        let bracket = Theme::accent();
+        let mut spans = Vec::new();
+
+        let name_end = tag
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| c.is_whitespace() || *c == '>' || (*c == '/' && !tag[1..].starts_with('/')))
+            .map_or(tag.len(), |(idx, _)| idx);
+        spans.push(Span::styled(tag[..name_end].to_string(), bracket));
+
+        let mut chars = tag[name_end..].char_indices().peekable();
+        let base = name_end;
+        while let Some((idx, ch)) = chars.next() {
+            let start = base + idx;
+            match ch {
+                '"' | '\'' => {
+                    let end = tag[start + 1..].find(ch).map_or(tag.len(), |pos| start + 1 + pos + 1);
+                    spans.push(Span::styled(tag[start..end].to_string(), Theme::json_string()));
+                    while chars.peek().is_some_and(|(idx, _)| base + idx < end) {
+                        chars.next();
+                    }
+                }
+                '/' | '?' | '>' => spans.push(Span::styled(ch.to_string(), bracket)),
+                '=' => spans.push(Span::raw("=")),
+                _ if ch.is_whitespace() => spans.push(Span::raw(ch.to_string())),
+                _ => {
+                    let end = tag[start..]
+                        .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/' | '?'))
+                        .map_or(tag.len(), |pos| start + pos);
+                    // Unquoted attribute values are colored like quoted ones
+                    let style = if tag[..start].ends_with('=') { Theme::json_string() } else { Theme::json_key() };
+                    spans.push(Span::styled(tag[start..end].to_string(), style));
+                    while chars.peek().is_some_and(|(idx, _)| base + idx < end) {
+                        chars.next();
+                    }
+                }
+            }
+        }
+
+        spans
+    }
+
+    /// Keys like JSON keys, scalars like JSON values, comments dimmed
+    fn colorize_yaml(text: &str) -> Vec<Line<'static>> {
+        let comment = Theme::dim();
+        let mut lines = Vec::new();
+
+        for line in text.lines() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('#') || line == "---" || line == "..." {
+                lines.push(Line::styled(line.to_string(), comment));
+                continue;
+            }
+
+            // Indentation and sequence dashes
+            let content_start = line.len() - trimmed.trim_start_matches(['-', ' ']).len();
+            let mut spans = vec![Span::raw(line[..content_start].to_string())];
+            let mut rest = &line[content_start..];
+
+            let (value_part, trailing_comment) = match Self::yaml_comment_start(rest) {
+                Some(idx) => (&rest[..idx], Some(&rest[idx..])),
+                None => (rest, None),
+            };
+            rest = value_part;
+
+            if let Some(colon) = Self::yaml_key_end(rest) {
+                spans.push(Span::styled(rest[..colon].to_string(), Theme::json_key()));
+                spans.push(Span::raw(":"));
+                rest = &rest[colon + 1..];
+            }
+
+            let value = rest.trim_start();
+            if rest.len() > value.len() {
+                spans.push(Span::raw(rest[..rest.len() - value.len()].to_string()));
+            }
+            if !value.is_empty() {
+                spans.push(Self::yaml_scalar_span(value));
+            }
+            if let Some(trailing_comment) = trailing_comment {
+                spans.push(Span::styled(trailing_comment.to_string(), comment));
+            }
+
+            lines.push(Line::from(spans));
+        }
+
+        lines
+    }
+
+    /// Offset of a ` #` comment outside of quotes
+    fn yaml_comment_start(text: &str) -> Option<usize> {
+        let mut quote = None;
+        let mut previous = ' ';
+        for (idx, ch) in text.char_indices() {
+            match (quote, ch) {
+                (None, '"' | '\'') => quote = Some(ch),
+                (Some(q), _) if ch == q => quote = None,
+                (None, '#') if previous.is_whitespace() && idx > 0 => return Some(idx),
+                _ => {}
+            }
+            previous = ch;
+        }
+        None
+    }
+
+    /// Offset of the `:` ending a mapping key, outside of quotes
+    fn yaml_key_end(text: &str) -> Option<usize> {
+        let mut quote = None;
+        let mut chars = text.char_indices().peekable();
+        while let Some((idx, ch)) = chars.next() {
+            match (quote, ch) {
+                (None, '"' | '\'') => quote = Some(ch),
+                (Some(q), _) if ch == q => quote = None,
+                (None, ':') if chars.peek().is_none_or(|(_, next)| *next == ' ') => return Some(idx),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn yaml_scalar_span(value: &str) -> Span<'static> {
+        let trimmed = value.trim_end();
+        let style = match trimmed {
+            "true" | "false" | "yes" | "no" | "on" | "off" | "True" | "False" => Theme::json_boolean(),
+            "null" | "~" | "Null" | "NULL" => Theme::json_null(),
+            "|" | ">" | "|-" | ">-" | "{}" | "[]" => Theme::default(),
+            _ if trimmed.parse::<f64>().is_ok() => Theme::json_number(),
+            _ => Theme::json_string(),
+        };
+        Span::styled(value.to_string(), style)
+    }
+
+    /// Header row and numbers like JSON numbers, column rules dimmed
+    fn colorize_csv(text: &str) -> Vec<Line<'static>> {
+        let rule = Theme::dim();
+        text.lines()
+            .enumerate()
+            .map(|(row, line)| {
+                if line.starts_with('─') {
+                    return Line::styled(line.to_string(), rule);
+                }
+                let mut spans = Vec::new();
+                for (col, cell) in line.split('│').enumerate() {
+                    if col > 0 {
+                        spans.push(Span::styled("│", rule));
+                    }
+                    let is_number = !cell.trim().is_empty() && cell.trim().parse::<f64>().is_ok();
+                    let style = if row == 0 || is_number {
+                        Theme::json_number()
+                    } else {
+                        Style::default()
+                    };
+                    spans.push(Span::styled(cell.to_string(), style));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// `key = value` lines with the key like a JSON key and the value like a string
+    fn colorize_form(text: &str) -> Vec<Line<'static>> {
+        text.lines()
+            .map(|line| match line.split_once(" = ") {
+                Some((key, value)) => Line::from(vec![
+                    Span::styled(key.to_string(), Theme::json_key()),
+                    Span::styled(" = ", Theme::dim()),
+                    Span::styled(value.to_string(), Theme::json_string()),
+                ]),
+                None => Line::styled(line.to_string(), Theme::json_key()),
+            })
+            .collect()
+    }
+
+    fn scalar_span(value: &serde_json::Value) -> Span<'static> {
+        let style = match value {
+            serde_json::Value::String(_) => Theme::json_string(),
+            serde_json::Value::Bool(_) => Theme::json_boolean(),
+            serde_json::Value::Number(_) => Theme::json_number(),
+            _ => Theme::json_null(),
+        };
+        Span::styled(value.to_string(), style)
+    }
+
+    fn tree_row_line(row: &JsonRow) -> Line<'static> {
+        let mut spans = vec![Span::raw("  ".repeat(row.depth))];
+
+        if row.kind != JsonRowKind::Close {
+            if let Some(key) = row.key {
+                spans.push(Span::styled(
+                    format!("{}: ", serde_json::Value::String(key.to_string())),
+                    Theme::json_key(),
+                ));
+            }
+        }
+
+        match row.kind {
+            JsonRowKind::Open { children: 0, .. } => {
+                spans.push(Span::raw(format!("{}{}", row.open_bracket(), row.close_bracket())));
+            }
+            JsonRowKind::Open { children, collapsed: true } => {
+                let noun = match (row.value.is_array(), children) {
+                    (true, 1) => "item",
+                    (true, _) => "items",
+                    (false, 1) => "key",
+                    (false, _) => "keys",
+                };
+                spans.push(Span::raw(format!("{} … {}", row.open_bracket(), row.close_bracket())));
+                spans.push(Span::styled(
+                    format!(" {} {}", children, noun),
+                    Theme::dim(),
+                ));
+            }
+            JsonRowKind::Open { .. } => spans.push(Span::raw(row.open_bracket())),
+            JsonRowKind::Close => spans.push(Span::raw(row.close_bracket())),
+            JsonRowKind::Scalar => spans.push(Self::scalar_span(row.value)),
+        }
+
+        let expanded = matches!(row.kind, JsonRowKind::Open { children, collapsed: false } if children > 0);
+        if row.comma && !expanded {
+            spans.push(Span::raw(","));
+        }
+
+        Line::from(spans)
+    }
+
+    /// Render the visible slice of the tree, keeping the cursor row on screen
+    fn render_tree(&self, tree: &JsonTree, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let height = area.height as usize;
+        let top = tree.cursor
+            .saturating_sub(height / 2)
+            .min(tree.row_count().saturating_sub(height));
+        let is_focused = self.state.focused_panel == Panel::Response;
+
+        // Only the rows on screen are resolved
+        let lines: Vec<Line> = (top..top + height)
+            .filter_map(|idx| tree.row(idx).map(|row| (idx, row)))
+            .map(|(idx, row)| {
+                let line = Self::tree_row_line(&row);
+                if idx == tree.cursor && is_focused {
+                    line.style(Theme::highlight())
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn filter_line(&self) -> Line<'static> {
+        let state = self.state;
+        let input = &state.response_filter_input;
+        let syntax = FilterSyntax::detect(input);
+
+        let mut spans = vec![Span::styled(
+            format!("Filter ({}): ", syntax.as_str()),
+            Theme::accent(),
+        )];
+        if state.response_filter_mode {
+            let cursor = state.response_filter_cursor.min(input.len());
+            spans.push(Span::raw(format!("{}│{}", &input[..cursor], &input[cursor..])));
+        } else {
+            spans.push(Span::raw(input.clone()));
+        }
+
+        match &state.response_filter_result {
+            _ if state.is_response_filter_pending() => spans.push(Span::styled(
+                "  filtering…",
+                Theme::dim(),
+            )),
+            Some(Ok(values)) => spans.push(Span::styled(
+                format!("  {} result{}", values.len(), if values.len() == 1 { "" } else { "s" }),
+                Theme::dim(),
+            )),
+            Some(Err(error)) => spans.push(Span::styled(
+                format!("  {}", error),
+                Theme::error(),
+            )),
+            None => {}
+        }
+
+        if state.response_filter_mode {
+            spans.push(Span::styled(
+                "  (Enter: keep, Esc: clear, ↑/↓: recent)",
+                Theme::dim(),
+            ));
+        }
+
+        Line::from(spans)
+    }
+
+    fn search_line(&self) -> Line<'static> {
+        let state = self.state;
+        let input = &state.response_search_input;
+
+        let mut spans = vec![Span::styled("Search: ", Theme::accent())];
+        if state.response_search_mode {
+            let cursor = state.response_search_cursor.min(input.len());
+            spans.push(Span::raw(format!("{}│{}", &input[..cursor], &input[cursor..])));
+        } else {
+            spans.push(Span::raw(input.clone()));
+        }
+
+        if let Some(error) = &state.response_search_error {
+            spans.push(Span::styled(format!("  {}", error), Theme::error()));
+        } else if !input.is_empty() {
+            let count = state.response_search_matches.len();
+            let position = if count == 0 {
+                "no matches".to_string()
+            } else {
+                format!("{}/{}", state.response_search_current + 1, count)
+            };
+            spans.push(Span::styled(format!("  {}", position), Theme::dim()));
+        }
+
+        let toggle_style = |on: bool| {
+            if on { Theme::selected() } else { Theme::dim() }
+        };
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled("Aa", toggle_style(state.response_search_case_sensitive)));
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(".*", toggle_style(state.response_search_regex)));
+
+        if state.response_search_mode {
+            spans.push(Span::styled(
+                "  (Enter: keep, Esc: clear, ↑/↓: prev/next, Alt+C: case, Alt+R: regex)",
+                Theme::dim(),
+            ));
+        }
+
+        Line::from(spans)
+    }
+
+    /// Give the matched byte ranges of a line a highlight background, keeping the syntax colors
+    fn highlight_matches(line: Line<'static>, ranges: &[(usize, usize, bool)]) -> Line<'static> {
+        let mut spans = Vec::new();
+        let mut offset = 0;
+
+        for span in line.spans {
+            let content = span.content.as_ref();
+            let span_end = offset + content.len();
+            let mut pos = offset;
+
+            for &(start, end, current) in ranges {
+                let (start, end) = (start.max(pos), end.min(span_end));
+                if start >= end {
+                    continue;
+                }
+                if start > pos {
+                    spans.push(Span::styled(content[pos - offset..start - offset].to_string(), span.style));
+                }
+                spans.push(Span::styled(
+                    content[start - offset..end - offset].to_string(),
+                    span.style.patch(Theme::search_match(current)),
+                ));
+                pos = end;
+            }
+
+            if pos < span_end {
+                spans.push(Span::styled(content[pos - offset..].to_string(), span.style));
+            }
+            offset = span_end;
+        }
+
+        Line::from(spans)
+    }
+
+    fn render_body(&self, format: BodyFormat, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let state = self.state;
+
+        let mut header = Vec::new();
+        if state.response_filter_mode || !state.response_filter_input.is_empty() {
+            header.push(self.filter_line());
+        }
+        if state.response_search_mode || !state.response_search_input.is_empty() {
+            header.push(self.search_line());
+        }
+        let area = if header.is_empty() {
+            area
+        } else {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(header.len() as u16), Constraint::Min(0)])
+                .split(area);
+            Paragraph::new(header).render(chunks[0], buf);
+            chunks[1]
+        };
+
+        if let Some(tree) = state.json_tree.as_ref().filter(|_| state.response_tree_mode) {
+            self.render_tree(tree, area, buf);
+            return;
+        }
+
+        let text = state.response_display_text().unwrap_or_default();
+        // Filter output is always JSON
+        let format = if matches!(state.response_filter_result, Some(Ok(_))) { BodyFormat::Json } else { format };
+        // Hex dumps can be large: only the lines on screen are built, and they are never
+        // wrapped so the columns stay aligned
+        let first_line = if format == BodyFormat::Hex { state.response_scroll as usize } else { 0 };
+        let mut body_content = match format {
+            BodyFormat::Json => self.colorize_json(text),
+            BodyFormat::Xml | BodyFormat::Html => Self::colorize_markup(text),
+            BodyFormat::Yaml => Self::colorize_yaml(text),
+            BodyFormat::Csv => Self::colorize_csv(text),
+            BodyFormat::Form => Self::colorize_form(text),
+            BodyFormat::Text => text.lines().map(|line| Line::from(line.to_string())).collect(),
+            BodyFormat::Hex => text
+                .lines()
+                .skip(first_line)
+                .take(area.height as usize)
+                .map(Self::colorize_hex_line)
+                .collect(),
+        };
+
+        let mut ranges_by_line: std::collections::BTreeMap<usize, Vec<(usize, usize, bool)>> = Default::default();
+        for (idx, m) in state.response_search_matches.iter().enumerate() {
+            ranges_by_line
+                .entry(m.line)
+                .or_default()
+                .push((m.start, m.end, idx == state.response_search_current));
+        }
+        for (line_idx, ranges) in ranges_by_line.range(first_line..) {
+            if let Some(line) = body_content.get_mut(line_idx - first_line) {
+                *line = Self::highlight_matches(std::mem::take(line), ranges);
+            }
+        }
+
+        let body_paragraph = if format == BodyFormat::Hex {
+            Paragraph::new(body_content)
+        } else {
+            Paragraph::new(body_content)
+                .wrap(Wrap { trim: false })
+                .scroll((state.response_scroll, 0))
+        };
+        body_paragraph.render(area, buf);
+    }
+
+    /// Offset dimmed, hex bytes plain and the ASCII column like JSON strings
+    fn colorize_hex_line(line: &str) -> Line<'static> {
+        let dim = Theme::dim();
+        match line.find(" |") {
+            Some(ascii) if line.len() > 8 => Line::from(vec![
+                Span::styled(line[..8].to_string(), dim),
+                Span::raw(line[8..ascii].to_string()),
+                Span::styled(line[ascii..].to_string(), Theme::json_string()),
+            ]),
+            _ => Line::styled(line.to_string(), dim),
+        }
+    }
+
+    fn no_response_text(&self, kind: &str) -> String {
+        match self.state.keymap.first_key(KeyScope::Global, Action::SendRequest) {
+            Some(key) => format!("No response yet\n\nPress {} to send {}", key, kind),
+            None => "No response yet".to_string(),
+        }
+    }
+
+    /// e.g. " | JSON", " | view as XML" or " | Hex, PNG image"
+    fn format_label(response: &HttpResponse, state: &AppState) -> String {
+        let format = state.response_body_format();
+        let mut label = if state.is_response_view_as_set() {
+            format!(" | view as {}", format.as_str())
+        } else {
+            format!(" | {}", format.as_str())
+        };
+        if format == BodyFormat::Hex {
+            if let Some(kind) = sniff(&response.body) {
+                label.push_str(&format!(", {}", kind.name));
+            }
+        }
+        label
+    }
+
+    fn header_line(name: &str, value: &str) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(format!("{}: ", name), Theme::accent()),
+            Span::raw(value.to_string()),
+        ])
+    }
+
+    fn headers_lines(response: &HttpResponse) -> Vec<Line<'static>> {
+        if response.raw_headers.is_empty() {
+            return vec![Line::styled("No headers", Theme::dim())];
+        }
+        response.raw_headers
+            .iter()
+            .map(|(name, value)| Self::header_line(name, value))
+            .collect()
+    }
+
+    /// One block per `Set-Cookie` header: the cookie, then its attributes indented
+    fn cookies_lines(response: &HttpResponse) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        for header in response.set_cookie_headers() {
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            let Some(cookie) = parse_set_cookie(header) else {
+                lines.push(Line::styled(format!("{} (unparsed)", header), Theme::error()));
+                continue;
+            };
+            lines.push(Line::from(vec![
+                Span::styled(cookie.name.clone(), Theme::accent()),
+                Span::raw(" = "),
+                Span::raw(cookie.value.clone()),
+            ]));
+            lines.extend(
+                cookie.display_lines()
+                    .into_iter()
+                    .skip(1)
+                    .map(|attribute| Line::styled(attribute, Theme::dim())),
+            );
+        }
+        if lines.is_empty() {
+            lines.push(Line::styled("No cookies set", Theme::dim()));
+        }
+        lines
+    }
+
+    fn request_lines(response: &HttpResponse) -> Vec<Line<'static>> {
+        let Some(request) = &response.sent_request else {
+            return vec![Line::styled("Request was not sent", Theme::dim())];
+        };
+        let mut lines = vec![Line::from(vec![
+            Span::styled(format!("{} ", request.method), Theme::method(&request.method)),
+            Span::raw(request.url.clone()),
+        ])];
+        lines.extend(request.headers.iter().map(|(name, value)| Self::header_line(name, value)));
+        if let Some(body) = &request.body {
+            lines.push(Line::from(""));
+            lines.extend(body.lines().map(|line| Line::from(line.to_string())));
+        }
+        lines
+    }
+
+    /// Render the active tab of an HTTP response below the tab bar
+    fn render_http_tab(&self, response: &HttpResponse, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        let state = self.state;
+        let lines = match state.response_tab {
+            ResponseTab::Body => {
+                self.render_body(state.response_body_format(), area, buf);
+                return;
+            }
+            ResponseTab::Headers => Self::headers_lines(response),
+            ResponseTab::Cookies => Self::cookies_lines(response),
+            ResponseTab::Raw => response.raw_text().lines().map(|line| Line::from(line.to_string())).collect(),
+            ResponseTab::Request => Self::request_lines(response),
+        };
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((state.response_scroll, 0))
+            .render(area, buf);
+    }
+}
+
+impl<'a> ResponseViewer<'a> {
+    /// Status line, tab titles and tab content of an HTTP response inside the borders
+    fn http_layout(inner_area: Rect) -> Rc<[Rect]> {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner_area)
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        if self.state.protocol_type() != ProtocolType::Http || self.state.current_response.is_none() {
+            return;
+        }
+        let chunks = Self::http_layout(Block::bordered().inner(area));
+        let titles: Vec<&str> = ResponseTab::ALL.iter().map(|tab| tab.as_str()).collect();
+        for (title, tab) in tab_areas(chunks[1], &titles).into_iter().zip(ResponseTab::ALL) {
+            hits.push(title, Target::ResponseTab(tab));
+        }
+    }
 }
 
 impl<'a> Widget for ResponseViewer<'a> {
@@ -162,8 +812,20 @@ impl<'a> Widget for ResponseViewer<'a> {
             Theme::unfocused_border()
         };
 
-        let title = if is_focused {
-            format!("Response [↑/↓ scroll, c: copy | line {}]", self.state.response_scroll + 1)
+        let view_as_hint = if self.state.protocol_type() == ProtocolType::Http { ", V: view as, S: save" } else { "" };
+        let title = if is_focused && self.state.is_json_tree_shown() {
+            let tree = self.state.json_tree.as_ref();
+            format!(
+                "Response [tree | ←/→ fold, 1-9 depth, 0 all, c/C: copy value/path, f: filter, v: text | row {}/{}]",
+                tree.map_or(0, |t| t.cursor) + 1,
+                tree.map_or(0, |t| t.row_count())
+            )
+        } else if is_focused && !self.state.is_response_body_shown() {
+            format!("Response [↑/↓ scroll, t: next tab, c: copy | line {}]", self.state.response_scroll + 1)
+        } else if is_focused && self.state.json_tree.is_some() {
+            format!("Response [↑/↓ scroll, /: search, c: copy, f: filter, v: tree{} | line {}]", view_as_hint, self.state.response_scroll + 1)
+        } else if is_focused {
+            format!("Response [↑/↓ scroll, /: search, c: copy{} | line {}]", view_as_hint, self.state.response_scroll + 1)
         } else {
             "Response".to_string()
         };
@@ -174,49 +836,46 @@ impl<'a> Widget for ResponseViewer<'a> {
             .border_style(border_style)
             .border_type(BorderType::Rounded);
 
-        match self.state.protocol_type {
+        match self.state.protocol_type() {
             ProtocolType::Http => {
                 if let Some(response) = &self.state.current_response {
                     let inner_area = block.inner(area);
                     block.render(area, buf);
 
-                    let chunks = Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([
-                            Constraint::Length(1),
-                            Constraint::Min(0),
-                        ])
-                        .split(inner_area);
+                    let chunks = Self::http_layout(inner_area);
 
-                    let status_line = Line::from(vec![
-                        Span::styled(
-                            format!("{} ", response.status_code),
-                            ratatui::style::Style::default().fg(response.status_color()),
-                        ),
-                        Span::raw(&response.status_text),
-                        Span::raw(format!(" | {}ms | {} bytes",
-                            response.duration_ms,
-                            response.size_bytes
-                        )),
-                    ]);
+                    let status_line = if response.is_cancelled() {
+                        Line::from(vec![
+                            Span::styled("Cancelled", Theme::warning()),
+                            Span::raw(format!(" | {}ms", response.duration_ms)),
+                        ])
+                    } else {
+                        Line::from(vec![
+                            Span::styled(
+                                format!("{} ", response.status_code),
+                                Theme::status_code(response.status_code),
+                            ),
+                            Span::raw(&response.status_text),
+                            Span::raw(format!(" | {}ms | {} bytes",
+                                response.duration_ms,
+                                response.size_bytes
+                            )),
+                            Span::styled(Self::format_label(response, self.state), Theme::dim()),
+                        ])
+                    };
 
                     let status_paragraph = Paragraph::new(status_line);
                     status_paragraph.render(chunks[0], buf);
 
-                    let body = response.formatted_body();
-
-                    let body_content = if response.is_json() {
-                        self.colorize_json(&body)
-                    } else {
-                        vec![Line::from(body)]
-                    };
+                    let tabs = Tabs::new(ResponseTab::ALL.iter().map(|tab| tab.as_str()).collect::<Vec<_>>())
+                        .select(self.state.response_tab.index())
+                        .style(Theme::default())
+                        .highlight_style(Theme::selected());
+                    tabs.render(chunks[1], buf);
 
-                    let body_paragraph = Paragraph::new(body_content)
-                        .wrap(Wrap { trim: false })
-                        .scroll((self.state.response_scroll, 0));
-                    body_paragraph.render(chunks[1], buf);
+                    self.render_http_tab(response, chunks[2], buf);
                 } else {
-                    let no_response = Paragraph::new("No response yet\n\nPress Enter to send request")
+                    let no_response = Paragraph::new(self.no_response_text("request"))
                         .block(block);
                     no_response.render(area, buf);
                 }
@@ -234,16 +893,10 @@ impl<'a> Widget for ResponseViewer<'a> {
                         ])
                         .split(inner_area);
 
-                    let status_color = if response.status.code == 0 {
-                        Color::Green
-                    } else {
-                        Color::Red
-                    };
-
                     let status_line = Line::from(vec![
                         Span::styled(
                             format!("Code {} ", response.status.code),
-                            ratatui::style::Style::default().fg(status_color),
+                            Theme::grpc_status(response.status.code),
                         ),
                         Span::raw(&response.status.message),
                         Span::raw(format!(" | {}ms | {} message(s)",
@@ -255,29 +908,10 @@ impl<'a> Widget for ResponseViewer<'a> {
                     let status_paragraph = Paragraph::new(status_line);
                     status_paragraph.render(chunks[0], buf);
 
-                    // Display messages
-                    let body = if response.messages.is_empty() {
-                        if response.status.code == 0 {
-                            "Success (no message body)".to_string()
-                        } else {
-                            format!("Error: {}", response.status.message)
-                        }
-                    } else {
-                        response.messages
-                            .iter()
-                            .map(|msg| msg.message_json.as_str())
-                            .collect::<Vec<_>>()
-                            .join("\n\n")
-                    };
-
-                    let body_content = self.colorize_json(&body);
-
-                    let body_paragraph = Paragraph::new(body_content)
-                        .wrap(Wrap { trim: false })
-                        .scroll((self.state.response_scroll, 0));
-                    body_paragraph.render(chunks[1], buf);
+                    // Messages are JSON
+                    self.render_body(BodyFormat::Json, chunks[1], buf);
                 } else {
-                    let no_response = Paragraph::new("No response yet\n\nPress Enter to send gRPC request")
+                    let no_response = Paragraph::new(self.no_response_text("gRPC request"))
                         .block(block);
                     no_response.render(area, buf);
                 }
@@ -1,8 +1,10 @@
-use crate::app::state::{AppState, ProtocolType};
+use crate::app::actions::Action;
+use crate::app::keymap::KeyScope;
+use crate::app::state::{AppState, InputMode, ProtocolType};
+use crate::ui::theme::Theme;
 use ratatui::{
     layout::Rect,
     widgets::{Block, Paragraph, Widget},
-    style::{Style, Color},
 };
 
 pub struct StatusBar<'a> {
@@ -14,6 +16,39 @@ impl<'a> StatusBar<'a> {
         Self { state }
     }
 
+    /// `key: label` hints for the mode, from the active keymap
+    fn hints(&self) -> String {
+        let keymap = &self.state.keymap;
+        let editing = self.state.input_mode == InputMode::Editing;
+        let scope = if editing { KeyScope::Editing } else { KeyScope::Global };
+        let tabs = keymap
+            .first_key(scope, Action::NextRequestTab)
+            .zip(keymap.first_key(scope, Action::PrevRequestTab))
+            .map(|(next, prev)| format!("{}/{}: tabs", next, prev));
+
+        let mut hints = if editing {
+            keymap.hints(scope, &[(Action::FinishEditing, "save & exit"), (Action::NextField, "next field")])
+        } else {
+            keymap.hints(scope, &[
+                (Action::Quit, "quit"),
+                (Action::ToggleHelp, "help"),
+                (Action::OpenCommandPalette, "commands"),
+                (Action::NextPanel, "next"),
+                (Action::SendRequest, "send"),
+                (Action::NewRequest, "new"),
+                (Action::NewGrpcRequest, "new gRPC"),
+            ])
+        };
+        hints.extend(tabs);
+        if !editing {
+            hints.extend(keymap.hints(scope, &[(Action::OpenExportMenu, "export")]));
+            if self.state.protocol_type() == ProtocolType::Grpc {
+                hints.extend(keymap.hints(scope, &[(Action::OpenProtoLoader, "load proto")]));
+            }
+        }
+        hints.join(" | ")
+    }
+
     fn truncate_text(text: &str, max_width: usize) -> String {
         if max_width == 0 {
             return String::new();
@@ -35,30 +70,35 @@ impl<'a> Widget for StatusBar<'a> {
             return;
         }
 
-        let protocol_name = match self.state.protocol_type {
-            ProtocolType::Http => "HTTP",
-            ProtocolType::Grpc => "gRPC",
+        let protocol_name = match (self.state.protocol_type(), self.state.zoomed) {
+            (ProtocolType::Http, false) => "HTTP",
+            (ProtocolType::Grpc, false) => "gRPC",
+            (ProtocolType::Http, true) => "HTTP, zoomed",
+            (ProtocolType::Grpc, true) => "gRPC, zoomed",
         };
 
+        let cancel = self
+            .state
+            .keymap
+            .first_key(KeyScope::Global, Action::CancelRequest)
+            .map_or_else(String::new, |key| format!(" | {}: cancel", key));
         let full_text = if self.state.is_loading {
-            format!(" [{}] Loading... | {}", protocol_name, self.state.loading_message)
-        } else {
-            match self.state.protocol_type {
-                ProtocolType::Http => {
-                    format!(" [{}] q: quit | ?: help | Tab: next | Enter: send | n: new | o: export | g: new gRPC | p: toggle protocol", protocol_name)
-                }
-                ProtocolType::Grpc => {
-                    format!(" [{}] q: quit | ?: help | Tab: next | Enter: send | n: new | o: export | l: load proto | p: toggle protocol", protocol_name)
-                }
+            let in_flight = self.state.in_flight.len();
+            if in_flight > 1 {
+                format!(" [{}] Loading... | {} ({} in flight){}", protocol_name, self.state.loading_message, in_flight, cancel)
+            } else {
+                format!(" [{}] Loading... | {}{}", protocol_name, self.state.loading_message, cancel)
             }
+        } else if let Some(message) = &self.state.status_message {
+            format!(" [{}] {}", protocol_name, message)
+        } else {
+            format!(" [{}] {}", protocol_name, self.hints())
         };
 
         let status_text = Self::truncate_text(&full_text, area.width as usize);
 
         let paragraph = Paragraph::new(status_text)
-            .style(Style::default()
-                .bg(Color::DarkGray)
-                .fg(Color::White))
+            .style(Theme::status_bar())
             .block(Block::default());
 
         Widget::render(paragraph, area, buf);
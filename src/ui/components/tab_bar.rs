@@ -0,0 +1,106 @@
+use crate::app::state::{AppState, ProtocolType, RequestTab};
+use crate::ui::hit_map::{HitMap, Target};
+use crate::ui::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::Modifier,
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+pub struct TabBar<'a> {
+    state: &'a AppState,
+}
+
+impl<'a> TabBar<'a> {
+    pub fn new(state: &'a AppState) -> Self {
+        Self { state }
+    }
+
+    fn tab_title(&self, tab: &RequestTab) -> String {
+        let name = tab.target.request_id
+            .and_then(|id| self.state.requests.iter().find(|r| r.id() == id))
+            .map(|r| r.name());
+        let prefix = match tab.target.protocol {
+            ProtocolType::Http => "[H]",
+            ProtocolType::Grpc => "[G]",
+        };
+        format!("{} {}", prefix, name.unwrap_or("No request"))
+    }
+}
+
+impl<'a> TabBar<'a> {
+    fn labels(&self) -> Vec<Span<'static>> {
+        let mut tabs = Vec::new();
+        for (idx, tab) in self.state.tabs.iter().enumerate() {
+            let mut label = format!(" {}", self.tab_title(tab));
+            if self.state.tab_has_unsaved_edits(idx) {
+                label.push_str(" ●");
+            }
+            if self.state.is_tab_loading(tab.id) {
+                label.push_str(" ⟳");
+            }
+            label.push(' ');
+
+            let mut style = if idx == self.state.active_tab {
+                Theme::selected()
+            } else {
+                Theme::muted()
+            };
+            if tab.preview {
+                style = style.add_modifier(Modifier::ITALIC);
+            }
+
+            tabs.push(Span::styled(label, style));
+        }
+        tabs
+    }
+
+    /// Drop tabs from the left until the active one fits, leaving room for the overflow markers
+    fn first_shown(&self, tabs: &[Span], width: usize) -> usize {
+        let active = self.state.active_tab.min(tabs.len().saturating_sub(1));
+        let mut first = 0;
+        while first < active {
+            let used: usize = tabs[first..=active].iter().map(|tab| tab.width() + 1).sum();
+            let marker = if first > 0 { 2 } else { 0 };
+            if used + marker <= width {
+                break;
+            }
+            first += 1;
+        }
+        first
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        let tabs = self.labels();
+        let first = self.first_shown(&tabs, area.width as usize);
+        let mut x = area.x + if first > 0 { 2 } else { 0 };
+        for (idx, tab) in tabs.iter().enumerate().skip(first) {
+            let width = tab.width() as u16;
+            hits.push(Rect::new(x, area.y, width, area.height.min(1)).intersection(area), Target::RequestTab(idx));
+            x = x.saturating_add(width + 1);
+        }
+    }
+}
+
+impl<'a> Widget for TabBar<'a> {
+    fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer) {
+        if area.height == 0 {
+            return;
+        }
+
+        let tabs = self.labels();
+        let first = self.first_shown(&tabs, area.width as usize);
+
+        let mut spans = Vec::new();
+        if first > 0 {
+            spans.push(Span::styled("‹ ", Theme::dim()));
+        }
+        for tab in tabs.into_iter().skip(first) {
+            spans.push(tab);
+            spans.push(Span::raw(" "));
+        }
+
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::app::text_editor::TextEditor;
+use crate::ui::theme::Theme;
+use crate::utils::json_format::check_json;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+/// Draws a [`TextEditor`] with line numbers, the selection, the matching bracket and JSON errors
+pub struct TextEditorView<'a> {
+    editor: &'a TextEditor,
+    block: Block<'a>,
+    focused: bool,
+    json: bool,
+}
+
+impl<'a> TextEditorView<'a> {
+    pub fn new(editor: &'a TextEditor, block: Block<'a>) -> Self {
+        Self {
+            editor,
+            block,
+            focused: false,
+            json: false,
+        }
+    }
+
+    /// Show the cursor and the bracket matching it
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Mark where the text stops being valid JSON
+    pub fn json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+}
+
+/// Move the cursor of `editor`, drawn with its borders in `area`, to the character at a screen cell
+pub fn click(editor: &mut TextEditor, area: Rect, column: u16, row: u16) {
+    let inner = Block::bordered().inner(area);
+    let number_width = editor.lines().len().to_string().len() as u16;
+    let (top, left) = editor.scroll.get();
+    // Clicks on the line numbers go to the start of the line
+    let col = column.saturating_sub(inner.x + number_width + 1) as usize;
+    editor.move_cursor_to(top + row.saturating_sub(inner.y) as usize, left + col);
+}
+
+/// Keep `cursor` within the `len` cells starting at `top`, moving as little as possible
+fn scroll_to(top: usize, cursor: usize, len: usize) -> usize {
+    if cursor < top {
+        cursor
+    } else if len > 0 && cursor >= top + len {
+        cursor + 1 - len
+    } else {
+        top
+    }
+}
+
+impl Widget for TextEditorView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let editor = self.editor;
+        let inner = self.block.inner(area);
+        self.block.render(area, buf);
+
+        let error = if self.json { check_json(&editor.text()) } else { None };
+        // The error message takes the last row
+        let height = inner.height.saturating_sub(error.is_some() as u16) as usize;
+        let lines = editor.lines();
+        let number_width = lines.len().to_string().len();
+        let width = (inner.width as usize).saturating_sub(number_width + 1);
+
+        let (cursor_row, cursor_col) = editor.cursor();
+        let (top, left) = editor.scroll.get();
+        let top = scroll_to(top, cursor_row, height);
+        let left = scroll_to(left, cursor_col, width);
+        editor.scroll.set((top, left));
+
+        let selection = editor.selection();
+        let brackets = if self.focused { editor.matching_brackets() } else { None };
+        let error_at = error.as_ref().map(|error| (error.line, error.column));
+
+        let mut rendered: Vec<Line> = Vec::with_capacity(inner.height as usize);
+        for (row, line) in lines.iter().enumerate().skip(top).take(height) {
+            let number_style = if error_at.is_some_and(|(line, _)| line == row) {
+                Theme::error().add_modifier(Modifier::BOLD)
+            } else if self.focused && row == cursor_row {
+                Theme::emphasis()
+            } else {
+                Theme::dim()
+            };
+            let mut spans = vec![Span::styled(format!("{:>number_width$} ", row + 1), number_style)];
+
+            // The cursor and errors at the end of the line are drawn on an extra space
+            let mut len = line.chars().count();
+            if (self.focused && row == cursor_row && cursor_col == len) || error_at == Some((row, len)) {
+                len += 1;
+            }
+
+            let mut run = String::new();
+            let mut run_style = Style::default();
+            for (col, c) in line.chars().chain(std::iter::once(' ')).enumerate().take(len).skip(left).take(width) {
+                let pos = (row, col);
+                let mut style = Style::default();
+                if selection.is_some_and(|(start, end)| start <= pos && pos < end) {
+                    style = style.patch(Theme::highlight());
+                }
+                if brackets.is_some_and(|(open, close)| open == pos || close == pos) {
+                    style = style.patch(Theme::emphasis()).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                if error_at == Some(pos) {
+                    style = style.patch(Theme::error_marker());
+                }
+                if self.focused && pos == (cursor_row, cursor_col) {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+
+                if style != run_style && !run.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut run), run_style));
+                }
+                run_style = style;
+                run.push(if c == '\t' { ' ' } else { c });
+            }
+            if !run.is_empty() {
+                spans.push(Span::styled(run, run_style));
+            }
+            rendered.push(Line::from(spans));
+        }
+
+        if let Some(error) = error {
+            rendered.resize(height, Line::default());
+            rendered.push(Line::styled(
+                format!("✗ line {}, column {}: {}", error.line + 1, error.column + 1, error.message),
+                Theme::error(),
+            ));
+        }
+
+        Paragraph::new(rendered).render(inner, buf);
+    }
+}
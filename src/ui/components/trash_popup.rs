@@ -0,0 +1,121 @@
+use crate::app::state::AppState;
+use crate::models::trash::TrashEntry;
+use crate::ui::components::buttons::ButtonRow;
+use crate::ui::hit_map::{HitMap, Target};
+use crate::ui::theme::Theme;
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+/// Width of the column naming what was deleted, `Proto schema` plus a space
+const KIND_WIDTH: usize = 13;
+
+pub struct TrashPopup<'a> {
+    state: &'a AppState,
+}
+
+impl<'a> TrashPopup<'a> {
+    pub fn new(state: &'a AppState) -> Self {
+        Self { state }
+    }
+
+    /// Entry list, status line, buttons and help line inside the borders
+    fn layout(inner: Rect) -> [Rect; 4] {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Fill(1), Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
+            .split(inner);
+        [chunks[0], chunks[1], chunks[2], chunks[3]]
+    }
+
+    /// First entry shown, so the selected one stays in view
+    fn first_shown(&self, list: Rect) -> usize {
+        self.state.selected_trash.saturating_sub((list.height as usize).saturating_sub(1))
+    }
+
+    fn buttons(&self) -> ButtonRow {
+        if self.state.trash.is_empty() {
+            ButtonRow::new(vec![("Close", KeyCode::Esc)])
+        } else {
+            ButtonRow::new(vec![
+                ("Restore", KeyCode::Enter),
+                ("Purge", KeyCode::Delete),
+                ("Empty trash", KeyCode::Char('D')),
+                ("Close", KeyCode::Esc),
+            ])
+        }
+    }
+
+    pub fn hit_targets(&self, area: Rect, hits: &mut HitMap) {
+        let [list, _, buttons, _] = Self::layout(Block::bordered().inner(area));
+        let first = self.first_shown(list);
+        for row in 0..self.state.trash.len().saturating_sub(first).min(list.height as usize) {
+            hits.push(Rect { y: list.y + row as u16, height: 1, ..list }, Target::PopupRow(first + row));
+        }
+        self.buttons().hit_targets(buttons, hits);
+    }
+
+    fn render_entry(entry: &TrashEntry, selected: bool, area: Rect, buf: &mut Buffer) {
+        let base = if selected { Theme::selected() } else { Style::default() };
+        buf.set_style(area, base);
+
+        // When it was deleted goes on the right, the name and contents get what is left
+        let deleted_at = entry.deleted_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+        let date_width = deleted_at.chars().count() as u16;
+        if date_width + 1 < area.width {
+            buf.set_string(area.right() - date_width - 1, area.y, &deleted_at, base.patch(Theme::dim()));
+        }
+
+        let mut spans = vec![
+            Span::styled(if selected { "▶ " } else { "  " }, base),
+            Span::styled(format!("{:<width$}", entry.kind.label(), width = KIND_WIDTH), base.patch(Theme::dim())),
+            Span::styled(entry.name.clone(), base),
+        ];
+        let summary = entry.summary();
+        if !summary.is_empty() {
+            spans.push(Span::styled(format!("  {}", summary), base.patch(Theme::dim())));
+        }
+        Line::from(spans).render(Rect { width: area.width.saturating_sub(date_width + 2), ..area }, buf);
+    }
+}
+
+impl<'a> Widget for TrashPopup<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(format!("Trash ({})", self.state.trash.len()))
+            .borders(Borders::ALL)
+            .border_style(Theme::focused_border())
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [list, status, buttons, help] = Self::layout(inner);
+        if self.state.trash.is_empty() {
+            Paragraph::new(Line::styled("  The trash is empty", Theme::dim())).render(list, buf);
+        }
+        let first = self.first_shown(list);
+        for (row, (idx, entry)) in self.state.trash.iter().enumerate().skip(first).take(list.height as usize).enumerate() {
+            let row_area = Rect { y: list.y + row as u16, height: 1, ..list };
+            Self::render_entry(entry, idx == self.state.selected_trash, row_area, buf);
+        }
+
+        // Outcome of the last restore or purge, e.g. why something cannot be restored yet
+        if let Some(message) = &self.state.status_message {
+            Paragraph::new(Line::styled(format!(" {}", message), Theme::accent())).render(status, buf);
+        }
+
+        self.buttons().render(buttons, buf);
+        Paragraph::new(Line::styled(
+            " ↑/↓: move | Enter/r: restore | d/Del: purge | D: empty trash | Esc: close",
+            Theme::dim(),
+        ))
+        .render(help, buf);
+    }
+}
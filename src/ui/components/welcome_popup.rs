@@ -3,7 +3,7 @@ use ratatui::{
     layout::Rect,
     text::Line,
     widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
-    style::{Style, Color, Modifier},
+    style::{Style, Modifier},
 };
 
 pub struct WelcomePopup;
@@ -45,6 +45,7 @@ impl Widget for WelcomePopup {
             "    • Enter             - Send the request",
             "    • n                 - Create a new request",
             "    • ?                 - Show help anytime",
+            "    • : / Ctrl+P        - Find any action or request",
             "",
             "  Get Started:",
             "    Check out the 'Example Collection' on the left to see",
@@ -60,7 +61,7 @@ impl Widget for WelcomePopup {
             .map(|(i, s)| {
                 // Color the logo lines (lines 1-7)
                 if i >= 1 && i <= 7 {
-                    Line::from(s).style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+                    Line::from(s).style(Theme::heading())
                 } else if i == 8 || i == 13 || i == 21 {
                     // Highlight section headers
                     Line::from(s).style(Style::default().add_modifier(Modifier::BOLD))
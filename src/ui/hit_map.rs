@@ -0,0 +1,114 @@
+use crate::app::state::{EditorField, EditorTab, GrpcEditorField, Panel, ResponseTab};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::{Position, Rect},
+    text::Line,
+};
+
+/// Something on screen that takes mouse clicks
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    Panel(Panel),
+    /// An open request tab, by position in the tab bar
+    RequestTab(usize),
+    Collection(usize),
+    /// A request, by index into the HTTP or gRPC requests
+    Request(usize),
+    EditorTab(EditorTab),
+    Field(EditorField),
+    GrpcField(GrpcEditorField),
+    ResponseTab(ResponseTab),
+    /// A row of the list in the open popup
+    PopupRow(usize),
+    /// A popup button, clicking it presses the key
+    Button(KeyCode),
+}
+
+/// Where the last frame drew each clickable thing, so mouse events can be mapped back to it
+#[derive(Debug, Default)]
+pub struct HitMap {
+    targets: Vec<(Rect, Target)>,
+}
+
+impl HitMap {
+    pub fn push(&mut self, area: Rect, target: Target) {
+        if !area.is_empty() {
+            self.targets.push((area, target));
+        }
+    }
+
+    /// Forget everything pushed so far, for popups that cover the panels
+    pub fn clear(&mut self) {
+        self.targets.clear();
+    }
+
+    /// The innermost target at a cell with the area it was drawn in; targets are pushed outside in
+    pub fn target_at(&self, column: u16, row: u16) -> Option<(Rect, Target)> {
+        let position = Position::new(column, row);
+        self.targets.iter().rev().find(|(area, _)| area.contains(position)).copied()
+    }
+
+    pub fn panel_at(&self, column: u16, row: u16) -> Option<Panel> {
+        let position = Position::new(column, row);
+        self.targets.iter().find_map(|(area, target)| match target {
+            Target::Panel(panel) if area.contains(position) => Some(*panel),
+            _ => None,
+        })
+    }
+}
+
+/// Cells of each title of a ratatui `Tabs` drawn in `area`, with its default one-cell padding and divider
+pub fn tab_areas(area: Rect, titles: &[&str]) -> Vec<Rect> {
+    let mut areas = Vec::with_capacity(titles.len());
+    let mut x = area.x;
+    for title in titles {
+        let width = (Line::from(*title).width() as u16 + 2).min(area.right().saturating_sub(x));
+        if width == 0 {
+            break;
+        }
+        areas.push(Rect::new(x, area.y, width, area.height.min(1)));
+        x = x.saturating_add(width + 1);
+    }
+    areas
+}
+
+/// Byte offset into `text` of the character under `column`, for a one-line field drawn with
+/// borders in `area`. While `focused` the field is scrolled to keep `cursor` in view.
+pub fn text_field_cursor(text: &str, cursor: usize, focused: bool, area: Rect, column: u16) -> usize {
+    let available_width = area.width.saturating_sub(2) as usize;
+    let scroll_offset = if focused && cursor >= available_width {
+        cursor.saturating_sub(available_width.saturating_sub(1))
+    } else {
+        0
+    };
+    let index = scroll_offset + column.saturating_sub(area.x + 1) as usize;
+    text.char_indices().nth(index).map_or(text.len(), |(offset, _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicks_map_to_tabs_and_characters() {
+        let areas = tab_areas(Rect::new(10, 3, 30, 1), &["Params", "Headers", "Body"]);
+        assert_eq!(areas, vec![Rect::new(10, 3, 8, 1), Rect::new(19, 3, 9, 1), Rect::new(29, 3, 6, 1)]);
+
+        let mut hits = HitMap::default();
+        hits.push(Rect::new(0, 0, 40, 10), Target::Panel(Panel::RequestEditor));
+        hits.push(areas[1], Target::EditorTab(EditorTab::Headers));
+        assert_eq!(hits.target_at(20, 3).map(|(_, target)| target), Some(Target::EditorTab(EditorTab::Headers)));
+        assert_eq!(hits.target_at(18, 3).map(|(_, target)| target), Some(Target::Panel(Panel::RequestEditor)));
+        assert_eq!(hits.panel_at(20, 3), Some(Panel::RequestEditor));
+        assert_eq!(hits.target_at(40, 3), None);
+
+        // 8 visible characters, the cursor at the end scrolls the text 4 to the left
+        let field = Rect::new(0, 0, 10, 3);
+        let url = "https://api";
+        assert_eq!(text_field_cursor(url, 0, true, field, 3), 2);
+        assert_eq!(text_field_cursor(url, 11, true, field, 3), 6);
+        assert_eq!(text_field_cursor(url, 11, false, field, 9), 8);
+        assert_eq!(text_field_cursor("héllo", 0, false, field, 4), 4);
+        assert_eq!(text_field_cursor(url, 0, false, Rect::new(0, 0, 40, 3), 30), url.len());
+    }
+}
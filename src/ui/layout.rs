@@ -1,6 +1,9 @@
+use crate::models::layout::PanelLayout;
 use ratatui::layout::{Constraint, Direction, Layout as RatatuiLayout, Rect};
 
+/// Screen areas of the frame; hidden panels get an empty area
 pub struct Layout {
+    pub tabs: Rect,
     pub collections: Rect,
     pub requests: Rect,
     pub editor: Rect,
@@ -9,32 +12,45 @@ pub struct Layout {
 }
 
 impl Layout {
-    pub fn new(area: Rect) -> Self {
+    /// Split `area` by `panels`. With `zoomed` set only that panel, by index, is shown.
+    pub fn new(area: Rect, panels: &PanelLayout, zoomed: Option<usize>) -> Self {
         let main_chunks = RatatuiLayout::default()
             .direction(Direction::Vertical)
             .constraints([
+                Constraint::Length(1),
                 Constraint::Min(0),
                 Constraint::Length(1),
             ])
             .split(area);
-        
-        let content_chunks = RatatuiLayout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(15),
-                Constraint::Percentage(20),
-                Constraint::Percentage(35),
-                Constraint::Percentage(30),
-            ])
-            .split(main_chunks[0]);
-        
+        let content = main_chunks[1];
+
+        let shown: Vec<usize> = match zoomed {
+            Some(index) => vec![index],
+            None => (0..panels.widths.len()).filter(|&index| panels.is_visible(index)).collect(),
+        };
+        let direction = if panels.is_stacked(content.width) {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        // Fill weights keep the proportions when sidebars are hidden
+        let chunks = RatatuiLayout::default()
+            .direction(direction)
+            .constraints(shown.iter().map(|&index| Constraint::Fill(panels.widths[index])))
+            .split(content);
+
+        let mut panel_areas = [Rect::default(); 4];
+        for (chunk, &index) in chunks.iter().zip(&shown) {
+            panel_areas[index] = *chunk;
+        }
+
         Self {
-            collections: content_chunks[0],
-            requests: content_chunks[1],
-            editor: content_chunks[2],
-            response: content_chunks[3],
-            statusbar: main_chunks[1],
+            tabs: main_chunks[0],
+            collections: panel_areas[0],
+            requests: panel_areas[1],
+            editor: panel_areas[2],
+            response: panel_areas[3],
+            statusbar: main_chunks[2],
         }
     }
 }
-
@@ -1,5 +1,6 @@
 pub mod app;
 pub mod layout;
 pub mod theme;
+pub mod hit_map;
 pub mod components;
 
@@ -1,48 +1,542 @@
-use ratatui::style::{Color, Style};
+use anyhow::{anyhow, bail, Context, Result};
+use ratatui::style::{Color, Modifier, Style};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Colors of the UI by role, every component picks its colors through [`Theme`].
+///
+/// With every color set to `reset` the terminal's own colors are used and
+/// [`Theme`] falls back to bold, dim and reverse video to tell things apart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    /// Plain text
+    pub text: Color,
+    /// Secondary text, e.g. inactive tabs
+    pub muted: Color,
+    /// Hints, comments and unimportant details
+    pub dim: Color,
+    /// Focused borders, labels and headings
+    pub accent: Color,
+    /// The line number of the cursor line and matching brackets
+    pub emphasis: Color,
+    pub selection_fg: Color,
+    pub selection_bg: Color,
+    /// Background of selected text and of the JSON tree cursor
+    pub highlight_bg: Color,
+    /// Unfocused borders
+    pub border: Color,
+    pub status_bar_fg: Color,
+    pub status_bar_bg: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub info: Color,
+    pub match_fg: Color,
+    pub match_bg: Color,
+    pub current_match_bg: Color,
+    pub method_get: Color,
+    pub method_post: Color,
+    pub method_put: Color,
+    pub method_delete: Color,
+    pub method_patch: Color,
+    pub method_other: Color,
+    pub json_key: Color,
+    pub json_string: Color,
+    pub json_number: Color,
+    pub json_boolean: Color,
+    pub json_null: Color,
+}
+
+impl Palette {
+    /// For dark terminals, the original look
+    pub const DARK: Palette = Palette {
+        text: Color::White,
+        muted: Color::Gray,
+        dim: Color::DarkGray,
+        accent: Color::Cyan,
+        emphasis: Color::Yellow,
+        selection_fg: Color::Black,
+        selection_bg: Color::Cyan,
+        highlight_bg: Color::DarkGray,
+        border: Color::DarkGray,
+        status_bar_fg: Color::White,
+        status_bar_bg: Color::DarkGray,
+        success: Color::Green,
+        warning: Color::Yellow,
+        error: Color::Red,
+        info: Color::Blue,
+        match_fg: Color::Black,
+        match_bg: Color::Yellow,
+        current_match_bg: Color::Magenta,
+        method_get: Color::Green,
+        method_post: Color::Blue,
+        method_put: Color::Yellow,
+        method_delete: Color::Red,
+        method_patch: Color::Magenta,
+        method_other: Color::Gray,
+        json_key: Color::White,
+        json_string: Color::Gray,
+        json_number: Color::Cyan,
+        json_boolean: Color::Blue,
+        json_null: Color::DarkGray,
+    };
+
+    /// For light terminals: no white or yellow text, 256-color grays that stay readable on white
+    pub const LIGHT: Palette = Palette {
+        text: Color::Black,
+        muted: Color::Indexed(238),
+        dim: Color::Indexed(244),
+        accent: Color::Blue,
+        emphasis: Color::Indexed(130),
+        selection_fg: Color::White,
+        selection_bg: Color::Blue,
+        highlight_bg: Color::Indexed(252),
+        border: Color::Indexed(248),
+        status_bar_fg: Color::Black,
+        status_bar_bg: Color::Indexed(252),
+        success: Color::Indexed(28),
+        warning: Color::Indexed(130),
+        error: Color::Indexed(160),
+        info: Color::Indexed(25),
+        match_fg: Color::Black,
+        match_bg: Color::Indexed(222),
+        current_match_bg: Color::Indexed(213),
+        method_get: Color::Indexed(28),
+        method_post: Color::Indexed(25),
+        method_put: Color::Indexed(130),
+        method_delete: Color::Indexed(160),
+        method_patch: Color::Indexed(127),
+        method_other: Color::Indexed(240),
+        json_key: Color::Indexed(24),
+        json_string: Color::Indexed(28),
+        json_number: Color::Indexed(127),
+        json_boolean: Color::Indexed(130),
+        json_null: Color::Indexed(244),
+    };
+
+    /// Bright colors only and no dark grays, for low vision or washed-out displays
+    pub const HIGH_CONTRAST: Palette = Palette {
+        text: Color::White,
+        muted: Color::White,
+        dim: Color::Gray,
+        accent: Color::LightYellow,
+        emphasis: Color::LightCyan,
+        selection_fg: Color::Black,
+        selection_bg: Color::LightYellow,
+        highlight_bg: Color::Blue,
+        border: Color::Gray,
+        status_bar_fg: Color::Black,
+        status_bar_bg: Color::White,
+        success: Color::LightGreen,
+        warning: Color::LightYellow,
+        error: Color::LightRed,
+        info: Color::LightCyan,
+        match_fg: Color::Black,
+        match_bg: Color::LightYellow,
+        current_match_bg: Color::LightMagenta,
+        method_get: Color::LightGreen,
+        method_post: Color::LightCyan,
+        method_put: Color::LightYellow,
+        method_delete: Color::LightRed,
+        method_patch: Color::LightMagenta,
+        method_other: Color::White,
+        json_key: Color::White,
+        json_string: Color::LightGreen,
+        json_number: Color::LightCyan,
+        json_boolean: Color::LightMagenta,
+        json_null: Color::Gray,
+    };
+
+    /// Solarized dark accents, in true color
+    pub const SOLARIZED: Palette = Palette {
+        text: Color::Rgb(0x93, 0xa1, 0xa1),
+        muted: Color::Rgb(0x83, 0x94, 0x96),
+        dim: Color::Rgb(0x58, 0x6e, 0x75),
+        accent: Color::Rgb(0x26, 0x8b, 0xd2),
+        emphasis: Color::Rgb(0xb5, 0x89, 0x00),
+        selection_fg: Color::Rgb(0xfd, 0xf6, 0xe3),
+        selection_bg: Color::Rgb(0x26, 0x8b, 0xd2),
+        highlight_bg: Color::Rgb(0x07, 0x36, 0x42),
+        border: Color::Rgb(0x58, 0x6e, 0x75),
+        status_bar_fg: Color::Rgb(0x93, 0xa1, 0xa1),
+        status_bar_bg: Color::Rgb(0x07, 0x36, 0x42),
+        success: Color::Rgb(0x85, 0x99, 0x00),
+        warning: Color::Rgb(0xb5, 0x89, 0x00),
+        error: Color::Rgb(0xdc, 0x32, 0x2f),
+        info: Color::Rgb(0x6c, 0x71, 0xc4),
+        match_fg: Color::Rgb(0x00, 0x2b, 0x36),
+        match_bg: Color::Rgb(0xb5, 0x89, 0x00),
+        current_match_bg: Color::Rgb(0xd3, 0x36, 0x82),
+        method_get: Color::Rgb(0x85, 0x99, 0x00),
+        method_post: Color::Rgb(0x26, 0x8b, 0xd2),
+        method_put: Color::Rgb(0xb5, 0x89, 0x00),
+        method_delete: Color::Rgb(0xdc, 0x32, 0x2f),
+        method_patch: Color::Rgb(0xd3, 0x36, 0x82),
+        method_other: Color::Rgb(0x83, 0x94, 0x96),
+        json_key: Color::Rgb(0x26, 0x8b, 0xd2),
+        json_string: Color::Rgb(0x2a, 0xa1, 0x98),
+        json_number: Color::Rgb(0xd3, 0x36, 0x82),
+        json_boolean: Color::Rgb(0xcb, 0x4b, 0x16),
+        json_null: Color::Rgb(0x58, 0x6e, 0x75),
+    };
+
+    /// The terminal's own colors, used when `NO_COLOR` is set
+    pub const NO_COLOR: Palette = Palette {
+        text: Color::Reset,
+        muted: Color::Reset,
+        dim: Color::Reset,
+        accent: Color::Reset,
+        emphasis: Color::Reset,
+        selection_fg: Color::Reset,
+        selection_bg: Color::Reset,
+        highlight_bg: Color::Reset,
+        border: Color::Reset,
+        status_bar_fg: Color::Reset,
+        status_bar_bg: Color::Reset,
+        success: Color::Reset,
+        warning: Color::Reset,
+        error: Color::Reset,
+        info: Color::Reset,
+        match_fg: Color::Reset,
+        match_bg: Color::Reset,
+        current_match_bg: Color::Reset,
+        method_get: Color::Reset,
+        method_post: Color::Reset,
+        method_put: Color::Reset,
+        method_delete: Color::Reset,
+        method_patch: Color::Reset,
+        method_other: Color::Reset,
+        json_key: Color::Reset,
+        json_string: Color::Reset,
+        json_number: Color::Reset,
+        json_boolean: Color::Reset,
+        json_null: Color::Reset,
+    };
+
+    /// The color of a role by its name in `theme.toml`
+    fn color_mut(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "dim" => &mut self.dim,
+            "accent" => &mut self.accent,
+            "emphasis" => &mut self.emphasis,
+            "selection_fg" => &mut self.selection_fg,
+            "selection_bg" => &mut self.selection_bg,
+            "highlight_bg" => &mut self.highlight_bg,
+            "border" => &mut self.border,
+            "status_bar_fg" => &mut self.status_bar_fg,
+            "status_bar_bg" => &mut self.status_bar_bg,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "info" => &mut self.info,
+            "match_fg" => &mut self.match_fg,
+            "match_bg" => &mut self.match_bg,
+            "current_match_bg" => &mut self.current_match_bg,
+            "method_get" => &mut self.method_get,
+            "method_post" => &mut self.method_post,
+            "method_put" => &mut self.method_put,
+            "method_delete" => &mut self.method_delete,
+            "method_patch" => &mut self.method_patch,
+            "method_other" => &mut self.method_other,
+            "json_key" => &mut self.json_key,
+            "json_string" => &mut self.json_string,
+            "json_number" => &mut self.json_number,
+            "json_boolean" => &mut self.json_boolean,
+            "json_null" => &mut self.json_null,
+            _ => return None,
+        })
+    }
+
+    /// A built-in palette by name
+    pub fn preset(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "dark" => Ok(Palette::DARK),
+            "light" => Ok(Palette::LIGHT),
+            "high-contrast" => Ok(Palette::HIGH_CONTRAST),
+            "solarized" => Ok(Palette::SOLARIZED),
+            "no-color" => Ok(Palette::NO_COLOR),
+            _ => bail!("unknown preset `{}`, expected dark, light, high-contrast, solarized or no-color", name),
+        }
+    }
+
+    /// `theme.toml` in the nexus config directory
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("nexus").join("theme.toml"))
+    }
+
+    /// The dark palette, or no colors when `NO_COLOR` is set
+    pub fn from_env() -> Self {
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            Palette::NO_COLOR
+        } else {
+            Palette::DARK
+        }
+    }
+
+    /// The palette from `theme.toml` on top of [`Palette::from_env`], which a `preset` in the file overrides
+    pub fn load() -> Result<Self> {
+        let base = Self::from_env();
+        let Some(path) = Self::config_path().filter(|path| path.exists()) else {
+            return Ok(base);
+        };
+        let text = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_toml(&text, base).with_context(|| format!("Invalid theme in {}", path.display()))
+    }
+
+    /// A `preset` key replaces `base`, then `[colors]` sets single roles to a color name,
+    /// a 256-color index or `#rrggbb`
+    pub fn from_toml(text: &str, base: Palette) -> Result<Self> {
+        let table: toml::Table = toml::from_str(text)?;
+        let mut palette = match table.get("preset") {
+            Some(toml::Value::String(name)) => Self::preset(name)?,
+            Some(_) => bail!("`preset` must be a string"),
+            None => base,
+        };
+
+        for (name, value) in &table {
+            match (name.as_str(), value) {
+                ("preset", _) => {}
+                ("colors", toml::Value::Table(colors)) => {
+                    for (role, color) in colors {
+                        let slot = palette
+                            .color_mut(role)
+                            .ok_or_else(|| anyhow!("[colors] unknown role `{}`", role))?;
+                        let color = color
+                            .as_str()
+                            .ok_or_else(|| anyhow!("[colors] {}: the color must be a string", role))?;
+                        *slot = color
+                            .parse()
+                            .map_err(|_| anyhow!("[colors] {}: unknown color `{}`", role, color))?;
+                    }
+                }
+                ("colors", _) => bail!("[colors] must be a table of role = color"),
+                _ => bail!("unknown key `{}`", name),
+            }
+        }
+        Ok(palette)
+    }
+}
+
+/// The palette the UI is drawn with, set once the config is loaded
+static ACTIVE: RwLock<Palette> = RwLock::new(Palette::DARK);
 
 pub struct Theme;
 
 impl Theme {
+    pub fn set_palette(palette: Palette) {
+        *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = palette;
+    }
+
+    pub fn palette() -> Palette {
+        *ACTIVE.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Foreground `color`, or `fallback` when the color is left to the terminal
+    fn fg_or(color: Color, fallback: Modifier) -> Style {
+        if color == Color::Reset {
+            Style::default().add_modifier(fallback)
+        } else {
+            Style::default().fg(color)
+        }
+    }
+
+    /// `fg` on `bg`, or `fallback` when the background is left to the terminal
+    fn on_bg_or(fg: Color, bg: Color, fallback: Modifier) -> Style {
+        if bg == Color::Reset {
+            Style::default().add_modifier(fallback)
+        } else {
+            Style::default().fg(fg).bg(bg)
+        }
+    }
+
     pub fn default() -> Style {
-        Style::default().fg(Color::White)
+        Style::default().fg(Self::palette().text)
     }
-    
+
+    pub fn muted() -> Style {
+        Style::default().fg(Self::palette().muted)
+    }
+
+    pub fn dim() -> Style {
+        Self::fg_or(Self::palette().dim, Modifier::DIM)
+    }
+
+    /// Labels such as header names and the `Filter:` prompt
+    pub fn accent() -> Style {
+        Style::default().fg(Self::palette().accent)
+    }
+
+    pub fn heading() -> Style {
+        Self::accent().add_modifier(Modifier::BOLD)
+    }
+
+    pub fn emphasis() -> Style {
+        Self::fg_or(Self::palette().emphasis, Modifier::BOLD)
+    }
+
     pub fn selected() -> Style {
-        Style::default()
-            .fg(Color::Black)
-            .bg(Color::Cyan)
+        let palette = Self::palette();
+        Self::on_bg_or(palette.selection_fg, palette.selection_bg, Modifier::REVERSED)
+    }
+
+    /// Background of selected text and the cursor row, keeping the text colors
+    pub fn highlight() -> Style {
+        let bg = Self::palette().highlight_bg;
+        if bg == Color::Reset {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().bg(bg)
+        }
     }
-    
+
     pub fn focused_border() -> Style {
-        Style::default().fg(Color::Cyan)
+        Self::fg_or(Self::palette().accent, Modifier::BOLD)
     }
-    
+
     pub fn unfocused_border() -> Style {
-        Style::default().fg(Color::DarkGray)
+        Self::fg_or(Self::palette().border, Modifier::DIM)
+    }
+
+    pub fn status_bar() -> Style {
+        let palette = Self::palette();
+        Self::on_bg_or(palette.status_bar_fg, palette.status_bar_bg, Modifier::REVERSED)
+    }
+
+    pub fn success() -> Style {
+        Style::default().fg(Self::palette().success)
     }
-    
+
+    pub fn warning() -> Style {
+        Self::fg_or(Self::palette().warning, Modifier::BOLD)
+    }
+
+    pub fn error() -> Style {
+        Self::fg_or(Self::palette().error, Modifier::BOLD)
+    }
+
+    /// The character where a JSON body stops parsing
+    pub fn error_marker() -> Style {
+        let palette = Self::palette();
+        Self::on_bg_or(palette.selection_fg, palette.error, Modifier::UNDERLINED | Modifier::BOLD)
+    }
+
+    /// A search match, the current one stands out from the rest
+    pub fn search_match(current: bool) -> Style {
+        let palette = Self::palette();
+        let bg = if current { palette.current_match_bg } else { palette.match_bg };
+        let fallback = if current { Modifier::REVERSED | Modifier::BOLD } else { Modifier::REVERSED };
+        Self::on_bg_or(palette.match_fg, bg, fallback)
+    }
+
+    /// 2xx success, 3xx info, 4xx warning, 5xx error
+    pub fn status_code(code: u16) -> Style {
+        match code {
+            200..=299 => Self::success(),
+            300..=399 => Style::default().fg(Self::palette().info),
+            400..=499 => Self::warning(),
+            500..=599 => Self::error(),
+            _ => Self::default(),
+        }
+    }
+
+    /// OK, CANCELLED and everything else
+    pub fn grpc_status(code: i32) -> Style {
+        match code {
+            0 => Self::success(),
+            1 => Self::warning(),
+            _ => Self::error(),
+        }
+    }
+
     pub fn method_get() -> Style {
-        Style::default().fg(Color::Green)
+        Style::default().fg(Self::palette().method_get)
     }
-    
+
     pub fn method_post() -> Style {
-        Style::default().fg(Color::Blue)
+        Style::default().fg(Self::palette().method_post)
     }
-    
+
     pub fn method_put() -> Style {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(Self::palette().method_put)
     }
-    
+
     pub fn method_delete() -> Style {
-        Style::default().fg(Color::Red)
+        Style::default().fg(Self::palette().method_delete)
     }
-    
+
     pub fn method_patch() -> Style {
-        Style::default().fg(Color::Magenta)
+        Style::default().fg(Self::palette().method_patch)
     }
-    
+
     pub fn method_other() -> Style {
-        Style::default().fg(Color::Gray)
+        Style::default().fg(Self::palette().method_other)
+    }
+
+    /// Method color by name, for requests that only keep the method as text
+    pub fn method(name: &str) -> Style {
+        match name {
+            "GET" => Self::method_get(),
+            "POST" => Self::method_post(),
+            "PUT" => Self::method_put(),
+            "DELETE" => Self::method_delete(),
+            "PATCH" => Self::method_patch(),
+            _ => Self::method_other(),
+        }
+    }
+
+    /// Object keys, also YAML keys, form field names and markup attribute names
+    pub fn json_key() -> Style {
+        Self::fg_or(Self::palette().json_key, Modifier::BOLD)
+    }
+
+    pub fn json_string() -> Style {
+        Style::default().fg(Self::palette().json_string)
+    }
+
+    pub fn json_number() -> Style {
+        Style::default().fg(Self::palette().json_number)
+    }
+
+    pub fn json_boolean() -> Style {
+        Style::default().fg(Self::palette().json_boolean)
+    }
+
+    pub fn json_null() -> Style {
+        Self::fg_or(Self::palette().json_null, Modifier::DIM)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_file_overrides_a_preset() {
+        let palette = Palette::from_toml(
+            r##"
+            preset = "light"
+
+            [colors]
+            accent = "magenta"
+            json_key = "#268bd2"
+            dim = "242"
+            "##,
+            Palette::DARK,
+        )
+        .unwrap();
+        assert_eq!(palette.accent, Color::Magenta);
+        assert_eq!(palette.json_key, Color::Rgb(0x26, 0x8b, 0xd2));
+        assert_eq!(palette.dim, Color::Indexed(242));
+        assert_eq!(palette.text, Palette::LIGHT.text);
+
+        // Without a preset the base is kept, e.g. no colors under NO_COLOR
+        assert_eq!(Palette::from_toml("", Palette::NO_COLOR).unwrap(), Palette::NO_COLOR);
+
+        let error = Palette::from_toml("[colors]\nbackground = \"red\"", Palette::DARK).unwrap_err();
+        assert_eq!(error.to_string(), "[colors] unknown role `background`");
+        assert!(Palette::from_toml("[colors]\naccent = \"redish\"", Palette::DARK).is_err());
+        assert!(Palette::from_toml("preset = \"sepia\"", Palette::DARK).is_err());
+    }
+}
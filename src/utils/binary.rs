@@ -0,0 +1,115 @@
+/// Bytes per hex dump line
+const BYTES_PER_LINE: usize = 16;
+
+/// Larger bodies are dumped up to here; save the body to a file to see the rest
+pub const MAX_HEX_DUMP_BYTES: usize = 1024 * 1024;
+
+/// A file format recognized from its leading bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FileKind {
+    pub name: &'static str,
+    pub extension: &'static str,
+}
+
+/// Signatures as (offset, magic bytes, format), most specific first
+const SIGNATURES: &[(usize, &[u8], FileKind)] = &[
+    (0, b"\x89PNG\r\n\x1a\n", FileKind { name: "PNG image", extension: "png" }),
+    (0, b"\xff\xd8\xff", FileKind { name: "JPEG image", extension: "jpg" }),
+    (0, b"GIF87a", FileKind { name: "GIF image", extension: "gif" }),
+    (0, b"GIF89a", FileKind { name: "GIF image", extension: "gif" }),
+    (8, b"WEBP", FileKind { name: "WebP image", extension: "webp" }),
+    (8, b"WAVE", FileKind { name: "WAV audio", extension: "wav" }),
+    (0, b"II*\x00", FileKind { name: "TIFF image", extension: "tiff" }),
+    (0, b"MM\x00*", FileKind { name: "TIFF image", extension: "tiff" }),
+    (0, b"\x00\x00\x01\x00", FileKind { name: "ICO image", extension: "ico" }),
+    (0, b"%PDF-", FileKind { name: "PDF document", extension: "pdf" }),
+    (0, b"PK\x03\x04", FileKind { name: "ZIP archive", extension: "zip" }),
+    (0, b"\x1f\x8b", FileKind { name: "gzip data", extension: "gz" }),
+    (0, b"BZh", FileKind { name: "bzip2 data", extension: "bz2" }),
+    (0, b"\xfd7zXZ\x00", FileKind { name: "xz data", extension: "xz" }),
+    (0, b"\x28\xb5\x2f\xfd", FileKind { name: "zstd data", extension: "zst" }),
+    (0, b"7z\xbc\xaf\x27\x1c", FileKind { name: "7-Zip archive", extension: "7z" }),
+    (4, b"ftyp", FileKind { name: "MP4 media", extension: "mp4" }),
+    (0, b"OggS", FileKind { name: "Ogg media", extension: "ogg" }),
+    (0, b"ID3", FileKind { name: "MP3 audio", extension: "mp3" }),
+    (0, b"fLaC", FileKind { name: "FLAC audio", extension: "flac" }),
+    (0, b"wOFF", FileKind { name: "WOFF font", extension: "woff" }),
+    (0, b"wOF2", FileKind { name: "WOFF2 font", extension: "woff2" }),
+    (0, b"\x00asm", FileKind { name: "WebAssembly module", extension: "wasm" }),
+    (0, b"\x7fELF", FileKind { name: "ELF binary", extension: "elf" }),
+    (0, b"SQLite format 3\x00", FileKind { name: "SQLite database", extension: "sqlite" }),
+];
+
+/// Name the format of a body from its magic bytes
+pub fn sniff(bytes: &[u8]) -> Option<FileKind> {
+    SIGNATURES
+        .iter()
+        .find(|(offset, magic, _)| bytes.get(*offset..offset + magic.len()) == Some(*magic))
+        .map(|(_, _, kind)| *kind)
+}
+
+/// Classic `hexdump -C` layout: offset, 16 hex bytes split in two groups, then the printable ASCII.
+///
+/// Only the first [`MAX_HEX_DUMP_BYTES`] are dumped, followed by a note with the remaining count.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let shown = &bytes[..bytes.len().min(MAX_HEX_DUMP_BYTES)];
+    let mut lines: Vec<String> = shown
+        .chunks(BYTES_PER_LINE)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            let mut hex = String::new();
+            for col in 0..BYTES_PER_LINE {
+                if col == BYTES_PER_LINE / 2 {
+                    hex.push(' ');
+                }
+                match chunk.get(col) {
+                    Some(byte) => hex.push_str(&format!("{:02x} ", byte)),
+                    None => hex.push_str("   "),
+                }
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' })
+                .collect();
+            format!("{:08x}  {} |{}|", idx * BYTES_PER_LINE, hex, ascii)
+        })
+        .collect();
+
+    if bytes.len() > shown.len() {
+        lines.push(format!("... {} more bytes not shown", bytes.len() - shown.len()));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_common_formats() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\x00\x00").unwrap().name, "PNG image");
+        assert_eq!(sniff(b"RIFF\x24\x00\x00\x00WEBPVP8 ").unwrap().extension, "webp");
+        assert_eq!(sniff(b"\x00\x00\x00\x18ftypmp42").unwrap().extension, "mp4");
+        assert_eq!(sniff(b"\x1f\x8b\x08\x00").unwrap().name, "gzip data");
+        assert!(sniff(b"\x0a\x05hello").is_none());
+        assert!(sniff(b"").is_none());
+    }
+
+    #[test]
+    fn dump_aligns_partial_lines() {
+        let bytes: Vec<u8> = (0..20).map(|n| n + 0x40).collect();
+        let dump = hex_dump(&bytes);
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            lines[0],
+            "00000000  40 41 42 43 44 45 46 47  48 49 4a 4b 4c 4d 4e 4f  |@ABCDEFGHIJKLMNO|"
+        );
+        assert_eq!(
+            lines[1],
+            "00000010  50 51 52 53                                       |PQRS|"
+        );
+
+        let large = vec![0u8; MAX_HEX_DUMP_BYTES + 5];
+        assert_eq!(hex_dump(&large).lines().last(), Some("... 5 more bytes not shown"));
+    }
+}
@@ -0,0 +1,458 @@
+use crate::utils::binary::hex_dump;
+use percent_encoding::percent_decode_str;
+
+/// Widest a CSV column is padded to; longer cells overflow instead of widening every row
+const MAX_CSV_COLUMN_WIDTH: usize = 40;
+
+/// HTML elements that never have a closing tag
+const HTML_VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// HTML elements whose content is kept as received
+const HTML_RAW_ELEMENTS: [&str; 4] = ["script", "style", "pre", "textarea"];
+
+/// How a response body is pretty-printed and highlighted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyFormat {
+    Json,
+    Xml,
+    Html,
+    Yaml,
+    Csv,
+    Form,
+    Text,
+    /// Offset, hex and ASCII columns of the raw bytes
+    Hex,
+}
+
+impl BodyFormat {
+    pub const ALL: [BodyFormat; 8] = [
+        BodyFormat::Json,
+        BodyFormat::Xml,
+        BodyFormat::Html,
+        BodyFormat::Yaml,
+        BodyFormat::Csv,
+        BodyFormat::Form,
+        BodyFormat::Text,
+        BodyFormat::Hex,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            BodyFormat::Json => "JSON",
+            BodyFormat::Xml => "XML",
+            BodyFormat::Html => "HTML",
+            BodyFormat::Yaml => "YAML",
+            BodyFormat::Csv => "CSV",
+            BodyFormat::Form => "Form",
+            BodyFormat::Text => "Text",
+            BodyFormat::Hex => "Hex",
+        }
+    }
+
+    /// File extension for a body of this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BodyFormat::Json => "json",
+            BodyFormat::Xml => "xml",
+            BodyFormat::Html => "html",
+            BodyFormat::Yaml => "yaml",
+            BodyFormat::Csv => "csv",
+            BodyFormat::Form | BodyFormat::Text => "txt",
+            BodyFormat::Hex => "bin",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let idx = Self::ALL.iter().position(|format| format == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// Pick the format from a `Content-Type` value, ignoring parameters like `charset`
+    pub fn from_content_type(content_type: &str) -> Self {
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        match mime.as_str() {
+            "application/json" | "text/json" => BodyFormat::Json,
+            "text/html" | "application/xhtml+xml" => BodyFormat::Html,
+            "application/xml" | "text/xml" => BodyFormat::Xml,
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => BodyFormat::Yaml,
+            "text/csv" | "application/csv" | "text/tab-separated-values" => BodyFormat::Csv,
+            "application/x-www-form-urlencoded" => BodyFormat::Form,
+            _ if mime.ends_with("+json") => BodyFormat::Json,
+            _ if mime.ends_with("+xml") => BodyFormat::Xml,
+            _ if mime.ends_with("+yaml") => BodyFormat::Yaml,
+            _ => BodyFormat::Text,
+        }
+    }
+}
+
+/// Pretty-print a body as `format`. Bodies that don't parse are returned unchanged.
+pub fn format_body(format: BodyFormat, text: &str) -> String {
+    let formatted = match format {
+        BodyFormat::Json => serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok()),
+        BodyFormat::Xml => format_markup(text, false),
+        BodyFormat::Html => format_markup(text, true),
+        BodyFormat::Csv => Some(format_csv(text)),
+        BodyFormat::Form => Some(format_form(text)),
+        BodyFormat::Hex => Some(hex_dump(text.as_bytes())),
+        // YAML is line based already and there's nothing to reflow in plain text
+        BodyFormat::Yaml | BodyFormat::Text => None,
+    };
+    formatted.unwrap_or_else(|| text.to_string())
+}
+
+#[derive(Debug, PartialEq)]
+enum MarkupToken<'a> {
+    Open { name: String, tag: &'a str, self_closing: bool },
+    Close { name: String, tag: &'a str },
+    /// Comments, CDATA, `<!DOCTYPE>` and `<?xml ...?>`
+    Other(&'a str),
+    Text(&'a str),
+}
+
+/// Split markup into tags and the text between them, `None` on an unterminated tag
+fn tokenize_markup(text: &str, html: bool) -> Option<Vec<MarkupToken<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(MarkupToken::Text(&rest[..end]));
+            rest = &rest[end..];
+            continue;
+        }
+
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else {
+            ">"
+        };
+        let end = if terminator == ">" {
+            tag_end(rest)?
+        } else {
+            rest.find(terminator)? + terminator.len()
+        };
+        let tag = &rest[..end];
+        rest = &rest[end..];
+
+        let token = if tag.starts_with("<!") || tag.starts_with("<?") {
+            MarkupToken::Other(tag)
+        } else if let Some(inner) = tag.strip_prefix("</") {
+            MarkupToken::Close { name: tag_name(inner), tag }
+        } else {
+            MarkupToken::Open { name: tag_name(&tag[1..]), tag, self_closing: tag.ends_with("/>") }
+        };
+
+        // Script and style contents may contain `<`, take everything up to the closing tag as text
+        if let MarkupToken::Open { name, self_closing: false, .. } = &token {
+            if html && HTML_RAW_ELEMENTS.iter().any(|raw| raw.eq_ignore_ascii_case(name)) {
+                let close = format!("</{}", name.to_ascii_lowercase());
+                let end = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+                tokens.push(token);
+                if end > 0 {
+                    tokens.push(MarkupToken::Text(&rest[..end]));
+                }
+                rest = &rest[end..];
+                continue;
+            }
+        }
+        tokens.push(token);
+    }
+
+    Some(tokens)
+}
+
+/// Byte offset just past the `>` closing the tag at the start of `text`, skipping quoted attribute values
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (idx, ch) in text.char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(q), _) if ch == q => quote = None,
+            (None, '>') => return Some(idx + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn tag_name(after_bracket: &str) -> String {
+    after_bracket
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '>' && *c != '/')
+        .collect()
+}
+
+/// Indent one element per line. Elements holding only a short text stay on one line.
+///
+/// HTML is matched leniently: void elements never open a level, and a closing tag
+/// also closes elements left open inside it, like `<li>` without `</li>`.
+fn format_markup(text: &str, html: bool) -> Option<String> {
+    let tokens = tokenize_markup(text, html)?;
+    let same_name = |a: &str, b: &str| if html { a.eq_ignore_ascii_case(b) } else { a == b };
+
+    let mut lines = Vec::new();
+    let mut open: Vec<String> = Vec::new();
+    let mut idx = 0;
+
+    while idx < tokens.len() {
+        let indent = "  ".repeat(open.len());
+        match &tokens[idx] {
+            MarkupToken::Open { name, tag, self_closing } => {
+                let is_void = html && HTML_VOID_ELEMENTS.iter().any(|void| void.eq_ignore_ascii_case(name));
+                if *self_closing || is_void {
+                    lines.push(format!("{}{}", indent, tag));
+                    idx += 1;
+                    continue;
+                }
+
+                // Keep script, style and pre contents exactly as received
+                if html && HTML_RAW_ELEMENTS.iter().any(|raw| raw.eq_ignore_ascii_case(name)) {
+                    let close = tokens[idx + 1..].iter().position(|token| {
+                        matches!(token, MarkupToken::Close { name: close, .. } if same_name(close, name))
+                    });
+                    if let Some(offset) = close {
+                        let start = tag.as_ptr() as usize - text.as_ptr() as usize;
+                        let MarkupToken::Close { tag: close_tag, .. } = &tokens[idx + 1 + offset] else {
+                            unreachable!()
+                        };
+                        let end = close_tag.as_ptr() as usize - text.as_ptr() as usize + close_tag.len();
+                        lines.push(format!("{}{}", indent, &text[start..end]));
+                        idx += offset + 2;
+                        continue;
+                    }
+                }
+
+                // `<a>text</a>` and `<a></a>` on one line
+                match (tokens.get(idx + 1), tokens.get(idx + 2)) {
+                    (Some(MarkupToken::Close { name: close, tag: close_tag }), _) if same_name(close, name) => {
+                        lines.push(format!("{}{}{}", indent, tag, close_tag));
+                        idx += 2;
+                        continue;
+                    }
+                    (Some(MarkupToken::Text(inner)), Some(MarkupToken::Close { name: close, tag: close_tag }))
+                        if same_name(close, name) && !inner.trim().contains('\n') =>
+                    {
+                        lines.push(format!("{}{}{}{}", indent, tag, inner.trim(), close_tag));
+                        idx += 3;
+                        continue;
+                    }
+                    _ => {}
+                }
+
+                lines.push(format!("{}{}", indent, tag));
+                open.push(name.clone());
+            }
+            MarkupToken::Close { name, tag } => {
+                // A stray closing tag changes nothing
+                if let Some(pos) = open.iter().rposition(|open| same_name(open, name)) {
+                    open.truncate(pos);
+                }
+                lines.push(format!("{}{}", "  ".repeat(open.len()), tag));
+            }
+            MarkupToken::Other(tag) => lines.push(format!("{}{}", indent, tag)),
+            MarkupToken::Text(text) => {
+                lines.extend(
+                    text.lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(|line| format!("{}{}", indent, line)),
+                );
+            }
+        }
+        idx += 1;
+    }
+
+    Some(lines.join("\n"))
+}
+
+/// The delimiter used most on the first line: comma, semicolon or tab
+fn csv_delimiter(text: &str) -> char {
+    let first_line = text.lines().next().unwrap_or_default();
+    // Ties go to the comma, the last maximum wins
+    ['\t', ';', ',']
+        .into_iter()
+        .max_by_key(|delimiter| first_line.matches(*delimiter).count())
+        .unwrap_or(',')
+}
+
+/// Split CSV into records following RFC 4180 quoting. Line breaks inside quotes become `⏎`.
+fn parse_csv(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' | '\n' if in_quotes => {
+                if ch == '\n' {
+                    field.push('⏎');
+                }
+            }
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ if ch == delimiter && !in_quotes => record.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Align the columns into a table, with a rule under the header row
+fn format_csv(text: &str) -> String {
+    let records = parse_csv(text, csv_delimiter(text));
+    let columns = records.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|col| {
+            records
+                .iter()
+                .filter_map(|record| record.get(col))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .min(MAX_CSV_COLUMN_WIDTH)
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    for (idx, record) in records.iter().enumerate() {
+        let cells: Vec<String> = record
+            .iter()
+            .enumerate()
+            .map(|(col, cell)| format!("{:width$}", cell, width = widths[col]))
+            .collect();
+        lines.push(cells.join(" │ ").trim_end().to_string());
+        if idx == 0 && records.len() > 1 {
+            lines.push(widths.iter().map(|width| "─".repeat(*width)).collect::<Vec<_>>().join("─┼─"));
+        }
+    }
+    lines.join("\n")
+}
+
+/// One decoded `key = value` pair per line
+fn format_form(text: &str) -> String {
+    let decode = |part: &str| percent_decode_str(&part.replace('+', " ")).decode_utf8_lossy().into_owned();
+    text.trim()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => format!("{} = {}", decode(key), decode(value)),
+            None => decode(pair),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_from_content_type() {
+        assert_eq!(BodyFormat::from_content_type("application/json; charset=utf-8"), BodyFormat::Json);
+        assert_eq!(BodyFormat::from_content_type("application/problem+json"), BodyFormat::Json);
+        assert_eq!(BodyFormat::from_content_type("application/soap+xml"), BodyFormat::Xml);
+        assert_eq!(BodyFormat::from_content_type("Text/HTML"), BodyFormat::Html);
+        assert_eq!(BodyFormat::from_content_type("application/x-yaml"), BodyFormat::Yaml);
+        assert_eq!(BodyFormat::from_content_type("text/csv"), BodyFormat::Csv);
+        assert_eq!(BodyFormat::from_content_type("application/x-www-form-urlencoded"), BodyFormat::Form);
+        assert_eq!(BodyFormat::from_content_type("application/octet-stream"), BodyFormat::Text);
+        assert_eq!(BodyFormat::Hex.next(), BodyFormat::Json);
+    }
+
+    #[test]
+    fn xml_is_indented() {
+        let xml = r#"<?xml version="1.0"?><soap:Envelope><soap:Body><m:Price a="1>2"><m:Amount>5</m:Amount><m:Empty/></m:Price><!-- done --></soap:Body></soap:Envelope>"#;
+        assert_eq!(
+            format_body(BodyFormat::Xml, xml),
+            [
+                r#"<?xml version="1.0"?>"#,
+                "<soap:Envelope>",
+                "  <soap:Body>",
+                r#"    <m:Price a="1>2">"#,
+                "      <m:Amount>5</m:Amount>",
+                "      <m:Empty/>",
+                "    </m:Price>",
+                "    <!-- done -->",
+                "  </soap:Body>",
+                "</soap:Envelope>",
+            ]
+            .join("\n")
+        );
+        assert_eq!(format_body(BodyFormat::Xml, "<a><b"), "<a><b");
+    }
+
+    #[test]
+    fn html_tolerates_void_and_unclosed_elements() {
+        let html = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><script>if (a < b) { go(); }</script></head>\
+                    <body><ul><li>One<li>Two</ul><br><p>Hi</p></body></html>";
+        assert_eq!(
+            format_body(BodyFormat::Html, html),
+            [
+                "<!DOCTYPE html>",
+                "<html>",
+                "  <head>",
+                "    <meta charset=\"utf-8\">",
+                "    <script>if (a < b) { go(); }</script>",
+                "  </head>",
+                "  <body>",
+                "    <ul>",
+                "      <li>",
+                "        One",
+                "        <li>",
+                "          Two",
+                "    </ul>",
+                "    <br>",
+                "    <p>Hi</p>",
+                "  </body>",
+                "</html>",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn csv_is_aligned() {
+        let csv = "id,name,note\r\n1,Ada,\"says \"\"hi\"\", twice\"\r\n22,Bo,\"two\nlines\"\r\n";
+        assert_eq!(
+            format_body(BodyFormat::Csv, csv),
+            [
+                "id │ name │ note",
+                "───┼──────┼─────────────────",
+                "1  │ Ada  │ says \"hi\", twice",
+                "22 │ Bo   │ two⏎lines",
+            ]
+            .join("\n")
+        );
+        assert_eq!(format_body(BodyFormat::Csv, "a;b\n1;2"), "a │ b\n──┼──\n1 │ 2");
+    }
+
+    #[test]
+    fn form_pairs_are_decoded() {
+        assert_eq!(
+            format_body(BodyFormat::Form, "name=Jane+Doe&email=jane%40example.com&flag&"),
+            "name = Jane Doe\nemail = jane@example.com\nflag"
+        );
+    }
+}
@@ -0,0 +1,386 @@
+use crate::models::json_tree::member_path;
+use crate::models::response::HttpResponse;
+use serde_json::Value;
+
+/// Beyond this many cells the changed middle of a text is shown as removed, then added
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Unchanged lines kept around each change of a text diff
+const CONTEXT_LINES: usize = 2;
+
+/// Longest value shown inline in a JSON change
+const MAX_VALUE_WIDTH: usize = 120;
+
+/// A difference between two JSON documents at one path
+#[derive(Debug, PartialEq)]
+pub enum JsonChange {
+    Added { path: String, value: Value },
+    Removed { path: String, value: Value },
+    Changed { path: String, old: Value, new: Value },
+}
+
+/// Compare two documents member by member and element by element, with JSONPath paths
+pub fn json_diff(old: &Value, new: &Value) -> Vec<JsonChange> {
+    let mut changes = Vec::new();
+    diff_values("$".to_string(), old, new, &mut changes);
+    changes
+}
+
+fn diff_values(path: String, old: &Value, new: &Value, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old_members), Value::Object(new_members)) => {
+            for (key, old_value) in old_members {
+                let child = member_path(&path, key);
+                match new_members.get(key) {
+                    Some(new_value) => diff_values(child, old_value, new_value, changes),
+                    None => changes.push(JsonChange::Removed { path: child, value: old_value.clone() }),
+                }
+            }
+            for (key, new_value) in new_members {
+                if !old_members.contains_key(key) {
+                    changes.push(JsonChange::Added { path: member_path(&path, key), value: new_value.clone() });
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for idx in 0..old_items.len().max(new_items.len()) {
+                let child = format!("{}[{}]", path, idx);
+                match (old_items.get(idx), new_items.get(idx)) {
+                    (Some(old_item), Some(new_item)) => diff_values(child, old_item, new_item, changes),
+                    (Some(old_item), None) => changes.push(JsonChange::Removed { path: child, value: old_item.clone() }),
+                    (None, Some(new_item)) => changes.push(JsonChange::Added { path: child, value: new_item.clone() }),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if old != new => changes.push(JsonChange::Changed { path, old: old.clone(), new: new.clone() }),
+        _ => {}
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff from the longest common subsequence, after skipping the common prefix and suffix
+pub fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut lines: Vec<DiffLine> = old[..prefix].iter().map(|line| DiffLine::Same(line)).collect();
+
+    if old_middle.len().saturating_mul(new_middle.len()) > MAX_LCS_CELLS {
+        lines.extend(old_middle.iter().map(|line| DiffLine::Removed(line)));
+        lines.extend(new_middle.iter().map(|line| DiffLine::Added(line)));
+    } else {
+        // lcs[i][j]: length of the common subsequence of old_middle[i..] and new_middle[j..]
+        let width = new_middle.len() + 1;
+        let mut lcs = vec![0u32; (old_middle.len() + 1) * width];
+        for i in (0..old_middle.len()).rev() {
+            for j in (0..new_middle.len()).rev() {
+                lcs[i * width + j] = if old_middle[i] == new_middle[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old_middle.len() && j < new_middle.len() {
+            if old_middle[i] == new_middle[j] {
+                lines.push(DiffLine::Same(old_middle[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                lines.push(DiffLine::Removed(old_middle[i]));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Added(new_middle[j]));
+                j += 1;
+            }
+        }
+        lines.extend(old_middle[i..].iter().map(|line| DiffLine::Removed(line)));
+        lines.extend(new_middle[j..].iter().map(|line| DiffLine::Added(line)));
+    }
+
+    lines.extend(old[old.len() - suffix..].iter().map(|line| DiffLine::Same(line)));
+    lines
+}
+
+/// A header whose values differ; `None` where the header is missing
+#[derive(Debug, PartialEq)]
+pub struct HeaderChange {
+    pub name: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Compare headers by case-insensitive name, repeated headers joined with `, `
+pub fn header_diff(old: &[(String, String)], new: &[(String, String)]) -> Vec<HeaderChange> {
+    let values = |headers: &[(String, String)], name: &str| {
+        let values: Vec<&str> = headers
+            .iter()
+            .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+            .collect();
+        (!values.is_empty()).then(|| values.join(", "))
+    };
+
+    let mut names: Vec<String> = Vec::new();
+    for (name, _) in old.iter().chain(new) {
+        let name = name.to_ascii_lowercase();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let (old, new) = (values(old, &name), values(new, &name));
+            (old != new).then_some(HeaderChange { name, old, new })
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReportLineKind {
+    Heading,
+    Same,
+    Added,
+    Removed,
+    Changed,
+    Note,
+}
+
+/// One line of a response comparison
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportLine {
+    pub kind: ReportLineKind,
+    pub text: String,
+}
+
+impl ReportLine {
+    fn new(kind: ReportLineKind, text: impl Into<String>) -> Self {
+        Self { kind, text: text.into() }
+    }
+}
+
+/// Status, timing and size, then the header diff, then a JSON or line diff of the bodies
+pub fn compare_responses(old: &HttpResponse, new: &HttpResponse) -> Vec<ReportLine> {
+    let mut report = Vec::new();
+
+    let status_kind = if old.status_text == new.status_text { ReportLineKind::Same } else { ReportLineKind::Changed };
+    report.push(ReportLine::new(status_kind, format!("Status   {} → {}", old.status_text, new.status_text)));
+    report.push(ReportLine::new(
+        ReportLineKind::Same,
+        format!(
+            "Time     {}ms → {}ms ({:+}ms)",
+            old.duration_ms,
+            new.duration_ms,
+            new.duration_ms as i64 - old.duration_ms as i64
+        ),
+    ));
+    report.push(ReportLine::new(
+        ReportLineKind::Same,
+        format!(
+            "Size     {} → {} bytes ({:+})",
+            old.size_bytes,
+            new.size_bytes,
+            new.size_bytes as i64 - old.size_bytes as i64
+        ),
+    ));
+
+    let headers = header_diff(&old.raw_headers, &new.raw_headers);
+    report.push(ReportLine::new(ReportLineKind::Note, ""));
+    report.push(ReportLine::new(ReportLineKind::Heading, format!("Headers ({} changed)", headers.len())));
+    if headers.is_empty() {
+        report.push(ReportLine::new(ReportLineKind::Note, "  identical"));
+    }
+    for change in headers {
+        report.push(match (change.old, change.new) {
+            (Some(old), Some(new)) => ReportLine::new(ReportLineKind::Changed, format!("~ {}: {} → {}", change.name, old, new)),
+            (None, Some(new)) => ReportLine::new(ReportLineKind::Added, format!("+ {}: {}", change.name, new)),
+            (Some(old), None) => ReportLine::new(ReportLineKind::Removed, format!("- {}: {}", change.name, old)),
+            (None, None) => continue,
+        });
+    }
+
+    report.push(ReportLine::new(ReportLineKind::Note, ""));
+    let parse = |response: &HttpResponse| {
+        response.body_text.as_deref().and_then(|text| serde_json::from_str::<Value>(text).ok())
+    };
+    match (parse(old), parse(new)) {
+        (Some(old_json), Some(new_json)) => report.extend(json_report(&old_json, &new_json)),
+        _ if old.body_text.is_none() || new.body_text.is_none() => {
+            let same = old.body == new.body;
+            report.push(ReportLine::new(ReportLineKind::Heading, "Body (binary)"));
+            report.push(ReportLine::new(
+                ReportLineKind::Note,
+                if same { "  identical" } else { "  the bytes differ, save both bodies to compare them externally" },
+            ));
+        }
+        _ => {
+            let old_text = old.formatted_body(old.body_format());
+            let new_text = new.formatted_body(new.body_format());
+            report.extend(text_report(&old_text, &new_text));
+        }
+    }
+
+    report
+}
+
+fn json_report(old: &Value, new: &Value) -> Vec<ReportLine> {
+    let changes = json_diff(old, new);
+    let mut report = vec![ReportLine::new(ReportLineKind::Heading, format!("Body (JSON, {} changes)", changes.len()))];
+    if changes.is_empty() {
+        report.push(ReportLine::new(ReportLineKind::Note, "  identical"));
+    }
+    report.extend(changes.into_iter().map(|change| match change {
+        JsonChange::Added { path, value } => ReportLine::new(ReportLineKind::Added, format!("+ {}: {}", path, compact(&value))),
+        JsonChange::Removed { path, value } => ReportLine::new(ReportLineKind::Removed, format!("- {}: {}", path, compact(&value))),
+        JsonChange::Changed { path, old, new } => {
+            ReportLine::new(ReportLineKind::Changed, format!("~ {}: {} → {}", path, compact(&old), compact(&new)))
+        }
+    }));
+    report
+}
+
+/// Changed lines with a little context; long unchanged runs are folded into a note
+fn text_report(old: &str, new: &str) -> Vec<ReportLine> {
+    let lines = line_diff(old, new);
+    let added = lines.iter().filter(|line| matches!(line, DiffLine::Added(_))).count();
+    let removed = lines.iter().filter(|line| matches!(line, DiffLine::Removed(_))).count();
+    let mut report = vec![ReportLine::new(ReportLineKind::Heading, format!("Body (text, +{} -{} lines)", added, removed))];
+    if added + removed == 0 {
+        report.push(ReportLine::new(ReportLineKind::Note, "  identical"));
+        return report;
+    }
+
+    let near_change = |idx: usize| {
+        let start = idx.saturating_sub(CONTEXT_LINES);
+        let end = (idx + CONTEXT_LINES + 1).min(lines.len());
+        lines[start..end].iter().any(|line| !matches!(line, DiffLine::Same(_)))
+    };
+
+    let mut folded = 0;
+    for (idx, line) in lines.iter().enumerate() {
+        let line = match line {
+            DiffLine::Same(_) if !near_change(idx) => {
+                folded += 1;
+                continue;
+            }
+            DiffLine::Same(text) => ReportLine::new(ReportLineKind::Same, format!("  {}", text)),
+            DiffLine::Removed(text) => ReportLine::new(ReportLineKind::Removed, format!("- {}", text)),
+            DiffLine::Added(text) => ReportLine::new(ReportLineKind::Added, format!("+ {}", text)),
+        };
+        if folded > 0 {
+            report.push(ReportLine::new(ReportLineKind::Note, format!("  ⋯ {} unchanged lines", folded)));
+            folded = 0;
+        }
+        report.push(line);
+    }
+    if folded > 0 {
+        report.push(ReportLine::new(ReportLineKind::Note, format!("  ⋯ {} unchanged lines", folded)));
+    }
+    report
+}
+
+/// Single-line JSON, cut off when long
+fn compact(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() <= MAX_VALUE_WIDTH {
+        return text;
+    }
+    format!("{}…", text.chars().take(MAX_VALUE_WIDTH).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_changes_are_reported_by_path() {
+        let old = json!({"user": {"name": "a", "odd key": 1}, "items": [1, 2, 3], "debug": true});
+        let new = json!({"user": {"name": "b", "odd key": 1}, "items": [1, 5], "extra": null});
+        assert_eq!(
+            json_diff(&old, &new),
+            vec![
+                JsonChange::Removed { path: "$.debug".to_string(), value: json!(true) },
+                JsonChange::Changed { path: "$.items[1]".to_string(), old: json!(2), new: json!(5) },
+                JsonChange::Removed { path: "$.items[2]".to_string(), value: json!(3) },
+                JsonChange::Changed { path: "$.user.name".to_string(), old: json!("a"), new: json!("b") },
+                JsonChange::Added { path: "$.extra".to_string(), value: json!(null) },
+            ]
+        );
+        assert!(json_diff(&old, &old).is_empty());
+        assert_eq!(json_diff(&json!(1), &json!("1")).len(), 1);
+    }
+
+    #[test]
+    fn line_diff_keeps_common_lines() {
+        let diff = line_diff("a\nb\nc\nd", "a\nc\nx\nd");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Same("c"),
+                DiffLine::Added("x"),
+                DiffLine::Same("d"),
+            ]
+        );
+        assert_eq!(line_diff("", "new"), vec![DiffLine::Added("new")]);
+    }
+
+    #[test]
+    fn headers_compare_case_insensitively() {
+        let headers = |pairs: &[(&str, &str)]| pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>();
+        let old = headers(&[("Content-Length", "10"), ("ETag", "\"x\""), ("Set-Cookie", "a=1"), ("Set-Cookie", "b=2")]);
+        let new = headers(&[("content-length", "12"), ("set-cookie", "a=1, b=2"), ("X-New", "1")]);
+        assert_eq!(
+            header_diff(&old, &new),
+            vec![
+                HeaderChange { name: "content-length".to_string(), old: Some("10".to_string()), new: Some("12".to_string()) },
+                HeaderChange { name: "etag".to_string(), old: Some("\"x\"".to_string()), new: None },
+                HeaderChange { name: "x-new".to_string(), old: None, new: Some("1".to_string()) },
+            ]
+        );
+    }
+
+    #[test]
+    fn text_report_folds_unchanged_runs() {
+        let old: String = (0..20).map(|n| format!("line {}\n", n)).collect();
+        let new = old.replace("line 10\n", "line ten\n");
+        let texts: Vec<String> = text_report(&old, &new).into_iter().map(|line| line.text).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Body (text, +1 -1 lines)",
+                "  ⋯ 8 unchanged lines",
+                "  line 8",
+                "  line 9",
+                "- line 10",
+                "+ line ten",
+                "  line 11",
+                "  line 12",
+                "  ⋯ 7 unchanged lines",
+            ]
+        );
+    }
+}
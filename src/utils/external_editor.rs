@@ -0,0 +1,67 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::process::Command;
+
+/// Used when neither `$VISUAL` nor `$EDITOR` is set
+const DEFAULT_EDITOR: &str = "vi";
+
+/// The user's editor command, `$VISUAL` first as it may be a full-screen one
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// Write `text` to a temp file, run `command` on it and return the saved content.
+///
+/// The command may carry arguments (e.g. `code --wait`); the file path is appended.
+/// The terminal must already be handed over to the editor. The trailing newline most
+/// editors add on save is dropped when `text` had none.
+pub fn edit_text(command: &str, text: &str, extension: &str) -> Result<String> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().context("The editor command is empty")?;
+
+    let path = std::env::temp_dir().join(format!("nexus-{}.{}", uuid::Uuid::new_v4(), extension));
+    fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to start {}", program));
+    let edited = fs::read_to_string(&path).with_context(|| format!("Failed to read back {}", path.display()));
+    let _ = fs::remove_file(&path);
+
+    let status = status?;
+    if !status.success() {
+        bail!("{} exited with {}, nothing was changed", program, status);
+    }
+    let mut edited = edited?;
+    if !text.ends_with('\n') && edited.ends_with('\n') {
+        edited.pop();
+        if edited.ends_with('\r') {
+            edited.pop();
+        }
+    }
+    Ok(edited)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_what_the_editor_saved() {
+        let replacement = std::env::temp_dir().join(format!("nexus-test-{}.json", uuid::Uuid::new_v4()));
+        fs::write(&replacement, "{\"edited\": true}\n").unwrap();
+
+        let edited = edit_text(&format!("cp {}", replacement.display()), "{}", "json").unwrap();
+        assert_eq!(edited, "{\"edited\": true}");
+        assert_eq!(edit_text("true", "a\nb\n", "txt").unwrap(), "a\nb\n");
+        assert!(edit_text("false", "{}", "json").unwrap_err().to_string().contains("nothing was changed"));
+
+        let _ = fs::remove_file(replacement);
+    }
+}
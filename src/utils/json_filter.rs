@@ -0,0 +1,205 @@
+use anyhow::{anyhow, bail, Result};
+use jaq_core::load::{Arena, File, Loader};
+use jaq_core::{Compiler, Ctx, RcIter};
+use jaq_json::Val;
+use jsonpath_rust::JsonPath;
+use serde_json::Value;
+use std::time::Duration;
+
+/// Stop collecting results after this many values, e.g. for `repeat(1)`
+const MAX_RESULTS: usize = 10_000;
+
+/// Give up on expressions that run longer than this, e.g. `last(range(1e12))`
+pub const FILTER_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterSyntax {
+    Jq,
+    JsonPath,
+}
+
+impl FilterSyntax {
+    /// JSONPath expressions start at the root `$`, everything else is jq
+    pub fn detect(expression: &str) -> Self {
+        if expression.trim_start().starts_with('$') {
+            FilterSyntax::JsonPath
+        } else {
+            FilterSyntax::Jq
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            FilterSyntax::Jq => "jq",
+            FilterSyntax::JsonPath => "JSONPath",
+        }
+    }
+}
+
+/// Run a jq or JSONPath expression against each input and collect all results
+pub fn apply_filter(expression: &str, inputs: &[Value]) -> Result<Vec<Value>> {
+    match FilterSyntax::detect(expression) {
+        FilterSyntax::Jq => apply_jq(expression, inputs),
+        FilterSyntax::JsonPath => apply_json_path(expression.trim(), inputs),
+    }
+}
+
+/// Run [`apply_filter`] on a blocking thread and give up after `timeout`.
+///
+/// jq evaluation cannot be interrupted, so an expression that times out keeps
+/// its thread busy until it finishes on its own; its result is discarded.
+pub async fn apply_filter_with_timeout(expression: String, inputs: Vec<Value>, timeout: Duration) -> Result<Vec<Value>> {
+    let evaluation = tokio::task::spawn_blocking(move || apply_filter(&expression, &inputs));
+    match tokio::time::timeout(timeout, evaluation).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => bail!("filter evaluation failed"),
+        Err(_) => bail!("timed out after {:?}", timeout),
+    }
+}
+
+fn apply_jq(expression: &str, inputs: &[Value]) -> Result<Vec<Value>> {
+    let program = File { code: expression, path: () };
+    let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+    let arena = Arena::default();
+
+    let modules = loader.load(&arena, program).map_err(|errors| {
+        let error = errors.into_iter().next().map(|(_, error)| error);
+        match error {
+            Some(jaq_core::load::Error::Lex(errors)) => match errors.first() {
+                Some((expect, at)) => anyhow!("expected {} at column {}", expect.as_str(), column(expression, at)),
+                None => anyhow!("invalid expression"),
+            },
+            Some(jaq_core::load::Error::Parse(errors)) => match errors.first() {
+                Some((expect, at)) => anyhow!("expected {} at column {}", expect.as_str(), column(expression, at)),
+                None => anyhow!("invalid expression"),
+            },
+            Some(jaq_core::load::Error::Io(errors)) => match errors.first() {
+                Some((_, message)) => anyhow!("{}", message),
+                None => anyhow!("invalid expression"),
+            },
+            None => anyhow!("invalid expression"),
+        }
+    })?;
+
+    let filter = Compiler::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .compile(modules)
+        .map_err(|errors| {
+            match errors.first().and_then(|(_, undefined)| undefined.first()) {
+                Some((name, kind)) => anyhow!("undefined {} {} at column {}", kind.as_str(), name, column(expression, name)),
+                None => anyhow!("invalid expression"),
+            }
+        })?;
+
+    let mut results = Vec::new();
+    for input in inputs {
+        let no_inputs = RcIter::new(core::iter::empty());
+        for output in filter.run((Ctx::new([], &no_inputs), Val::from(input.clone()))) {
+            match output {
+                Ok(value) => results.push(Value::from(value)),
+                Err(error) => bail!("{}", error),
+            }
+            if results.len() >= MAX_RESULTS {
+                return Ok(results);
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+fn apply_json_path(expression: &str, inputs: &[Value]) -> Result<Vec<Value>> {
+    let mut results = Vec::new();
+    for input in inputs {
+        let found = input.query(expression).map_err(|error| anyhow!(json_path_error(&error.to_string())))?;
+        results.extend(found.into_iter().cloned());
+    }
+    Ok(results)
+}
+
+/// Condense the multi-line parser report into "expected ... at column N"
+fn json_path_error(report: &str) -> String {
+    let position = report
+        .lines()
+        .find_map(|line| line.split("--> ").nth(1))
+        .and_then(|pos| pos.split(':').nth(1));
+    let message = report
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("= "))
+        .unwrap_or_else(|| report.lines().next().unwrap_or_default().trim_end_matches(|c: char| c == ':' || c.is_whitespace()));
+
+    match position {
+        Some(column) => format!("{} at column {}", message, column),
+        None => message.to_string(),
+    }
+}
+
+/// 1-based column of a slice of the expression
+fn column(expression: &str, at: &str) -> usize {
+    let offset = (at.as_ptr() as usize).saturating_sub(expression.as_ptr() as usize);
+    expression
+        .get(..offset.min(expression.len()))
+        .map_or(0, |before| before.chars().count())
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn body() -> Value {
+        json!({"items": [{"id": 1, "tags": ["a"]}, {"id": 2, "tags": []}], "total": 2})
+    }
+
+    #[test]
+    fn jq_expressions() {
+        assert_eq!(apply_filter(".items[].id", &[body()]).unwrap(), vec![json!(1), json!(2)]);
+        assert_eq!(
+            apply_filter("[.items[] | select(.tags | length > 0) | .id]", &[body()]).unwrap(),
+            vec![json!([1])]
+        );
+        assert_eq!(apply_filter(".total", &[body(), json!({"total": 5})]).unwrap(), vec![json!(2), json!(5)]);
+    }
+
+    #[test]
+    fn json_path_expressions() {
+        assert_eq!(FilterSyntax::detect(" $.items"), FilterSyntax::JsonPath);
+        assert_eq!(apply_filter("$.items[*].id", &[body()]).unwrap(), vec![json!(1), json!(2)]);
+        assert_eq!(apply_filter("$..tags[0]", &[body()]).unwrap(), vec![json!("a")]);
+        assert_eq!(apply_filter("$.items[?(@.id > 1)].id", &[body()]).unwrap(), vec![json!(2)]);
+    }
+
+    #[test]
+    fn errors_are_short_and_point_at_the_problem() {
+        let error = apply_filter(".items[", &[body()]).unwrap_err().to_string();
+        assert!(error.contains("column"), "{}", error);
+        assert!(!error.contains('\n'), "{}", error);
+
+        let error = apply_filter(".items | nope", &[body()]).unwrap_err().to_string();
+        assert_eq!(error, "undefined filter nope at column 10");
+
+        let error = apply_filter("$.items[", &[body()]).unwrap_err().to_string();
+        assert!(error.contains("column"), "{}", error);
+        assert!(!error.contains('\n'), "{}", error);
+
+        let error = apply_filter(".total | .x", &[body()]).unwrap_err().to_string();
+        assert!(error.starts_with("cannot use 2"), "{}", error);
+    }
+
+    #[test]
+    fn slow_expressions_time_out() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(apply_filter_with_timeout(
+            "last(range(1e15))".to_string(),
+            vec![body()],
+            Duration::from_millis(50),
+        ));
+        assert_eq!(result.unwrap_err().to_string(), "timed out after 50ms");
+
+        let result = runtime.block_on(apply_filter_with_timeout(".total".to_string(), vec![body()], FILTER_TIMEOUT));
+        assert_eq!(result.unwrap(), vec![json!(2)]);
+        // The abandoned evaluation is still running; don't wait for it
+        runtime.shutdown_background();
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::{bail, Result};
+
+/// Spaces per nesting level when formatting
+const INDENT: &str = "  ";
+
+/// Where a JSON document stops parsing
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonSyntaxError {
+    /// 0-based line
+    pub line: usize,
+    /// 0-based character column within the line
+    pub column: usize,
+    pub message: String,
+}
+
+/// Check that `text` is one JSON value. Blank text is accepted, it is an empty body.
+pub fn check_json(text: &str) -> Option<JsonSyntaxError> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    let error = serde_json::from_str::<serde::de::IgnoredAny>(text).err()?;
+
+    // serde_json counts lines from 1 and columns in bytes from 1, 0 meaning before the first one
+    let line = error.line().saturating_sub(1);
+    let line_text = text.lines().nth(line).unwrap_or("");
+    let byte_column = error.column().saturating_sub(1).min(line_text.len());
+    let column = line_text
+        .char_indices()
+        .take_while(|(idx, _)| *idx < byte_column)
+        .count();
+
+    let message = error.to_string();
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+    Some(JsonSyntaxError {
+        line,
+        column,
+        message: message.strip_suffix(&suffix).unwrap_or(&message).to_string(),
+    })
+}
+
+/// Re-indent a JSON document, keeping the key order and the literals as written
+pub fn reformat_json(text: &str) -> Result<String> {
+    if let Some(error) = check_json(text) {
+        bail!("line {}, column {}: {}", error.line + 1, error.column + 1, error.message);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut depth = 0;
+    let mut chars = text.trim().chars().peekable();
+    let newline = |out: &mut String, depth: usize| {
+        out.push('\n');
+        out.push_str(&INDENT.repeat(depth));
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    out.push(c);
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => break,
+                        _ => escaped = false,
+                    }
+                }
+            }
+            '{' | '[' => {
+                out.push(c);
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                // Empty containers stay on one line
+                if let Some(close) = chars.next_if(|next| matches!((c, next), ('{', '}') | ('[', ']'))) {
+                    out.push(close);
+                } else {
+                    depth += 1;
+                    newline(&mut out, depth);
+                }
+            }
+            '}' | ']' => {
+                depth -= 1;
+                newline(&mut out, depth);
+                out.push(c);
+            }
+            ',' => {
+                out.push(c);
+                newline(&mut out, depth);
+            }
+            ':' => out.push_str(": "),
+            c if c.is_whitespace() => {}
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_point_at_the_offending_character() {
+        assert_eq!(check_json("  \n"), None);
+        assert_eq!(check_json("{\"a\": [1, 2]}"), None);
+
+        let error = check_json("{\n  \"naïve\": 1\n  \"b\": 2\n}").unwrap();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.message, "expected `,` or `}`");
+
+        let error = check_json("{\"é\": tru}").unwrap();
+        assert_eq!((error.line, error.column), (0, 9));
+    }
+
+    #[test]
+    fn reformat_keeps_key_order_and_strings() {
+        let text = r#"{"z":1,"a":{ },"list":[true,null,"x, \"y\": [z]"],"n":{"k":[]}}"#;
+        assert_eq!(
+            reformat_json(text).unwrap(),
+            "{\n  \"z\": 1,\n  \"a\": {},\n  \"list\": [\n    true,\n    null,\n    \"x, \\\"y\\\": [z]\"\n  ],\n  \"n\": {\n    \"k\": []\n  }\n}"
+        );
+        assert!(reformat_json("{\"a\": }").unwrap_err().to_string().starts_with("line 1, column 7"));
+    }
+}
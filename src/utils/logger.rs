@@ -1,8 +1,8 @@
+use crate::config::Config;
 use anyhow::Result;
-use std::path::PathBuf;
 
 pub fn init() -> Result<()> {
-    let log_dir = get_log_dir()?;
+    let log_dir = Config::global().paths.log_dir()?;
     std::fs::create_dir_all(&log_dir)?;
     
     let log_file = log_dir.join("nexus.log");
@@ -19,10 +19,3 @@ pub fn init() -> Result<()> {
     Ok(())
 }
 
-fn get_log_dir() -> Result<PathBuf> {
-    let config_dir = dirs::config_dir()
-        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
-    
-    Ok(config_dir.join("nexus").join("logs"))
-}
-
@@ -1,2 +1,8 @@
 pub mod logger;
-
+pub mod json_filter;
+pub mod text_search;
+pub mod body_format;
+pub mod binary;
+pub mod diff;
+pub mod json_format;
+pub mod external_editor;
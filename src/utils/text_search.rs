@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use regex::RegexBuilder;
+
+/// A hit inside one line of text, as a byte range of that line
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextMatch {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Find every occurrence of `query` in `text`, line by line.
+///
+/// Without `use_regex` the query is matched literally. Empty matches (e.g. `a*`) are skipped.
+pub fn find_matches(text: &str, query: &str, case_sensitive: bool, use_regex: bool) -> Result<Vec<TextMatch>> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pattern = if use_regex { query.to_string() } else { regex::escape(query) };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .context("Invalid regex")?;
+
+    Ok(text
+        .lines()
+        .enumerate()
+        .flat_map(|(line, content)| {
+            regex
+                .find_iter(content)
+                .filter(|m| !m.is_empty())
+                .map(move |m| TextMatch { line, start: m.start(), end: m.end() })
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "{\n  \"Name\": \"nexus\",\n  \"name\": \"a.b\"\n}";
+
+    #[test]
+    fn literal_search_ignores_case_by_default() {
+        let matches = find_matches(BODY, "name", false, false).unwrap();
+        assert_eq!(
+            matches,
+            vec![TextMatch { line: 1, start: 3, end: 7 }, TextMatch { line: 2, start: 3, end: 7 }]
+        );
+        assert_eq!(find_matches(BODY, "name", true, false).unwrap().len(), 1);
+
+        // Regex metacharacters are literal unless regex mode is on
+        assert_eq!(find_matches(BODY, "a.b", false, false).unwrap().len(), 1);
+        assert_eq!(find_matches(BODY, "n.x", false, false).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn regex_search() {
+        let matches = find_matches(BODY, r#""\w+":"#, true, true).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(find_matches(BODY, "x*", true, true).unwrap(), vec![TextMatch { line: 1, start: 13, end: 14 }]);
+        assert!(find_matches(BODY, "(", true, true).is_err());
+    }
+}